@@ -0,0 +1,4 @@
+-- Optional per-webhook body template, extra request headers and event filters
+ALTER TABLE webhooks ADD COLUMN payload_template TEXT;  -- MiniJinja template over the delivery envelope
+ALTER TABLE webhooks ADD COLUMN headers TEXT;           -- JSON object: {"Authorization": "Bearer ..."}
+ALTER TABLE webhooks ADD COLUMN filters TEXT;           -- JSON object, see WebhookFilters
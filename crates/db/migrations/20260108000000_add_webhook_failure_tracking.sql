@@ -0,0 +1,18 @@
+-- Track consecutive permanently failed deliveries so dead endpoints can be disabled
+ALTER TABLE webhooks ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0;
+
+-- Audit trail of webhook activation changes
+CREATE TABLE webhook_history (
+    id          BLOB PRIMARY KEY,
+    webhook_id  BLOB NOT NULL,
+    action      TEXT NOT NULL
+                   CHECK (action IN ('enabled', 'disabled', 'auto_disabled')),
+    reason      TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_webhook_history_webhook_id ON webhook_history(webhook_id);
+
+-- Index for retention pruning and time-range replays
+CREATE INDEX idx_webhook_deliveries_created_at ON webhook_deliveries(created_at);
@@ -0,0 +1,29 @@
+-- Project-scoped API tokens for scripts and services on other machines
+CREATE TABLE api_tokens (
+    id            BLOB PRIMARY KEY,
+    project_id    BLOB NOT NULL,
+    name          TEXT NOT NULL CHECK(name != ''),
+    token_hash    TEXT NOT NULL UNIQUE,  -- SHA-256 hex of the token, the token itself is never stored
+    token_prefix  TEXT NOT NULL,         -- First characters of the token, for display
+    scopes        TEXT NOT NULL CHECK(scopes != ''),  -- JSON array: ["tasks:read", "tasks:write", ...]
+    expires_at    TEXT,
+    last_used_at  TEXT,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_api_tokens_project_id ON api_tokens(project_id);
+
+-- Signed inbound endpoints that external systems call to create or transition tasks
+CREATE TABLE inbound_webhooks (
+    id            BLOB PRIMARY KEY,
+    project_id    BLOB NOT NULL,
+    name          TEXT NOT NULL CHECK(name != ''),
+    secret        TEXT NOT NULL CHECK(secret != ''),  -- For HMAC verification
+    is_active     INTEGER NOT NULL DEFAULT 1,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_inbound_webhooks_project_id ON inbound_webhooks(project_id);
@@ -0,0 +1,7 @@
+-- Record who made each task change and store long text changes as a diff
+ALTER TABLE task_history ADD COLUMN actor_type TEXT NOT NULL DEFAULT 'user'
+    CHECK (actor_type IN ('user', 'agent', 'system', 'api_token'));
+ALTER TABLE task_history ADD COLUMN diff TEXT;
+
+-- Index for the project activity feed
+CREATE INDEX idx_task_history_changed_at ON task_history(changed_at);
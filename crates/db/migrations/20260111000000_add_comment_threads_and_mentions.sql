@@ -0,0 +1,38 @@
+-- Reply threading, edits and author kinds for task comments
+ALTER TABLE task_comments ADD COLUMN parent_comment_id BLOB REFERENCES task_comments(id) ON DELETE CASCADE;
+ALTER TABLE task_comments ADD COLUMN author_type TEXT NOT NULL DEFAULT 'user'
+    CHECK (author_type IN ('user', 'agent', 'system', 'api_token'));
+ALTER TABLE task_comments ADD COLUMN edited_at TEXT;
+
+CREATE INDEX idx_task_comments_parent_comment_id ON task_comments(parent_comment_id);
+
+-- Previous versions of edited comments
+CREATE TABLE task_comment_edits (
+    id                BLOB PRIMARY KEY,
+    comment_id        BLOB NOT NULL,
+    previous_content  TEXT NOT NULL,
+    edited_by         TEXT NOT NULL,
+    edited_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (comment_id) REFERENCES task_comments(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_comment_edits_comment_id ON task_comment_edits(comment_id);
+
+-- @mentions that resolved to the task's assignee or one of its agents
+CREATE TABLE task_comment_mentions (
+    comment_id    BLOB NOT NULL,
+    mention       TEXT NOT NULL,
+    mention_type  TEXT NOT NULL CHECK (mention_type IN ('user', 'agent')),
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (comment_id, mention),
+    FOREIGN KEY (comment_id) REFERENCES task_comments(id) ON DELETE CASCADE
+);
+
+-- How far each reader has read the comments on a task
+CREATE TABLE task_comment_reads (
+    task_id       BLOB NOT NULL,
+    reader        TEXT NOT NULL,
+    last_read_at  TEXT NOT NULL,
+    PRIMARY KEY (task_id, reader),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
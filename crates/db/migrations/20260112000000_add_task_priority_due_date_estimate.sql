@@ -0,0 +1,7 @@
+-- Planning fields for tasks
+ALTER TABLE tasks ADD COLUMN priority TEXT NOT NULL DEFAULT 'medium'
+    CHECK (priority IN ('low', 'medium', 'high', 'urgent'));
+ALTER TABLE tasks ADD COLUMN due_date TEXT;
+ALTER TABLE tasks ADD COLUMN estimate_hours REAL CHECK (estimate_hours IS NULL OR estimate_hours >= 0);
+
+CREATE INDEX idx_tasks_project_id_due_date ON tasks(project_id, due_date);
@@ -0,0 +1,26 @@
+-- Custom board columns per project. Each state counts as one of the built-in
+-- statuses so status-driven automation keeps working.
+CREATE TABLE project_workflow_states (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    category    TEXT NOT NULL
+        CHECK (category IN ('todo', 'inprogress', 'inreview', 'done', 'cancelled')),
+    position    INTEGER NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+CREATE INDEX idx_project_workflow_states_project_id ON project_workflow_states(project_id);
+
+-- Allowed moves between states. A project without transitions allows every move.
+CREATE TABLE project_workflow_transitions (
+    project_id  BLOB NOT NULL,
+    from_state  TEXT NOT NULL,
+    to_state    TEXT NOT NULL,
+    PRIMARY KEY (project_id, from_state, to_state),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+ALTER TABLE tasks ADD COLUMN workflow_state TEXT;
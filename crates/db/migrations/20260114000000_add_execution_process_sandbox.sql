@@ -0,0 +1,2 @@
+-- Sandbox backend, mounts and resource limits a process was started with (JSON)
+ALTER TABLE execution_processes ADD COLUMN sandbox TEXT;
@@ -0,0 +1,7 @@
+-- Language, interpreter, timeout and resource limits per configured script (JSON)
+ALTER TABLE project_repos ADD COLUMN setup_script_options TEXT NOT NULL DEFAULT '{}';
+ALTER TABLE project_repos ADD COLUMN cleanup_script_options TEXT NOT NULL DEFAULT '{}';
+ALTER TABLE project_repos ADD COLUMN dev_script_options TEXT NOT NULL DEFAULT '{}';
+
+-- Why a failed process ended, when it was not its own exit code (e.g. 'timedout')
+ALTER TABLE execution_processes ADD COLUMN exit_reason TEXT;
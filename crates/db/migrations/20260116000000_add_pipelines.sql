@@ -0,0 +1,46 @@
+-- Declarative pipelines per project, stored as TOML
+CREATE TABLE project_pipelines (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    definition  TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+-- A run of a pipeline in a session. The definition is copied so editing the
+-- pipeline does not change runs in flight.
+CREATE TABLE pipeline_runs (
+    id                   BLOB PRIMARY KEY,
+    session_id           BLOB NOT NULL,
+    pipeline_id          BLOB,
+    name                 TEXT NOT NULL,
+    definition           TEXT NOT NULL,
+    executor_profile_id  TEXT NOT NULL,
+    status               TEXT NOT NULL DEFAULT 'running'
+                            CHECK (status IN ('running', 'completed', 'failed', 'cancelled')),
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at         TEXT,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (pipeline_id) REFERENCES project_pipelines(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_pipeline_runs_session_id ON pipeline_runs(session_id);
+
+-- One execution process per step run. triggered_by_id points at the step run
+-- whose transition started this one, so the runs of a session form a graph.
+CREATE TABLE pipeline_step_runs (
+    id                    BLOB PRIMARY KEY,
+    pipeline_run_id       BLOB NOT NULL,
+    step_id               TEXT NOT NULL,
+    execution_process_id  BLOB NOT NULL UNIQUE,
+    triggered_by_id       BLOB,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (pipeline_run_id) REFERENCES pipeline_runs(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (triggered_by_id) REFERENCES pipeline_step_runs(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_pipeline_step_runs_pipeline_run_id ON pipeline_step_runs(pipeline_run_id);
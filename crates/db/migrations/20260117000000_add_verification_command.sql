@@ -0,0 +1,11 @@
+-- Command run after every successful coding agent turn, and how many
+-- automatic fix follow-ups may be sent when it fails
+ALTER TABLE projects ADD COLUMN verification_command TEXT;
+ALTER TABLE projects ADD COLUMN verification_working_dir TEXT;
+ALTER TABLE projects ADD COLUMN verification_max_retries INTEGER NOT NULL DEFAULT 3;
+
+-- Result of the verification run after a turn, and how many automatic fixes came
+-- before it (0 for turns started by a person)
+ALTER TABLE coding_agent_turns ADD COLUMN verification_status TEXT
+    CHECK (verification_status IN ('passed', 'failed'));
+ALTER TABLE coding_agent_turns ADD COLUMN verification_retry INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,22 @@
+-- Tokens reported by coding agents, one row per usage report, with the cost
+-- estimated from the price table at the time it was recorded
+CREATE TABLE execution_process_usage (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    executor             TEXT NOT NULL,
+    variant              TEXT,
+    model                TEXT,
+    input_tokens         INTEGER NOT NULL DEFAULT 0,
+    output_tokens        INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens    INTEGER NOT NULL DEFAULT 0,
+    cache_write_tokens   INTEGER NOT NULL DEFAULT 0,
+    cost_usd             REAL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_process_usage_execution_process_id
+    ON execution_process_usage(execution_process_id);
+
+-- Spend after which no further coding agent turns are started in the project
+ALTER TABLE projects ADD COLUMN usage_budget_usd REAL;
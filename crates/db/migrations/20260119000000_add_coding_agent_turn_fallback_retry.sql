@@ -0,0 +1,3 @@
+-- Automatic retries of the same executor profile after transient failures that
+-- came right before a turn (0 for turns started by a person or a fallback switch)
+ALTER TABLE coding_agent_turns ADD COLUMN fallback_retry INTEGER NOT NULL DEFAULT 0;
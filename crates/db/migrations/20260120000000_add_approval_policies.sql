@@ -0,0 +1,37 @@
+-- Rules deciding tool approvals before a person is asked. Every criterion that is
+-- set must match; deny rules win over ask rules, which win over allow rules.
+CREATE TABLE approval_rules (
+    id             BLOB PRIMARY KEY,
+    project_id     BLOB NOT NULL,
+    action         TEXT NOT NULL CHECK (action IN ('allow', 'deny', 'ask')),
+    tool_name      TEXT,
+    command_regex  TEXT,
+    path_glob      TEXT,
+    executor       TEXT,
+    description    TEXT,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_approval_rules_project_id ON approval_rules(project_id);
+
+-- Outcome of every approval request, whether a rule, a person or the timeout
+-- decided it
+CREATE TABLE approval_decisions (
+    id                    BLOB PRIMARY KEY,
+    approval_id           TEXT NOT NULL,
+    execution_process_id  BLOB NOT NULL,
+    tool_name             TEXT NOT NULL,
+    tool_input            TEXT NOT NULL,
+    status                TEXT NOT NULL CHECK (status IN ('approved', 'denied', 'timed_out')),
+    reason                TEXT,
+    decided_by            TEXT NOT NULL CHECK (decided_by IN ('rule', 'user', 'timeout')),
+    rule_id               BLOB,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (rule_id) REFERENCES approval_rules(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_approval_decisions_execution_process_id
+    ON approval_decisions(execution_process_id);
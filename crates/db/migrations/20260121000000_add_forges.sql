@@ -0,0 +1,10 @@
+-- Forge hosting a repository. NULL detects it from the remote URL.
+ALTER TABLE repos ADD COLUMN forge TEXT CHECK (forge IN ('github', 'gitlab', 'gitea'));
+
+-- Base URL of a self-hosted forge, e.g. https://git.example.com. NULL derives it
+-- from the remote URL.
+ALTER TABLE repos ADD COLUMN forge_url TEXT;
+
+-- Forge a pull or merge request was opened on. Rows from before GitLab and
+-- Gitea were supported are GitHub pull requests and stay NULL.
+ALTER TABLE merges ADD COLUMN pr_forge TEXT CHECK (pr_forge IN ('github', 'gitlab', 'gitea'));
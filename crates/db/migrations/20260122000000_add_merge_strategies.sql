@@ -0,0 +1,12 @@
+-- How direct merges land on the target branch of this repo
+ALTER TABLE project_repos ADD COLUMN merge_strategy TEXT NOT NULL DEFAULT 'squash'
+    CHECK (merge_strategy IN ('squash', 'rebase', 'merge_commit'));
+
+-- Rebase the task branch onto the target branch first when the target has moved
+-- ahead, instead of refusing the merge
+ALTER TABLE project_repos ADD COLUMN rebase_before_merge INTEGER NOT NULL DEFAULT 0;
+
+-- Strategy a direct merge was made with. Direct merges from before strategies
+-- existed were squash merges and stay NULL.
+ALTER TABLE merges ADD COLUMN merge_strategy TEXT
+    CHECK (merge_strategy IN ('squash', 'rebase', 'merge_commit'));
@@ -0,0 +1,22 @@
+-- Agent turns resolving the conflicts of an interrupted rebase or merge. A rebase
+-- can stop again at a later commit, so one resolution may take several rounds.
+CREATE TABLE conflict_resolutions (
+    id                    BLOB PRIMARY KEY,
+    workspace_id          BLOB NOT NULL,
+    repo_id               BLOB NOT NULL,
+    execution_process_id  BLOB,
+    conflicted_files      TEXT NOT NULL DEFAULT '[]',
+    rounds                INTEGER NOT NULL DEFAULT 1,
+    status                TEXT NOT NULL DEFAULT 'running'
+                              CHECK (status IN ('running', 'resolved', 'aborted', 'failed')),
+    message               TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at          TEXT,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_conflict_resolutions_workspace_id ON conflict_resolutions(workspace_id);
+CREATE INDEX idx_conflict_resolutions_execution_process_id
+    ON conflict_resolutions(execution_process_id);
@@ -0,0 +1,10 @@
+-- Workspace whose branch this workspace builds on. Cleared once the parent has
+-- merged and the workspace was moved onto the parent's target branch.
+ALTER TABLE workspaces ADD COLUMN stacked_on_workspace_id BLOB
+    REFERENCES workspaces(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_workspaces_stacked_on_workspace_id ON workspaces(stacked_on_workspace_id);
+
+-- Commit of the parent branch a stacked repo was last rebased onto. Commits after
+-- it are the workspace's own; NULL for repos that are not stacked.
+ALTER TABLE workspace_repos ADD COLUMN stack_base_commit TEXT;
@@ -0,0 +1,43 @@
+-- Agent turns addressing the review comments of a pull request. The turn's commit is
+-- pushed to the PR branch and can be announced in a reply to each comment.
+CREATE TABLE review_follow_ups (
+    id                    BLOB PRIMARY KEY,
+    workspace_id          BLOB NOT NULL,
+    repo_id               BLOB NOT NULL,
+    pr_number             INTEGER NOT NULL,
+    execution_process_id  BLOB,
+    -- Branch head before the turn, to tell whether the agent changed anything
+    base_commit           TEXT,
+    reply_to_comments     INTEGER NOT NULL DEFAULT 0,
+    status                TEXT NOT NULL DEFAULT 'running'
+                              CHECK (status IN ('running', 'pushed', 'no_changes', 'failed')),
+    commit_sha            TEXT,
+    message               TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at          TEXT,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_review_follow_ups_workspace_id ON review_follow_ups(workspace_id);
+CREATE INDEX idx_review_follow_ups_execution_process_id
+    ON review_follow_ups(execution_process_id);
+
+-- The comments a follow-up addressed and the commit that addressed them
+CREATE TABLE review_follow_up_comments (
+    id                    BLOB PRIMARY KEY,
+    review_follow_up_id   BLOB NOT NULL,
+    comment_id            TEXT NOT NULL,
+    comment_type          TEXT NOT NULL CHECK (comment_type IN ('general', 'review')),
+    url                   TEXT NOT NULL,
+    path                  TEXT,
+    line                  INTEGER,
+    commit_sha            TEXT,
+    replied_at            TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (review_follow_up_id) REFERENCES review_follow_ups(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_review_follow_up_comments_review_follow_up_id
+    ON review_follow_up_comments(review_follow_up_id);
@@ -0,0 +1,11 @@
+-- Signatures of accepted inbound webhook calls. A call is only accepted within a short
+-- window around its signed timestamp, and never twice within that window.
+CREATE TABLE inbound_webhook_receipts (
+    inbound_webhook_id  BLOB NOT NULL,
+    signature           TEXT NOT NULL,
+    received_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (inbound_webhook_id, signature),
+    FOREIGN KEY (inbound_webhook_id) REFERENCES inbound_webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_inbound_webhook_receipts_received_at ON inbound_webhook_receipts(received_at);
@@ -0,0 +1,169 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Permissions that can be granted to an API token
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, TS)]
+pub enum ApiTokenScope {
+    #[serde(rename = "tasks:read")]
+    TasksRead,
+    #[serde(rename = "tasks:write")]
+    TasksWrite,
+    #[serde(rename = "workspaces:start")]
+    WorkspacesStart,
+    #[serde(rename = "approvals:respond")]
+    ApprovalsRespond,
+}
+
+impl ApiTokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiTokenScope::TasksRead => "tasks:read",
+            ApiTokenScope::TasksWrite => "tasks:write",
+            ApiTokenScope::WorkspacesStart => "workspaces:start",
+            ApiTokenScope::ApprovalsRespond => "approvals:respond",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "tasks:read" => Some(ApiTokenScope::TasksRead),
+            "tasks:write" => Some(ApiTokenScope::TasksWrite),
+            "workspaces:start" => Some(ApiTokenScope::WorkspacesStart),
+            "approvals:respond" => Some(ApiTokenScope::ApprovalsRespond),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    #[serde(skip)]
+    #[ts(skip)]
+    pub token_hash: String,
+    /// First characters of the token, to tell tokens apart
+    pub token_prefix: String,
+    /// JSON array of scopes, e.g., ["tasks:read", "tasks:write"]
+    pub scopes: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateApiToken {
+    pub project_id: Uuid,
+    pub name: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    /// Parse the scopes JSON array
+    pub fn get_scopes(&self) -> Vec<ApiTokenScope> {
+        serde_json::from_str::<Vec<String>>(&self.scopes)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|s| ApiTokenScope::from_str(&s))
+            .collect()
+    }
+
+    pub fn has_scope(&self, scope: ApiTokenScope) -> bool {
+        self.get_scopes().contains(&scope)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, token_hash, token_prefix, scopes, expires_at as "expires_at: DateTime<Utc>", last_used_at as "last_used_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, token_hash, token_prefix, scopes, expires_at as "expires_at: DateTime<Utc>", last_used_at as "last_used_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE token_hash = $1"#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, token_hash, token_prefix, scopes, expires_at as "expires_at: DateTime<Utc>", last_used_at as "last_used_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE project_id = $1
+               ORDER BY created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateApiToken) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let scopes_json =
+            serde_json::to_string(&data.scopes.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+                .map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize scopes: {}", e)))?;
+
+        sqlx::query_as!(
+            ApiToken,
+            r#"INSERT INTO api_tokens (id, project_id, name, token_hash, token_prefix, scopes, expires_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, token_hash, token_prefix, scopes, expires_at as "expires_at: DateTime<Utc>", last_used_at as "last_used_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.token_hash,
+            data.token_prefix,
+            scopes_json,
+            data.expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn touch_last_used(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE api_tokens SET last_used_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM api_tokens WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_decision_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum ApprovalDecisionStatus {
+    Approved,
+    Denied,
+    TimedOut,
+}
+
+/// Who decided an approval request. Decisions of a `Rule` reference it by `rule_id`.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_decider", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(use_ts_enum)]
+pub enum ApprovalDecider {
+    Rule,
+    User,
+    Timeout,
+}
+
+/// Log entry for a decided tool approval
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApprovalDecision {
+    pub id: Uuid,
+    /// Id of the approval request; the tool call id when a rule decided it
+    pub approval_id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    #[ts(type = "JsonValue")]
+    pub tool_input: sqlx::types::Json<Value>,
+    pub status: ApprovalDecisionStatus,
+    pub reason: Option<String>,
+    pub decided_by: ApprovalDecider,
+    /// The matching rule; None for manual decisions or once the rule is deleted
+    pub rule_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateApprovalDecision {
+    pub approval_id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    pub tool_input: Value,
+    pub status: ApprovalDecisionStatus,
+    pub reason: Option<String>,
+    pub decided_by: ApprovalDecider,
+    pub rule_id: Option<Uuid>,
+}
+
+impl ApprovalDecision {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateApprovalDecision,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let tool_input = sqlx::types::Json(&data.tool_input);
+        sqlx::query_as!(
+            ApprovalDecision,
+            r#"INSERT INTO approval_decisions (id, approval_id, execution_process_id, tool_name, tool_input, status, reason, decided_by, rule_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid", approval_id, execution_process_id as "execution_process_id!: Uuid", tool_name, tool_input as "tool_input!: sqlx::types::Json<Value>", status as "status!: ApprovalDecisionStatus", reason, decided_by as "decided_by!: ApprovalDecider", rule_id as "rule_id: Uuid", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.approval_id,
+            data.execution_process_id,
+            data.tool_name,
+            tool_input,
+            data.status,
+            data.reason,
+            data.decided_by,
+            data.rule_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Most recent decisions across all attempts of a project, newest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        limit: u32,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let limit = i64::from(limit);
+        sqlx::query_as!(
+            ApprovalDecision,
+            r#"SELECT ad.id as "id!: Uuid", ad.approval_id, ad.execution_process_id as "execution_process_id!: Uuid", ad.tool_name, ad.tool_input as "tool_input!: sqlx::types::Json<Value>", ad.status as "status!: ApprovalDecisionStatus", ad.reason, ad.decided_by as "decided_by!: ApprovalDecider", ad.rule_id as "rule_id: Uuid", ad.created_at as "created_at!: DateTime<Utc>"
+               FROM approval_decisions ad
+               JOIN execution_processes ep ON ad.execution_process_id = ep.id
+               JOIN sessions s ON ep.session_id = s.id
+               JOIN workspaces w ON s.workspace_id = w.id
+               JOIN tasks t ON w.task_id = t.id
+               WHERE t.project_id = $1
+               ORDER BY ad.created_at DESC
+               LIMIT $2"#,
+            project_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use executors::executors::BaseCodingAgent;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What a matching rule does with an approval request. `Ask` always asks a
+/// person, even when an allow rule matches too.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_rule_action", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(use_ts_enum)]
+pub enum ApprovalRuleAction {
+    Allow,
+    Deny,
+    Ask,
+}
+
+/// A rule of a project's approval policy. Criteria left empty match any request.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApprovalRule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub action: ApprovalRuleAction,
+    /// Tool name as reported by the agent, compared case-insensitively
+    pub tool_name: Option<String>,
+    /// Regex searched in the command a shell tool runs
+    pub command_regex: Option<String>,
+    /// Glob matched against the files a tool reads or edits
+    pub path_glob: Option<String>,
+    pub executor: Option<BaseCodingAgent>,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct CreateApprovalRule {
+    pub action: ApprovalRuleAction,
+    pub tool_name: Option<String>,
+    pub command_regex: Option<String>,
+    pub path_glob: Option<String>,
+    pub executor: Option<BaseCodingAgent>,
+    pub description: Option<String>,
+}
+
+/// Replaces every field of a rule; criteria sent as null are cleared
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct UpdateApprovalRule {
+    pub action: ApprovalRuleAction,
+    pub tool_name: Option<String>,
+    pub command_regex: Option<String>,
+    pub path_glob: Option<String>,
+    pub executor: Option<BaseCodingAgent>,
+    pub description: Option<String>,
+}
+
+impl ApprovalRule {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", action as "action!: ApprovalRuleAction", tool_name, command_regex, path_glob, executor as "executor: BaseCodingAgent", description, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM approval_rules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", action as "action!: ApprovalRuleAction", tool_name, command_regex, path_glob, executor as "executor: BaseCodingAgent", description, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM approval_rules
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateApprovalRule,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"INSERT INTO approval_rules (id, project_id, action, tool_name, command_regex, path_glob, executor, description)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", action as "action!: ApprovalRuleAction", tool_name, command_regex, path_glob, executor as "executor: BaseCodingAgent", description, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.action,
+            data.tool_name,
+            data.command_regex,
+            data.path_glob,
+            data.executor,
+            data.description
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateApprovalRule,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"UPDATE approval_rules
+               SET action = $2,
+                   tool_name = $3,
+                   command_regex = $4,
+                   path_glob = $5,
+                   executor = $6,
+                   description = $7,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", action as "action!: ApprovalRuleAction", tool_name, command_regex, path_glob, executor as "executor: BaseCodingAgent", description, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.action,
+            data.tool_name,
+            data.command_regex,
+            data.path_glob,
+            data.executor,
+            data.description
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM approval_rules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,9 +1,21 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::Display;
 use ts_rs::TS;
 use uuid::Uuid;
 
+/// Result of the project's verification command after a turn
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, Display)]
+#[sqlx(type_name = "verification_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+#[ts(use_ts_enum)]
+pub enum VerificationStatus {
+    Passed,
+    Failed,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct CodingAgentTurn {
     pub id: Uuid,
@@ -11,6 +23,15 @@ pub struct CodingAgentTurn {
     pub agent_session_id: Option<String>, // Session ID from Claude/Amp coding agent
     pub prompt: Option<String>,           // The prompt sent to the executor
     pub summary: Option<String>,          // Final assistant message/summary
+    /// None until verification has run after this turn
+    pub verification_status: Option<VerificationStatus>,
+    /// Automatic fix follow-ups before this turn; 0 for turns started by a person
+    #[ts(type = "number")]
+    pub verification_retry: i64,
+    /// Automatic retries of this turn's profile after transient failures right
+    /// before it; 0 for turns started by a person or by switching profiles
+    #[ts(type = "number")]
+    pub fallback_retry: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -35,6 +56,9 @@ impl CodingAgentTurn {
                 agent_session_id,
                 prompt,
                 summary,
+                verification_status as "verification_status: VerificationStatus",
+                verification_retry,
+                fallback_retry,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM coding_agent_turns
@@ -57,6 +81,9 @@ impl CodingAgentTurn {
                 agent_session_id,
                 prompt,
                 summary,
+                verification_status as "verification_status: VerificationStatus",
+                verification_retry,
+                fallback_retry,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM coding_agent_turns
@@ -96,6 +123,9 @@ impl CodingAgentTurn {
                 agent_session_id,
                 prompt,
                 summary,
+                verification_status as "verification_status: VerificationStatus",
+                verification_retry,
+                fallback_retry,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -110,6 +140,35 @@ impl CodingAgentTurn {
         .await
     }
 
+    /// The latest turn of a session
+    pub async fn find_latest_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CodingAgentTurn,
+            r#"SELECT
+                cat.id as "id!: Uuid",
+                cat.execution_process_id as "execution_process_id!: Uuid",
+                cat.agent_session_id,
+                cat.prompt,
+                cat.summary,
+                cat.verification_status as "verification_status: VerificationStatus",
+                cat.verification_retry,
+                cat.fallback_retry,
+                cat.created_at as "created_at!: DateTime<Utc>",
+                cat.updated_at as "updated_at!: DateTime<Utc>"
+               FROM coding_agent_turns cat
+               JOIN execution_processes ep ON ep.id = cat.execution_process_id
+               WHERE ep.session_id = $1
+               ORDER BY ep.created_at DESC
+               LIMIT 1"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     /// Update coding agent turn with agent session ID
     pub async fn update_agent_session_id(
         pool: &SqlitePool,
@@ -151,4 +210,67 @@ impl CodingAgentTurn {
 
         Ok(())
     }
+
+    pub async fn set_verification_status(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        status: &VerificationStatus,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE coding_agent_turns
+               SET verification_status = $1, updated_at = $2
+               WHERE execution_process_id = $3"#,
+            status,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark a turn as the `retry`th automatic fix of a failed verification
+    pub async fn set_verification_retry(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        retry: i64,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE coding_agent_turns
+               SET verification_retry = $1, updated_at = $2
+               WHERE execution_process_id = $3"#,
+            retry,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark a turn as the `retry`th automatic retry of its profile after transient
+    /// failures
+    pub async fn set_fallback_retry(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        retry: i64,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE coding_agent_turns
+               SET fallback_retry = $1, updated_at = $2
+               WHERE execution_process_id = $3"#,
+            retry,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "conflict_resolution_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(use_ts_enum)]
+pub enum ConflictResolutionStatus {
+    /// An agent turn is working on the conflicts
+    Running,
+    /// The interrupted operation was continued to the end
+    Resolved,
+    /// The agent left conflict markers behind, so the operation was aborted
+    Aborted,
+    /// The agent turn failed or continuing the operation failed
+    Failed,
+}
+
+/// Agent-assisted resolution of the conflicts of an interrupted rebase or merge
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ConflictResolution {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    /// Agent turn of the current round; the last one once finished
+    pub execution_process_id: Option<Uuid>,
+    /// Files that had conflicts when the current round started
+    #[ts(type = "string[]")]
+    pub conflicted_files: sqlx::types::Json<Vec<String>>,
+    /// Agent turns used so far; a continued rebase can stop at a later commit
+    #[ts(type = "number")]
+    pub rounds: i64,
+    pub status: ConflictResolutionStatus,
+    /// How the resolution ended, e.g. the files still holding conflict markers
+    pub message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl ConflictResolution {
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        execution_process_id: Uuid,
+        conflicted_files: &[String],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let conflicted_files = sqlx::types::Json(conflicted_files);
+        sqlx::query_as!(
+            ConflictResolution,
+            r#"INSERT INTO conflict_resolutions (id, workspace_id, repo_id, execution_process_id, conflicted_files)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         execution_process_id as "execution_process_id: Uuid",
+                         conflicted_files as "conflicted_files!: sqlx::types::Json<Vec<String>>",
+                         rounds,
+                         status as "status!: ConflictResolutionStatus",
+                         message,
+                         created_at as "created_at!: DateTime<Utc>",
+                         completed_at as "completed_at: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            repo_id,
+            execution_process_id,
+            conflicted_files
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ConflictResolution,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      execution_process_id as "execution_process_id: Uuid",
+                      conflicted_files as "conflicted_files!: sqlx::types::Json<Vec<String>>",
+                      rounds,
+                      status as "status!: ConflictResolutionStatus",
+                      message,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>"
+               FROM conflict_resolutions
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Resolutions of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ConflictResolution,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      execution_process_id as "execution_process_id: Uuid",
+                      conflicted_files as "conflicted_files!: sqlx::types::Json<Vec<String>>",
+                      rounds,
+                      status as "status!: ConflictResolutionStatus",
+                      message,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>"
+               FROM conflict_resolutions
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Hand the resolution to the agent turn of its next round
+    pub async fn start_round(
+        pool: &SqlitePool,
+        id: Uuid,
+        execution_process_id: Uuid,
+        conflicted_files: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let conflicted_files = sqlx::types::Json(conflicted_files);
+        sqlx::query!(
+            r#"UPDATE conflict_resolutions
+               SET execution_process_id = $1, conflicted_files = $2, rounds = rounds + 1
+               WHERE id = $3"#,
+            execution_process_id,
+            conflicted_files,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn finish(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: ConflictResolutionStatus,
+        message: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE conflict_resolutions
+               SET status = $1, message = $2, completed_at = $3
+               WHERE id = $4"#,
+            status,
+            message,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use executors::{
     actions::{ExecutorAction, ExecutorActionType},
     profile::ExecutorProfileId,
+    sandbox::SandboxPolicy,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -55,6 +56,18 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    PipelineStep,
+}
+
+/// Why a process was ended by vibe-kanban rather than exiting on its own.
+/// `TimedOut`: a script ran longer than its `timeout_secs`.
+/// `BudgetExceeded`: a coding agent's usage pushed its project's spend past the budget.
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "execution_process_exit_reason", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionProcessExitReason {
+    TimedOut,
+    BudgetExceeded,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -66,6 +79,10 @@ pub struct ExecutionProcess {
     pub executor_action: sqlx::types::Json<ExecutorActionField>,
     pub status: ExecutionProcessStatus,
     pub exit_code: Option<i64>,
+    pub exit_reason: Option<ExecutionProcessExitReason>,
+    /// Isolation and resource limits the process ran with, if any
+    #[ts(type = "SandboxPolicy | null")]
+    pub sandbox: Option<sqlx::types::Json<SandboxPolicy>>,
     /// dropped: true if this process is excluded from the current
     /// history view (due to restore/trimming). Hidden from logs/timeline;
     /// still listed in the Processes tab.
@@ -131,6 +148,8 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason: ExecutionProcessExitReason",
+                    ep.sandbox as "sandbox: sqlx::types::Json<SandboxPolicy>",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -205,6 +224,8 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason: ExecutionProcessExitReason",
+                    ep.sandbox as "sandbox: sqlx::types::Json<SandboxPolicy>",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -232,6 +253,8 @@ impl ExecutionProcess {
                       ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                       ep.status          as "status!: ExecutionProcessStatus",
                       ep.exit_code,
+                      ep.exit_reason as "exit_reason: ExecutionProcessExitReason",
+                      ep.sandbox as "sandbox: sqlx::types::Json<SandboxPolicy>",
                       ep.dropped as "dropped!: bool",
                       ep.started_at      as "started_at!: DateTime<Utc>",
                       ep.completed_at    as "completed_at?: DateTime<Utc>",
@@ -259,6 +282,8 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason: ExecutionProcessExitReason",
+                    ep.sandbox as "sandbox: sqlx::types::Json<SandboxPolicy>",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -278,7 +303,7 @@ impl ExecutionProcess {
         sqlx::query_as!(
             ExecutionProcess,
             r#"SELECT ep.id as "id!: Uuid", ep.session_id as "session_id!: Uuid", ep.run_reason as "run_reason!: ExecutionProcessRunReason", ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
-                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code,
+                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code, ep.exit_reason as "exit_reason: ExecutionProcessExitReason", ep.sandbox as "sandbox: sqlx::types::Json<SandboxPolicy>",
                       ep.dropped as "dropped!: bool", ep.started_at as "started_at!: DateTime<Utc>", ep.completed_at as "completed_at?: DateTime<Utc>", ep.created_at as "created_at!: DateTime<Utc>", ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
@@ -311,6 +336,26 @@ impl ExecutionProcess {
         Ok(count > 0)
     }
 
+    /// Count running coding agents per project. Setup scripts are included because they
+    /// run immediately before the coding agent and already occupy its slot.
+    pub async fn count_running_agents_by_project(
+        pool: &SqlitePool,
+    ) -> Result<Vec<(Uuid, i64)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT t.project_id as "project_id!: Uuid", COUNT(DISTINCT w.id) as "count!: i64"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               JOIN workspaces w ON s.workspace_id = w.id
+               JOIN tasks t ON w.task_id = t.id
+               WHERE ep.status = 'running'
+                 AND ep.run_reason IN ('codingagent', 'setupscript')
+               GROUP BY t.project_id"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|r| (r.project_id, r.count)).collect())
+    }
+
     /// Find running dev servers for a specific workspace (across all sessions)
     pub async fn find_running_dev_servers_by_workspace(
         pool: &SqlitePool,
@@ -326,6 +371,8 @@ impl ExecutionProcess {
             ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
             ep.status as "status!: ExecutionProcessStatus",
             ep.exit_code,
+            ep.exit_reason as "exit_reason: ExecutionProcessExitReason",
+            ep.sandbox as "sandbox: sqlx::types::Json<SandboxPolicy>",
             ep.dropped as "dropped!: bool",
             ep.started_at as "started_at!: DateTime<Utc>",
             ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -388,6 +435,8 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason: ExecutionProcessExitReason",
+                    ep.sandbox as "sandbox: sqlx::types::Json<SandboxPolicy>",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -418,6 +467,8 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason: ExecutionProcessExitReason",
+                    ep.sandbox as "sandbox: sqlx::types::Json<SandboxPolicy>",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -488,6 +539,39 @@ impl ExecutionProcess {
         false
     }
 
+    /// Record why a process was ended, alongside its completion
+    pub async fn set_exit_reason(
+        pool: &SqlitePool,
+        id: Uuid,
+        exit_reason: &ExecutionProcessExitReason,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_processes SET exit_reason = $1 WHERE id = $2",
+            exit_reason,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the sandbox a process was started in
+    pub async fn set_sandbox(
+        pool: &SqlitePool,
+        id: Uuid,
+        sandbox: &SandboxPolicy,
+    ) -> Result<(), sqlx::Error> {
+        let sandbox_json = sqlx::types::Json(sandbox);
+        sqlx::query!(
+            "UPDATE execution_processes SET sandbox = $1 WHERE id = $2",
+            sandbox_json,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Update execution process status and completion info
     pub async fn update_completion(
         pool: &SqlitePool,
@@ -644,6 +728,8 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason: ExecutionProcessExitReason",
+                    ep.sandbox as "sandbox: sqlx::types::Json<SandboxPolicy>",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -0,0 +1,264 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Tokens reported by a coding agent during an execution process. A process can
+/// have several rows, e.g. one per model request.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionProcessUsage {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub executor: String,
+    pub variant: Option<String>,
+    pub model: Option<String>,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_read_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_write_tokens: i64,
+    /// None when neither the price table nor the agent knew the cost
+    pub cost_usd: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateExecutionProcessUsage {
+    pub executor: String,
+    pub variant: Option<String>,
+    pub model: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub cost_usd: Option<f64>,
+}
+
+/// Usage summed over a set of execution processes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct UsageTotals {
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_read_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_write_tokens: i64,
+    pub cost_usd: f64,
+    /// Usage reports without a known cost, which are missing from `cost_usd`
+    #[ts(type = "number")]
+    pub unpriced_reports: i64,
+}
+
+/// Usage of one executor profile
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExecutorUsage {
+    pub executor: String,
+    pub variant: Option<String>,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub totals: UsageTotals,
+}
+
+struct ExecutorUsageRow {
+    executor: String,
+    variant: Option<String>,
+    input_tokens: i64,
+    output_tokens: i64,
+    cache_read_tokens: i64,
+    cache_write_tokens: i64,
+    cost_usd: f64,
+    unpriced_reports: i64,
+}
+
+impl From<ExecutorUsageRow> for ExecutorUsage {
+    fn from(row: ExecutorUsageRow) -> Self {
+        Self {
+            executor: row.executor,
+            variant: row.variant,
+            totals: UsageTotals {
+                input_tokens: row.input_tokens,
+                output_tokens: row.output_tokens,
+                cache_read_tokens: row.cache_read_tokens,
+                cache_write_tokens: row.cache_write_tokens,
+                cost_usd: row.cost_usd,
+                unpriced_reports: row.unpriced_reports,
+            },
+        }
+    }
+}
+
+impl ExecutionProcessUsage {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        data: &CreateExecutionProcessUsage,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ExecutionProcessUsage,
+            r#"INSERT INTO execution_process_usage (
+                    id, execution_process_id, executor, variant, model, input_tokens,
+                    output_tokens, cache_read_tokens, cache_write_tokens, cost_usd
+               ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid",
+                         execution_process_id as "execution_process_id!: Uuid",
+                         executor,
+                         variant,
+                         model,
+                         input_tokens,
+                         output_tokens,
+                         cache_read_tokens,
+                         cache_write_tokens,
+                         cost_usd,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            data.executor,
+            data.variant,
+            data.model,
+            data.input_tokens,
+            data.output_tokens,
+            data.cache_read_tokens,
+            data.cache_write_tokens,
+            data.cost_usd,
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessUsage,
+            r#"SELECT id as "id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      executor,
+                      variant,
+                      model,
+                      input_tokens,
+                      output_tokens,
+                      cache_read_tokens,
+                      cache_write_tokens,
+                      cost_usd,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_usage
+               WHERE execution_process_id = $1
+               ORDER BY created_at ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn totals_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<UsageTotals, sqlx::Error> {
+        sqlx::query_as!(
+            UsageTotals,
+            r#"SELECT COALESCE(SUM(u.input_tokens), 0) as "input_tokens!: i64",
+                      COALESCE(SUM(u.output_tokens), 0) as "output_tokens!: i64",
+                      COALESCE(SUM(u.cache_read_tokens), 0) as "cache_read_tokens!: i64",
+                      COALESCE(SUM(u.cache_write_tokens), 0) as "cache_write_tokens!: i64",
+                      COALESCE(SUM(u.cost_usd), 0.0) as "cost_usd!: f64",
+                      COUNT(u.id) - COUNT(u.cost_usd) as "unpriced_reports!: i64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE w.task_id = $1"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn totals_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<UsageTotals, sqlx::Error> {
+        sqlx::query_as!(
+            UsageTotals,
+            r#"SELECT COALESCE(SUM(u.input_tokens), 0) as "input_tokens!: i64",
+                      COALESCE(SUM(u.output_tokens), 0) as "output_tokens!: i64",
+                      COALESCE(SUM(u.cache_read_tokens), 0) as "cache_read_tokens!: i64",
+                      COALESCE(SUM(u.cache_write_tokens), 0) as "cache_write_tokens!: i64",
+                      COALESCE(SUM(u.cost_usd), 0.0) as "cost_usd!: f64",
+                      COUNT(u.id) - COUNT(u.cost_usd) as "unpriced_reports!: i64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn by_executor_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<ExecutorUsage>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            ExecutorUsageRow,
+            r#"SELECT u.executor as "executor!",
+                      u.variant,
+                      COALESCE(SUM(u.input_tokens), 0) as "input_tokens!: i64",
+                      COALESCE(SUM(u.output_tokens), 0) as "output_tokens!: i64",
+                      COALESCE(SUM(u.cache_read_tokens), 0) as "cache_read_tokens!: i64",
+                      COALESCE(SUM(u.cache_write_tokens), 0) as "cache_write_tokens!: i64",
+                      COALESCE(SUM(u.cost_usd), 0.0) as "cost_usd!: f64",
+                      COUNT(u.id) - COUNT(u.cost_usd) as "unpriced_reports!: i64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE w.task_id = $1
+               GROUP BY u.executor, u.variant
+               ORDER BY SUM(u.cost_usd) DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(ExecutorUsage::from).collect())
+    }
+
+    pub async fn by_executor_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<ExecutorUsage>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            ExecutorUsageRow,
+            r#"SELECT u.executor as "executor!",
+                      u.variant,
+                      COALESCE(SUM(u.input_tokens), 0) as "input_tokens!: i64",
+                      COALESCE(SUM(u.output_tokens), 0) as "output_tokens!: i64",
+                      COALESCE(SUM(u.cache_read_tokens), 0) as "cache_read_tokens!: i64",
+                      COALESCE(SUM(u.cache_write_tokens), 0) as "cache_write_tokens!: i64",
+                      COALESCE(SUM(u.cost_usd), 0.0) as "cost_usd!: f64",
+                      COUNT(u.id) - COUNT(u.cost_usd) as "unpriced_reports!: i64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1
+               GROUP BY u.executor, u.variant
+               ORDER BY SUM(u.cost_usd) DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(ExecutorUsage::from).collect())
+    }
+}
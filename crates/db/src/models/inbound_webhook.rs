@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct InboundWebhook {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    /// Signing secret. Only sent to the client once, in the create response.
+    #[serde(skip)]
+    #[ts(skip)]
+    pub secret: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A newly created inbound webhook together with its signing secret
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct InboundWebhookWithSecret {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub inbound_webhook: InboundWebhook,
+    pub secret: String,
+}
+
+impl From<InboundWebhook> for InboundWebhookWithSecret {
+    fn from(inbound_webhook: InboundWebhook) -> Self {
+        let secret = inbound_webhook.secret.clone();
+        Self {
+            inbound_webhook,
+            secret,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateInboundWebhook {
+    pub project_id: Uuid,
+    pub name: String,
+    pub secret: String,
+}
+
+impl InboundWebhook {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            InboundWebhook,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, secret, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM inbound_webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            InboundWebhook,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, secret, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM inbound_webhooks
+               WHERE project_id = $1
+               ORDER BY created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateInboundWebhook,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            InboundWebhook,
+            r#"INSERT INTO inbound_webhooks (id, project_id, name, secret)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, secret, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.secret
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn set_active(
+        pool: &SqlitePool,
+        id: Uuid,
+        is_active: bool,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            InboundWebhook,
+            r#"UPDATE inbound_webhooks
+               SET is_active = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, secret, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            is_active
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record an accepted call and forget calls older than `keep_secs`.
+    /// Returns false if a call with the same signature was already recorded.
+    pub async fn record_receipt(
+        pool: &SqlitePool,
+        id: Uuid,
+        signature: &str,
+        keep_secs: i64,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM inbound_webhook_receipts
+               WHERE received_at < datetime('now', '-' || $1 || ' seconds')"#,
+            keep_secs
+        )
+        .execute(pool)
+        .await?;
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO inbound_webhook_receipts (inbound_webhook_id, signature)
+               VALUES ($1, $2)"#,
+            id,
+            signature
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM inbound_webhooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
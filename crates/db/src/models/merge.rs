@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -14,6 +15,69 @@ pub enum MergeStatus {
     Unknown,
 }
 
+/// Service hosting a repository's pull or merge requests
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    TS,
+    Type,
+    Display,
+    EnumString,
+)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ForgeKind {
+    #[default]
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+impl ForgeKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "GitHub",
+            ForgeKind::GitLab => "GitLab",
+            ForgeKind::Gitea => "Gitea",
+        }
+    }
+}
+
+/// How a direct merge lands the task branch on the target branch
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    TS,
+    Type,
+    Display,
+    EnumString,
+)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum MergeStrategy {
+    /// One commit on the target branch with the task's title and description
+    #[default]
+    Squash,
+    /// Rebase the task commits onto the target branch, then fast-forward it
+    Rebase,
+    /// A merge commit with the target and task branches as parents
+    MergeCommit,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Merge {
@@ -28,6 +92,7 @@ pub struct DirectMerge {
     pub repo_id: Uuid,
     pub merge_commit: String,
     pub target_branch_name: String,
+    pub merge_strategy: MergeStrategy,
     pub created_at: DateTime<Utc>,
 }
 
@@ -49,6 +114,8 @@ pub struct PullRequestInfo {
     pub status: MergeStatus,
     pub merged_at: Option<chrono::DateTime<chrono::Utc>>,
     pub merge_commit_sha: Option<String>,
+    #[serde(default)]
+    pub forge: ForgeKind,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -71,6 +138,8 @@ struct MergeRow {
     pr_status: Option<MergeStatus>,
     pr_merged_at: Option<DateTime<Utc>>,
     pr_merge_commit_sha: Option<String>,
+    pr_forge: Option<ForgeKind>,
+    merge_strategy: Option<MergeStrategy>,
     created_at: DateTime<Utc>,
 }
 
@@ -89,6 +158,7 @@ impl Merge {
         repo_id: Uuid,
         target_branch_name: &str,
         merge_commit: &str,
+        merge_strategy: MergeStrategy,
     ) -> Result<DirectMerge, sqlx::Error> {
         let id = Uuid::new_v4();
         let now = Utc::now();
@@ -96,8 +166,8 @@ impl Merge {
         sqlx::query_as!(
             MergeRow,
             r#"INSERT INTO merges (
-                id, workspace_id, repo_id, merge_type, merge_commit, created_at, target_branch_name
-            ) VALUES ($1, $2, $3, 'direct', $4, $5, $6)
+                id, workspace_id, repo_id, merge_type, merge_commit, merge_strategy, created_at, target_branch_name
+            ) VALUES ($1, $2, $3, 'direct', $4, $5, $6, $7)
             RETURNING
                 id as "id!: Uuid",
                 workspace_id as "workspace_id!: Uuid",
@@ -109,6 +179,8 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -116,6 +188,7 @@ impl Merge {
             workspace_id,
             repo_id,
             merge_commit,
+            merge_strategy,
             now,
             target_branch_name
         )
@@ -129,8 +202,7 @@ impl Merge {
         workspace_id: Uuid,
         repo_id: Uuid,
         target_branch_name: &str,
-        pr_number: i64,
-        pr_url: &str,
+        pr_info: &PullRequestInfo,
     ) -> Result<PrMerge, sqlx::Error> {
         let id = Uuid::new_v4();
         let now = Utc::now();
@@ -138,8 +210,8 @@ impl Merge {
         sqlx::query_as!(
             MergeRow,
             r#"INSERT INTO merges (
-                id, workspace_id, repo_id, merge_type, pr_number, pr_url, pr_status, created_at, target_branch_name
-            ) VALUES ($1, $2, $3, 'pr', $4, $5, 'open', $6, $7)
+                id, workspace_id, repo_id, merge_type, pr_number, pr_url, pr_status, pr_forge, created_at, target_branch_name
+            ) VALUES ($1, $2, $3, 'pr', $4, $5, 'open', $6, $7, $8)
             RETURNING
                 id as "id!: Uuid",
                 workspace_id as "workspace_id!: Uuid",
@@ -151,14 +223,17 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
             id,
             workspace_id,
             repo_id,
-            pr_number,
-            pr_url,
+            pr_info.number,
+            pr_info.url,
+            pr_info.forge,
             now,
             target_branch_name
         )
@@ -182,6 +257,8 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
                FROM merges
@@ -223,6 +300,35 @@ impl Merge {
 
         Ok(())
     }
+
+    pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            MergeRow,
+            r#"SELECT
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                merge_type as "merge_type!: MergeType",
+                merge_commit,
+                pr_number,
+                pr_url,
+                pr_status as "pr_status?: MergeStatus",
+                pr_merged_at as "pr_merged_at?: DateTime<Utc>",
+                pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                merge_strategy as "merge_strategy?: MergeStrategy",
+                target_branch_name as "target_branch_name!: String",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM merges
+            WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
     /// Find all merges for a workspace (returns both direct and PR merges)
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
@@ -242,6 +348,8 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -275,6 +383,8 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -301,6 +411,7 @@ impl From<MergeRow> for DirectMerge {
                 .merge_commit
                 .expect("direct merge must have merge_commit"),
             target_branch_name: row.target_branch_name,
+            merge_strategy: row.merge_strategy.unwrap_or_default(),
             created_at: row.created_at,
         }
     }
@@ -319,6 +430,7 @@ impl From<MergeRow> for PrMerge {
                 status: row.pr_status.expect("pr merge must have status"),
                 merged_at: row.pr_merged_at,
                 merge_commit_sha: row.pr_merge_commit_sha,
+                forge: row.pr_forge.unwrap_or_default(),
             },
             created_at: row.created_at,
         }
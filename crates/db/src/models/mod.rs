@@ -1,19 +1,30 @@
+pub mod api_token;
+pub mod approval_decision;
+pub mod approval_rule;
 pub mod coding_agent_turn;
+pub mod conflict_resolution;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_process_usage;
 pub mod image;
+pub mod inbound_webhook;
 pub mod merge;
+pub mod pipeline;
 pub mod project;
 pub mod project_repo;
+pub mod project_workflow;
 pub mod repo;
+pub mod review_follow_up;
 pub mod scratch;
 pub mod session;
 pub mod tag;
 pub mod task;
 pub mod task_comment;
+pub mod task_dependency;
 pub mod task_history;
 pub mod webhook;
 pub mod webhook_delivery;
+pub mod webhook_history;
 pub mod workspace;
 pub mod workspace_repo;
@@ -0,0 +1,302 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessStatus;
+
+/// A named pipeline of a project. `definition` holds the TOML source.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectPipeline {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub definition: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct CreateProjectPipeline {
+    pub name: String,
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct UpdateProjectPipeline {
+    pub name: Option<String>,
+    pub definition: Option<String>,
+}
+
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "pipeline_run_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(use_ts_enum)]
+pub enum PipelineRunStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PipelineRun {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    /// None once the pipeline has been deleted
+    pub pipeline_id: Option<Uuid>,
+    pub name: String,
+    /// TOML source the run was started with
+    pub definition: String,
+    /// Coding agent used by agent steps
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: sqlx::types::Json<ExecutorProfileId>,
+    pub status: PipelineRunStatus,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A step of a run and the execution process it started
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PipelineStepRun {
+    pub id: Uuid,
+    pub pipeline_run_id: Uuid,
+    pub step_id: String,
+    pub execution_process_id: Uuid,
+    /// The step run whose transition started this one; None for the first step
+    pub triggered_by_id: Option<Uuid>,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A run with its step runs in start order, i.e. the graph of its execution processes
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PipelineRunWithSteps {
+    #[serde(flatten)]
+    pub run: PipelineRun,
+    pub steps: Vec<PipelineStepRun>,
+}
+
+impl ProjectPipeline {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPipeline,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, definition, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_pipelines
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPipeline,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, definition, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_pipelines
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateProjectPipeline,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ProjectPipeline,
+            r#"INSERT INTO project_pipelines (id, project_id, name, definition)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, definition, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.name,
+            data.definition
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateProjectPipeline,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPipeline,
+            r#"UPDATE project_pipelines
+               SET name = COALESCE($2, name),
+                   definition = COALESCE($3, definition),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, definition, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.definition
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM project_pipelines WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl PipelineRun {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRun,
+            r#"SELECT id as "id!: Uuid", session_id as "session_id!: Uuid", pipeline_id as "pipeline_id: Uuid", name, definition, executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>", status as "status!: PipelineRunStatus", created_at as "created_at!: DateTime<Utc>", completed_at as "completed_at: DateTime<Utc>"
+               FROM pipeline_runs
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRun,
+            r#"SELECT id as "id!: Uuid", session_id as "session_id!: Uuid", pipeline_id as "pipeline_id: Uuid", name, definition, executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>", status as "status!: PipelineRunStatus", created_at as "created_at!: DateTime<Utc>", completed_at as "completed_at: DateTime<Utc>"
+               FROM pipeline_runs
+               WHERE session_id = $1
+               ORDER BY created_at ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        pipeline: &ProjectPipeline,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let executor_profile_id = sqlx::types::Json(executor_profile_id);
+        sqlx::query_as!(
+            PipelineRun,
+            r#"INSERT INTO pipeline_runs (id, session_id, pipeline_id, name, definition, executor_profile_id)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", session_id as "session_id!: Uuid", pipeline_id as "pipeline_id: Uuid", name, definition, executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>", status as "status!: PipelineRunStatus", created_at as "created_at!: DateTime<Utc>", completed_at as "completed_at: DateTime<Utc>""#,
+            id,
+            session_id,
+            pipeline.id,
+            pipeline.name,
+            pipeline.definition,
+            executor_profile_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Move a running run to `status`. Returns false when it had already finished,
+    /// so only one of several branches ending together finishes the run.
+    pub async fn finish(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: PipelineRunStatus,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE pipeline_runs
+               SET status = $2, completed_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'running'"#,
+            id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_with_steps_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<PipelineRunWithSteps>, sqlx::Error> {
+        let runs = Self::find_by_session_id(pool, session_id).await?;
+        let mut result = Vec::with_capacity(runs.len());
+        for run in runs {
+            let steps = PipelineStepRun::find_by_run_id(pool, run.id).await?;
+            result.push(PipelineRunWithSteps { run, steps });
+        }
+        Ok(result)
+    }
+}
+
+impl PipelineStepRun {
+    pub async fn find_by_run_id(
+        pool: &SqlitePool,
+        pipeline_run_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineStepRun,
+            r#"SELECT psr.id as "id!: Uuid", psr.pipeline_run_id as "pipeline_run_id!: Uuid", psr.step_id, psr.execution_process_id as "execution_process_id!: Uuid", psr.triggered_by_id as "triggered_by_id: Uuid", ep.status as "status!: ExecutionProcessStatus", ep.exit_code, psr.created_at as "created_at!: DateTime<Utc>"
+               FROM pipeline_step_runs psr
+               JOIN execution_processes ep ON ep.id = psr.execution_process_id
+               WHERE psr.pipeline_run_id = $1
+               ORDER BY psr.created_at ASC"#,
+            pipeline_run_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineStepRun,
+            r#"SELECT psr.id as "id!: Uuid", psr.pipeline_run_id as "pipeline_run_id!: Uuid", psr.step_id, psr.execution_process_id as "execution_process_id!: Uuid", psr.triggered_by_id as "triggered_by_id: Uuid", ep.status as "status!: ExecutionProcessStatus", ep.exit_code, psr.created_at as "created_at!: DateTime<Utc>"
+               FROM pipeline_step_runs psr
+               JOIN execution_processes ep ON ep.id = psr.execution_process_id
+               WHERE psr.execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        pipeline_run_id: Uuid,
+        step_id: &str,
+        execution_process_id: Uuid,
+        triggered_by_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO pipeline_step_runs (id, pipeline_run_id, step_id, execution_process_id, triggered_by_id)
+               VALUES ($1, $2, $3, $4, $5)"#,
+            id,
+            pipeline_run_id,
+            step_id,
+            execution_process_id,
+            triggered_by_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
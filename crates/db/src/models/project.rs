@@ -24,6 +24,16 @@ pub struct Project {
     pub dev_script: Option<String>,
     pub dev_script_working_dir: Option<String>,
     pub default_agent_working_dir: Option<String>,
+    /// Run after every successful coding agent turn, e.g. `cargo test`
+    pub verification_command: Option<String>,
+    /// Directory to run the verification command from, relative to the workspace
+    /// root. Defaults to the agent working directory.
+    pub verification_working_dir: Option<String>,
+    /// Automatic fix follow-ups sent while verification keeps failing
+    #[ts(type = "number")]
+    pub verification_max_retries: i64,
+    /// Spend in USD after which no further coding agent turns are started
+    pub usage_budget_usd: Option<f64>,
     pub remote_project_id: Option<Uuid>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
@@ -43,6 +53,22 @@ pub struct UpdateProject {
     pub dev_script: Option<String>,
     pub dev_script_working_dir: Option<String>,
     pub default_agent_working_dir: Option<String>,
+    /// Left unchanged when omitted; an empty string clears it
+    #[serde(default)]
+    #[ts(optional)]
+    pub verification_command: Option<String>,
+    /// Left unchanged when omitted; an empty string clears it
+    #[serde(default)]
+    #[ts(optional)]
+    pub verification_working_dir: Option<String>,
+    /// Left unchanged when omitted
+    #[serde(default)]
+    #[ts(optional, type = "number")]
+    pub verification_max_retries: Option<i64>,
+    /// Left unchanged when omitted; zero or less removes the budget
+    #[serde(default)]
+    #[ts(optional)]
+    pub usage_budget_usd: Option<f64>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -74,6 +100,10 @@ impl Project {
                       dev_script,
                       dev_script_working_dir,
                       default_agent_working_dir,
+                      verification_command,
+                      verification_working_dir,
+                      verification_max_retries,
+                      usage_budget_usd,
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -90,7 +120,8 @@ impl Project {
             Project,
             r#"
             SELECT p.id as "id!: Uuid", p.name, p.dev_script, p.dev_script_working_dir,
-                   p.default_agent_working_dir,
+                   p.default_agent_working_dir, p.verification_command,
+                   p.verification_working_dir, p.verification_max_retries, p.usage_budget_usd,
                    p.remote_project_id as "remote_project_id: Uuid",
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
@@ -116,6 +147,10 @@ impl Project {
                       dev_script,
                       dev_script_working_dir,
                       default_agent_working_dir,
+                      verification_command,
+                      verification_working_dir,
+                      verification_max_retries,
+                      usage_budget_usd,
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -135,6 +170,10 @@ impl Project {
                       dev_script,
                       dev_script_working_dir,
                       default_agent_working_dir,
+                      verification_command,
+                      verification_working_dir,
+                      verification_max_retries,
+                      usage_budget_usd,
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -157,6 +196,10 @@ impl Project {
                       dev_script,
                       dev_script_working_dir,
                       default_agent_working_dir,
+                      verification_command,
+                      verification_working_dir,
+                      verification_max_retries,
+                      usage_budget_usd,
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -187,6 +230,10 @@ impl Project {
                           dev_script,
                           dev_script_working_dir,
                           default_agent_working_dir,
+                          verification_command,
+                          verification_working_dir,
+                          verification_max_retries,
+                          usage_budget_usd,
                           remote_project_id as "remote_project_id: Uuid",
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
@@ -210,17 +257,41 @@ impl Project {
         let dev_script = payload.dev_script.clone();
         let dev_script_working_dir = payload.dev_script_working_dir.clone();
         let default_agent_working_dir = payload.default_agent_working_dir.clone();
+        let trimmed_or = |value: &Option<String>, existing: Option<String>| match value {
+            Some(value) => Some(value.trim().to_string()).filter(|value| !value.is_empty()),
+            None => existing,
+        };
+        let verification_command =
+            trimmed_or(&payload.verification_command, existing.verification_command);
+        let verification_working_dir = trimmed_or(
+            &payload.verification_working_dir,
+            existing.verification_working_dir,
+        );
+        let verification_max_retries = payload
+            .verification_max_retries
+            .unwrap_or(existing.verification_max_retries)
+            .max(0);
+        let usage_budget_usd = match payload.usage_budget_usd {
+            Some(budget) => Some(budget).filter(|budget| *budget > 0.0),
+            None => existing.usage_budget_usd,
+        };
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2, dev_script = $3, dev_script_working_dir = $4, default_agent_working_dir = $5
+               SET name = $2, dev_script = $3, dev_script_working_dir = $4, default_agent_working_dir = $5,
+                   verification_command = $6, verification_working_dir = $7, verification_max_retries = $8,
+                   usage_budget_usd = $9
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
                          dev_script,
                          dev_script_working_dir,
                          default_agent_working_dir,
+                         verification_command,
+                         verification_working_dir,
+                         verification_max_retries,
+                         usage_budget_usd,
                          remote_project_id as "remote_project_id: Uuid",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
@@ -229,6 +300,10 @@ impl Project {
             dev_script,
             dev_script_working_dir,
             default_agent_working_dir,
+            verification_command,
+            verification_working_dir,
+            verification_max_retries,
+            usage_budget_usd,
         )
         .fetch_one(pool)
         .await
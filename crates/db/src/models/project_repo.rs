@@ -1,13 +1,17 @@
 use std::path::Path;
 
 use chrono::{DateTime, Utc};
+use executors::actions::script::ScriptOptions;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::{
+    merge::{ForgeKind, MergeStrategy},
+    repo::Repo,
+};
 
 #[derive(Debug, Error)]
 pub enum ProjectRepoError {
@@ -28,6 +32,18 @@ pub struct ProjectRepo {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
+    #[ts(type = "ScriptOptions")]
+    pub setup_script_options: sqlx::types::Json<ScriptOptions>,
+    #[ts(type = "ScriptOptions")]
+    pub cleanup_script_options: sqlx::types::Json<ScriptOptions>,
+    /// Used when the project's dev server script runs in this repo
+    #[ts(type = "ScriptOptions")]
+    pub dev_script_options: sqlx::types::Json<ScriptOptions>,
+    /// How direct merges land on the target branch
+    pub merge_strategy: MergeStrategy,
+    /// Rebase onto the target branch first when it has moved ahead, instead of
+    /// refusing the merge
+    pub rebase_before_merge: bool,
 }
 
 /// ProjectRepo with the associated repo name (for script execution in worktrees)
@@ -41,6 +57,9 @@ pub struct ProjectRepoWithName {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
+    pub setup_script_options: sqlx::types::Json<ScriptOptions>,
+    pub cleanup_script_options: sqlx::types::Json<ScriptOptions>,
+    pub dev_script_options: sqlx::types::Json<ScriptOptions>,
 }
 
 #[derive(Debug, Clone, Deserialize, TS)]
@@ -56,6 +75,42 @@ pub struct UpdateProjectRepo {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: Option<bool>,
+    /// Left unchanged when omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub setup_script_options: Option<ScriptOptions>,
+    /// Left unchanged when omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub cleanup_script_options: Option<ScriptOptions>,
+    /// Left unchanged when omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub dev_script_options: Option<ScriptOptions>,
+    /// Left unchanged when omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub merge_strategy: Option<MergeStrategy>,
+    /// Left unchanged when omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub rebase_before_merge: Option<bool>,
+}
+
+impl ProjectRepoWithName {
+    /// Options for the project's dev server script, taken from the repo it runs in: the
+    /// first component of `working_dir`, or the only repo of a single-repo project
+    pub fn dev_script_options(repos: &[Self], working_dir: Option<&str>) -> ScriptOptions {
+        let repo = match working_dir.and_then(|dir| Path::new(dir).components().next()) {
+            Some(first) => repos
+                .iter()
+                .find(|repo| first.as_os_str() == repo.repo_name.as_str()),
+            None if repos.len() == 1 => repos.first(),
+            None => None,
+        };
+        repo.map(|repo| repo.dev_script_options.0.clone())
+            .unwrap_or_default()
+    }
 }
 
 impl ProjectRepo {
@@ -71,7 +126,12 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      setup_script_options as "setup_script_options!: sqlx::types::Json<ScriptOptions>",
+                      cleanup_script_options as "cleanup_script_options!: sqlx::types::Json<ScriptOptions>",
+                      dev_script_options as "dev_script_options!: sqlx::types::Json<ScriptOptions>",
+                      merge_strategy as "merge_strategy!: MergeStrategy",
+                      rebase_before_merge as "rebase_before_merge!: bool"
                FROM project_repos
                WHERE project_id = $1"#,
             project_id
@@ -92,7 +152,12 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      setup_script_options as "setup_script_options!: sqlx::types::Json<ScriptOptions>",
+                      cleanup_script_options as "cleanup_script_options!: sqlx::types::Json<ScriptOptions>",
+                      dev_script_options as "dev_script_options!: sqlx::types::Json<ScriptOptions>",
+                      merge_strategy as "merge_strategy!: MergeStrategy",
+                      rebase_before_merge as "rebase_before_merge!: bool"
                FROM project_repos
                WHERE repo_id = $1"#,
             repo_id
@@ -114,7 +179,10 @@ impl ProjectRepo {
                       pr.setup_script,
                       pr.cleanup_script,
                       pr.copy_files,
-                      pr.parallel_setup_script as "parallel_setup_script!: bool"
+                      pr.parallel_setup_script as "parallel_setup_script!: bool",
+                      pr.setup_script_options as "setup_script_options!: sqlx::types::Json<ScriptOptions>",
+                      pr.cleanup_script_options as "cleanup_script_options!: sqlx::types::Json<ScriptOptions>",
+                      pr.dev_script_options as "dev_script_options!: sqlx::types::Json<ScriptOptions>"
                FROM project_repos pr
                JOIN repos r ON r.id = pr.repo_id
                WHERE pr.project_id = $1
@@ -135,6 +203,8 @@ impl ProjectRepo {
                       r.path,
                       r.name,
                       r.display_name, 
+                      r.forge as "forge: ForgeKind",
+                      r.forge_url,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -160,7 +230,12 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      setup_script_options as "setup_script_options!: sqlx::types::Json<ScriptOptions>",
+                      cleanup_script_options as "cleanup_script_options!: sqlx::types::Json<ScriptOptions>",
+                      dev_script_options as "dev_script_options!: sqlx::types::Json<ScriptOptions>",
+                      merge_strategy as "merge_strategy!: MergeStrategy",
+                      rebase_before_merge as "rebase_before_merge!: bool"
                FROM project_repos
                WHERE project_id = $1 AND repo_id = $2"#,
             project_id,
@@ -235,7 +310,12 @@ impl ProjectRepo {
                          setup_script,
                          cleanup_script,
                          copy_files,
-                         parallel_setup_script as "parallel_setup_script!: bool""#,
+                         parallel_setup_script as "parallel_setup_script!: bool",
+                         setup_script_options as "setup_script_options!: sqlx::types::Json<ScriptOptions>",
+                         cleanup_script_options as "cleanup_script_options!: sqlx::types::Json<ScriptOptions>",
+                         dev_script_options as "dev_script_options!: sqlx::types::Json<ScriptOptions>",
+                         merge_strategy as "merge_strategy!: MergeStrategy",
+                         rebase_before_merge as "rebase_before_merge!: bool""#,
             id,
             project_id,
             repo_id
@@ -259,6 +339,25 @@ impl ProjectRepo {
         let parallel_setup_script = payload
             .parallel_setup_script
             .unwrap_or(existing.parallel_setup_script);
+        let setup_script_options = payload
+            .setup_script_options
+            .clone()
+            .map(sqlx::types::Json)
+            .unwrap_or(existing.setup_script_options);
+        let cleanup_script_options = payload
+            .cleanup_script_options
+            .clone()
+            .map(sqlx::types::Json)
+            .unwrap_or(existing.cleanup_script_options);
+        let dev_script_options = payload
+            .dev_script_options
+            .clone()
+            .map(sqlx::types::Json)
+            .unwrap_or(existing.dev_script_options);
+        let merge_strategy = payload.merge_strategy.unwrap_or(existing.merge_strategy);
+        let rebase_before_merge = payload
+            .rebase_before_merge
+            .unwrap_or(existing.rebase_before_merge);
 
         sqlx::query_as!(
             ProjectRepo,
@@ -266,19 +365,34 @@ impl ProjectRepo {
                SET setup_script = $1,
                    cleanup_script = $2,
                    copy_files = $3,
-                   parallel_setup_script = $4
-               WHERE project_id = $5 AND repo_id = $6
+                   parallel_setup_script = $4,
+                   setup_script_options = $5,
+                   cleanup_script_options = $6,
+                   dev_script_options = $7,
+                   merge_strategy = $8,
+                   rebase_before_merge = $9
+               WHERE project_id = $10 AND repo_id = $11
                RETURNING id as "id!: Uuid",
                          project_id as "project_id!: Uuid",
                          repo_id as "repo_id!: Uuid",
                          setup_script,
                          cleanup_script,
                          copy_files,
-                         parallel_setup_script as "parallel_setup_script!: bool""#,
+                         parallel_setup_script as "parallel_setup_script!: bool",
+                         setup_script_options as "setup_script_options!: sqlx::types::Json<ScriptOptions>",
+                         cleanup_script_options as "cleanup_script_options!: sqlx::types::Json<ScriptOptions>",
+                         dev_script_options as "dev_script_options!: sqlx::types::Json<ScriptOptions>",
+                         merge_strategy as "merge_strategy!: MergeStrategy",
+                         rebase_before_merge as "rebase_before_merge!: bool""#,
             setup_script,
             cleanup_script,
             copy_files,
             parallel_setup_script,
+            setup_script_options,
+            cleanup_script_options,
+            dev_script_options,
+            merge_strategy,
+            rebase_before_merge,
             project_id,
             repo_id
         )
@@ -0,0 +1,540 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use strum::IntoEnumIterator;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{Task, TaskStatus};
+
+#[derive(Debug, Error)]
+pub enum WorkflowError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Workflow state names cannot be empty")]
+    EmptyStateName,
+    #[error("Duplicate workflow state: {0}")]
+    DuplicateState(String),
+    #[error("Unknown workflow state: {0}")]
+    UnknownState(String),
+    #[error("Workflow has no state for status {0}")]
+    NoStateForStatus(TaskStatus),
+    #[error("Moving from {from} to {to} is not allowed by the project workflow")]
+    TransitionNotAllowed { from: String, to: String },
+}
+
+/// A column of a project's board. Every state counts as one of the built-in
+/// statuses, so automation that moves tasks by status keeps working.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkflowState {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    /// Built-in status this state counts as
+    pub category: TaskStatus,
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An allowed move between two states, by name
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkflowTransition {
+    pub from_state: String,
+    pub to_state: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectWorkflow {
+    pub project_id: Uuid,
+    /// States in board order. Empty when the project uses the built-in statuses.
+    pub states: Vec<WorkflowState>,
+    /// Allowed moves. When empty, every move between states is allowed.
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct CreateWorkflowState {
+    pub name: String,
+    pub category: TaskStatus,
+}
+
+/// Replaces a project's whole workflow. An empty `states` list removes it.
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct SetProjectWorkflow {
+    pub states: Vec<CreateWorkflowState>,
+    #[serde(default)]
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+impl ProjectWorkflow {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let states = sqlx::query_as!(
+            WorkflowState,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, category as "category!: TaskStatus", position, created_at as "created_at!: DateTime<Utc>"
+               FROM project_workflow_states
+               WHERE project_id = $1
+               ORDER BY position ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let transitions = sqlx::query_as!(
+            WorkflowTransition,
+            r#"SELECT from_state, to_state
+               FROM project_workflow_transitions
+               WHERE project_id = $1
+               ORDER BY from_state ASC, to_state ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Self {
+            project_id,
+            states,
+            transitions,
+        })
+    }
+
+    /// Replace the workflow of a project. Every status needs at least one state so
+    /// tasks always have somewhere to land. Tasks whose state no longer exists, or
+    /// now counts as a different status, fall back to their status' default state.
+    pub async fn set(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &SetProjectWorkflow,
+    ) -> Result<Self, WorkflowError> {
+        let mut names: Vec<String> = Vec::with_capacity(data.states.len());
+        for state in &data.states {
+            let name = state.name.trim();
+            if name.is_empty() {
+                return Err(WorkflowError::EmptyStateName);
+            }
+            if names
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(name))
+            {
+                return Err(WorkflowError::DuplicateState(name.to_string()));
+            }
+            names.push(name.to_string());
+        }
+        if !data.states.is_empty()
+            && let Some(missing) = TaskStatus::iter()
+                .find(|status| !data.states.iter().any(|state| &state.category == status))
+        {
+            return Err(WorkflowError::NoStateForStatus(missing));
+        }
+
+        let canonical = |name: &str| {
+            names
+                .iter()
+                .find(|existing| existing.eq_ignore_ascii_case(name.trim()))
+                .cloned()
+                .ok_or_else(|| WorkflowError::UnknownState(name.trim().to_string()))
+        };
+        let mut transitions: Vec<(String, String)> = Vec::new();
+        for transition in &data.transitions {
+            let pair = (
+                canonical(&transition.from_state)?,
+                canonical(&transition.to_state)?,
+            );
+            if pair.0 != pair.1 && !transitions.contains(&pair) {
+                transitions.push(pair);
+            }
+        }
+
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM project_workflow_transitions WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM project_workflow_states WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for (position, (name, state)) in names.iter().zip(&data.states).enumerate() {
+            let id = Uuid::new_v4();
+            let position = position as i64;
+            sqlx::query!(
+                r#"INSERT INTO project_workflow_states (id, project_id, name, category, position)
+                   VALUES ($1, $2, $3, $4, $5)"#,
+                id,
+                project_id,
+                name,
+                state.category,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for (from_state, to_state) in &transitions {
+            sqlx::query!(
+                r#"INSERT INTO project_workflow_transitions (project_id, from_state, to_state)
+                   VALUES ($1, $2, $3)"#,
+                project_id,
+                from_state,
+                to_state
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"UPDATE tasks
+               SET workflow_state = (
+                   SELECT s.name FROM project_workflow_states s
+                    WHERE s.project_id = tasks.project_id AND s.category = tasks.status
+                    ORDER BY s.position LIMIT 1
+               )
+               WHERE project_id = $1
+                 AND NOT EXISTS (
+                     SELECT 1 FROM project_workflow_states s
+                      WHERE s.project_id = tasks.project_id
+                        AND s.name = tasks.workflow_state
+                        AND s.category = tasks.status
+                 )"#,
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Self::find_by_project_id(pool, project_id).await?)
+    }
+
+    /// True when the project uses the built-in statuses only
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn find_state(&self, name: &str) -> Option<&WorkflowState> {
+        let name = name.trim();
+        self.states
+            .iter()
+            .find(|state| state.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The first state, in board order, that counts as `status`
+    pub fn default_state(&self, status: &TaskStatus) -> Option<&WorkflowState> {
+        self.states.iter().find(|state| &state.category == status)
+    }
+
+    /// The state a task is in, falling back to the default state of its status
+    pub fn current_state(&self, task: &Task) -> Option<&WorkflowState> {
+        task.workflow_state
+            .as_deref()
+            .and_then(|name| self.find_state(name))
+            .filter(|state| state.category == task.status)
+            .or_else(|| self.default_state(&task.status))
+    }
+
+    pub fn check_transition(
+        &self,
+        from: Option<&WorkflowState>,
+        to: &WorkflowState,
+    ) -> Result<(), WorkflowError> {
+        let Some(from) = from else {
+            return Ok(());
+        };
+        if self.transitions.is_empty()
+            || from.name == to.name
+            || self
+                .transitions
+                .iter()
+                .any(|t| t.from_state == from.name && t.to_state == to.name)
+        {
+            return Ok(());
+        }
+        Err(WorkflowError::TransitionNotAllowed {
+            from: from.name.clone(),
+            to: to.name.clone(),
+        })
+    }
+
+    /// Work out the state a task lands in when its status changes to `status`.
+    /// A task already in a state of that status stays where it is.
+    pub fn resolve_status(
+        &self,
+        task: &Task,
+        status: &TaskStatus,
+    ) -> Result<Option<String>, WorkflowError> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let current = self.current_state(task);
+        if let Some(current) = current.filter(|state| &state.category == status) {
+            return Ok(Some(current.name.clone()));
+        }
+        let target = self
+            .default_state(status)
+            .ok_or_else(|| WorkflowError::NoStateForStatus(status.clone()))?;
+        self.check_transition(current, target)?;
+        Ok(Some(target.name.clone()))
+    }
+
+    /// Work out the state a task lands in when automation moves it to `status`.
+    /// Transition rules only bind users and API clients, so this cannot fail.
+    pub fn system_state(&self, task: &Task, status: &TaskStatus) -> Option<String> {
+        self.current_state(task)
+            .filter(|state| &state.category == status)
+            .or_else(|| self.default_state(status))
+            .map(|state| state.name.clone())
+    }
+
+    /// Validate a move of `task` into the state called `name`
+    pub fn resolve_state(&self, task: &Task, name: &str) -> Result<&WorkflowState, WorkflowError> {
+        let target = self
+            .find_state(name)
+            .ok_or_else(|| WorkflowError::UnknownState(name.trim().to_string()))?;
+        self.check_transition(self.current_state(task), target)?;
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::task::TaskPriority,
+        test_utils::{create_project, create_task, pool},
+    };
+
+    fn workflow(states: &[(&str, TaskStatus)], transitions: &[(&str, &str)]) -> ProjectWorkflow {
+        let project_id = Uuid::new_v4();
+        ProjectWorkflow {
+            project_id,
+            states: states
+                .iter()
+                .enumerate()
+                .map(|(position, (name, category))| WorkflowState {
+                    id: Uuid::new_v4(),
+                    project_id,
+                    name: name.to_string(),
+                    category: category.clone(),
+                    position: position as i64,
+                    created_at: Utc::now(),
+                })
+                .collect(),
+            transitions: transitions
+                .iter()
+                .map(|(from, to)| WorkflowTransition {
+                    from_state: from.to_string(),
+                    to_state: to.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn task(status: TaskStatus, workflow_state: Option<&str>) -> Task {
+        Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: "task".to_string(),
+            description: None,
+            status,
+            workflow_state: workflow_state.map(str::to_string),
+            parent_workspace_id: None,
+            shared_task_id: None,
+            assignee: None,
+            priority: TaskPriority::Medium,
+            due_date: None,
+            estimate_hours: None,
+            agent_metadata: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Backlog -> Doing -> Review -> Shipped, with Dropped reachable from Backlog only
+    fn guarded() -> ProjectWorkflow {
+        workflow(
+            &[
+                ("Backlog", TaskStatus::Todo),
+                ("Doing", TaskStatus::InProgress),
+                ("Review", TaskStatus::InReview),
+                ("Shipped", TaskStatus::Done),
+                ("Dropped", TaskStatus::Cancelled),
+            ],
+            &[
+                ("Backlog", "Doing"),
+                ("Doing", "Review"),
+                ("Review", "Shipped"),
+                ("Backlog", "Dropped"),
+            ],
+        )
+    }
+
+    fn all_statuses(names: &[&str]) -> SetProjectWorkflow {
+        SetProjectWorkflow {
+            states: names
+                .iter()
+                .zip(TaskStatus::iter())
+                .map(|(name, category)| CreateWorkflowState {
+                    name: name.to_string(),
+                    category,
+                })
+                .collect(),
+            transitions: vec![],
+        }
+    }
+
+    #[test]
+    fn empty_workflow_allows_everything() {
+        let workflow = workflow(&[], &[]);
+        let task = task(TaskStatus::Todo, None);
+
+        assert_eq!(
+            workflow.resolve_status(&task, &TaskStatus::Done).unwrap(),
+            None
+        );
+        assert!(matches!(
+            workflow.resolve_state(&task, "Doing"),
+            Err(WorkflowError::UnknownState(name)) if name == "Doing"
+        ));
+        assert_eq!(workflow.system_state(&task, &TaskStatus::Done), None);
+    }
+
+    #[test]
+    fn allowed_moves_land_in_the_target_state() {
+        let workflow = guarded();
+        let task = task(TaskStatus::Todo, Some("Backlog"));
+
+        assert_eq!(
+            workflow
+                .resolve_status(&task, &TaskStatus::InProgress)
+                .unwrap()
+                .as_deref(),
+            Some("Doing")
+        );
+        let target = workflow.resolve_state(&task, "dropped").unwrap();
+        assert_eq!(target.name, "Dropped");
+        assert_eq!(target.category, TaskStatus::Cancelled);
+    }
+
+    #[test]
+    fn forbidden_moves_are_rejected() {
+        let workflow = guarded();
+        let task = task(TaskStatus::InProgress, Some("Doing"));
+
+        assert!(matches!(
+            workflow.resolve_status(&task, &TaskStatus::Done),
+            Err(WorkflowError::TransitionNotAllowed { from, to }) if from == "Doing" && to == "Shipped"
+        ));
+        assert!(matches!(
+            workflow.resolve_state(&task, "Backlog"),
+            Err(WorkflowError::TransitionNotAllowed { .. })
+        ));
+        assert!(
+            workflow
+                .check_transition(
+                    workflow.find_state("Doing"),
+                    workflow.find_state("Doing").unwrap()
+                )
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn system_moves_ignore_transition_rules() {
+        let workflow = guarded();
+        let task = task(TaskStatus::InProgress, Some("Doing"));
+
+        assert_eq!(
+            workflow.system_state(&task, &TaskStatus::Done).as_deref(),
+            Some("Shipped")
+        );
+        // A task already in a state of the target status stays there
+        assert_eq!(
+            workflow
+                .system_state(&task, &TaskStatus::InProgress)
+                .as_deref(),
+            Some("Doing")
+        );
+    }
+
+    #[test]
+    fn moving_to_a_status_without_a_state_fails() {
+        let workflow = workflow(
+            &[("Open", TaskStatus::Todo), ("Closed", TaskStatus::Done)],
+            &[],
+        );
+        let task = task(TaskStatus::Todo, Some("Open"));
+
+        assert!(matches!(
+            workflow.resolve_status(&task, &TaskStatus::InReview),
+            Err(WorkflowError::NoStateForStatus(TaskStatus::InReview))
+        ));
+    }
+
+    #[tokio::test]
+    async fn set_requires_a_state_for_every_status() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let mut data = all_statuses(&["Backlog", "Doing", "Review", "Shipped", "Dropped"]);
+        data.states
+            .retain(|state| state.category != TaskStatus::Cancelled);
+
+        assert!(matches!(
+            ProjectWorkflow::set(&pool, project.id, &data).await,
+            Err(WorkflowError::NoStateForStatus(TaskStatus::Cancelled))
+        ));
+        assert!(
+            ProjectWorkflow::find_by_project_id(&pool, project.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn set_rejects_names_that_differ_only_in_case() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let mut data = all_statuses(&["Backlog", "Doing", "Review", "Shipped", "Dropped"]);
+        data.states.push(CreateWorkflowState {
+            name: " backlog ".to_string(),
+            category: TaskStatus::Todo,
+        });
+
+        assert!(matches!(
+            ProjectWorkflow::set(&pool, project.id, &data).await,
+            Err(WorkflowError::DuplicateState(name)) if name == "backlog"
+        ));
+    }
+
+    #[tokio::test]
+    async fn set_moves_existing_tasks_into_default_states() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let task = create_task(&pool, project.id, "a", TaskStatus::InReview).await;
+        let mut data = all_statuses(&["Backlog", "Doing", "Review", "Shipped", "Dropped"]);
+        data.transitions.push(WorkflowTransition {
+            from_state: "backlog".to_string(),
+            to_state: "DOING".to_string(),
+        });
+
+        let workflow = ProjectWorkflow::set(&pool, project.id, &data)
+            .await
+            .unwrap();
+
+        assert_eq!(workflow.states.len(), 5);
+        assert_eq!(workflow.transitions.len(), 1);
+        assert_eq!(workflow.transitions[0].from_state, "Backlog");
+        assert_eq!(workflow.transitions[0].to_state, "Doing");
+        let task = Task::find_by_id(&pool, task.id).await.unwrap().unwrap();
+        assert_eq!(task.workflow_state.as_deref(), Some("Review"));
+    }
+}
@@ -7,6 +7,8 @@ use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::merge::ForgeKind;
+
 #[derive(Debug, Error)]
 pub enum RepoError {
     #[error(transparent)]
@@ -21,6 +23,10 @@ pub struct Repo {
     pub path: PathBuf,
     pub name: String,
     pub display_name: String,
+    /// Forge hosting the repo; detected from the remote URL when None
+    pub forge: Option<ForgeKind>,
+    /// Base URL of a self-hosted forge; derived from the remote URL when None
+    pub forge_url: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -37,6 +43,8 @@ impl Repo {
                       path,
                       name,
                       display_name,
+                      forge as "forge: ForgeKind",
+                      forge_url,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -63,6 +71,33 @@ impl Repo {
         Ok(())
     }
 
+    pub async fn update_forge(
+        pool: &SqlitePool,
+        id: Uuid,
+        forge: Option<ForgeKind>,
+        forge_url: Option<&str>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Repo,
+            r#"UPDATE repos
+               SET forge = $2, forge_url = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         path,
+                         name,
+                         display_name,
+                         forge as "forge: ForgeKind",
+                         forge_url,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            forge,
+            forge_url
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Repo,
@@ -70,6 +105,8 @@ impl Repo {
                       path,
                       name,
                       display_name,
+                      forge as "forge: ForgeKind",
+                      forge_url,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -105,6 +142,8 @@ impl Repo {
                          path,
                          name,
                          display_name,
+                         forge as "forge: ForgeKind",
+                         forge_url,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -0,0 +1,333 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "review_follow_up_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum ReviewFollowUpStatus {
+    /// The agent is working on the comments
+    Running,
+    /// The agent's commit was pushed to the pull request branch
+    Pushed,
+    /// The agent finished without changing anything, so nothing was pushed
+    NoChanges,
+    /// The agent turn or the push failed
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, Hash, TS)]
+#[sqlx(type_name = "review_comment_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewCommentType {
+    /// Conversation comment on the pull request
+    General,
+    /// Inline comment on the code
+    Review,
+}
+
+/// Agent turn addressing review comments of a pull request
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ReviewFollowUp {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    #[ts(type = "number")]
+    pub pr_number: i64,
+    pub execution_process_id: Option<Uuid>,
+    /// Branch head before the turn
+    pub base_commit: Option<String>,
+    /// Whether each addressed comment gets a reply naming the commit
+    pub reply_to_comments: bool,
+    pub status: ReviewFollowUpStatus,
+    /// Commit pushed for the comments
+    pub commit_sha: Option<String>,
+    /// How the follow-up ended, e.g. why the push failed
+    pub message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A comment addressed by a follow-up, and the commit that addressed it
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ReviewFollowUpComment {
+    pub id: Uuid,
+    pub review_follow_up_id: Uuid,
+    /// Id of the comment on the forge
+    pub comment_id: String,
+    pub comment_type: ReviewCommentType,
+    pub url: String,
+    pub path: Option<String>,
+    #[ts(type = "number | null")]
+    pub line: Option<i64>,
+    pub commit_sha: Option<String>,
+    pub replied_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateReviewFollowUp {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub pr_number: i64,
+    pub execution_process_id: Uuid,
+    pub base_commit: Option<String>,
+    pub reply_to_comments: bool,
+    pub comments: Vec<CreateReviewFollowUpComment>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateReviewFollowUpComment {
+    pub comment_id: String,
+    pub comment_type: ReviewCommentType,
+    pub url: String,
+    pub path: Option<String>,
+    pub line: Option<i64>,
+}
+
+impl ReviewFollowUp {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateReviewFollowUp,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let mut tx = pool.begin().await?;
+        let follow_up = sqlx::query_as!(
+            ReviewFollowUp,
+            r#"INSERT INTO review_follow_ups (id, workspace_id, repo_id, pr_number, execution_process_id, base_commit, reply_to_comments)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         pr_number,
+                         execution_process_id as "execution_process_id: Uuid",
+                         base_commit,
+                         reply_to_comments as "reply_to_comments!: bool",
+                         status as "status!: ReviewFollowUpStatus",
+                         commit_sha,
+                         message,
+                         created_at as "created_at!: DateTime<Utc>",
+                         completed_at as "completed_at: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.repo_id,
+            data.pr_number,
+            data.execution_process_id,
+            data.base_commit,
+            data.reply_to_comments
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for comment in &data.comments {
+            let comment_id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO review_follow_up_comments (id, review_follow_up_id, comment_id, comment_type, url, path, line)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+                comment_id,
+                id,
+                comment.comment_id,
+                comment.comment_type,
+                comment.url,
+                comment.path,
+                comment.line
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(follow_up)
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ReviewFollowUp,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      pr_number,
+                      execution_process_id as "execution_process_id: Uuid",
+                      base_commit,
+                      reply_to_comments as "reply_to_comments!: bool",
+                      status as "status!: ReviewFollowUpStatus",
+                      commit_sha,
+                      message,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>"
+               FROM review_follow_ups
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Follow-ups of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ReviewFollowUp,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      pr_number,
+                      execution_process_id as "execution_process_id: Uuid",
+                      base_commit,
+                      reply_to_comments as "reply_to_comments!: bool",
+                      status as "status!: ReviewFollowUpStatus",
+                      commit_sha,
+                      message,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>"
+               FROM review_follow_ups
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Latest follow-up of a pull request that pushed a commit
+    pub async fn find_latest_pushed(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        pr_number: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ReviewFollowUp,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      pr_number,
+                      execution_process_id as "execution_process_id: Uuid",
+                      base_commit,
+                      reply_to_comments as "reply_to_comments!: bool",
+                      status as "status!: ReviewFollowUpStatus",
+                      commit_sha,
+                      message,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>"
+               FROM review_follow_ups
+               WHERE workspace_id = $1 AND repo_id = $2 AND pr_number = $3 AND status = 'pushed'
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            workspace_id,
+            repo_id,
+            pr_number
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Ids of the comments of a pull request that a pushed follow-up addressed
+    pub async fn find_addressed_comment_ids(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        pr_number: i64,
+    ) -> Result<Vec<(ReviewCommentType, String)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT c.comment_type as "comment_type!: ReviewCommentType",
+                      c.comment_id
+               FROM review_follow_up_comments c
+               JOIN review_follow_ups f ON f.id = c.review_follow_up_id
+               WHERE f.workspace_id = $1 AND f.repo_id = $2 AND f.pr_number = $3
+                 AND f.status = 'pushed'"#,
+            workspace_id,
+            repo_id,
+            pr_number
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.comment_type, row.comment_id))
+            .collect())
+    }
+
+    pub async fn finish(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: ReviewFollowUpStatus,
+        commit_sha: Option<&str>,
+        message: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"UPDATE review_follow_ups
+               SET status = $1, commit_sha = $2, message = $3, completed_at = $4
+               WHERE id = $5"#,
+            status,
+            commit_sha,
+            message,
+            now,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"UPDATE review_follow_up_comments
+               SET commit_sha = $1
+               WHERE review_follow_up_id = $2"#,
+            commit_sha,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+impl ReviewFollowUpComment {
+    pub async fn find_by_review_follow_up_id(
+        pool: &SqlitePool,
+        review_follow_up_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ReviewFollowUpComment,
+            r#"SELECT id as "id!: Uuid",
+                      review_follow_up_id as "review_follow_up_id!: Uuid",
+                      comment_id,
+                      comment_type as "comment_type!: ReviewCommentType",
+                      url,
+                      path,
+                      line,
+                      commit_sha,
+                      replied_at as "replied_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM review_follow_up_comments
+               WHERE review_follow_up_id = $1
+               ORDER BY created_at ASC"#,
+            review_follow_up_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_replied(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE review_follow_up_comments SET replied_at = $1 WHERE id = $2"#,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,11 +1,18 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
-use strum_macros::{Display, EnumString};
+use strum_macros::{Display, EnumIter, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, workspace::Workspace};
+use super::{
+    coding_agent_turn::VerificationStatus,
+    project::Project,
+    project_workflow::{ProjectWorkflow, WorkflowError},
+    workspace::Workspace,
+};
 
 /// Represents a single entry in the agent metadata history for a task.
 /// Tracks which agent performed what action on the task and when.
@@ -34,7 +41,17 @@ impl AgentMetadataEntry {
 }
 
 #[derive(
-    Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
+    Debug,
+    Clone,
+    Type,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    TS,
+    EnumString,
+    Display,
+    Default,
+    EnumIter,
 )]
 #[sqlx(type_name = "task_status", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
@@ -48,6 +65,20 @@ pub enum TaskStatus {
     Cancelled,
 }
 
+#[derive(
+    Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
+)]
+#[sqlx(type_name = "task_priority", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Urgent,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Task {
     pub id: Uuid,
@@ -55,9 +86,15 @@ pub struct Task {
     pub title: String,
     pub description: Option<String>,
     pub status: TaskStatus,
+    /// Project workflow state within `status`, when the project defines a workflow
+    pub workflow_state: Option<String>,
     pub parent_workspace_id: Option<Uuid>, // Foreign key to parent Workspace
     pub shared_task_id: Option<Uuid>,
     pub assignee: Option<String>,
+    pub priority: TaskPriority,
+    pub due_date: Option<DateTime<Utc>>,
+    /// Estimated effort in hours
+    pub estimate_hours: Option<f64>,
     /// JSON-serialized array of AgentMetadataEntry for tracking agent activity
     pub agent_metadata: Option<String>,
     pub created_at: DateTime<Utc>,
@@ -71,7 +108,11 @@ pub struct TaskWithAttemptStatus {
     pub task: Task,
     pub has_in_progress_attempt: bool,
     pub last_attempt_failed: bool,
+    /// Result of the latest verified agent turn, if the project verifies turns
+    pub last_verification_status: Option<VerificationStatus>,
     pub executor: String,
+    /// True if any task this task depends on is not yet done
+    pub blocked: bool,
 }
 
 impl std::ops::Deref for TaskWithAttemptStatus {
@@ -92,6 +133,8 @@ pub struct TaskRelationships {
     pub parent_task: Option<Task>, // The task that owns the parent workspace
     pub current_workspace: Workspace, // The workspace we're viewing
     pub children: Vec<Task>,       // Tasks created from this workspace
+    pub stacked_on: Option<Workspace>, // Workspace whose branch this one builds on
+    pub stacked_children: Vec<Workspace>, // Workspaces stacked on this one
 }
 
 /// Simplified task relationships without requiring a workspace reference.
@@ -112,6 +155,11 @@ pub struct CreateTask {
     pub parent_workspace_id: Option<Uuid>,
     pub image_ids: Option<Vec<Uuid>>,
     pub shared_task_id: Option<Uuid>,
+    /// Defaults to medium
+    pub priority: Option<TaskPriority>,
+    pub due_date: Option<DateTime<Utc>>,
+    /// Estimated effort in hours
+    pub estimate_hours: Option<f64>,
 }
 
 impl CreateTask {
@@ -128,6 +176,9 @@ impl CreateTask {
             parent_workspace_id: None,
             image_ids: None,
             shared_task_id: None,
+            priority: None,
+            due_date: None,
+            estimate_hours: None,
         }
     }
 
@@ -146,6 +197,9 @@ impl CreateTask {
             parent_workspace_id: None,
             image_ids: None,
             shared_task_id: Some(shared_task_id),
+            priority: None,
+            due_date: None,
+            estimate_hours: None,
         }
     }
 }
@@ -158,6 +212,16 @@ pub struct UpdateTask {
     pub parent_workspace_id: Option<Uuid>,
     pub image_ids: Option<Vec<Uuid>>,
     pub assignee: Option<String>,
+    pub priority: Option<TaskPriority>,
+    pub due_date: Option<DateTime<Utc>>,
+    /// Estimated effort in hours
+    pub estimate_hours: Option<f64>,
+    /// Remove the due date. Takes precedence over `due_date`.
+    #[ts(optional)]
+    pub clear_due_date: Option<bool>,
+    /// Remove the estimate. Takes precedence over `estimate_hours`.
+    #[ts(optional)]
+    pub clear_estimate: Option<bool>,
 }
 
 impl Task {
@@ -184,9 +248,13 @@ impl Task {
   t.title,
   t.description,
   t.status                        AS "status!: TaskStatus",
+  t.workflow_state,
   t.parent_workspace_id           AS "parent_workspace_id: Uuid",
   t.shared_task_id                AS "shared_task_id: Uuid",
   t.assignee,
+  t.priority                      AS "priority!: TaskPriority",
+  t.due_date                      AS "due_date: DateTime<Utc>",
+  t.estimate_hours,
   t.agent_metadata,
   t.created_at                    AS "created_at!: DateTime<Utc>",
   t.updated_at                    AS "updated_at!: DateTime<Utc>",
@@ -198,7 +266,7 @@ impl Task {
       JOIN execution_processes ep ON ep.session_id = s.id
      WHERE w.task_id       = t.id
        AND ep.status        = 'running'
-       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','pipelinestep')
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
 
@@ -208,19 +276,38 @@ impl Task {
       JOIN sessions s ON s.workspace_id = w.id
       JOIN execution_processes ep ON ep.session_id = s.id
      WHERE w.task_id       = t.id
-     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','pipelinestep')
      ORDER BY ep.created_at DESC
      LIMIT 1
   ) IN ('failed','killed') THEN 1 ELSE 0 END
                                  AS "last_attempt_failed!: i64",
 
+  ( SELECT cat.verification_status
+      FROM workspaces w
+      JOIN sessions s ON s.workspace_id = w.id
+      JOIN execution_processes ep ON ep.session_id = s.id
+      JOIN coding_agent_turns cat ON cat.execution_process_id = ep.id
+     WHERE w.task_id = t.id
+       AND cat.verification_status IS NOT NULL
+     ORDER BY ep.created_at DESC
+      LIMIT 1
+    )                               AS "last_verification_status: VerificationStatus",
+
   ( SELECT s.executor
       FROM workspaces w
       JOIN sessions s ON s.workspace_id = w.id
       WHERE w.task_id = t.id
      ORDER BY s.created_at DESC
       LIMIT 1
-    )                               AS "executor!: String"
+    )                               AS "executor!: String",
+
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM task_dependencies td
+      JOIN tasks bt ON bt.id = td.depends_on_task_id
+     WHERE td.task_id = t.id
+       AND bt.status != 'done'
+  ) THEN 1 ELSE 0 END            AS "blocked!: i64"
 
 FROM tasks t
 WHERE t.project_id = $1
@@ -239,16 +326,22 @@ ORDER BY t.created_at DESC"#,
                     title: rec.title,
                     description: rec.description,
                     status: rec.status,
+                    workflow_state: rec.workflow_state,
                     parent_workspace_id: rec.parent_workspace_id,
                     shared_task_id: rec.shared_task_id,
                     assignee: rec.assignee,
+                    priority: rec.priority,
+                    due_date: rec.due_date,
+                    estimate_hours: rec.estimate_hours,
                     agent_metadata: rec.agent_metadata,
                     created_at: rec.created_at,
                     updated_at: rec.updated_at,
                 },
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
+                last_verification_status: rec.last_verification_status,
                 executor: rec.executor,
+                blocked: rec.blocked != 0,
             })
             .collect();
 
@@ -260,6 +353,9 @@ ORDER BY t.created_at DESC"#,
         project_id: Uuid,
         statuses: Option<Vec<TaskStatus>>,
         assignee: Option<&str>,
+        priorities: Option<Vec<TaskPriority>>,
+        due_after: Option<DateTime<Utc>>,
+        due_before: Option<DateTime<Utc>>,
         created_after: Option<DateTime<Utc>>,
         created_before: Option<DateTime<Utc>>,
         updated_after: Option<DateTime<Utc>>,
@@ -278,9 +374,13 @@ ORDER BY t.created_at DESC"#,
   t.title,
   t.description,
   t.status,
+  t.workflow_state,
   t.parent_workspace_id,
   t.shared_task_id,
   t.assignee,
+  t.priority,
+  t.due_date,
+  t.estimate_hours,
   t.agent_metadata,
   t.created_at,
   t.updated_at,
@@ -292,7 +392,7 @@ ORDER BY t.created_at DESC"#,
       JOIN execution_processes ep ON ep.session_id = s.id
      WHERE w.task_id       = t.id
        AND ep.status        = 'running'
-       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','pipelinestep')
      LIMIT 1
   ) THEN 1 ELSE 0 END AS has_in_progress_attempt,
 
@@ -302,11 +402,22 @@ ORDER BY t.created_at DESC"#,
       JOIN sessions s ON s.workspace_id = w.id
       JOIN execution_processes ep ON ep.session_id = s.id
      WHERE w.task_id       = t.id
-     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','pipelinestep')
      ORDER BY ep.created_at DESC
      LIMIT 1
   ) IN ('failed','killed') THEN 1 ELSE 0 END AS last_attempt_failed,
 
+  ( SELECT cat.verification_status
+      FROM workspaces w
+      JOIN sessions s ON s.workspace_id = w.id
+      JOIN execution_processes ep ON ep.session_id = s.id
+      JOIN coding_agent_turns cat ON cat.execution_process_id = ep.id
+     WHERE w.task_id = t.id
+       AND cat.verification_status IS NOT NULL
+     ORDER BY ep.created_at DESC
+      LIMIT 1
+    )                               AS last_verification_status,
+
   COALESCE((
     SELECT s.executor
       FROM workspaces w
@@ -314,7 +425,15 @@ ORDER BY t.created_at DESC"#,
       WHERE w.task_id = t.id
      ORDER BY s.created_at DESC
       LIMIT 1
-    ), '') AS executor
+    ), '') AS executor,
+
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM task_dependencies td
+      JOIN tasks bt ON bt.id = td.depends_on_task_id
+     WHERE td.task_id = t.id
+       AND bt.status != 'done'
+  ) THEN 1 ELSE 0 END AS blocked
 
 FROM tasks t
 WHERE t.project_id = "#,
@@ -340,6 +459,30 @@ WHERE t.project_id = "#,
             query_builder.push_bind(assignee_name);
         }
 
+        // Add priority filters
+        if let Some(ref priority_list) = priorities {
+            if !priority_list.is_empty() {
+                query_builder.push(" AND t.priority IN (");
+                let mut separated = query_builder.separated(", ");
+                for priority in priority_list {
+                    separated.push_bind(priority);
+                }
+                separated.push_unseparated(")");
+            }
+        }
+
+        // Add due date filters. Tasks without a due date never match.
+        if let Some(due_after) = due_after {
+            query_builder.push(" AND datetime(t.due_date) >= datetime(");
+            query_builder.push_bind(due_after);
+            query_builder.push(")");
+        }
+        if let Some(due_before) = due_before {
+            query_builder.push(" AND datetime(t.due_date) <= datetime(");
+            query_builder.push_bind(due_before);
+            query_builder.push(")");
+        }
+
         // Add date filters
         if let Some(created_after) = created_after {
             query_builder.push(" AND t.created_at >= ");
@@ -358,19 +501,26 @@ WHERE t.project_id = "#,
             query_builder.push_bind(updated_before);
         }
 
-        // Add sorting
-        query_builder.push(" ORDER BY t.");
+        // Add sorting. Tasks without a due date or estimate sort last either way.
+        query_builder.push(" ORDER BY ");
         match sort_by {
-            "created_at" => query_builder.push("created_at"),
-            "updated_at" => query_builder.push("updated_at"),
-            "title" => query_builder.push("title"),
-            _ => query_builder.push("created_at"),
+            "created_at" => query_builder.push("t.created_at"),
+            "updated_at" => query_builder.push("t.updated_at"),
+            "title" => query_builder.push("t.title"),
+            "priority" => query_builder.push(
+                "CASE t.priority WHEN 'urgent' THEN 3 WHEN 'high' THEN 2 WHEN 'medium' THEN 1 ELSE 0 END",
+            ),
+            "due_date" => query_builder.push("t.due_date IS NULL, datetime(t.due_date)"),
+            "estimate_hours" => query_builder.push("t.estimate_hours IS NULL, t.estimate_hours"),
+            _ => query_builder.push("t.created_at"),
         };
         query_builder.push(" ");
         match sort_order {
             "asc" => query_builder.push("ASC"),
             _ => query_builder.push("DESC"),
         };
+        // Keep pages stable when many tasks share a sort value
+        query_builder.push(", t.created_at DESC");
 
         // Add pagination
         query_builder.push(" LIMIT ");
@@ -390,16 +540,23 @@ WHERE t.project_id = "#,
                 let title: String = row.try_get("title").unwrap_or_default();
                 let description: Option<String> = row.try_get("description").ok();
                 let status: TaskStatus = row.try_get("status").unwrap_or_default();
+                let workflow_state: Option<String> = row.try_get("workflow_state").ok().flatten();
                 let parent_workspace_id: Option<Uuid> = row.try_get("parent_workspace_id").ok();
                 let shared_task_id: Option<Uuid> = row.try_get("shared_task_id").ok();
                 let assignee: Option<String> = row.try_get("assignee").ok().flatten();
+                let priority: TaskPriority = row.try_get("priority").unwrap_or_default();
+                let due_date: Option<DateTime<Utc>> = row.try_get("due_date").ok().flatten();
+                let estimate_hours: Option<f64> = row.try_get("estimate_hours").ok().flatten();
                 let agent_metadata: Option<String> = row.try_get("agent_metadata").ok().flatten();
                 let created_at: DateTime<Utc> = row.try_get("created_at").unwrap_or_default();
                 let updated_at: DateTime<Utc> = row.try_get("updated_at").unwrap_or_default();
                 let has_in_progress_attempt: i64 =
                     row.try_get("has_in_progress_attempt").unwrap_or(0);
                 let last_attempt_failed: i64 = row.try_get("last_attempt_failed").unwrap_or(0);
+                let last_verification_status: Option<VerificationStatus> =
+                    row.try_get("last_verification_status").ok().flatten();
                 let executor: String = row.try_get("executor").unwrap_or_default();
+                let blocked: i64 = row.try_get("blocked").unwrap_or(0);
 
                 TaskWithAttemptStatus {
                     task: Task {
@@ -408,16 +565,22 @@ WHERE t.project_id = "#,
                         title,
                         description,
                         status,
+                        workflow_state,
                         parent_workspace_id,
                         shared_task_id,
                         assignee,
+                        priority,
+                        due_date,
+                        estimate_hours,
                         agent_metadata,
                         created_at,
                         updated_at,
                     },
                     has_in_progress_attempt: has_in_progress_attempt != 0,
                     last_attempt_failed: last_attempt_failed != 0,
+                    last_verification_status,
                     executor,
+                    blocked: blocked != 0,
                 }
             })
             .collect();
@@ -428,7 +591,7 @@ WHERE t.project_id = "#,
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE id = $1"#,
             id
@@ -440,7 +603,7 @@ WHERE t.project_id = "#,
     pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE rowid = $1"#,
             rowid
@@ -458,7 +621,7 @@ WHERE t.project_id = "#,
     {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE shared_task_id = $1
                LIMIT 1"#,
@@ -471,7 +634,7 @@ WHERE t.project_id = "#,
     pub async fn find_all_shared(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE shared_task_id IS NOT NULL"#
         )
@@ -485,18 +648,24 @@ WHERE t.project_id = "#,
         task_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
         let status = data.status.clone().unwrap_or_default();
+        let priority = data.priority.clone().unwrap_or_default();
         sqlx::query_as!(
             Task,
-            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_workspace_id, shared_task_id)
-               VALUES ($1, $2, $3, $4, $5, $6, $7)
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO tasks (id, project_id, title, description, status, workflow_state, parent_workspace_id, shared_task_id, priority, due_date, estimate_hours)
+               VALUES ($1, $2, $3, $4, $5,
+                       (SELECT name FROM project_workflow_states WHERE project_id = $2 AND category = $5 ORDER BY position LIMIT 1),
+                       $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             task_id,
             data.project_id,
             data.title,
             data.description,
             status,
             data.parent_workspace_id,
-            data.shared_task_id
+            data.shared_task_id,
+            priority,
+            data.due_date,
+            data.estimate_hours
         )
         .fetch_one(pool)
         .await
@@ -516,7 +685,7 @@ WHERE t.project_id = "#,
             r#"UPDATE tasks
                SET title = $3, description = $4, status = $5, parent_workspace_id = $6
                WHERE id = $1 AND project_id = $2
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             project_id,
             title,
@@ -528,52 +697,115 @@ WHERE t.project_id = "#,
         .await
     }
 
+    /// Move a task to `status` for a user or API client. Projects with a workflow
+    /// only allow the moves it defines, and the task lands in the default state of
+    /// that status.
     pub async fn update_status(
         pool: &SqlitePool,
         id: Uuid,
         status: TaskStatus,
+    ) -> Result<(), WorkflowError> {
+        let Some(task) = Self::find_by_id(pool, id).await? else {
+            return Ok(());
+        };
+        let workflow = ProjectWorkflow::find_by_project_id(pool, task.project_id).await?;
+        let workflow_state = workflow.resolve_status(&task, &status)?;
+        Self::set_status(pool, id, &status, workflow_state.as_deref()).await?;
+        Ok(())
+    }
+
+    /// Move a task to `status` on behalf of automation such as agent runs, merges
+    /// and approvals. The workflow's transition rules are not checked.
+    pub async fn update_status_by_system(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: TaskStatus,
     ) -> Result<(), sqlx::Error> {
+        let Some(task) = Self::find_by_id(pool, id).await? else {
+            return Ok(());
+        };
+        let workflow = ProjectWorkflow::find_by_project_id(pool, task.project_id).await?;
+        let workflow_state = workflow.system_state(&task, &status);
+        Self::set_status(pool, id, &status, workflow_state.as_deref()).await
+    }
+
+    /// Move a task to a named state of its project's workflow
+    pub async fn update_workflow_state(
+        pool: &SqlitePool,
+        id: Uuid,
+        state: &str,
+    ) -> Result<Self, WorkflowError> {
+        let task = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let workflow = ProjectWorkflow::find_by_project_id(pool, task.project_id).await?;
+        let target = workflow.resolve_state(&task, state)?;
+        Self::set_status(pool, id, &target.category, Some(&target.name)).await?;
+        Ok(Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?)
+    }
+
+    /// Write a status and workflow state as they are, without checking the workflow
+    pub async fn set_status<'e, E>(
+        executor: E,
+        id: Uuid,
+        status: &TaskStatus,
+        workflow_state: Option<&str>,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         sqlx::query!(
-            "UPDATE tasks SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            "UPDATE tasks SET status = $2, workflow_state = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
             id,
-            status
+            status,
+            workflow_state
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
         Ok(())
     }
 
-    /// Update the status of multiple tasks at once.
+    /// Update the status of multiple tasks at once. Nothing changes unless
+    /// every move is allowed by the tasks' project workflows.
     /// Returns the updated tasks.
     pub async fn bulk_update_status(
         pool: &SqlitePool,
         task_ids: &[Uuid],
         status: TaskStatus,
-    ) -> Result<Vec<Task>, sqlx::Error> {
+    ) -> Result<Vec<Task>, WorkflowError> {
         if task_ids.is_empty() {
             return Ok(Vec::new());
         }
 
         use sqlx::QueryBuilder;
 
-        // First update the tasks
-        let mut update_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
-            "UPDATE tasks SET status = ",
-        );
-        update_builder.push_bind(&status);
-        update_builder.push(", updated_at = CURRENT_TIMESTAMP WHERE id IN (");
-
-        let mut separated = update_builder.separated(", ");
+        // First check every move against its project's workflow
+        let mut workflows: HashMap<Uuid, ProjectWorkflow> = HashMap::new();
+        let mut changes = Vec::with_capacity(task_ids.len());
         for id in task_ids {
-            separated.push_bind(id);
+            let Some(task) = Self::find_by_id(pool, *id).await? else {
+                continue;
+            };
+            if !workflows.contains_key(&task.project_id) {
+                let workflow = ProjectWorkflow::find_by_project_id(pool, task.project_id).await?;
+                workflows.insert(task.project_id, workflow);
+            }
+            let workflow_state = workflows[&task.project_id].resolve_status(&task, &status)?;
+            changes.push((task.id, workflow_state));
         }
-        separated.push_unseparated(")");
 
-        update_builder.build().execute(pool).await?;
+        // Then update the tasks
+        let mut tx = pool.begin().await?;
+        for (id, workflow_state) in &changes {
+            Self::set_status(&mut *tx, *id, &status, workflow_state.as_deref()).await?;
+        }
+        tx.commit().await?;
 
         // Then fetch and return the updated tasks
         let mut select_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
-            r#"SELECT id, project_id, title, description, status, parent_workspace_id, shared_task_id, assignee, agent_metadata, created_at, updated_at
+            r#"SELECT id, project_id, title, description, status, workflow_state, parent_workspace_id, shared_task_id, assignee, priority, due_date, estimate_hours, agent_metadata, created_at, updated_at
                FROM tasks WHERE id IN ("#,
         );
 
@@ -595,9 +827,13 @@ WHERE t.project_id = "#,
                     title: row.try_get("title").unwrap_or_default(),
                     description: row.try_get("description").ok().flatten(),
                     status: row.try_get("status").unwrap_or_default(),
+                    workflow_state: row.try_get("workflow_state").ok().flatten(),
                     parent_workspace_id: row.try_get("parent_workspace_id").ok().flatten(),
                     shared_task_id: row.try_get("shared_task_id").ok().flatten(),
                     assignee: row.try_get("assignee").ok().flatten(),
+                    priority: row.try_get("priority").unwrap_or_default(),
+                    due_date: row.try_get("due_date").ok().flatten(),
+                    estimate_hours: row.try_get("estimate_hours").ok().flatten(),
                     agent_metadata: row.try_get("agent_metadata").ok().flatten(),
                     created_at: row.try_get("created_at").unwrap_or_default(),
                     updated_at: row.try_get("updated_at").unwrap_or_default(),
@@ -633,7 +869,7 @@ WHERE t.project_id = "#,
         sqlx::query_as!(
             Task,
             r#"UPDATE tasks SET assignee = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             task_id,
             assignee
         )
@@ -641,6 +877,27 @@ WHERE t.project_id = "#,
         .await
     }
 
+    /// Update the priority, due date and estimate of a task
+    pub async fn update_planning(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        priority: TaskPriority,
+        due_date: Option<DateTime<Utc>>,
+        estimate_hours: Option<f64>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"UPDATE tasks SET priority = $2, due_date = $3, estimate_hours = $4, updated_at = CURRENT_TIMESTAMP WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            task_id,
+            priority,
+            due_date,
+            estimate_hours
+        )
+        .fetch_one(pool)
+        .await
+    }
+
     /// Nullify parent_workspace_id for all tasks that reference the given workspace ID
     /// This breaks parent-child relationships before deleting a parent task
     pub async fn nullify_children_by_workspace_id<'e, E>(
@@ -741,7 +998,7 @@ WHERE t.project_id = "#,
         // Find only child tasks that have this workspace as their parent
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE parent_workspace_id = $1
                ORDER BY created_at DESC"#,
@@ -753,20 +1010,23 @@ WHERE t.project_id = "#,
 
     /// Search tasks by text in title and description using LIKE with wildcards.
     /// Returns tasks matching the query, ordered by relevance (title matches first, then by updated_at).
+    /// When `priority` is set only tasks with that priority are returned.
     pub async fn search_by_query(
         pool: &SqlitePool,
         project_id: Uuid,
         query: &str,
+        priority: Option<TaskPriority>,
         limit: u32,
         offset: u32,
     ) -> Result<Vec<Task>, sqlx::Error> {
         let search_pattern = format!("%{}%", query);
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE project_id = $1
                  AND (title LIKE $2 OR description LIKE $2)
+                 AND ($5 IS NULL OR priority = $5)
                ORDER BY
                  CASE WHEN title LIKE $2 THEN 0 ELSE 1 END,
                  updated_at DESC
@@ -774,7 +1034,8 @@ WHERE t.project_id = "#,
             project_id,
             search_pattern,
             limit,
-            offset
+            offset,
+            priority
         )
         .fetch_all(pool)
         .await
@@ -807,10 +1068,19 @@ WHERE t.project_id = "#,
         // 3. Get children tasks (created from this workspace)
         let children = Self::find_children_by_workspace_id(pool, workspace.id).await?;
 
+        // 4. Stacked branches: the workspace this one builds on and those building on it
+        let stacked_on = match workspace.stacked_on_workspace_id {
+            Some(parent_id) => Workspace::find_by_id(pool, parent_id).await?,
+            None => None,
+        };
+        let stacked_children = Workspace::find_stacked_on(pool, workspace.id).await?;
+
         Ok(TaskRelationships {
             parent_task,
             current_workspace: workspace.clone(),
             children,
+            stacked_on,
+            stacked_children,
         })
     }
 
@@ -896,7 +1166,7 @@ WHERE t.project_id = "#,
         sqlx::query_as!(
             Task,
             r#"UPDATE tasks SET agent_metadata = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state, parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", assignee, priority as "priority!: TaskPriority", due_date as "due_date: DateTime<Utc>", estimate_hours, agent_metadata, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             task_id,
             new_metadata
         )
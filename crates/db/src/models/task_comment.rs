@@ -1,16 +1,24 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::{task::Task, task_history::TaskActorType};
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct TaskComment {
     pub id: Uuid,
     pub task_id: Uuid,
+    /// The comment this is a reply to, if any
+    pub parent_comment_id: Option<Uuid>,
     pub content: String,
     pub author: String,
+    pub author_type: TaskActorType,
     pub created_at: DateTime<Utc>,
+    /// When the comment was last edited
+    pub edited_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -18,6 +26,101 @@ pub struct CreateTaskComment {
     pub task_id: Uuid,
     pub content: String,
     pub author: String,
+    #[serde(default)]
+    pub parent_comment_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct UpdateTaskComment {
+    pub content: String,
+}
+
+/// A previous version of an edited comment
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskCommentEdit {
+    pub id: Uuid,
+    pub comment_id: Uuid,
+    pub previous_content: String,
+    pub edited_by: String,
+    pub edited_at: DateTime<Utc>,
+}
+
+/// Whether a mention refers to an agent that worked on the task or a person
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display)]
+#[sqlx(type_name = "mention_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum MentionType {
+    User,
+    Agent,
+}
+
+/// An `@name` in a comment that resolved against the task
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, PartialEq, Eq, TS)]
+pub struct TaskCommentMention {
+    pub comment_id: Uuid,
+    /// The mentioned name as it appears on the task
+    pub mention: String,
+    pub mention_type: MentionType,
+}
+
+/// Extract the `@name` tokens from comment text, in order and without duplicates.
+///
+/// A mention must start the text or follow whitespace or an opening bracket, so
+/// email addresses are not picked up. Trailing punctuation is dropped.
+pub fn parse_mentions(content: &str) -> Vec<String> {
+    let is_name_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.');
+
+    let mut mentions: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    let mut chars = content.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let at_boundary = previous.is_none_or(|p| p.is_whitespace() || matches!(p, '(' | '['));
+        previous = Some(c);
+        if c != '@' || !at_boundary {
+            continue;
+        }
+
+        let mut end = start + 1;
+        while let Some(&(index, next)) = chars.peek() {
+            if !is_name_char(next) {
+                break;
+            }
+            end = index + next.len_utf8();
+            previous = Some(next);
+            chars.next();
+        }
+
+        let name = content[start + 1..end].trim_end_matches(['.', '-']);
+        if !name.is_empty() && !mentions.iter().any(|m| m.eq_ignore_ascii_case(name)) {
+            mentions.push(name.to_string());
+        }
+    }
+    mentions
+}
+
+/// Resolve mentioned names against the task's assignee and the agents recorded in
+/// its metadata. Names that match neither are dropped.
+pub fn resolve_mentions(task: &Task, names: &[String]) -> Vec<(String, MentionType)> {
+    let agents: Vec<String> = task
+        .get_agent_metadata_entries()
+        .into_iter()
+        .map(|entry| entry.agent_name)
+        .collect();
+
+    names
+        .iter()
+        .filter_map(|name| {
+            if let Some(agent) = agents.iter().find(|agent| agent.eq_ignore_ascii_case(name)) {
+                Some((agent.clone(), MentionType::Agent))
+            } else {
+                task.assignee
+                    .as_ref()
+                    .filter(|assignee| assignee.eq_ignore_ascii_case(name))
+                    .map(|assignee| (assignee.clone(), MentionType::User))
+            }
+        })
+        .collect()
 }
 
 impl TaskComment {
@@ -27,7 +130,7 @@ impl TaskComment {
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             TaskComment,
-            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", content, author, created_at as "created_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", parent_comment_id as "parent_comment_id: Uuid", content, author, author_type as "author_type!: TaskActorType", created_at as "created_at!: DateTime<Utc>", edited_at as "edited_at: DateTime<Utc>"
                FROM task_comments
                WHERE task_id = $1
                ORDER BY created_at ASC"#,
@@ -40,7 +143,7 @@ impl TaskComment {
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             TaskComment,
-            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", content, author, created_at as "created_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", parent_comment_id as "parent_comment_id: Uuid", content, author, author_type as "author_type!: TaskActorType", created_at as "created_at!: DateTime<Utc>", edited_at as "edited_at: DateTime<Utc>"
                FROM task_comments
                WHERE id = $1"#,
             id
@@ -49,22 +152,217 @@ impl TaskComment {
         .await
     }
 
-    pub async fn create(pool: &SqlitePool, data: &CreateTaskComment) -> Result<Self, sqlx::Error> {
+    pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskComment,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", parent_comment_id as "parent_comment_id: Uuid", content, author, author_type as "author_type!: TaskActorType", created_at as "created_at!: DateTime<Utc>", edited_at as "edited_at: DateTime<Utc>"
+               FROM task_comments
+               WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Comments on a task that mention `reader` or reply to one of its comments,
+    /// created or edited since the reader last marked the task as read.
+    pub async fn find_unread_for(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        reader: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskComment,
+            r#"SELECT c.id as "id!: Uuid", c.task_id as "task_id!: Uuid", c.parent_comment_id as "parent_comment_id: Uuid", c.content, c.author, c.author_type as "author_type!: TaskActorType", c.created_at as "created_at!: DateTime<Utc>", c.edited_at as "edited_at: DateTime<Utc>"
+               FROM task_comments c
+               WHERE c.task_id = $1
+                 AND LOWER(c.author) != LOWER($2)
+                 AND (
+                    EXISTS (SELECT 1 FROM task_comment_mentions m
+                            WHERE m.comment_id = c.id AND LOWER(m.mention) = LOWER($2))
+                    OR EXISTS (SELECT 1 FROM task_comments p
+                               WHERE p.id = c.parent_comment_id AND LOWER(p.author) = LOWER($2))
+                 )
+                 AND NOT EXISTS (SELECT 1 FROM task_comment_reads r
+                                 WHERE r.task_id = c.task_id AND r.reader = LOWER($2)
+                                   AND julianday(r.last_read_at) >= julianday(COALESCE(c.edited_at, c.created_at)))
+               ORDER BY c.created_at ASC"#,
+            task_id,
+            reader
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskComment,
+        author_type: &TaskActorType,
+    ) -> Result<Self, sqlx::Error> {
         let id = Uuid::new_v4();
         sqlx::query_as!(
             TaskComment,
-            r#"INSERT INTO task_comments (id, task_id, content, author)
-               VALUES ($1, $2, $3, $4)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", content, author, created_at as "created_at!: DateTime<Utc>""#,
+            r#"INSERT INTO task_comments (id, task_id, parent_comment_id, content, author, author_type)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", parent_comment_id as "parent_comment_id: Uuid", content, author, author_type as "author_type!: TaskActorType", created_at as "created_at!: DateTime<Utc>", edited_at as "edited_at: DateTime<Utc>""#,
             id,
             data.task_id,
+            data.parent_comment_id,
             data.content,
-            data.author
+            data.author,
+            author_type
         )
         .fetch_one(pool)
         .await
     }
 
+    /// Replace the content of a comment, keeping the previous version in its edit history
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        content: &str,
+        edited_by: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let Some(existing) = Self::find_by_id(pool, id).await? else {
+            return Ok(None);
+        };
+        if existing.content == content {
+            return Ok(Some(existing));
+        }
+
+        let mut tx = pool.begin().await?;
+        let edit_id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO task_comment_edits (id, comment_id, previous_content, edited_by)
+               VALUES ($1, $2, $3, $4)"#,
+            edit_id,
+            id,
+            existing.content,
+            edited_by
+        )
+        .execute(&mut *tx)
+        .await?;
+        let comment = sqlx::query_as!(
+            TaskComment,
+            r#"UPDATE task_comments
+               SET content = $2, edited_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", parent_comment_id as "parent_comment_id: Uuid", content, author, author_type as "author_type!: TaskActorType", created_at as "created_at!: DateTime<Utc>", edited_at as "edited_at: DateTime<Utc>""#,
+            id,
+            content
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(Some(comment))
+    }
+
+    /// Edit history of a comment, oldest first
+    pub async fn find_edits(
+        pool: &SqlitePool,
+        comment_id: Uuid,
+    ) -> Result<Vec<TaskCommentEdit>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskCommentEdit,
+            r#"SELECT id as "id!: Uuid", comment_id as "comment_id!: Uuid", previous_content, edited_by, edited_at as "edited_at!: DateTime<Utc>"
+               FROM task_comment_edits
+               WHERE comment_id = $1
+               ORDER BY edited_at ASC"#,
+            comment_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_mentions(
+        pool: &SqlitePool,
+        comment_id: Uuid,
+    ) -> Result<Vec<TaskCommentMention>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskCommentMention,
+            r#"SELECT comment_id as "comment_id!: Uuid", mention, mention_type as "mention_type!: MentionType"
+               FROM task_comment_mentions
+               WHERE comment_id = $1
+               ORDER BY created_at ASC"#,
+            comment_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_mention_by_rowid(
+        pool: &SqlitePool,
+        rowid: i64,
+    ) -> Result<Option<TaskCommentMention>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskCommentMention,
+            r#"SELECT comment_id as "comment_id!: Uuid", mention, mention_type as "mention_type!: MentionType"
+               FROM task_comment_mentions
+               WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store the resolved mentions of a comment and return the ones it did not
+    /// already have, so edits only notify newly mentioned people.
+    pub async fn add_mentions(
+        pool: &SqlitePool,
+        comment_id: Uuid,
+        mentions: &[(String, MentionType)],
+    ) -> Result<Vec<TaskCommentMention>, sqlx::Error> {
+        let mut added = Vec::new();
+        for (mention, mention_type) in mentions {
+            let result = sqlx::query!(
+                r#"INSERT INTO task_comment_mentions (comment_id, mention, mention_type)
+                   VALUES ($1, $2, $3)
+                   ON CONFLICT (comment_id, mention) DO NOTHING"#,
+                comment_id,
+                mention,
+                mention_type
+            )
+            .execute(pool)
+            .await?;
+            if result.rows_affected() > 0 {
+                added.push(TaskCommentMention {
+                    comment_id,
+                    mention: mention.clone(),
+                    mention_type: mention_type.clone(),
+                });
+            }
+        }
+        Ok(added)
+    }
+
+    /// Move the read marker of `reader` on a task forward to `read_at`.
+    /// Readers are matched case-insensitively and the marker never moves back.
+    pub async fn mark_read(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        reader: &str,
+        read_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let reader = reader.to_lowercase();
+        sqlx::query!(
+            r#"INSERT INTO task_comment_reads (task_id, reader, last_read_at)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (task_id, reader) DO UPDATE
+               SET last_read_at = CASE
+                   WHEN julianday(excluded.last_read_at) > julianday(task_comment_reads.last_read_at)
+                   THEN excluded.last_read_at
+                   ELSE task_comment_reads.last_read_at
+               END"#,
+            task_id,
+            reader,
+            read_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
         let result = sqlx::query!("DELETE FROM task_comments WHERE id = $1", id)
             .execute(pool)
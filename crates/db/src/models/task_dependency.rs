@@ -0,0 +1,464 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{Task, TaskPriority, TaskStatus};
+
+#[derive(Debug, Error)]
+pub enum TaskDependencyError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Task not found")]
+    TaskNotFound,
+    #[error("A task cannot depend on itself")]
+    SelfDependency,
+    #[error("Tasks must belong to the same project")]
+    CrossProject,
+    #[error("Dependency already exists")]
+    AlreadyExists,
+    #[error("Dependency would create a cycle")]
+    CycleDetected,
+}
+
+/// A "blocked by" edge: `task_id` cannot start until `depends_on_task_id` is done.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskDependency {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub depends_on_task_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateTaskDependency {
+    pub depends_on_task_id: Uuid,
+}
+
+/// Both directions of the dependency graph around a single task.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskDependencies {
+    pub task_id: Uuid,
+    /// Tasks that must be done before this task can start
+    pub blocked_by: Vec<Task>,
+    /// Tasks waiting on this task
+    pub blocks: Vec<Task>,
+    /// True if any task in `blocked_by` is not done
+    pub blocked: bool,
+}
+
+impl TaskDependency {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDependency,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", depends_on_task_id as "depends_on_task_id!: Uuid", created_at as "created_at!: DateTime<Utc>"
+               FROM task_dependencies
+               WHERE task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tasks that `task_id` is waiting on
+    pub async fn find_blockers(pool: &SqlitePool, task_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.workflow_state, t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.assignee, t.priority as "priority!: TaskPriority", t.due_date as "due_date: DateTime<Utc>", t.estimate_hours, t.agent_metadata, t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.depends_on_task_id
+               WHERE td.task_id = $1
+               ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tasks that are waiting on `task_id`
+    pub async fn find_dependents(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.workflow_state, t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.assignee, t.priority as "priority!: TaskPriority", t.due_date as "due_date: DateTime<Utc>", t.estimate_hours, t.agent_metadata, t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.task_id
+               WHERE td.depends_on_task_id = $1
+               ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Returns true if any blocker of `task_id` is not yet done
+    pub async fn is_blocked(pool: &SqlitePool, task_id: Uuid) -> Result<bool, sqlx::Error> {
+        let blocked = sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+                   SELECT 1
+                     FROM task_dependencies td
+                     JOIN tasks bt ON bt.id = td.depends_on_task_id
+                    WHERE td.task_id = $1
+                      AND bt.status != 'done'
+               ) AS "blocked!: bool""#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(blocked)
+    }
+
+    /// Todo tasks that have never been attempted and whose blockers are all done, in start
+    /// order: highest priority first, then earliest due date, then oldest
+    pub async fn find_ready_tasks(pool: &SqlitePool) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.workflow_state, t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.assignee, t.priority as "priority!: TaskPriority", t.due_date as "due_date: DateTime<Utc>", t.estimate_hours, t.agent_metadata, t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks t
+               WHERE t.status = 'todo'
+                 AND NOT EXISTS (SELECT 1 FROM workspaces w WHERE w.task_id = t.id)
+                 AND NOT EXISTS (
+                     SELECT 1
+                       FROM task_dependencies td
+                       JOIN tasks bt ON bt.id = td.depends_on_task_id
+                      WHERE td.task_id = t.id
+                        AND bt.status != 'done'
+                 )
+               ORDER BY CASE t.priority WHEN 'urgent' THEN 3 WHEN 'high' THEN 2 WHEN 'medium' THEN 1 ELSE 0 END DESC,
+                        t.due_date IS NULL, datetime(t.due_date) ASC,
+                        t.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Todo tasks that have never been attempted and are waiting on unfinished blockers, in the
+    /// same order as [`Self::find_ready_tasks`]
+    pub async fn find_blocked_tasks(pool: &SqlitePool) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.workflow_state, t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.assignee, t.priority as "priority!: TaskPriority", t.due_date as "due_date: DateTime<Utc>", t.estimate_hours, t.agent_metadata, t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks t
+               WHERE t.status = 'todo'
+                 AND NOT EXISTS (SELECT 1 FROM workspaces w WHERE w.task_id = t.id)
+                 AND EXISTS (
+                     SELECT 1
+                       FROM task_dependencies td
+                       JOIN tasks bt ON bt.id = td.depends_on_task_id
+                      WHERE td.task_id = t.id
+                        AND bt.status != 'done'
+                 )
+               ORDER BY CASE t.priority WHEN 'urgent' THEN 3 WHEN 'high' THEN 2 WHEN 'medium' THEN 1 ELSE 0 END DESC,
+                        t.due_date IS NULL, datetime(t.due_date) ASC,
+                        t.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Returns true if `from_task_id` already (transitively) depends on `to_task_id`.
+    /// Adding the edge `to_task_id -> from_task_id` would then close a cycle.
+    pub async fn depends_transitively<'e, E>(
+        executor: E,
+        from_task_id: Uuid,
+        to_task_id: Uuid,
+    ) -> Result<bool, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let reachable = sqlx::query_scalar!(
+            r#"WITH RECURSIVE reachable(id) AS (
+                   SELECT depends_on_task_id FROM task_dependencies WHERE task_id = $1
+                   UNION
+                   SELECT td.depends_on_task_id
+                     FROM task_dependencies td
+                     JOIN reachable r ON td.task_id = r.id
+               )
+               SELECT EXISTS (SELECT 1 FROM reachable WHERE id = $2) AS "reachable!: bool""#,
+            from_task_id,
+            to_task_id
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(reachable)
+    }
+
+    async fn project_id_of<'e, E>(executor: E, task_id: Uuid) -> Result<Option<Uuid>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_scalar!(
+            r#"SELECT project_id AS "project_id!: Uuid" FROM tasks WHERE id = $1"#,
+            task_id
+        )
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Create a dependency after validating both tasks exist in the same project,
+    /// the edge is new, and it does not introduce a cycle. The checks and the insert
+    /// run in one immediate transaction, so two concurrent requests cannot each pass
+    /// the cycle check and together close a cycle.
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &CreateTaskDependency,
+    ) -> Result<Self, TaskDependencyError> {
+        let depends_on_task_id = data.depends_on_task_id;
+        if task_id == depends_on_task_id {
+            return Err(TaskDependencyError::SelfDependency);
+        }
+
+        // Take the write lock before reading, not at the insert
+        let mut tx = pool.begin_with("BEGIN IMMEDIATE").await?;
+
+        let task_project_id = Self::project_id_of(&mut *tx, task_id)
+            .await?
+            .ok_or(TaskDependencyError::TaskNotFound)?;
+        let blocker_project_id = Self::project_id_of(&mut *tx, depends_on_task_id)
+            .await?
+            .ok_or(TaskDependencyError::TaskNotFound)?;
+        if task_project_id != blocker_project_id {
+            return Err(TaskDependencyError::CrossProject);
+        }
+
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+                   SELECT 1 FROM task_dependencies WHERE task_id = $1 AND depends_on_task_id = $2
+               ) AS "exists!: bool""#,
+            task_id,
+            depends_on_task_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if exists {
+            return Err(TaskDependencyError::AlreadyExists);
+        }
+
+        if Self::depends_transitively(&mut *tx, depends_on_task_id, task_id).await? {
+            return Err(TaskDependencyError::CycleDetected);
+        }
+
+        let id = Uuid::new_v4();
+        let dependency = sqlx::query_as!(
+            TaskDependency,
+            r#"INSERT INTO task_dependencies (id, task_id, depends_on_task_id)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", depends_on_task_id as "depends_on_task_id!: Uuid", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            depends_on_task_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(dependency)
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM task_dependencies WHERE task_id = $1 AND depends_on_task_id = $2",
+            task_id,
+            depends_on_task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_dependencies_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<TaskDependencies, sqlx::Error> {
+        let blocked_by = Self::find_blockers(pool, task_id).await?;
+        let blocks = Self::find_dependents(pool, task_id).await?;
+        let blocked = blocked_by.iter().any(|t| t.status != TaskStatus::Done);
+
+        Ok(TaskDependencies {
+            task_id,
+            blocked_by,
+            blocks,
+            blocked,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::test_utils::{create_project, create_task, pool};
+
+    async fn depend(
+        pool: &SqlitePool,
+        task: &Task,
+        blocker: &Task,
+    ) -> Result<TaskDependency, TaskDependencyError> {
+        let data = CreateTaskDependency {
+            depends_on_task_id: blocker.id,
+        };
+        TaskDependency::create(pool, task.id, &data).await
+    }
+
+    fn ids(tasks: &[Task]) -> HashSet<Uuid> {
+        tasks.iter().map(|task| task.id).collect()
+    }
+
+    #[tokio::test]
+    async fn rejects_self_dependency() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let task = create_task(&pool, project.id, "a", TaskStatus::Todo).await;
+
+        assert!(matches!(
+            depend(&pool, &task, &task).await,
+            Err(TaskDependencyError::SelfDependency)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_direct_cycle() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let a = create_task(&pool, project.id, "a", TaskStatus::Todo).await;
+        let b = create_task(&pool, project.id, "b", TaskStatus::Todo).await;
+
+        depend(&pool, &a, &b).await.unwrap();
+        assert!(matches!(
+            depend(&pool, &b, &a).await,
+            Err(TaskDependencyError::CycleDetected)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_transitive_cycle() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let a = create_task(&pool, project.id, "a", TaskStatus::Todo).await;
+        let b = create_task(&pool, project.id, "b", TaskStatus::Todo).await;
+        let c = create_task(&pool, project.id, "c", TaskStatus::Todo).await;
+
+        depend(&pool, &a, &b).await.unwrap();
+        depend(&pool, &b, &c).await.unwrap();
+        assert!(
+            TaskDependency::depends_transitively(&pool, a.id, c.id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !TaskDependency::depends_transitively(&pool, c.id, a.id)
+                .await
+                .unwrap()
+        );
+        assert!(matches!(
+            depend(&pool, &c, &a).await,
+            Err(TaskDependencyError::CycleDetected)
+        ));
+        // A shortcut along the existing direction is not a cycle
+        depend(&pool, &a, &c).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn classifies_ready_and_blocked_tasks() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let blocker = create_task(&pool, project.id, "blocker", TaskStatus::Todo).await;
+        let waiting = create_task(&pool, project.id, "waiting", TaskStatus::Todo).await;
+        let finished = create_task(&pool, project.id, "finished", TaskStatus::Done).await;
+        let unblocked = create_task(&pool, project.id, "unblocked", TaskStatus::Todo).await;
+        let started = create_task(&pool, project.id, "started", TaskStatus::InProgress).await;
+        depend(&pool, &waiting, &blocker).await.unwrap();
+        depend(&pool, &unblocked, &finished).await.unwrap();
+        depend(&pool, &started, &blocker).await.unwrap();
+
+        let ready = TaskDependency::find_ready_tasks(&pool).await.unwrap();
+        let blocked = TaskDependency::find_blocked_tasks(&pool).await.unwrap();
+        assert_eq!(ids(&ready), HashSet::from([blocker.id, unblocked.id]));
+        assert_eq!(ids(&blocked), HashSet::from([waiting.id]));
+        assert!(TaskDependency::is_blocked(&pool, waiting.id).await.unwrap());
+        assert!(
+            !TaskDependency::is_blocked(&pool, unblocked.id)
+                .await
+                .unwrap()
+        );
+
+        sqlx::query("UPDATE tasks SET status = 'done' WHERE id = $1")
+            .bind(blocker.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let ready = TaskDependency::find_ready_tasks(&pool).await.unwrap();
+        assert_eq!(ids(&ready), HashSet::from([waiting.id, unblocked.id]));
+        assert!(
+            TaskDependency::find_blocked_tasks(&pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn ready_tasks_are_ordered_by_priority_then_due_date_then_age() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let oldest_low = create_task(&pool, project.id, "low", TaskStatus::Todo).await;
+        let undated_high = create_task(&pool, project.id, "undated", TaskStatus::Todo).await;
+        let late_high = create_task(&pool, project.id, "late", TaskStatus::Todo).await;
+        let soon_high = create_task(&pool, project.id, "soon", TaskStatus::Todo).await;
+        let urgent = create_task(&pool, project.id, "urgent", TaskStatus::Todo).await;
+
+        for (task, priority, due_date, created_at) in [
+            (&oldest_low, "low", None, "2024-01-01 00:00:00"),
+            (&undated_high, "high", None, "2024-01-02 00:00:00"),
+            (
+                &late_high,
+                "high",
+                Some("2024-03-01T00:00:00Z"),
+                "2024-01-03 00:00:00",
+            ),
+            (
+                &soon_high,
+                "high",
+                Some("2024-02-01T00:00:00Z"),
+                "2024-01-04 00:00:00",
+            ),
+            (&urgent, "urgent", None, "2024-01-05 00:00:00"),
+        ] {
+            sqlx::query(
+                "UPDATE tasks SET priority = $2, due_date = $3, created_at = $4 WHERE id = $1",
+            )
+            .bind(task.id)
+            .bind(priority)
+            .bind(due_date)
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let ready = TaskDependency::find_ready_tasks(&pool).await.unwrap();
+        assert_eq!(
+            ready.iter().map(|task| task.id).collect::<Vec<_>>(),
+            vec![
+                urgent.id,
+                soon_high.id,
+                late_high.id,
+                undated_high.id,
+                oldest_low.id
+            ]
+        );
+    }
+}
@@ -1,17 +1,82 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, Sqlite, SqlitePool, Type};
+use strum_macros::{Display, EnumString};
 use ts_rs::TS;
+use utils::diff::create_unified_diff;
 use uuid::Uuid;
 
+use super::task::{Task, TaskStatus};
+
+/// Text values longer than this are stored as a unified diff instead of full copies
+const COMPACT_DIFF_THRESHOLD: usize = 200;
+
+/// What kind of actor changed a task
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display)]
+#[sqlx(type_name = "task_actor_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TaskActorType {
+    /// Someone using the UI or the local API
+    User,
+    /// A coding agent, usually through the MCP server
+    Agent,
+    /// A background service such as the PR monitor or shared task sync
+    System,
+    /// A script authenticated with a project API token
+    ApiToken,
+}
+
+/// Who made a task change. `name` is stored as `changed_by`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct TaskActor {
+    pub actor_type: TaskActorType,
+    pub name: String,
+}
+
+impl TaskActor {
+    pub fn user() -> Self {
+        Self {
+            actor_type: TaskActorType::User,
+            name: "user".to_string(),
+        }
+    }
+
+    pub fn agent(name: impl Into<String>) -> Self {
+        Self {
+            actor_type: TaskActorType::Agent,
+            name: name.into(),
+        }
+    }
+
+    pub fn system(service: impl Into<String>) -> Self {
+        Self {
+            actor_type: TaskActorType::System,
+            name: service.into(),
+        }
+    }
+
+    pub fn api_token(name: impl Into<String>) -> Self {
+        Self {
+            actor_type: TaskActorType::ApiToken,
+            name: name.into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct TaskHistory {
     pub id: Uuid,
     pub task_id: Uuid,
     pub field_changed: String,
+    /// Previous value. Empty when the change is stored as a diff.
     pub old_value: Option<String>,
+    /// New value. Empty when the change is stored as a diff.
     pub new_value: Option<String>,
     pub changed_by: String,
+    pub actor_type: TaskActorType,
+    /// Unified diff for long text fields such as the description
+    pub diff: Option<String>,
     pub changed_at: DateTime<Utc>,
 }
 
@@ -22,6 +87,69 @@ pub struct CreateTaskHistory {
     pub old_value: Option<String>,
     pub new_value: Option<String>,
     pub changed_by: String,
+    pub actor_type: TaskActorType,
+    pub diff: Option<String>,
+}
+
+impl CreateTaskHistory {
+    /// Build a history entry for one field. Long text values are reduced to a diff.
+    pub fn for_field(
+        task_id: Uuid,
+        field: &str,
+        old_value: Option<String>,
+        new_value: Option<String>,
+        actor: &TaskActor,
+    ) -> Self {
+        let is_long = |value: &Option<String>| {
+            value
+                .as_ref()
+                .is_some_and(|v| v.chars().count() > COMPACT_DIFF_THRESHOLD)
+        };
+        let (old_value, new_value, diff) = if is_long(&old_value) || is_long(&new_value) {
+            let diff = create_unified_diff(
+                field,
+                old_value.as_deref().unwrap_or_default(),
+                new_value.as_deref().unwrap_or_default(),
+            );
+            (None, None, Some(diff))
+        } else {
+            (old_value, new_value, None)
+        };
+
+        Self {
+            task_id,
+            field_changed: field.to_string(),
+            old_value,
+            new_value,
+            changed_by: actor.name.clone(),
+            actor_type: actor.actor_type.clone(),
+            diff,
+        }
+    }
+}
+
+/// History entry with the title of its task, for the project activity feed
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskActivity {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub history: TaskHistory,
+    pub task_title: String,
+}
+
+/// Filters for the project activity feed. Unset filters match everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct TaskActivityFilters {
+    pub task_id: Option<Uuid>,
+    pub actor_type: Option<TaskActorType>,
+    pub changed_by: Option<String>,
+    pub field_changed: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+fn uuid_string(id: Option<Uuid>) -> Option<String> {
+    id.map(|id| id.to_string())
 }
 
 impl TaskHistory {
@@ -31,7 +159,7 @@ impl TaskHistory {
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             TaskHistory,
-            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", field_changed, old_value, new_value, changed_by, changed_at as "changed_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", field_changed, old_value, new_value, changed_by, actor_type as "actor_type!: TaskActorType", diff, changed_at as "changed_at!: DateTime<Utc>"
                FROM task_history
                WHERE task_id = $1
                ORDER BY changed_at ASC"#,
@@ -44,7 +172,7 @@ impl TaskHistory {
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             TaskHistory,
-            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", field_changed, old_value, new_value, changed_by, changed_at as "changed_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", field_changed, old_value, new_value, changed_by, actor_type as "actor_type!: TaskActorType", diff, changed_at as "changed_at!: DateTime<Utc>"
                FROM task_history
                WHERE id = $1"#,
             id
@@ -53,24 +181,214 @@ impl TaskHistory {
         .await
     }
 
+    /// Newest-first history across all tasks of a project
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        filters: &TaskActivityFilters,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<TaskActivity>, sqlx::Error> {
+        use sqlx::{QueryBuilder, Row};
+
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            r#"SELECT h.id, h.task_id, h.field_changed, h.old_value, h.new_value, h.changed_by, h.actor_type, h.diff, h.changed_at, t.title AS task_title
+               FROM task_history h
+               JOIN tasks t ON t.id = h.task_id
+               WHERE t.project_id = "#,
+        );
+        query_builder.push_bind(project_id);
+
+        if let Some(task_id) = filters.task_id {
+            query_builder.push(" AND h.task_id = ");
+            query_builder.push_bind(task_id);
+        }
+        if let Some(actor_type) = &filters.actor_type {
+            query_builder.push(" AND h.actor_type = ");
+            query_builder.push_bind(actor_type.to_string());
+        }
+        if let Some(changed_by) = &filters.changed_by {
+            query_builder.push(" AND h.changed_by = ");
+            query_builder.push_bind(changed_by.clone());
+        }
+        if let Some(field_changed) = &filters.field_changed {
+            query_builder.push(" AND h.field_changed = ");
+            query_builder.push_bind(field_changed.clone());
+        }
+        if let Some(since) = filters.since {
+            query_builder.push(" AND datetime(h.changed_at) >= datetime(");
+            query_builder.push_bind(since);
+            query_builder.push(")");
+        }
+        if let Some(until) = filters.until {
+            query_builder.push(" AND datetime(h.changed_at) <= datetime(");
+            query_builder.push_bind(until);
+            query_builder.push(")");
+        }
+
+        query_builder.push(" ORDER BY h.changed_at DESC LIMIT ");
+        query_builder.push_bind(limit as i64);
+        query_builder.push(" OFFSET ");
+        query_builder.push_bind(offset as i64);
+
+        let rows = query_builder.build().fetch_all(pool).await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(TaskActivity {
+                    history: TaskHistory {
+                        id: row.try_get("id")?,
+                        task_id: row.try_get("task_id")?,
+                        field_changed: row.try_get("field_changed")?,
+                        old_value: row.try_get("old_value")?,
+                        new_value: row.try_get("new_value")?,
+                        changed_by: row.try_get("changed_by")?,
+                        actor_type: row.try_get("actor_type")?,
+                        diff: row.try_get("diff")?,
+                        changed_at: row.try_get("changed_at")?,
+                    },
+                    task_title: row.try_get("task_title")?,
+                })
+            })
+            .collect()
+    }
+
     pub async fn create(pool: &SqlitePool, data: &CreateTaskHistory) -> Result<Self, sqlx::Error> {
         let id = Uuid::new_v4();
         sqlx::query_as!(
             TaskHistory,
-            r#"INSERT INTO task_history (id, task_id, field_changed, old_value, new_value, changed_by)
-               VALUES ($1, $2, $3, $4, $5, $6)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", field_changed, old_value, new_value, changed_by, changed_at as "changed_at!: DateTime<Utc>""#,
+            r#"INSERT INTO task_history (id, task_id, field_changed, old_value, new_value, changed_by, actor_type, diff)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", field_changed, old_value, new_value, changed_by, actor_type as "actor_type!: TaskActorType", diff, changed_at as "changed_at!: DateTime<Utc>""#,
             id,
             data.task_id,
             data.field_changed,
             data.old_value,
             data.new_value,
-            data.changed_by
+            data.changed_by,
+            data.actor_type,
+            data.diff
         )
         .fetch_one(pool)
         .await
     }
 
+    /// Record the creation of a task
+    pub async fn record_created(
+        pool: &SqlitePool,
+        task: &Task,
+        actor: &TaskActor,
+    ) -> Result<Self, sqlx::Error> {
+        let entry =
+            CreateTaskHistory::for_field(task.id, "created", None, Some(task.title.clone()), actor);
+        Self::create(pool, &entry).await
+    }
+
+    /// Record a status change for callers that update the status column directly.
+    /// Does nothing if the status did not change.
+    pub async fn record_status_change(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        old_status: &TaskStatus,
+        new_status: &TaskStatus,
+        actor: &TaskActor,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        if old_status == new_status {
+            return Ok(None);
+        }
+        let entry = CreateTaskHistory::for_field(
+            task_id,
+            "status",
+            Some(old_status.to_string()),
+            Some(new_status.to_string()),
+            actor,
+        );
+        Self::create(pool, &entry).await.map(Some)
+    }
+
+    /// Record one entry per field that differs between two snapshots of the same task.
+    ///
+    /// This is the single place task mutations are turned into history, so every
+    /// mutation path should pass its before and after snapshots through here.
+    pub async fn record_changes(
+        pool: &SqlitePool,
+        before: &Task,
+        after: &Task,
+        actor: &TaskActor,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut entries = Vec::new();
+        let mut push = |field: &str, old: Option<String>, new: Option<String>| {
+            if old != new {
+                entries.push(CreateTaskHistory::for_field(
+                    after.id, field, old, new, actor,
+                ));
+            }
+        };
+
+        push(
+            "title",
+            Some(before.title.clone()),
+            Some(after.title.clone()),
+        );
+        push(
+            "description",
+            before.description.clone(),
+            after.description.clone(),
+        );
+        push(
+            "status",
+            Some(before.status.to_string()),
+            Some(after.status.to_string()),
+        );
+        push(
+            "workflow_state",
+            before.workflow_state.clone(),
+            after.workflow_state.clone(),
+        );
+        push("assignee", before.assignee.clone(), after.assignee.clone());
+        push(
+            "priority",
+            Some(before.priority.to_string()),
+            Some(after.priority.to_string()),
+        );
+        push(
+            "due_date",
+            before.due_date.map(|d| d.to_rfc3339()),
+            after.due_date.map(|d| d.to_rfc3339()),
+        );
+        push(
+            "estimate_hours",
+            before.estimate_hours.map(|h| h.to_string()),
+            after.estimate_hours.map(|h| h.to_string()),
+        );
+        push(
+            "parent_workspace_id",
+            uuid_string(before.parent_workspace_id),
+            uuid_string(after.parent_workspace_id),
+        );
+        push(
+            "shared_task_id",
+            uuid_string(before.shared_task_id),
+            uuid_string(after.shared_task_id),
+        );
+
+        // Agent metadata is append-only, so record the new entries rather than the whole array
+        let previous_count = before.get_agent_metadata_entries().len();
+        for entry in after
+            .get_agent_metadata_entries()
+            .into_iter()
+            .skip(previous_count)
+        {
+            push("agent_metadata", None, serde_json::to_string(&entry).ok());
+        }
+
+        let mut recorded = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            recorded.push(Self::create(pool, entry).await?);
+        }
+        Ok(recorded)
+    }
+
     pub async fn delete_by_task_id(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
         let result = sqlx::query!("DELETE FROM task_history WHERE task_id = $1", task_id)
             .execute(pool)
@@ -78,3 +396,145 @@ impl TaskHistory {
         Ok(result.rows_affected())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::task::TaskPriority,
+        test_utils::{create_project, create_task, pool},
+    };
+
+    #[test]
+    fn short_values_are_stored_in_full() {
+        let task_id = Uuid::new_v4();
+        let entry = CreateTaskHistory::for_field(
+            task_id,
+            "title",
+            Some("Old title".to_string()),
+            Some("New title".to_string()),
+            &TaskActor::agent("claude"),
+        );
+
+        assert_eq!(entry.task_id, task_id);
+        assert_eq!(entry.field_changed, "title");
+        assert_eq!(entry.old_value.as_deref(), Some("Old title"));
+        assert_eq!(entry.new_value.as_deref(), Some("New title"));
+        assert_eq!(entry.changed_by, "claude");
+        assert_eq!(entry.actor_type, TaskActorType::Agent);
+        assert!(entry.diff.is_none());
+    }
+
+    #[test]
+    fn long_values_are_stored_as_a_diff() {
+        let old = "unchanged line\n".repeat(20);
+        let new = format!("{old}added line\n");
+        assert!(old.chars().count() > COMPACT_DIFF_THRESHOLD);
+
+        let entry = CreateTaskHistory::for_field(
+            Uuid::new_v4(),
+            "description",
+            Some(old.clone()),
+            Some(new),
+            &TaskActor::user(),
+        );
+
+        assert!(entry.old_value.is_none());
+        assert!(entry.new_value.is_none());
+        let diff = entry.diff.expect("diff for long values");
+        assert!(diff.contains("+added line"));
+        // Only the changed region and its context are kept, not both full copies
+        assert!(diff.len() < old.len());
+    }
+
+    #[test]
+    fn one_long_side_is_enough_for_a_diff() {
+        let entry = CreateTaskHistory::for_field(
+            Uuid::new_v4(),
+            "description",
+            None,
+            Some("x".repeat(COMPACT_DIFF_THRESHOLD + 1)),
+            &TaskActor::user(),
+        );
+
+        assert!(entry.old_value.is_none());
+        assert!(entry.new_value.is_none());
+        assert!(entry.diff.is_some());
+    }
+
+    #[tokio::test]
+    async fn record_changes_skips_unchanged_fields() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let before = create_task(&pool, project.id, "Write docs", TaskStatus::Todo).await;
+
+        let mut after = before.clone();
+        after.status = TaskStatus::InProgress;
+        after.priority = TaskPriority::High;
+        after.assignee = Some("sam".to_string());
+
+        let recorded =
+            TaskHistory::record_changes(&pool, &before, &after, &TaskActor::system("scheduler"))
+                .await
+                .unwrap();
+
+        let mut fields: Vec<_> = recorded.iter().map(|h| h.field_changed.as_str()).collect();
+        fields.sort();
+        assert_eq!(fields, ["assignee", "priority", "status"]);
+
+        let status = recorded
+            .iter()
+            .find(|h| h.field_changed == "status")
+            .unwrap();
+        assert_eq!(status.old_value.as_deref(), Some("todo"));
+        assert_eq!(status.new_value.as_deref(), Some("inprogress"));
+        assert_eq!(status.actor_type, TaskActorType::System);
+        assert_eq!(status.changed_by, "scheduler");
+
+        let stored = TaskHistory::find_by_task_id(&pool, before.id)
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn record_changes_stores_long_descriptions_as_diffs() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let mut before = create_task(&pool, project.id, "Write docs", TaskStatus::Todo).await;
+        before.description = Some("step\n".repeat(50));
+
+        let mut after = before.clone();
+        after.description = Some(format!("{}final step\n", "step\n".repeat(50)));
+
+        let recorded = TaskHistory::record_changes(&pool, &before, &after, &TaskActor::user())
+            .await
+            .unwrap();
+
+        assert_eq!(recorded.len(), 1);
+        let entry = &recorded[0];
+        assert_eq!(entry.field_changed, "description");
+        assert!(entry.old_value.is_none());
+        assert!(entry.new_value.is_none());
+        assert!(entry.diff.as_deref().unwrap().contains("+final step"));
+    }
+
+    #[tokio::test]
+    async fn record_changes_without_changes_records_nothing() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let task = create_task(&pool, project.id, "Write docs", TaskStatus::Todo).await;
+
+        let recorded = TaskHistory::record_changes(&pool, &task, &task, &TaskActor::user())
+            .await
+            .unwrap();
+
+        assert!(recorded.is_empty());
+        assert!(
+            TaskHistory::find_by_task_id(&pool, task.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
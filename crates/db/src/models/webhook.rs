@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool, Type};
@@ -5,6 +7,8 @@ use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::task::{Task, TaskStatus};
+
 /// Webhook event types that can trigger deliveries
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display)]
 #[sqlx(type_name = "webhook_event", rename_all = "snake_case")]
@@ -15,6 +19,16 @@ pub enum WebhookEvent {
     TaskUpdated,
     TaskCompleted,
     WorkspaceStarted,
+    ExecutionCompleted,
+    ExecutionFailed,
+    ApprovalRequested,
+    ApprovalResponded,
+    PrCreated,
+    PrMerged,
+    PrClosed,
+    TaskCommentAdded,
+    TaskCommentMentioned,
+    TaskAssigned,
 }
 
 impl WebhookEvent {
@@ -25,6 +39,16 @@ impl WebhookEvent {
             WebhookEvent::TaskUpdated => "task_updated",
             WebhookEvent::TaskCompleted => "task_completed",
             WebhookEvent::WorkspaceStarted => "workspace_started",
+            WebhookEvent::ExecutionCompleted => "execution_completed",
+            WebhookEvent::ExecutionFailed => "execution_failed",
+            WebhookEvent::ApprovalRequested => "approval_requested",
+            WebhookEvent::ApprovalResponded => "approval_responded",
+            WebhookEvent::PrCreated => "pr_created",
+            WebhookEvent::PrMerged => "pr_merged",
+            WebhookEvent::PrClosed => "pr_closed",
+            WebhookEvent::TaskCommentAdded => "task_comment_added",
+            WebhookEvent::TaskCommentMentioned => "task_comment_mentioned",
+            WebhookEvent::TaskAssigned => "task_assigned",
         }
     }
 
@@ -35,6 +59,16 @@ impl WebhookEvent {
             "task_updated" => Some(WebhookEvent::TaskUpdated),
             "task_completed" => Some(WebhookEvent::TaskCompleted),
             "workspace_started" => Some(WebhookEvent::WorkspaceStarted),
+            "execution_completed" => Some(WebhookEvent::ExecutionCompleted),
+            "execution_failed" => Some(WebhookEvent::ExecutionFailed),
+            "approval_requested" => Some(WebhookEvent::ApprovalRequested),
+            "approval_responded" => Some(WebhookEvent::ApprovalResponded),
+            "pr_created" => Some(WebhookEvent::PrCreated),
+            "pr_merged" => Some(WebhookEvent::PrMerged),
+            "pr_closed" => Some(WebhookEvent::PrClosed),
+            "task_comment_added" => Some(WebhookEvent::TaskCommentAdded),
+            "task_comment_mentioned" => Some(WebhookEvent::TaskCommentMentioned),
+            "task_assigned" => Some(WebhookEvent::TaskAssigned),
             _ => None,
         }
     }
@@ -49,10 +83,78 @@ pub struct Webhook {
     /// JSON array of event types, e.g., ["task_created", "task_updated"]
     pub events: String,
     pub is_active: bool,
+    /// MiniJinja template for the request body. The generic JSON envelope is sent when unset.
+    pub payload_template: Option<String>,
+    /// JSON object of extra request headers
+    pub headers: Option<String>,
+    /// JSON-serialized WebhookFilters
+    pub filters: Option<String>,
+    /// Deliveries in a row that failed after exhausting their retries
+    pub consecutive_failures: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Conditions an event must meet before a delivery is queued.
+///
+/// Every non-empty list must match. Events that are not about a task only pass
+/// when no filters are set.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct WebhookFilters {
+    /// Only tasks assigned to one of these users
+    #[serde(default)]
+    pub assignees: Vec<String>,
+    /// Only tasks currently in one of these statuses
+    #[serde(default)]
+    pub to_statuses: Vec<TaskStatus>,
+    /// Only task updates that moved the task out of one of these statuses
+    #[serde(default)]
+    pub from_statuses: Vec<TaskStatus>,
+}
+
+impl WebhookFilters {
+    pub fn is_empty(&self) -> bool {
+        self.assignees.is_empty() && self.to_statuses.is_empty() && self.from_statuses.is_empty()
+    }
+
+    /// Check the filters against the task an event is about. `previous_status` is the
+    /// status before the change, for task updates where it is known.
+    pub fn matches(&self, task: Option<&Task>, previous_status: Option<&TaskStatus>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let Some(task) = task else {
+            return false;
+        };
+
+        if !self.assignees.is_empty()
+            && !task
+                .assignee
+                .as_ref()
+                .is_some_and(|assignee| self.assignees.contains(assignee))
+        {
+            return false;
+        }
+
+        if !self.to_statuses.is_empty() && !self.to_statuses.contains(&task.status) {
+            return false;
+        }
+
+        if !self.from_statuses.is_empty() {
+            match previous_status {
+                Some(previous) if *previous != task.status => {
+                    if !self.from_statuses.contains(previous) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct CreateWebhook {
     pub project_id: Uuid,
@@ -60,14 +162,44 @@ pub struct CreateWebhook {
     pub secret: String,
     /// List of event types to subscribe to
     pub events: Vec<WebhookEvent>,
+    pub payload_template: Option<String>,
+    pub headers: Option<HashMap<String, String>>,
+    pub filters: Option<WebhookFilters>,
 }
 
+/// Fields left as `None` are unchanged. An empty template, header map or filter set
+/// clears the stored value.
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct UpdateWebhook {
     pub url: Option<String>,
     pub secret: Option<String>,
     pub events: Option<Vec<WebhookEvent>>,
     pub is_active: Option<bool>,
+    pub payload_template: Option<String>,
+    pub headers: Option<HashMap<String, String>>,
+    pub filters: Option<WebhookFilters>,
+}
+
+fn template_column(template: &str) -> Option<String> {
+    (!template.trim().is_empty()).then(|| template.to_string())
+}
+
+fn headers_column(headers: &HashMap<String, String>) -> Result<Option<String>, sqlx::Error> {
+    if headers.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(headers)
+        .map(Some)
+        .map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize headers: {}", e)))
+}
+
+fn filters_column(filters: &WebhookFilters) -> Result<Option<String>, sqlx::Error> {
+    if filters.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(filters)
+        .map(Some)
+        .map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize filters: {}", e)))
 }
 
 impl Webhook {
@@ -85,10 +217,26 @@ impl Webhook {
         self.get_events().contains(event)
     }
 
+    /// Parse the headers JSON object, empty if unset
+    pub fn get_headers(&self) -> HashMap<String, String> {
+        self.headers
+            .as_deref()
+            .and_then(|h| serde_json::from_str(h).ok())
+            .unwrap_or_default()
+    }
+
+    /// Parse the stored filters, empty (match everything) if unset
+    pub fn get_filters(&self) -> WebhookFilters {
+        self.filters
+            .as_deref()
+            .and_then(|f| serde_json::from_str(f).ok())
+            .unwrap_or_default()
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Webhook,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", payload_template, headers, filters, consecutive_failures as "consecutive_failures!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM webhooks
                WHERE id = $1"#,
             id
@@ -103,7 +251,7 @@ impl Webhook {
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Webhook,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", payload_template, headers, filters, consecutive_failures as "consecutive_failures!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM webhooks
                WHERE project_id = $1
                ORDER BY created_at DESC"#,
@@ -124,7 +272,7 @@ impl Webhook {
         let pattern = format!("%\"{}%", event_str);
         sqlx::query_as!(
             Webhook,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", payload_template, headers, filters, consecutive_failures as "consecutive_failures!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM webhooks
                WHERE project_id = $1
                  AND is_active = 1
@@ -146,7 +294,7 @@ impl Webhook {
         let pattern = format!("%\"{}%", event_str);
         sqlx::query_as!(
             Webhook,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", payload_template, headers, filters, consecutive_failures as "consecutive_failures!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM webhooks
                WHERE is_active = 1
                  AND events LIKE $1
@@ -167,17 +315,29 @@ impl Webhook {
                 .collect::<Vec<_>>(),
         )
         .map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize events: {}", e)))?;
+        let payload_template = data.payload_template.as_deref().and_then(template_column);
+        let headers_json = match &data.headers {
+            Some(headers) => headers_column(headers)?,
+            None => None,
+        };
+        let filters_json = match &data.filters {
+            Some(filters) => filters_column(filters)?,
+            None => None,
+        };
 
         sqlx::query_as!(
             Webhook,
-            r#"INSERT INTO webhooks (id, project_id, url, secret, events)
-               VALUES ($1, $2, $3, $4, $5)
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO webhooks (id, project_id, url, secret, events, payload_template, headers, filters)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", payload_template, headers, filters, consecutive_failures as "consecutive_failures!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             data.project_id,
             data.url,
             data.secret,
-            events_json
+            events_json,
+            payload_template,
+            headers_json,
+            filters_json
         )
         .fetch_one(pool)
         .await
@@ -204,18 +364,35 @@ impl Webhook {
         } else {
             existing.events.clone()
         };
+        let payload_template = match &data.payload_template {
+            Some(template) => template_column(template),
+            None => existing.payload_template.clone(),
+        };
+        let headers_json = match &data.headers {
+            Some(headers) => headers_column(headers)?,
+            None => existing.headers.clone(),
+        };
+        let filters_json = match &data.filters {
+            Some(filters) => filters_column(filters)?,
+            None => existing.filters.clone(),
+        };
 
         sqlx::query_as!(
             Webhook,
             r#"UPDATE webhooks
-               SET url = $2, secret = $3, events = $4, is_active = $5, updated_at = datetime('now', 'subsec')
+               SET url = $2, secret = $3, events = $4, is_active = $5, payload_template = $6, headers = $7, filters = $8,
+                   consecutive_failures = CASE WHEN $5 AND NOT is_active THEN 0 ELSE consecutive_failures END,
+                   updated_at = datetime('now', 'subsec')
                WHERE id = $1
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", payload_template, headers, filters, consecutive_failures as "consecutive_failures!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             url,
             secret,
             events_json,
-            is_active
+            is_active,
+            payload_template,
+            headers_json,
+            filters_json
         )
         .fetch_optional(pool)
         .await
@@ -238,6 +415,30 @@ impl Webhook {
         Ok(result.rows_affected())
     }
 
+    /// Count a permanently failed delivery and return the new number of consecutive failures
+    pub async fn record_failure(pool: &SqlitePool, id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"UPDATE webhooks
+               SET consecutive_failures = consecutive_failures + 1
+               WHERE id = $1
+               RETURNING consecutive_failures as "consecutive_failures!: i64""#,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Reset the consecutive failure count after a successful delivery
+    pub async fn reset_failures(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE webhooks SET consecutive_failures = 0 WHERE id = $1 AND consecutive_failures != 0",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Set the active status of a webhook
     pub async fn set_active(
         pool: &SqlitePool,
@@ -247,9 +448,11 @@ impl Webhook {
         sqlx::query_as!(
             Webhook,
             r#"UPDATE webhooks
-               SET is_active = $2, updated_at = datetime('now', 'subsec')
+               SET is_active = $2,
+                   consecutive_failures = CASE WHEN $2 AND NOT is_active THEN 0 ELSE consecutive_failures END,
+                   updated_at = datetime('now', 'subsec')
                WHERE id = $1
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", url, secret, events, is_active as "is_active!: bool", payload_template, headers, filters, consecutive_failures as "consecutive_failures!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             is_active
         )
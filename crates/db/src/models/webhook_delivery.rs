@@ -207,6 +207,44 @@ impl WebhookDelivery {
         .await
     }
 
+    /// Reset a delivery to pending so the worker sends it again with a fresh retry budget
+    pub async fn requeue(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"UPDATE webhook_deliveries
+               SET status = 'pending', attempts = 0, next_retry_at = NULL, delivered_at = NULL
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", webhook_id as "webhook_id!: Uuid", event_type, payload, status as "status!: DeliveryStatus", attempts as "attempts!: i64", last_error, next_retry_at as "next_retry_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", delivered_at as "delivered_at: DateTime<Utc>""#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Requeue every failed delivery of a webhook created within the given range.
+    /// Open-ended when a bound is `None`.
+    pub async fn requeue_failed(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = 'pending', attempts = 0, next_retry_at = NULL
+               WHERE webhook_id = $1
+                 AND status = 'failed'
+                 AND ($2 IS NULL OR created_at >= datetime($2, 'subsec'))
+                 AND ($3 IS NULL OR created_at <= datetime($3, 'subsec'))"#,
+            webhook_id,
+            from,
+            to
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
         let result = sqlx::query!("DELETE FROM webhook_deliveries WHERE id = $1", id)
             .execute(pool)
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What happened to a webhook
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display)]
+#[sqlx(type_name = "webhook_history_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WebhookHistoryAction {
+    Enabled,
+    Disabled,
+    /// Deactivated by the delivery engine after repeated failures
+    AutoDisabled,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WebhookHistory {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub action: WebhookHistoryAction,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateWebhookHistory {
+    pub webhook_id: Uuid,
+    pub action: WebhookHistoryAction,
+    pub reason: Option<String>,
+}
+
+impl WebhookHistory {
+    pub async fn find_by_webhook_id(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookHistory,
+            r#"SELECT id as "id!: Uuid", webhook_id as "webhook_id!: Uuid", action as "action!: WebhookHistoryAction", reason, created_at as "created_at!: DateTime<Utc>"
+               FROM webhook_history
+               WHERE webhook_id = $1
+               ORDER BY created_at DESC"#,
+            webhook_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateWebhookHistory,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            WebhookHistory,
+            r#"INSERT INTO webhook_history (id, webhook_id, action, reason)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", webhook_id as "webhook_id!: Uuid", action as "action!: WebhookHistoryAction", reason, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.webhook_id,
+            data.action,
+            data.reason
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -96,6 +96,8 @@ pub struct Workspace {
     pub setup_completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Workspace whose branch this one builds on, until that workspace has merged
+    pub stacked_on_workspace_id: Option<Uuid>,
 }
 
 /// GitHub PR creation parameters
@@ -134,6 +136,7 @@ pub struct CreateWorkspace {
     pub branch: String,
     pub agent_working_dir: Option<String>,
     pub workspace_mode: String,
+    pub stacked_on_workspace_id: Option<Uuid>,
 }
 
 impl Workspace {
@@ -167,7 +170,8 @@ impl Workspace {
                               workspace_mode,
                               setup_completed_at AS "setup_completed_at: DateTime<Utc>",
                               created_at AS "created_at!: DateTime<Utc>",
-                              updated_at AS "updated_at!: DateTime<Utc>"
+                              updated_at AS "updated_at!: DateTime<Utc>",
+                              stacked_on_workspace_id AS "stacked_on_workspace_id: Uuid"
                        FROM workspaces
                        WHERE task_id = $1
                        ORDER BY created_at DESC"#,
@@ -186,7 +190,8 @@ impl Workspace {
                               workspace_mode,
                               setup_completed_at AS "setup_completed_at: DateTime<Utc>",
                               created_at AS "created_at!: DateTime<Utc>",
-                              updated_at AS "updated_at!: DateTime<Utc>"
+                              updated_at AS "updated_at!: DateTime<Utc>",
+                              stacked_on_workspace_id AS "stacked_on_workspace_id: Uuid"
                        FROM workspaces
                        ORDER BY created_at DESC"#
             )
@@ -215,7 +220,8 @@ impl Workspace {
                        w.workspace_mode,
                        w.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
                        w.created_at        AS "created_at!: DateTime<Utc>",
-                       w.updated_at        AS "updated_at!: DateTime<Utc>"
+                       w.updated_at        AS "updated_at!: DateTime<Utc>",
+                       w.stacked_on_workspace_id AS "stacked_on_workspace_id: Uuid"
                FROM    workspaces w
                JOIN    tasks t ON w.task_id = t.id
                JOIN    projects p ON t.project_id = p.id
@@ -290,7 +296,8 @@ impl Workspace {
                        workspace_mode,
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
                        created_at        AS "created_at!: DateTime<Utc>",
-                       updated_at        AS "updated_at!: DateTime<Utc>"
+                       updated_at        AS "updated_at!: DateTime<Utc>",
+                       stacked_on_workspace_id AS "stacked_on_workspace_id: Uuid"
                FROM    workspaces
                WHERE   id = $1"#,
             id
@@ -310,7 +317,8 @@ impl Workspace {
                        workspace_mode,
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
                        created_at        AS "created_at!: DateTime<Utc>",
-                       updated_at        AS "updated_at!: DateTime<Utc>"
+                       updated_at        AS "updated_at!: DateTime<Utc>",
+                       stacked_on_workspace_id AS "stacked_on_workspace_id: Uuid"
                FROM    workspaces
                WHERE   rowid = $1"#,
             rowid
@@ -349,7 +357,8 @@ impl Workspace {
                 w.workspace_mode as "workspace_mode!",
                 w.setup_completed_at as "setup_completed_at: DateTime<Utc>",
                 w.created_at as "created_at!: DateTime<Utc>",
-                w.updated_at as "updated_at!: DateTime<Utc>"
+                w.updated_at as "updated_at!: DateTime<Utc>",
+                w.stacked_on_workspace_id as "stacked_on_workspace_id: Uuid"
             FROM workspaces w
             LEFT JOIN sessions s ON w.id = s.workspace_id
             LEFT JOIN execution_processes ep ON s.id = ep.session_id AND ep.completed_at IS NOT NULL
@@ -389,16 +398,17 @@ impl Workspace {
     ) -> Result<Self, WorkspaceError> {
         Ok(sqlx::query_as!(
             Workspace,
-            r#"INSERT INTO workspaces (id, task_id, container_ref, branch, agent_working_dir, workspace_mode, setup_completed_at)
-               VALUES ($1, $2, $3, $4, $5, $6, $7)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, agent_working_dir, workspace_mode, setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO workspaces (id, task_id, container_ref, branch, agent_working_dir, workspace_mode, setup_completed_at, stacked_on_workspace_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, agent_working_dir, workspace_mode, setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", stacked_on_workspace_id as "stacked_on_workspace_id: Uuid""#,
             id,
             task_id,
             Option::<String>::None,
             data.branch,
             data.agent_working_dir,
             data.workspace_mode,
-            Option::<DateTime<Utc>>::None
+            Option::<DateTime<Utc>>::None,
+            data.stacked_on_workspace_id
         )
         .fetch_one(pool)
         .await?)
@@ -420,6 +430,76 @@ impl Workspace {
         Ok(())
     }
 
+    /// Workspaces stacked on `parent_workspace_id`, oldest first
+    pub async fn find_stacked_on(
+        pool: &SqlitePool,
+        parent_workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT  id                AS "id!: Uuid",
+                       task_id           AS "task_id!: Uuid",
+                       container_ref,
+                       branch,
+                       agent_working_dir,
+                       workspace_mode,
+                       setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       created_at        AS "created_at!: DateTime<Utc>",
+                       updated_at        AS "updated_at!: DateTime<Utc>",
+                       stacked_on_workspace_id AS "stacked_on_workspace_id: Uuid"
+               FROM    workspaces
+               WHERE   stacked_on_workspace_id = $1
+               ORDER BY created_at ASC"#,
+            parent_workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// All workspaces that are stacked on another workspace
+    pub async fn find_all_stacked(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT  id                AS "id!: Uuid",
+                       task_id           AS "task_id!: Uuid",
+                       container_ref,
+                       branch,
+                       agent_working_dir,
+                       workspace_mode,
+                       setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       created_at        AS "created_at!: DateTime<Utc>",
+                       updated_at        AS "updated_at!: DateTime<Utc>",
+                       stacked_on_workspace_id AS "stacked_on_workspace_id: Uuid"
+               FROM    workspaces
+               WHERE   stacked_on_workspace_id IS NOT NULL
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Detach a workspace from its stack once its parent has merged
+    pub async fn clear_stacked_on(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET stacked_on_workspace_id = NULL, updated_at = datetime('now') WHERE id = $1",
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete a workspace row; its sessions, repos and merges cascade with it
+    pub async fn delete(pool: &SqlitePool, workspace_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM workspaces WHERE id = $1", workspace_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
     /// Set setup_completed_at to the current time for a workspace.
     /// Used in branch-only mode where setup completes immediately.
     pub async fn set_setup_completed(
@@ -6,7 +6,7 @@ use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::{merge::ForgeKind, repo::Repo};
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct WorkspaceRepo {
@@ -18,12 +18,18 @@ pub struct WorkspaceRepo {
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
     pub updated_at: DateTime<Utc>,
+    /// Commit of the parent workspace's branch this repo was last rebased onto,
+    /// while the workspace is stacked
+    pub stack_base_commit: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, TS)]
 pub struct CreateWorkspaceRepo {
     pub repo_id: Uuid,
     pub target_branch: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub stack_base_commit: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -55,18 +61,20 @@ impl WorkspaceRepo {
             let id = Uuid::new_v4();
             let workspace_repo = sqlx::query_as!(
                 WorkspaceRepo,
-                r#"INSERT INTO workspace_repos (id, workspace_id, repo_id, target_branch)
-                   VALUES ($1, $2, $3, $4)
+                r#"INSERT INTO workspace_repos (id, workspace_id, repo_id, target_branch, stack_base_commit)
+                   VALUES ($1, $2, $3, $4, $5)
                    RETURNING id as "id!: Uuid",
                              workspace_id as "workspace_id!: Uuid",
                              repo_id as "repo_id!: Uuid",
                              target_branch,
                              created_at as "created_at!: DateTime<Utc>",
-                             updated_at as "updated_at!: DateTime<Utc>""#,
+                             updated_at as "updated_at!: DateTime<Utc>",
+                             stack_base_commit"#,
                 id,
                 workspace_id,
                 repo.repo_id,
-                repo.target_branch
+                repo.target_branch,
+                repo.stack_base_commit
             )
             .fetch_one(pool)
             .await?;
@@ -87,7 +95,8 @@ impl WorkspaceRepo {
                       repo_id as "repo_id!: Uuid",
                       target_branch,
                       created_at as "created_at!: DateTime<Utc>",
-                      updated_at as "updated_at!: DateTime<Utc>"
+                      updated_at as "updated_at!: DateTime<Utc>",
+                      stack_base_commit
                FROM workspace_repos
                WHERE workspace_id = $1"#,
             workspace_id
@@ -106,6 +115,8 @@ impl WorkspaceRepo {
                       r.path,
                       r.name,
                       r.display_name,
+                      r.forge as "forge: ForgeKind",
+                      r.forge_url,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -127,6 +138,8 @@ impl WorkspaceRepo {
                       r.path,
                       r.name,
                       r.display_name,
+                      r.forge as "forge: ForgeKind",
+                      r.forge_url,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>",
                       wr.target_branch
@@ -147,6 +160,8 @@ impl WorkspaceRepo {
                     path: PathBuf::from(row.path),
                     name: row.name,
                     display_name: row.display_name,
+                    forge: row.forge,
+                    forge_url: row.forge_url,
                     created_at: row.created_at,
                     updated_at: row.updated_at,
                 },
@@ -167,7 +182,8 @@ impl WorkspaceRepo {
                       repo_id as "repo_id!: Uuid",
                       target_branch,
                       created_at as "created_at!: DateTime<Utc>",
-                      updated_at as "updated_at!: DateTime<Utc>"
+                      updated_at as "updated_at!: DateTime<Utc>",
+                      stack_base_commit
                FROM workspace_repos
                WHERE workspace_id = $1 AND repo_id = $2"#,
             workspace_id,
@@ -194,6 +210,25 @@ impl WorkspaceRepo {
         Ok(())
     }
 
+    /// Record the parent commit a stacked repo is now based on, or None once it
+    /// has moved off the stack
+    pub async fn update_stack_base_commit(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        stack_base_commit: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspace_repos SET stack_base_commit = $1, updated_at = datetime('now') WHERE workspace_id = $2 AND repo_id = $3",
+            stack_base_commit,
+            workspace_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn update_target_branch_for_children_of_workspace(
         pool: &SqlitePool,
         parent_workspace_id: Uuid,
@@ -228,6 +263,8 @@ impl WorkspaceRepo {
                       r.path,
                       r.name,
                       r.display_name,
+                      r.forge as "forge: ForgeKind",
+                      r.forge_url,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -0,0 +1,100 @@
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest},
+};
+use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use uuid::Uuid;
+
+use crate::models::{
+    execution_process::{CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason},
+    project::{CreateProject, Project},
+    session::{CreateSession, Session},
+    task::{CreateTask, Task, TaskStatus},
+    workspace::{CreateWorkspace, Workspace},
+};
+
+/// Fresh in-memory database with every migration applied. A single connection
+/// that never expires keeps the database alive for the whole test.
+pub async fn pool() -> SqlitePool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await
+        .expect("in-memory database");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+pub async fn create_project(pool: &SqlitePool) -> Project {
+    let data = CreateProject {
+        name: "Test project".to_string(),
+        repositories: vec![],
+    };
+    Project::create(pool, &data, Uuid::new_v4())
+        .await
+        .expect("project")
+}
+
+pub async fn create_task(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    title: &str,
+    status: TaskStatus,
+) -> Task {
+    let mut data = CreateTask::from_title_description(project_id, title.to_string(), None);
+    data.status = Some(status);
+    Task::create(pool, &data, Uuid::new_v4())
+        .await
+        .expect("task")
+}
+
+/// A running setup script in a new workspace and session of the task
+pub async fn create_execution_process(pool: &SqlitePool, task_id: Uuid) -> ExecutionProcess {
+    let workspace = Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: "test-branch".to_string(),
+            agent_working_dir: None,
+            workspace_mode: "worktree".to_string(),
+            stacked_on_workspace_id: None,
+        },
+        Uuid::new_v4(),
+        task_id,
+    )
+    .await
+    .expect("workspace");
+    let session = Session::create(
+        pool,
+        &CreateSession { executor: None },
+        Uuid::new_v4(),
+        workspace.id,
+    )
+    .await
+    .expect("session");
+    let action = ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: "true".to_string(),
+            context: ScriptContext::SetupScript,
+            working_dir: None,
+            options: Default::default(),
+        }),
+        None,
+    );
+    ExecutionProcess::create(
+        pool,
+        &CreateExecutionProcess {
+            session_id: session.id,
+            executor_action: action,
+            run_reason: ExecutionProcessRunReason::SetupScript,
+        },
+        Uuid::new_v4(),
+        &[],
+    )
+    .await
+    .expect("execution process")
+}
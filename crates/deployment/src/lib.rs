@@ -33,6 +33,7 @@ use services::services::{
     repo::RepoService,
     share::SharePublisher,
     webhook_worker::WebhookWorkerService,
+    webhooks::WebhookService,
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
@@ -108,6 +109,8 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn approvals(&self) -> &Approvals;
 
+    fn webhooks(&self) -> &WebhookService;
+
     fn queued_message_service(&self) -> &QueuedMessageService;
 
     fn auth_context(&self) -> &AuthContext;
@@ -142,10 +145,20 @@ pub trait Deployment: Clone + Send + Sync + 'static {
     /// This worker processes pending webhook deliveries on a configurable interval.
     /// The interval can be set via the `WEBHOOK_WORKER_POLL_INTERVAL_SECS` environment variable.
     async fn spawn_webhook_worker_service(&self) -> tokio::task::JoinHandle<()> {
-        let db = self.db().clone();
-        WebhookWorkerService::spawn(db).await
+        WebhookWorkerService::spawn(self.webhooks().clone()).await
     }
 
+    /// Spawn the background scheduler that auto-starts ready tasks.
+    ///
+    /// Implemented per deployment because the scheduler needs an owned container service.
+    async fn spawn_task_scheduler_service(&self) -> tokio::task::JoinHandle<()>;
+
+    /// Spawn the background monitor that restacks stacked workspaces onto their
+    /// parents.
+    ///
+    /// Implemented per deployment because the monitor needs an owned container service.
+    async fn spawn_stack_monitor_service(&self) -> tokio::task::JoinHandle<()>;
+
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
         let analytics_enabled = self.config().read().await.analytics_enabled;
         // Track events unless user has explicitly opted out
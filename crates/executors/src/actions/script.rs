@@ -1,22 +1,44 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::shell::get_shell_command;
 
 use crate::{
     actions::Executable,
     approvals::ExecutorApprovalService,
+    command::{CommandBuildError, CommandBuilder},
     env::ExecutionEnv,
     executors::{ExecutorError, SpawnedChild},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
 pub enum ScriptRequestLanguage {
+    #[default]
     Bash,
+    Python,
+    Node,
+    PowerShell,
+    Custom,
+}
+
+impl ScriptRequestLanguage {
+    /// Interpreter command; the script is passed as its last argument
+    fn default_interpreter(&self) -> Option<&'static str> {
+        match self {
+            Self::Bash => None,
+            Self::Python if cfg!(windows) => Some("python -c"),
+            Self::Python => Some("python3 -c"),
+            Self::Node => Some("node -e"),
+            Self::PowerShell => Some("pwsh -NoProfile -NonInteractive -Command"),
+            Self::Custom => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -25,17 +47,68 @@ pub enum ScriptContext {
     CleanupScript,
     DevServer,
     ToolInstallScript,
+    PipelineStep,
+    Verification,
+}
+
+/// How a script is interpreted and how long and how much it may run
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+pub struct ScriptOptions {
+    #[serde(default)]
+    pub language: ScriptRequestLanguage,
+    /// Interpreter command, e.g. `ruby -e` or `/opt/venv/bin/python -c`. The script is
+    /// passed as its last argument. Required for `Custom`, overrides the default otherwise.
+    #[serde(default)]
+    pub interpreter: Option<String>,
+    /// Kill the script and fail the process after this many seconds
+    #[serde(default)]
+    pub timeout_secs: Option<u32>,
+    /// Data segment limit per process in megabytes, on top of the sandbox config
+    #[serde(default)]
+    pub memory_limit_mb: Option<u32>,
+    /// CPU time limit per process in seconds, on top of the sandbox config
+    #[serde(default)]
+    pub cpu_time_limit_secs: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ScriptRequest {
     pub script: String,
-    pub language: ScriptRequestLanguage,
     pub context: ScriptContext,
     /// Optional relative path to execute the script in (relative to container_ref).
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    #[serde(flatten)]
+    pub options: ScriptOptions,
+}
+
+impl ScriptRequest {
+    async fn interpreter(&self) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        let interpreter = self
+            .options
+            .interpreter
+            .as_deref()
+            .map(str::trim)
+            .filter(|interpreter| !interpreter.is_empty())
+            .or(self.options.language.default_interpreter());
+
+        match interpreter {
+            Some(interpreter) => {
+                CommandBuilder::new(interpreter)
+                    .build_initial()?
+                    .into_resolved()
+                    .await
+            }
+            None if self.options.language == ScriptRequestLanguage::Custom => {
+                Err(CommandBuildError::EmptyCommand.into())
+            }
+            None => {
+                let (shell_cmd, shell_arg) = get_shell_command();
+                Ok((PathBuf::from(shell_cmd), vec![shell_arg.to_string()]))
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -52,14 +125,14 @@ impl Executable for ScriptRequest {
             None => current_dir.to_path_buf(),
         };
 
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+        let (program, args) = self.interpreter().await?;
+        let mut command = env.command(program);
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .arg(shell_arg)
+            .args(&args)
             .arg(&self.script)
             .current_dir(&effective_dir);
 
@@ -71,3 +144,50 @@ impl Executable for ScriptRequest {
         Ok(child.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_requests_stored_before_script_options() {
+        let request: ScriptRequest = serde_json::from_str(
+            r#"{"script":"npm install","language":"Bash","context":"SetupScript","working_dir":"web"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(request.options, ScriptOptions::default());
+        assert_eq!(request.working_dir.as_deref(), Some("web"));
+    }
+
+    #[test]
+    fn options_are_flattened_into_the_request() {
+        let request: ScriptRequest = serde_json::from_str(
+            r#"{"script":"print(1)","context":"DevServer","language":"Python","timeout_secs":30}"#,
+        )
+        .unwrap();
+
+        assert_eq!(request.options.language, ScriptRequestLanguage::Python);
+        assert_eq!(request.options.timeout_secs, Some(30));
+        assert_eq!(request.working_dir, None);
+    }
+
+    #[tokio::test]
+    async fn custom_language_requires_an_interpreter() {
+        let request = ScriptRequest {
+            script: "puts 1".to_string(),
+            context: ScriptContext::SetupScript,
+            working_dir: None,
+            options: ScriptOptions {
+                language: ScriptRequestLanguage::Custom,
+                interpreter: Some("  ".to_string()),
+                ..Default::default()
+            },
+        };
+
+        assert!(matches!(
+            request.interpreter().await,
+            Err(ExecutorError::CommandBuild(CommandBuildError::EmptyCommand))
+        ));
+    }
+}
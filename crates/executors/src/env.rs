@@ -1,19 +1,35 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ffi::OsStr};
 
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{command::CmdOverrides, sandbox::Sandbox};
 
-/// Environment variables to inject into executor processes
+/// Environment variables to inject into executor processes, and the sandbox they run in
 #[derive(Debug, Clone, Default)]
 pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
+    pub sandbox: Option<Sandbox>,
 }
 
 impl ExecutionEnv {
     pub fn new() -> Self {
         Self {
             vars: HashMap::new(),
+            sandbox: None,
+        }
+    }
+
+    /// Run executor processes inside `sandbox`
+    pub fn with_sandbox(mut self, sandbox: Option<Sandbox>) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Create the command for an executor process, wrapped in the sandbox if there is one
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        match &self.sandbox {
+            Some(sandbox) => sandbox.command(program),
+            None => Command::new(program),
         }
     }
 
@@ -9,7 +9,7 @@ use agent_client_protocol as proto;
 use agent_client_protocol::Agent as _;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
-use tokio::{io::AsyncWriteExt, process::Command, sync::mpsc};
+use tokio::{io::AsyncWriteExt, sync::mpsc};
 use tokio_util::{
     compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt},
     io::ReaderStream,
@@ -22,7 +22,10 @@ use crate::{
     approvals::ExecutorApprovalService,
     command::{CmdOverrides, CommandParts},
     env::ExecutionEnv,
-    executors::{ExecutorError, ExecutorExitResult, SpawnedChild, acp::AcpEvent},
+    executors::{
+        ExecutorError, ExecutorExitResult, SpawnedChild,
+        acp::{AcpEvent, AcpUsage},
+    },
 };
 
 /// Reusable harness for ACP-based conns (Gemini, Qwen, etc.)
@@ -78,7 +81,7 @@ impl AcpAgentHarness {
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
-        let mut command = Command::new(program_path);
+        let mut command = env.command(program_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -127,7 +130,7 @@ impl AcpAgentHarness {
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
-        let mut command = Command::new(program_path);
+        let mut command = env.command(program_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -441,6 +444,12 @@ impl AcpAgentHarness {
                             // Send the prompt and await completion to obtain stop_reason
                             match conn.prompt(req).await {
                                 Ok(resp) => {
+                                    if let Some(usage) = serde_json::to_value(&resp)
+                                        .ok()
+                                        .and_then(|resp| AcpUsage::from_prompt_response(&resp))
+                                    {
+                                        let _ = log_tx.send(AcpEvent::Usage(usage).to_string());
+                                    }
                                     // Emit done with stop_reason
                                     let stop_reason = serde_json::to_string(&resp.stop_reason)
                                         .unwrap_or_default();
@@ -28,6 +28,7 @@ pub enum AcpEvent {
     ApprovalResponse(ApprovalResponse),
     Error(String),
     Done(String),
+    Usage(AcpUsage),
     Other(agent_client_protocol::SessionNotification),
 }
 
@@ -45,6 +46,42 @@ impl FromStr for AcpEvent {
     }
 }
 
+/// Token counts attached to a prompt response. Agents report them either as
+/// `usage` or inside `_meta`, in camelCase or snake_case.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcpUsage {
+    #[serde(default, alias = "input_tokens")]
+    pub input_tokens: u64,
+    #[serde(default, alias = "output_tokens")]
+    pub output_tokens: u64,
+    #[serde(
+        default,
+        alias = "cachedReadTokens",
+        alias = "cache_read_tokens",
+        alias = "cache_read_input_tokens"
+    )]
+    pub cache_read_tokens: u64,
+    #[serde(
+        default,
+        alias = "cachedWriteTokens",
+        alias = "cache_write_tokens",
+        alias = "cache_creation_input_tokens"
+    )]
+    pub cache_write_tokens: u64,
+}
+
+impl AcpUsage {
+    pub fn from_prompt_response(response: &serde_json::Value) -> Option<Self> {
+        let usage = response
+            .get("usage")
+            .or_else(|| response.pointer("/_meta/usage"))?;
+        serde_json::from_value::<Self>(usage.clone())
+            .ok()
+            .filter(|usage| usage != &Self::default())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApprovalResponse {
     pub tool_call_id: String,
@@ -8,7 +8,7 @@ use agent_client_protocol::{self as acp, SessionNotification};
 use futures::StreamExt;
 use regex::Regex;
 use serde::Deserialize;
-use workspace_utils::{approvals::ApprovalStatus, msg_store::MsgStore};
+use workspace_utils::{approvals::ApprovalStatus, log_msg::TokenUsage, msg_store::MsgStore};
 
 pub use super::AcpAgentHarness;
 use super::AcpEvent;
@@ -64,6 +64,16 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         streaming.assistant_text = None;
                         streaming.thinking_text = None;
                     }
+                    AcpEvent::Usage(usage) => {
+                        msg_store.push_usage(TokenUsage {
+                            model: None,
+                            input_tokens: usage.input_tokens,
+                            output_tokens: usage.output_tokens,
+                            cache_read_tokens: usage.cache_read_tokens,
+                            cache_write_tokens: usage.cache_write_tokens,
+                            cost_usd: None,
+                        });
+                    }
                     AcpEvent::Message(content) => {
                         streaming.thinking_text = None;
                         if let agent_client_protocol::ContentBlock::Text(text) = content {
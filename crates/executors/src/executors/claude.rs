@@ -10,10 +10,12 @@ use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::create_unified_diff, log_msg::LogMsg, msg_store::MsgStore,
+    approvals::ApprovalStatus,
+    diff::create_unified_diff,
+    log_msg::{LogMsg, TokenUsage},
+    msg_store::MsgStore,
     path::make_path_relative,
 };
 
@@ -241,7 +243,7 @@ impl ClaudeCode {
         let (program_path, args) = command_parts.into_resolved().await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let mut command = env.command(program_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -368,7 +370,10 @@ impl ClaudeLogProcessor {
             while let Some(Ok(msg)) = stream.next().await {
                 let chunk = match msg {
                     LogMsg::Stdout(x) => x,
-                    LogMsg::JsonPatch(_) | LogMsg::SessionId(_) | LogMsg::Stderr(_) => continue,
+                    LogMsg::JsonPatch(_)
+                    | LogMsg::SessionId(_)
+                    | LogMsg::Usage(_)
+                    | LogMsg::Stderr(_) => continue,
                     LogMsg::Finished => break,
                 };
 
@@ -404,6 +409,12 @@ impl ClaudeLogProcessor {
                                 session_id_extracted = true;
                             }
 
+                            if let Some(usage) =
+                                Self::extract_usage(&claude_json, processor.model_name.as_deref())
+                            {
+                                msg_store.push_usage(usage);
+                            }
+
                             let patches = processor.normalize_entries(
                                 &claude_json,
                                 &worktree_path,
@@ -452,6 +463,29 @@ impl ClaudeLogProcessor {
         });
     }
 
+    /// Extract the token usage of the whole run from the final result message
+    fn extract_usage(claude_json: &ClaudeJson, model: Option<&str>) -> Option<TokenUsage> {
+        let ClaudeJson::Result {
+            usage,
+            total_cost_usd,
+            ..
+        } = claude_json
+        else {
+            return None;
+        };
+        let usage = usage.as_ref();
+        let tokens = |count: Option<u64>| count.unwrap_or(0);
+        let usage = TokenUsage {
+            model: model.map(str::to_string),
+            input_tokens: tokens(usage.and_then(|u| u.input_tokens)),
+            output_tokens: tokens(usage.and_then(|u| u.output_tokens)),
+            cache_read_tokens: tokens(usage.and_then(|u| u.cache_read_input_tokens)),
+            cache_write_tokens: tokens(usage.and_then(|u| u.cache_creation_input_tokens)),
+            cost_usd: *total_cost_usd,
+        };
+        (!usage.is_empty()).then_some(usage)
+    }
+
     /// Extract session ID from Claude JSON
     fn extract_session_id(claude_json: &ClaudeJson) -> Option<String> {
         match claude_json {
@@ -1504,6 +1538,10 @@ pub enum ClaudeJson {
         num_turns: Option<u32>,
         #[serde(default, alias = "sessionId")]
         session_id: Option<String>,
+        #[serde(default)]
+        usage: Option<ClaudeUsage>,
+        #[serde(default, alias = "totalCostUsd")]
+        total_cost_usd: Option<f64>,
     },
     #[serde(rename = "approval_response")]
     ApprovalResponse {
@@ -1903,6 +1941,24 @@ mod tests {
         assert_eq!(entries.len(), 0); // Should be ignored like in old implementation
     }
 
+    #[test]
+    fn test_result_usage_extraction() {
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"total_cost_usd":0.042,"usage":{"input_tokens":12,"cache_creation_input_tokens":3400,"cache_read_input_tokens":18000,"output_tokens":560}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+
+        let usage = ClaudeLogProcessor::extract_usage(&parsed, Some("claude-sonnet-4")).unwrap();
+        assert_eq!(usage.model.as_deref(), Some("claude-sonnet-4"));
+        assert_eq!(usage.input_tokens, 12);
+        assert_eq!(usage.output_tokens, 560);
+        assert_eq!(usage.cache_read_tokens, 18000);
+        assert_eq!(usage.cache_write_tokens, 3400);
+        assert_eq!(usage.cost_usd, Some(0.042));
+
+        let without_usage = r#"{"type":"result","subtype":"success","is_error":false}"#;
+        let parsed: ClaudeJson = serde_json::from_str(without_usage).unwrap();
+        assert!(ClaudeLogProcessor::extract_usage(&parsed, None).is_none());
+    }
+
     #[test]
     fn test_thinking_content() {
         let thinking_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Let me think about this..."}]}}"#;
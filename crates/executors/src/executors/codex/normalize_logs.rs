@@ -26,8 +26,8 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::normalize_unified_diff, msg_store::MsgStore,
-    path::make_path_relative,
+    approvals::ApprovalStatus, diff::normalize_unified_diff, log_msg::TokenUsage,
+    msg_store::MsgStore, path::make_path_relative,
 };
 
 use crate::{
@@ -216,6 +216,7 @@ struct LogState {
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
     token_usage_info: Option<TokenUsageInfo>,
+    model: Option<String>,
 }
 
 enum StreamingTextKind {
@@ -234,6 +235,7 @@ impl LogState {
             patches: HashMap::new(),
             web_searches: HashMap::new(),
             token_usage_info: None,
+            model: None,
         }
     }
 
@@ -380,7 +382,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             }
 
             if let Ok(response) = serde_json::from_str::<JSONRPCResponse>(&line) {
-                handle_jsonrpc_response(response, &msg_store, &entry_index);
+                if let Some(model) = handle_jsonrpc_response(response, &msg_store, &entry_index) {
+                    state.model = Some(model);
+                }
                 continue;
             }
 
@@ -389,6 +393,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -427,6 +432,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -971,6 +977,11 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        if let Some(usage) =
+                            usage_since(state.token_usage_info.as_ref(), &info, state.model.clone())
+                        {
+                            msg_store.push_usage(usage);
+                        }
                         state.token_usage_info = Some(info);
                     }
                 }
@@ -1017,14 +1028,15 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
     });
 }
 
+/// Returns the model of a new conversation
 fn handle_jsonrpc_response(
     response: JSONRPCResponse,
     msg_store: &Arc<MsgStore>,
     entry_index: &EntryIndexProvider,
-) {
+) -> Option<String> {
     let Ok(response) = serde_json::from_value::<NewConversationResponse>(response.result.clone())
     else {
-        return;
+        return None;
     };
 
     match SessionHandler::extract_session_id_from_rollout_path(response.rollout_path) {
@@ -1033,11 +1045,49 @@ fn handle_jsonrpc_response(
     }
 
     handle_model_params(
-        response.model,
+        response.model.clone(),
         response.reasoning_effort,
         msg_store,
         entry_index,
     );
+    Some(response.model)
+}
+
+/// Tokens used since the previous `TokenCount` event. Codex reports running totals,
+/// and the same totals again when only the rate limits changed.
+fn usage_since(
+    previous: Option<&TokenUsageInfo>,
+    current: &TokenUsageInfo,
+    model: Option<String>,
+) -> Option<TokenUsage> {
+    fn count(value: impl TryInto<u64>) -> u64 {
+        value.try_into().unwrap_or(0)
+    }
+
+    fn totals(info: &TokenUsageInfo) -> [u64; 3] {
+        let usage = &info.total_token_usage;
+        [
+            count(usage.input_tokens),
+            count(usage.cached_input_tokens),
+            count(usage.output_tokens),
+        ]
+    }
+
+    let [input, cached, output] = totals(current);
+    let [previous_input, previous_cached, previous_output] =
+        previous.map(totals).unwrap_or_default();
+    // Cached input is part of the input count
+    let input = input.saturating_sub(previous_input);
+    let cached = cached.saturating_sub(previous_cached);
+    let usage = TokenUsage {
+        model,
+        input_tokens: input.saturating_sub(cached),
+        output_tokens: output.saturating_sub(previous_output),
+        cache_read_tokens: cached,
+        cache_write_tokens: 0,
+        cost_usd: None,
+    };
+    (!usage.is_empty()).then_some(usage)
 }
 
 fn handle_model_params(
@@ -0,0 +1,623 @@
+//! Agent CLIs described entirely by their profile.
+//!
+//! A `CUSTOM_CLI` configuration names the commands to run and how to read their
+//! output, so an agent can be added from `profiles.json` without code changes:
+//!
+//! ```json
+//! "CUSTOM_CLI": {
+//!   "MY_AGENT": {
+//!     "CUSTOM_CLI": {
+//!       "initial_command": "my-agent run --json {prompt}",
+//!       "follow_up_command": "my-agent run --json --resume {session_id} {prompt}",
+//!       "output_format": "json_lines",
+//!       "json_lines": { "assistant_kinds": ["message"], "tool_kinds": ["tool_call"] },
+//!       "session_id": { "json_pointer": "/session_id" }
+//!     }
+//!   }
+//! }
+//! ```
+use std::{path::Path, process::Stdio, sync::Arc};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use derivative::Derivative;
+use futures::{StreamExt, future::ready};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path_blocking};
+
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuildError, CommandBuilder, CommandParts, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+        acp::AcpAgentHarness,
+    },
+    logs::{
+        ActionType, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolStatus,
+        plain_text_processor::PlainTextLogProcessor,
+        stderr_processor::normalize_stderr_logs,
+        utils::{EntryIndexProvider, patch::add_normalized_entry},
+    },
+};
+
+const PROMPT_PLACEHOLDER: &str = "{prompt}";
+const SESSION_ID_PLACEHOLDER: &str = "{session_id}";
+
+/// How the output of a custom CLI is turned into conversation entries: plain text
+/// becomes assistant messages, JSON lines are mapped with [`JsonLinesMapping`], and
+/// ACP agents speak the Agent Client Protocol over stdio
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomCliOutputFormat")]
+pub enum OutputFormat {
+    #[default]
+    PlainText,
+    JsonLines,
+    Acp,
+}
+
+/// Where to find the parts of an event in JSON lines output. Fields are JSON
+/// pointers such as `/message/text`; lines whose kind is in none of the lists
+/// are skipped, and lines that are not JSON are shown as system messages.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(default)]
+pub struct JsonLinesMapping {
+    #[schemars(
+        title = "Kind Pointer",
+        description = "JSON pointer to the field naming the kind of event"
+    )]
+    pub kind_pointer: String,
+    #[schemars(
+        title = "Text Pointer",
+        description = "JSON pointer to the text of an event"
+    )]
+    pub text_pointer: String,
+    #[schemars(
+        title = "Tool Name Pointer",
+        description = "JSON pointer to the tool name of a tool event"
+    )]
+    pub tool_name_pointer: String,
+    #[schemars(
+        title = "Tool Input Pointer",
+        description = "JSON pointer to the arguments of a tool event"
+    )]
+    pub tool_input_pointer: String,
+    #[schemars(
+        title = "Assistant Kinds",
+        description = "Kinds shown as assistant messages"
+    )]
+    pub assistant_kinds: Vec<String>,
+    #[schemars(title = "Thinking Kinds", description = "Kinds shown as thinking")]
+    pub thinking_kinds: Vec<String>,
+    #[schemars(title = "Tool Kinds", description = "Kinds shown as tool calls")]
+    pub tool_kinds: Vec<String>,
+    #[schemars(title = "Error Kinds", description = "Kinds shown as errors")]
+    pub error_kinds: Vec<String>,
+}
+
+impl Default for JsonLinesMapping {
+    fn default() -> Self {
+        Self {
+            kind_pointer: "/type".to_string(),
+            text_pointer: "/content".to_string(),
+            tool_name_pointer: "/name".to_string(),
+            tool_input_pointer: "/input".to_string(),
+            assistant_kinds: vec!["assistant".to_string(), "message".to_string()],
+            thinking_kinds: vec!["thinking".to_string(), "reasoning".to_string()],
+            tool_kinds: vec!["tool_call".to_string(), "tool_use".to_string()],
+            error_kinds: vec!["error".to_string()],
+        }
+    }
+}
+
+impl JsonLinesMapping {
+    /// The conversation entry for one JSON line, if its kind is mapped
+    pub fn entry(&self, event: &Value) -> Option<NormalizedEntry> {
+        let kind = event.pointer(&self.kind_pointer)?.as_str()?;
+        let text = event.pointer(&self.text_pointer).map(value_text);
+        let is = |kinds: &[String]| kinds.iter().any(|k| k == kind);
+
+        let (entry_type, content) = if is(&self.assistant_kinds) {
+            (NormalizedEntryType::AssistantMessage, text?)
+        } else if is(&self.thinking_kinds) {
+            (NormalizedEntryType::Thinking, text?)
+        } else if is(&self.error_kinds) {
+            (
+                NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::Other,
+                },
+                text?,
+            )
+        } else if is(&self.tool_kinds) {
+            let tool_name = event
+                .pointer(&self.tool_name_pointer)
+                .map(value_text)
+                .unwrap_or_else(|| kind.to_string());
+            let entry_type = NormalizedEntryType::ToolUse {
+                tool_name: tool_name.clone(),
+                action_type: ActionType::Tool {
+                    tool_name: tool_name.clone(),
+                    arguments: event.pointer(&self.tool_input_pointer).cloned(),
+                    result: None,
+                },
+                status: ToolStatus::Success,
+            };
+            (entry_type, text.unwrap_or(tool_name))
+        } else {
+            return None;
+        };
+
+        Some(NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content,
+            metadata: None,
+        })
+    }
+}
+
+/// Rules for finding the agent's session id, which follow-ups pass as `{session_id}`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[ts(rename = "CustomCliSessionId")]
+pub struct SessionIdExtraction {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Regex",
+        description = "Regex matched against each stdout line; its first capture group is the session id"
+    )]
+    pub regex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "JSON Pointer",
+        description = "JSON pointer to the session id in JSON lines output"
+    )]
+    pub json_pointer: Option<String>,
+}
+
+impl SessionIdExtraction {
+    fn compile(&self) -> Option<Regex> {
+        let pattern = self.regex.as_deref()?;
+        Regex::new(pattern)
+            .inspect_err(|e| tracing::error!("Invalid session id regex {pattern:?}: {e}"))
+            .ok()
+    }
+
+    /// The session id in an output line, parsed as JSON when `event` is given
+    pub fn extract(
+        &self,
+        regex: Option<&Regex>,
+        line: &str,
+        event: Option<&Value>,
+    ) -> Option<String> {
+        if let (Some(pointer), Some(event)) = (&self.json_pointer, event)
+            && let Some(session_id) = event.pointer(pointer).map(value_text)
+            && !session_id.is_empty()
+        {
+            return Some(session_id);
+        }
+        regex?
+            .captures(line)?
+            .get(1)
+            .map(|m| m.as_str().to_string())
+            .filter(|session_id| !session_id.is_empty())
+    }
+}
+
+/// Custom CLI executor configuration
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct CustomCli {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Initial Command",
+        description = "Command starting a conversation. {prompt} is replaced by the prompt; without it the prompt is written to stdin."
+    )]
+    pub initial_command: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Follow-up Command",
+        description = "Command continuing a conversation, with {prompt} and {session_id}. Follow-ups are unsupported when empty, except for ACP agents."
+    )]
+    pub follow_up_command: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Output Format",
+        description = "How stdout is turned into conversation entries"
+    )]
+    pub output_format: OutputFormat,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "JSON Lines Mapping",
+        description = "Where to find events in json_lines output"
+    )]
+    pub json_lines: Option<JsonLinesMapping>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Session ID",
+        description = "How to find the session id passed to follow-ups"
+    )]
+    pub session_id: Option<SessionIdExtraction>,
+
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl CustomCli {
+    fn build_command(&self, template: &str) -> Result<CommandParts, ExecutorError> {
+        if template.trim().is_empty() {
+            return Err(CommandBuildError::EmptyCommand.into());
+        }
+        Ok(apply_overrides(CommandBuilder::new(template), &self.cmd).build_initial()?)
+    }
+
+    fn follow_up_template(&self) -> Option<&str> {
+        self.follow_up_command
+            .as_deref()
+            .filter(|command| !command.trim().is_empty())
+    }
+
+    async fn spawn_command(
+        &self,
+        current_dir: &Path,
+        command_parts: CommandParts,
+        prompt: &str,
+        session_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let (program_path, args) = command_parts.into_resolved().await?;
+        let (args, prompt_in_args) = fill_placeholders(args, prompt, session_id);
+
+        let mut command = env.command(program_path);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(&args);
+
+        env.clone()
+            .with_profile(&self.cmd)
+            .apply_to_command(&mut command);
+
+        let mut child = command.group_spawn()?;
+
+        if let Some(mut stdin) = child.inner().stdin.take() {
+            if !prompt_in_args {
+                stdin.write_all(prompt.as_bytes()).await?;
+            }
+            stdin.shutdown().await?;
+        }
+
+        Ok(child.into())
+    }
+
+    fn normalize_stdout(&self, msg_store: Arc<MsgStore>, entry_index_provider: EntryIndexProvider) {
+        let output_format = self.output_format;
+        let mapping = self.json_lines.clone().unwrap_or_default();
+        let session_rules = self.session_id.clone().unwrap_or_default();
+
+        tokio::spawn(async move {
+            let session_regex = session_rules.compile();
+            let mut session_id_found = false;
+            let mut plain_text = plain_text_normalizer(entry_index_provider.clone());
+
+            let mut lines = msg_store
+                .stdout_lines_stream()
+                .filter_map(|res| ready(res.ok()));
+
+            while let Some(line) = lines.next().await {
+                let trimmed = line.trim();
+                let event = match output_format {
+                    OutputFormat::JsonLines => serde_json::from_str::<Value>(trimmed).ok(),
+                    _ => None,
+                };
+
+                if !session_id_found
+                    && let Some(session_id) =
+                        session_rules.extract(session_regex.as_ref(), trimmed, event.as_ref())
+                {
+                    msg_store.push_session_id(session_id);
+                    session_id_found = true;
+                }
+
+                match (output_format, event) {
+                    (OutputFormat::JsonLines, Some(event)) => {
+                        if let Some(entry) = mapping.entry(&event) {
+                            add_normalized_entry(&msg_store, &entry_index_provider, entry);
+                        }
+                    }
+                    (OutputFormat::JsonLines, None) => {
+                        if !trimmed.is_empty() {
+                            let entry = NormalizedEntry {
+                                timestamp: None,
+                                entry_type: NormalizedEntryType::SystemMessage,
+                                content: strip_ansi_escapes::strip_str(trimmed),
+                                metadata: None,
+                            };
+                            add_normalized_entry(&msg_store, &entry_index_provider, entry);
+                        }
+                    }
+                    _ => {
+                        for patch in plain_text.process(line + "\n") {
+                            msg_store.push_patch(patch);
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for CustomCli {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command_parts = self.build_command(&self.initial_command)?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+
+        if self.output_format == OutputFormat::Acp {
+            return AcpAgentHarness::with_session_namespace("custom_cli_sessions")
+                .spawn_with_command(
+                    current_dir,
+                    combined_prompt,
+                    command_parts,
+                    env,
+                    &self.cmd,
+                    self.approvals.clone(),
+                )
+                .await;
+        }
+
+        self.spawn_command(current_dir, command_parts, &combined_prompt, None, env)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+
+        if self.output_format == OutputFormat::Acp {
+            // ACP agents resume sessions over the protocol
+            let template = self.follow_up_template().unwrap_or(&self.initial_command);
+            return AcpAgentHarness::with_session_namespace("custom_cli_sessions")
+                .spawn_follow_up_with_command(
+                    current_dir,
+                    combined_prompt,
+                    session_id,
+                    self.build_command(template)?,
+                    env,
+                    &self.cmd,
+                    self.approvals.clone(),
+                )
+                .await;
+        }
+
+        let template = self.follow_up_template().ok_or_else(|| {
+            ExecutorError::FollowUpNotSupported(
+                "this custom CLI profile has no follow_up_command".to_string(),
+            )
+        })?;
+        let command_parts = self.build_command(template)?;
+        self.spawn_command(
+            current_dir,
+            command_parts,
+            &combined_prompt,
+            Some(session_id),
+            env,
+        )
+        .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        if self.output_format == OutputFormat::Acp {
+            crate::executors::acp::normalize_logs(msg_store, worktree_path);
+            return;
+        }
+
+        let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+        normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+        self.normalize_stdout(msg_store, entry_index_provider);
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let program = shlex::split(&self.initial_command)
+            .and_then(|parts| parts.into_iter().next())
+            .map(|program| match &self.cmd.base_command_override {
+                Some(base) => shlex::split(base)
+                    .and_then(|parts| parts.into_iter().next())
+                    .unwrap_or(program),
+                None => program,
+            });
+
+        match program {
+            Some(program) if resolve_executable_path_blocking(&program).is_some() => {
+                AvailabilityInfo::InstallationFound
+            }
+            _ => AvailabilityInfo::NotFound,
+        }
+    }
+}
+
+/// Replace `{prompt}` and `{session_id}` in each argument. Arguments are split
+/// before filling in, so a prompt with spaces or quotes stays one argument.
+/// Returns whether the prompt was passed as an argument.
+fn fill_placeholders(
+    args: Vec<String>,
+    prompt: &str,
+    session_id: Option<&str>,
+) -> (Vec<String>, bool) {
+    let mut prompt_in_args = false;
+    let args = args
+        .into_iter()
+        .map(|arg| {
+            let mut arg = arg;
+            if arg.contains(PROMPT_PLACEHOLDER) {
+                prompt_in_args = true;
+                arg = arg.replace(PROMPT_PLACEHOLDER, prompt);
+            }
+            if let Some(session_id) = session_id {
+                arg = arg.replace(SESSION_ID_PLACEHOLDER, session_id);
+            }
+            arg
+        })
+        .collect();
+    (args, prompt_in_args)
+}
+
+fn plain_text_normalizer(index_provider: EntryIndexProvider) -> PlainTextLogProcessor {
+    PlainTextLogProcessor::builder()
+        .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content,
+            metadata: None,
+        }))
+        .transform_lines(Box::new(|lines| {
+            lines.iter_mut().for_each(|line| {
+                *line = strip_ansi_escapes::strip_str(&line);
+            })
+        }))
+        .index_provider(index_provider)
+        .build()
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn fills_placeholders_within_arguments() {
+        let (args, prompt_in_args) = fill_placeholders(
+            vec!["--resume={session_id}".to_string(), "{prompt}".to_string()],
+            "fix \"the\" bug",
+            Some("abc"),
+        );
+        assert_eq!(args, vec!["--resume=abc", "fix \"the\" bug"]);
+        assert!(prompt_in_args);
+
+        let (args, prompt_in_args) = fill_placeholders(vec!["--json".to_string()], "prompt", None);
+        assert_eq!(args, vec!["--json"]);
+        assert!(!prompt_in_args);
+    }
+
+    #[test]
+    fn maps_json_lines_to_entries() {
+        let mapping = JsonLinesMapping::default();
+
+        let entry = mapping
+            .entry(&json!({ "type": "assistant", "content": "Done" }))
+            .unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(entry.content, "Done");
+
+        let entry = mapping
+            .entry(&json!({ "type": "tool_call", "name": "bash", "input": { "cmd": "ls" } }))
+            .unwrap();
+        match entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type: ActionType::Tool { arguments, .. },
+                ..
+            } => {
+                assert_eq!(tool_name, "bash");
+                assert_eq!(arguments, Some(json!({ "cmd": "ls" })));
+            }
+            other => panic!("unexpected entry type {other:?}"),
+        }
+        assert_eq!(entry.content, "bash");
+
+        assert!(
+            mapping
+                .entry(&json!({ "type": "usage", "tokens": 5 }))
+                .is_none()
+        );
+        assert!(mapping.entry(&json!({ "type": "assistant" })).is_none());
+    }
+
+    #[test]
+    fn extracts_session_id_from_json_or_regex() {
+        let rules = SessionIdExtraction {
+            regex: Some(r"session: (\S+)".to_string()),
+            json_pointer: Some("/meta/session".to_string()),
+        };
+        let regex = rules.compile();
+
+        let event = json!({ "meta": { "session": "s-1" } });
+        assert_eq!(
+            rules.extract(regex.as_ref(), "", Some(&event)).as_deref(),
+            Some("s-1")
+        );
+        assert_eq!(
+            rules
+                .extract(regex.as_ref(), "started session: s-2", None)
+                .as_deref(),
+            Some("s-2")
+        );
+        assert!(rules.extract(regex.as_ref(), "hello", None).is_none());
+    }
+
+    #[test]
+    fn deserializes_profile_configuration() {
+        let config: CustomCli = serde_json::from_value(json!({
+            "initial_command": "my-agent run {prompt}",
+            "output_format": "json_lines",
+            "json_lines": { "assistant_kinds": ["text"] },
+            "session_id": { "json_pointer": "/session_id" }
+        }))
+        .unwrap();
+
+        assert_eq!(config.output_format, OutputFormat::JsonLines);
+        let mapping = config.json_lines.unwrap();
+        assert_eq!(mapping.assistant_kinds, vec!["text"]);
+        assert_eq!(mapping.kind_pointer, "/type");
+        assert!(config.follow_up_command.is_none());
+    }
+}
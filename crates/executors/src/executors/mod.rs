@@ -19,7 +19,8 @@ use crate::{
     env::ExecutionEnv,
     executors::{
         amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
-        droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        custom_cli::CustomCli, droid::Droid, gemini::Gemini, openai_compatible::OpenaiCompatible,
+        opencode::Opencode, qwen::QwenCode,
     },
     mcp_config::McpConfig,
 };
@@ -30,8 +31,10 @@ pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom_cli;
 pub mod droid;
 pub mod gemini;
+pub mod openai_compatible;
 pub mod opencode;
 pub mod qwen;
 
@@ -70,6 +73,8 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error("Sandbox unavailable: {0}")]
+    SandboxUnavailable(String),
 }
 
 #[enum_dispatch]
@@ -100,6 +105,8 @@ pub enum CodingAgent {
     QwenCode,
     Copilot,
     Droid,
+    CustomCli,
+    OpenaiCompatible,
 }
 
 impl CodingAgent {
@@ -160,13 +167,14 @@ impl CodingAgent {
             | Self::Gemini(_)
             | Self::QwenCode(_)
             | Self::Droid(_)
-            | Self::Opencode(_) => vec![BaseAgentCapability::SessionFork],
+            | Self::Opencode(_)
+            | Self::OpenaiCompatible(_) => vec![BaseAgentCapability::SessionFork],
             Self::Codex(_) => vec![
                 BaseAgentCapability::SessionFork,
                 BaseAgentCapability::SetupHelper,
             ],
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Copilot(_) => vec![],
+            Self::Copilot(_) | Self::CustomCli(_) => vec![],
         }
     }
 }
@@ -0,0 +1,493 @@
+//! Coding agent that talks to an OpenAI compatible chat completions endpoint
+//! directly instead of running a vendor CLI. The agent loop runs in this process
+//! with the small tool set in [`tools`]; the conversation is written to the
+//! execution's stdout as [`AgentEvent`] lines and normalized from there.
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use derivative::Derivative;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::{
+    approvals::ApprovalStatus, assets::asset_dir, msg_store::MsgStore, shell::get_shell_command,
+};
+
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::CmdOverrides,
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, ExecutorExitResult, SpawnedChild,
+        StandardCodingAgentExecutor, codex::client::LogWriter,
+    },
+    stdout_dup::create_stdout_pipe_writer,
+};
+
+pub mod chat;
+pub mod normalize_logs;
+pub mod tools;
+
+use self::{
+    chat::{ChatClient, ChatMessage},
+    tools::{ToolInput, ToolOutput, ToolRunner},
+};
+
+const DEFAULT_BASE_URL: &str = "http://localhost:11434/v1";
+const DEFAULT_MAX_REQUESTS: u32 = 50;
+const DEFAULT_COMMAND_TIMEOUT_SECS: u32 = 120;
+
+/// Events written to stdout by the agent loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentEvent {
+    Session {
+        session_id: String,
+        model: String,
+    },
+    Thinking {
+        content: String,
+    },
+    Assistant {
+        content: String,
+    },
+    /// `input` is None when the model called an unknown tool or sent invalid arguments
+    ToolCall {
+        id: String,
+        name: String,
+        arguments: String,
+        input: Option<ToolInput>,
+    },
+    ToolResult {
+        id: String,
+        result: ToolOutput,
+    },
+    ApprovalResponse {
+        id: String,
+        tool_name: String,
+        status: ApprovalStatus,
+    },
+    Error {
+        message: String,
+    },
+    /// Tokens used by one model request; `input_tokens` includes `cached_tokens`
+    Usage {
+        model: String,
+        input_tokens: u64,
+        output_tokens: u64,
+        cached_tokens: u64,
+    },
+}
+
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct OpenaiCompatible {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Base URL",
+        description = "Base URL of the OpenAI compatible API, e.g. http://localhost:11434/v1 for Ollama or http://localhost:8080/v1 for llama.cpp"
+    )]
+    pub base_url: Option<String>,
+
+    #[serde(default)]
+    #[schemars(title = "Model", description = "Model name sent with every request")]
+    pub model: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "API Key Variable",
+        description = "Environment variable holding the API key. Requests are sent without a key when unset."
+    )]
+    pub api_key_env: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(title = "Temperature")]
+    pub temperature: Option<f32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Max Requests",
+        description = "Most model requests per turn before the agent stops. Defaults to 50."
+    )]
+    pub max_requests: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Command Timeout",
+        description = "Seconds a run_command call may take. Defaults to 120."
+    )]
+    pub command_timeout_secs: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Approvals",
+        description = "Ask for approval before every tool call"
+    )]
+    pub approvals: Option<bool>,
+
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    approvals_service: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl OpenaiCompatible {
+    async fn spawn_agent(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        history: Vec<ChatMessage>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let model = self.model.trim();
+        if model.is_empty() {
+            return Err(ExecutorError::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "OPENAI_COMPATIBLE profiles need a model",
+            )));
+        }
+
+        let env = env.clone().with_profile(&self.cmd);
+        let api_key = self.api_key_env.as_ref().and_then(|name| {
+            env.vars
+                .get(name)
+                .cloned()
+                .or_else(|| std::env::var(name).ok())
+        });
+        let client = ChatClient::new(
+            self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL),
+            api_key,
+            model.to_string(),
+            self.temperature,
+        );
+        let command_timeout = Duration::from_secs(
+            self.command_timeout_secs
+                .unwrap_or(DEFAULT_COMMAND_TIMEOUT_SECS)
+                .into(),
+        );
+
+        // The agent runs in-process; an idle child stands in for it so the container can
+        // manage the execution like any other. Its stdout carries the agent's events.
+        let mut child = idle_host_command(current_dir).group_spawn()?;
+        let log_writer = LogWriter::new(create_stdout_pipe_writer(&mut child)?);
+        let host_stdin = child.inner().stdin.take();
+
+        let mut messages = vec![ChatMessage::system(system_prompt(current_dir))];
+        messages.extend(history);
+        messages.push(ChatMessage::user(self.append_prompt.combine_prompt(prompt)));
+
+        let agent = AgentLoop {
+            client,
+            tools: ToolRunner::new(current_dir.to_path_buf(), env, command_timeout),
+            approvals: self
+                .approvals
+                .unwrap_or(false)
+                .then(|| self.approvals_service.clone())
+                .flatten(),
+            log_writer,
+            model: model.to_string(),
+            max_requests: self.max_requests.unwrap_or(DEFAULT_MAX_REQUESTS),
+        };
+
+        let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
+        let (interrupt_tx, interrupt_rx) = tokio::sync::oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let result = tokio::select! {
+                result = agent.run(messages) => result,
+                Ok(()) = interrupt_rx => ExecutorExitResult::Failure,
+            };
+            let _ = exit_tx.send(result);
+            // Closing stdin lets the idle child exit
+            drop(host_stdin);
+        });
+
+        Ok(SpawnedChild {
+            child,
+            exit_signal: Some(exit_rx),
+            interrupt_sender: Some(interrupt_tx),
+        })
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for OpenaiCompatible {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals_service = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_agent(current_dir, prompt, Vec::new(), env).await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let history = load_session(session_id).map_err(ExecutorError::Io)?;
+        self.spawn_agent(current_dir, prompt, history, env).await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        normalize_logs::normalize_logs(msg_store, worktree_path);
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        // The endpoint is only contacted when the agent runs
+        if self.model.trim().is_empty() {
+            AvailabilityInfo::NotFound
+        } else {
+            AvailabilityInfo::InstallationFound
+        }
+    }
+}
+
+struct AgentLoop {
+    client: ChatClient,
+    tools: ToolRunner,
+    /// None when tools run without approval
+    approvals: Option<Arc<dyn ExecutorApprovalService>>,
+    log_writer: LogWriter,
+    model: String,
+    max_requests: u32,
+}
+
+impl AgentLoop {
+    async fn run(self, mut messages: Vec<ChatMessage>) -> ExecutorExitResult {
+        // Every turn gets its own session so that earlier turns can be resumed
+        let session_id = Uuid::new_v4().to_string();
+        self.log(AgentEvent::Session {
+            session_id: session_id.clone(),
+            model: self.model.clone(),
+        })
+        .await;
+        self.save(&session_id, &messages);
+
+        let tool_definitions = tools::definitions();
+        for _ in 0..self.max_requests {
+            let mut reply = match self.client.complete(&messages, &tool_definitions).await {
+                Ok(completion) => {
+                    if let Some(usage) = &completion.usage {
+                        self.log(AgentEvent::Usage {
+                            model: self.model.clone(),
+                            input_tokens: usage.prompt_tokens,
+                            output_tokens: usage.completion_tokens,
+                            cached_tokens: usage.cached_tokens(),
+                        })
+                        .await;
+                    }
+                    completion.message
+                }
+                Err(e) => {
+                    self.log(AgentEvent::Error {
+                        message: e.to_string(),
+                    })
+                    .await;
+                    return ExecutorExitResult::Failure;
+                }
+            };
+
+            if let Some(content) = non_empty(&reply.reasoning_content) {
+                self.log(AgentEvent::Thinking { content }).await;
+            }
+            if let Some(content) = non_empty(&reply.content) {
+                self.log(AgentEvent::Assistant { content }).await;
+            }
+
+            for call in reply.tool_calls.iter_mut() {
+                if call.id.is_empty() {
+                    call.id = format!("call_{}", Uuid::new_v4().simple());
+                }
+            }
+            let calls = reply.tool_calls.clone();
+            messages.push(reply);
+
+            for call in &calls {
+                let output = self
+                    .handle_tool_call(&call.id, &call.function.name, &call.function.arguments)
+                    .await;
+                messages.push(ChatMessage::tool_result(&call.id, output));
+            }
+            self.save(&session_id, &messages);
+
+            if calls.is_empty() {
+                return ExecutorExitResult::Success;
+            }
+        }
+
+        self.log(AgentEvent::Error {
+            message: format!(
+                "Stopped after {} model requests without a final answer",
+                self.max_requests
+            ),
+        })
+        .await;
+        ExecutorExitResult::Failure
+    }
+
+    /// Run one tool call and return the text sent back to the model
+    async fn handle_tool_call(&self, id: &str, name: &str, arguments: &str) -> String {
+        let input = ToolInput::parse(name, arguments);
+        self.log(AgentEvent::ToolCall {
+            id: id.to_string(),
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            input: input.as_ref().ok().cloned(),
+        })
+        .await;
+
+        let result = match input {
+            Ok(input) => match self.approve(id, name, &input).await {
+                Ok(()) => self.tools.run(&input).await,
+                Err(denial) => return denial,
+            },
+            Err(error) => ToolOutput::error(error),
+        };
+        self.log(AgentEvent::ToolResult {
+            id: id.to_string(),
+            result: result.clone(),
+        })
+        .await;
+
+        match result.exit_code {
+            Some(code) => format!("Exit code: {code}\n{}", result.output),
+            None => result.output,
+        }
+    }
+
+    /// Ask the approval service, returning the message for the model when the call
+    /// may not run
+    async fn approve(&self, id: &str, name: &str, input: &ToolInput) -> Result<(), String> {
+        let Some(approvals) = &self.approvals else {
+            return Ok(());
+        };
+        let tool_input = serde_json::to_value(input).unwrap_or_default();
+        let status = match approvals.request_tool_approval(name, tool_input, id).await {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::error!("Tool approval request failed: {e}");
+                return Err("Tool approval request failed".to_string());
+            }
+        };
+        self.log(AgentEvent::ApprovalResponse {
+            id: id.to_string(),
+            tool_name: name.to_string(),
+            status: status.clone(),
+        })
+        .await;
+
+        match status {
+            ApprovalStatus::Approved => Ok(()),
+            ApprovalStatus::Denied { reason } => Err(match reason {
+                Some(reason) if !reason.trim().is_empty() => {
+                    format!("The user denied this tool call: {}", reason.trim())
+                }
+                _ => "The user denied this tool call".to_string(),
+            }),
+            ApprovalStatus::TimedOut => Err("The approval request timed out".to_string()),
+            ApprovalStatus::Pending => Err("Approval still pending (unexpected)".to_string()),
+        }
+    }
+
+    async fn log(&self, event: AgentEvent) {
+        let line = match serde_json::to_string(&event) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::error!("Failed to serialize agent event: {e}");
+                return;
+            }
+        };
+        if let Err(e) = self.log_writer.log_raw(&line).await {
+            tracing::error!("Failed to write agent event: {e}");
+        }
+    }
+
+    fn save(&self, session_id: &str, messages: &[ChatMessage]) {
+        // The system prompt is rebuilt for every turn
+        let history = messages.get(1..).unwrap_or_default();
+        if let Err(e) = save_session(session_id, history) {
+            tracing::error!("Failed to save session {session_id}: {e}");
+        }
+    }
+}
+
+fn system_prompt(current_dir: &Path) -> String {
+    format!(
+        "You are a coding agent working in the repository at {}.\n\
+         Use the tools to read and search files, write files and run commands. \
+         Paths are relative to the repository root and must stay inside it.\n\
+         Make the requested changes, check them where you can, and finish with a short \
+         summary of what you did.",
+        current_dir.display()
+    )
+}
+
+fn non_empty(text: &Option<String>) -> Option<String> {
+    text.as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+/// A child process that waits until its stdin is closed
+fn idle_host_command(current_dir: &Path) -> tokio::process::Command {
+    let (shell, shell_arg) = get_shell_command();
+    let script = if cfg!(windows) { "set /p _=" } else { "read _" };
+    let mut command = tokio::process::Command::new(shell);
+    command
+        .kill_on_drop(true)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .current_dir(current_dir)
+        .arg(shell_arg)
+        .arg(script);
+    command
+}
+
+fn sessions_dir() -> PathBuf {
+    asset_dir().join("openai_compatible_sessions")
+}
+
+fn load_session(session_id: &str) -> io::Result<Vec<ChatMessage>> {
+    let path = sessions_dir().join(format!("{session_id}.json"));
+    let contents = std::fs::read_to_string(&path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Failed to read session {}: {e}", path.display()),
+        )
+    })?;
+    serde_json::from_str(&contents).map_err(io::Error::other)
+}
+
+fn save_session(session_id: &str, messages: &[ChatMessage]) -> io::Result<()> {
+    let dir = sessions_dir();
+    std::fs::create_dir_all(&dir)?;
+    let contents = serde_json::to_string(messages).map_err(io::Error::other)?;
+    std::fs::write(dir.join(format!("{session_id}.json")), contents)
+}
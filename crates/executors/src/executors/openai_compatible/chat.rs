@@ -0,0 +1,236 @@
+//! Minimal client for the OpenAI chat completions API, as served by OpenAI and
+//! by local servers such as llama.cpp, Ollama, vLLM and LM Studio.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: Role,
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Reasoning text returned by servers that separate it from the answer
+    #[serde(default, skip_serializing)]
+    pub reasoning_content: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::text(Role::System, content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::text(Role::User, content)
+    }
+
+    pub fn tool_result(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::text(Role::Tool, content)
+        }
+    }
+
+    fn text(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: Some(content.into()),
+            reasoning_content: None,
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    #[serde(default)]
+    pub id: String,
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    pub function: FunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    /// JSON encoded arguments. Some servers send an object instead of a string.
+    #[serde(default, deserialize_with = "arguments_as_string")]
+    pub arguments: String,
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+fn arguments_as_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(arguments) => arguments,
+        Value::Null => String::new(),
+        other => other.to_string(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    tools: &'a [Value],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+/// Token counts of one completion. `prompt_tokens` includes cached tokens.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Usage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+    #[serde(default)]
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PromptTokensDetails {
+    #[serde(default)]
+    pub cached_tokens: u64,
+}
+
+impl Usage {
+    pub fn cached_tokens(&self) -> u64 {
+        self.prompt_tokens_details
+            .as_ref()
+            .map_or(0, |details| details.cached_tokens)
+    }
+}
+
+/// The assistant's reply and the tokens the request used, if the server reports them
+#[derive(Debug)]
+pub struct ChatCompletion {
+    pub message: ChatMessage,
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Error)]
+pub enum ChatError {
+    #[error("request to {url} failed: {source}")]
+    Request { url: String, source: reqwest::Error },
+    #[error("{url} returned {status}: {body}")]
+    Status {
+        url: String,
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    #[error("{url} returned a response without choices")]
+    NoChoices { url: String },
+}
+
+pub struct ChatClient {
+    http: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+    model: String,
+    temperature: Option<f32>,
+}
+
+impl ChatClient {
+    pub fn new(
+        base_url: &str,
+        api_key: Option<String>,
+        model: String,
+        temperature: Option<f32>,
+    ) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url: format!("{}/chat/completions", base_url.trim_end_matches('/')),
+            api_key,
+            model,
+            temperature,
+        }
+    }
+
+    /// Send the conversation and return the assistant's reply
+    pub async fn complete(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[Value],
+    ) -> Result<ChatCompletion, ChatError> {
+        let request = ChatRequest {
+            model: &self.model,
+            messages,
+            tools,
+            temperature: self.temperature,
+        };
+        let mut builder = self
+            .http
+            .post(&self.url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&request);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+
+        let request_error = |source| ChatError::Request {
+            url: self.url.clone(),
+            source,
+        };
+        let response = builder.send().await.map_err(request_error)?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ChatError::Status {
+                url: self.url.clone(),
+                status,
+                body,
+            });
+        }
+
+        let response: ChatResponse = response.json().await.map_err(request_error)?;
+        let message = response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message)
+            .ok_or_else(|| ChatError::NoChoices {
+                url: self.url.clone(),
+            })?;
+        Ok(ChatCompletion {
+            message,
+            usage: response.usage,
+        })
+    }
+}
@@ -0,0 +1,306 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use futures::{StreamExt, future::ready};
+use serde_json::Value;
+use workspace_utils::{
+    approvals::ApprovalStatus, log_msg::TokenUsage, msg_store::MsgStore, path::make_path_relative,
+};
+
+use super::{
+    AgentEvent,
+    tools::{ToolInput, ToolOutput},
+};
+use crate::{
+    approvals::ToolCallMetadata,
+    logs::{
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
+        NormalizedEntryError, NormalizedEntryType, ToolResult, ToolStatus,
+        stderr_processor::normalize_stderr_logs,
+        utils::{
+            EntryIndexProvider,
+            patch::{add_normalized_entry, replace_normalized_entry},
+        },
+    },
+};
+
+pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
+    let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+    normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+    let worktree_path = worktree_path.to_string_lossy().to_string();
+    tokio::spawn(async move {
+        // Tool call id -> (entry index, entry)
+        let mut tool_calls: HashMap<String, (usize, NormalizedEntry)> = HashMap::new();
+        let mut session_id_stored = false;
+
+        let mut lines = msg_store
+            .stdout_lines_stream()
+            .filter_map(|res| ready(res.ok()));
+
+        while let Some(line) = lines.next().await {
+            let trimmed = line.trim();
+            let Ok(event) = serde_json::from_str::<AgentEvent>(trimmed) else {
+                if !trimmed.is_empty() {
+                    add_normalized_entry(
+                        &msg_store,
+                        &entry_index_provider,
+                        message(NormalizedEntryType::SystemMessage, trimmed),
+                    );
+                }
+                continue;
+            };
+
+            match event {
+                AgentEvent::Session { session_id, .. } => {
+                    if !session_id_stored {
+                        msg_store.push_session_id(session_id);
+                        session_id_stored = true;
+                    }
+                }
+                AgentEvent::Thinking { content } => {
+                    add_normalized_entry(
+                        &msg_store,
+                        &entry_index_provider,
+                        message(NormalizedEntryType::Thinking, content),
+                    );
+                }
+                AgentEvent::Assistant { content } => {
+                    add_normalized_entry(
+                        &msg_store,
+                        &entry_index_provider,
+                        message(NormalizedEntryType::AssistantMessage, content),
+                    );
+                }
+                AgentEvent::ToolCall {
+                    id,
+                    name,
+                    arguments,
+                    input,
+                } => {
+                    let entry = tool_entry(&id, &name, &arguments, input, &worktree_path);
+                    let index =
+                        add_normalized_entry(&msg_store, &entry_index_provider, entry.clone());
+                    tool_calls.insert(id, (index, entry));
+                }
+                AgentEvent::ToolResult { id, result } => {
+                    let Some((index, entry)) = tool_calls.remove(&id) else {
+                        continue;
+                    };
+                    replace_normalized_entry(&msg_store, index, with_result(entry, &result));
+                }
+                AgentEvent::ApprovalResponse {
+                    tool_name, status, ..
+                } => {
+                    // Pending and approved calls are shown by the approval service
+                    let entry = match status {
+                        ApprovalStatus::Denied { reason } => message(
+                            NormalizedEntryType::UserFeedback {
+                                denied_tool: tool_name,
+                            },
+                            reason
+                                .map(|reason| reason.trim().to_string())
+                                .filter(|reason| !reason.is_empty())
+                                .unwrap_or_else(|| "User denied this tool use request".to_string()),
+                        ),
+                        ApprovalStatus::TimedOut => message(
+                            NormalizedEntryType::ErrorMessage {
+                                error_type: NormalizedEntryError::Other,
+                            },
+                            format!("Approval timed out for tool {tool_name}"),
+                        ),
+                        ApprovalStatus::Approved | ApprovalStatus::Pending => continue,
+                    };
+                    add_normalized_entry(&msg_store, &entry_index_provider, entry);
+                }
+                AgentEvent::Usage {
+                    model,
+                    input_tokens,
+                    output_tokens,
+                    cached_tokens,
+                } => {
+                    msg_store.push_usage(TokenUsage {
+                        model: Some(model),
+                        input_tokens: input_tokens.saturating_sub(cached_tokens),
+                        output_tokens,
+                        cache_read_tokens: cached_tokens,
+                        cache_write_tokens: 0,
+                        cost_usd: None,
+                    });
+                }
+                AgentEvent::Error { message: error } => {
+                    add_normalized_entry(
+                        &msg_store,
+                        &entry_index_provider,
+                        message(
+                            NormalizedEntryType::ErrorMessage {
+                                error_type: NormalizedEntryError::Other,
+                            },
+                            error,
+                        ),
+                    );
+                }
+            }
+        }
+    });
+}
+
+fn message(entry_type: NormalizedEntryType, content: impl Into<String>) -> NormalizedEntry {
+    NormalizedEntry {
+        timestamp: None,
+        entry_type,
+        content: content.into(),
+        metadata: None,
+    }
+}
+
+fn tool_entry(
+    id: &str,
+    name: &str,
+    arguments: &str,
+    input: Option<ToolInput>,
+    worktree_path: &str,
+) -> NormalizedEntry {
+    let relative = |path: &str| make_path_relative(path, worktree_path);
+    let (action_type, content) = match input {
+        Some(ToolInput::ReadFile { path, .. }) => {
+            let path = relative(&path);
+            (ActionType::FileRead { path: path.clone() }, path)
+        }
+        Some(ToolInput::WriteFile { path, content }) => {
+            let path = relative(&path);
+            (
+                ActionType::FileEdit {
+                    path: path.clone(),
+                    changes: vec![FileChange::Write { content }],
+                },
+                path,
+            )
+        }
+        Some(ToolInput::RunCommand { command }) => (
+            ActionType::CommandRun {
+                command: command.clone(),
+                result: None,
+            },
+            command,
+        ),
+        Some(ToolInput::Search { pattern, .. }) => (
+            ActionType::Search {
+                query: pattern.clone(),
+            },
+            pattern,
+        ),
+        None => (
+            ActionType::Tool {
+                tool_name: name.to_string(),
+                arguments: Some(
+                    serde_json::from_str(arguments)
+                        .unwrap_or_else(|_| Value::String(arguments.to_string())),
+                ),
+                result: None,
+            },
+            name.to_string(),
+        ),
+    };
+
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::ToolUse {
+            tool_name: name.to_string(),
+            action_type,
+            status: ToolStatus::Created,
+        },
+        content,
+        metadata: serde_json::to_value(ToolCallMetadata {
+            tool_call_id: id.to_string(),
+        })
+        .ok(),
+    }
+}
+
+fn with_result(mut entry: NormalizedEntry, result: &ToolOutput) -> NormalizedEntry {
+    if let NormalizedEntryType::ToolUse {
+        action_type,
+        status,
+        ..
+    } = &mut entry.entry_type
+    {
+        *status = if result.success {
+            ToolStatus::Success
+        } else {
+            ToolStatus::Failed
+        };
+        match action_type {
+            ActionType::CommandRun { result: slot, .. } => {
+                *slot = Some(CommandRunResult {
+                    exit_status: result
+                        .exit_code
+                        .map(|code| CommandExitStatus::ExitCode { code }),
+                    output: Some(result.output.clone()),
+                });
+            }
+            ActionType::Tool { result: slot, .. } => {
+                *slot = Some(ToolResult::markdown(result.output.clone()));
+            }
+            _ => {}
+        }
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_results_fill_in_the_tool_entry() {
+        let input = ToolInput::RunCommand {
+            command: "cargo test".to_string(),
+        };
+        let entry = tool_entry("call_1", "run_command", "{}", Some(input), "/work");
+        let entry = with_result(
+            entry,
+            &ToolOutput {
+                output: "1 failed".to_string(),
+                success: false,
+                exit_code: Some(101),
+            },
+        );
+
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::CommandRun { command, result },
+            status,
+            ..
+        } = entry.entry_type
+        else {
+            panic!("expected a command entry");
+        };
+        assert_eq!(command, "cargo test");
+        assert!(matches!(status, ToolStatus::Failed));
+        let result = result.unwrap();
+        assert!(matches!(
+            result.exit_status,
+            Some(CommandExitStatus::ExitCode { code: 101 })
+        ));
+        assert_eq!(
+            entry.metadata,
+            Some(serde_json::json!({ "tool_call_id": "call_1" }))
+        );
+    }
+
+    #[test]
+    fn file_paths_are_relative_to_the_worktree() {
+        let input = ToolInput::WriteFile {
+            path: "/work/src/lib.rs".to_string(),
+            content: "fn main() {}".to_string(),
+        };
+        let entry = tool_entry("call_2", "write_file", "{}", Some(input), "/work");
+        assert_eq!(entry.content, "src/lib.rs");
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileEdit { .. },
+                ..
+            }
+        ));
+    }
+}
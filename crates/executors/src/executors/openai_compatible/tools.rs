@@ -0,0 +1,508 @@
+//! Tools offered to the model. Every tool runs inside the worktree: paths are
+//! resolved against it and may not leave it, and commands start in it.
+
+use std::{
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use command_group::AsyncCommandGroup;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use workspace_utils::shell::get_shell_command;
+
+use crate::env::ExecutionEnv;
+
+/// Longest tool output sent back to the model, in bytes
+const MAX_OUTPUT_BYTES: usize = 30_000;
+/// Most lines returned by a single read_file call
+const MAX_READ_LINES: usize = 2_000;
+/// Most matches returned by a single search call
+const MAX_SEARCH_MATCHES: usize = 200;
+
+/// A parsed tool call
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "tool", rename_all = "snake_case")]
+pub enum ToolInput {
+    ReadFile {
+        path: String,
+        /// First line to return, starting at 1
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        offset: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<usize>,
+    },
+    WriteFile {
+        path: String,
+        content: String,
+    },
+    RunCommand {
+        command: String,
+    },
+    Search {
+        pattern: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+    },
+}
+
+impl ToolInput {
+    /// Parse the JSON arguments the model sent for `name`
+    pub fn parse(name: &str, arguments: &str) -> Result<Self, String> {
+        let arguments = if arguments.trim().is_empty() {
+            "{}"
+        } else {
+            arguments
+        };
+        let mut value: Value = serde_json::from_str(arguments)
+            .map_err(|e| format!("Arguments for {name} are not valid JSON: {e}"))?;
+        let Some(object) = value.as_object_mut() else {
+            return Err(format!("Arguments for {name} must be a JSON object"));
+        };
+        if !TOOL_NAMES.contains(&name) {
+            return Err(format!(
+                "Unknown tool {name}. Available tools: {}",
+                TOOL_NAMES.join(", ")
+            ));
+        }
+        object.insert("tool".to_string(), Value::String(name.to_string()));
+        serde_json::from_value(value).map_err(|e| format!("Invalid arguments for {name}: {e}"))
+    }
+}
+
+/// Outcome of a tool call, sent back to the model
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolOutput {
+    pub output: String,
+    pub success: bool,
+    /// Exit code of run_command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+}
+
+impl ToolOutput {
+    fn ok(output: impl Into<String>) -> Self {
+        Self {
+            output: truncate(output.into()),
+            success: true,
+            exit_code: None,
+        }
+    }
+
+    pub fn error(output: impl Into<String>) -> Self {
+        Self {
+            output: output.into(),
+            success: false,
+            exit_code: None,
+        }
+    }
+}
+
+const TOOL_NAMES: [&str; 4] = ["read_file", "write_file", "run_command", "search"];
+
+/// Function definitions for the chat completions `tools` parameter
+pub fn definitions() -> Vec<Value> {
+    let function = |name: &str, description: &str, parameters: Value| {
+        json!({
+            "type": "function",
+            "function": { "name": name, "description": description, "parameters": parameters }
+        })
+    };
+    vec![
+        function(
+            "read_file",
+            "Read a text file. Lines are numbered from 1; use offset and limit for large files.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path relative to the repository root" },
+                    "offset": { "type": "integer", "description": "First line to read" },
+                    "limit": { "type": "integer", "description": "Number of lines to read" }
+                },
+                "required": ["path"]
+            }),
+        ),
+        function(
+            "write_file",
+            "Create or overwrite a file with the given content.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path relative to the repository root" },
+                    "content": { "type": "string", "description": "Complete new content of the file" }
+                },
+                "required": ["path", "content"]
+            }),
+        ),
+        function(
+            "run_command",
+            "Run a shell command in the repository root and return its output and exit code.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "command": { "type": "string", "description": "Shell command to run" }
+                },
+                "required": ["command"]
+            }),
+        ),
+        function(
+            "search",
+            "Search file contents with a regular expression, skipping ignored files.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string", "description": "Regular expression to search for" },
+                    "path": { "type": "string", "description": "Directory or file to search, relative to the repository root" }
+                },
+                "required": ["pattern"]
+            }),
+        ),
+    ]
+}
+
+/// Runs tool calls inside a worktree
+pub struct ToolRunner {
+    root: PathBuf,
+    env: ExecutionEnv,
+    command_timeout: Duration,
+}
+
+impl ToolRunner {
+    pub fn new(root: PathBuf, env: ExecutionEnv, command_timeout: Duration) -> Self {
+        Self {
+            root,
+            env,
+            command_timeout,
+        }
+    }
+
+    pub async fn run(&self, input: &ToolInput) -> ToolOutput {
+        let result = match input {
+            ToolInput::ReadFile {
+                path,
+                offset,
+                limit,
+            } => self.read_file(path, *offset, *limit).await,
+            ToolInput::WriteFile { path, content } => self.write_file(path, content).await,
+            ToolInput::RunCommand { command } => return self.run_command(command).await,
+            ToolInput::Search { pattern, path } => self.search(pattern, path.as_deref()).await,
+        };
+        result.unwrap_or_else(ToolOutput::error)
+    }
+
+    async fn read_file(
+        &self,
+        path: &str,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<ToolOutput, String> {
+        let full_path = resolve_path(&self.root, path)?;
+        let content = tokio::fs::read_to_string(&full_path)
+            .await
+            .map_err(|e| format!("Failed to read {path}: {e}"))?;
+
+        let start = offset.unwrap_or(1).max(1);
+        let limit = limit.unwrap_or(MAX_READ_LINES).min(MAX_READ_LINES);
+        let total = content.lines().count();
+        let mut output = content
+            .lines()
+            .enumerate()
+            .skip(start - 1)
+            .take(limit)
+            .map(|(idx, line)| format!("{:>6}\t{line}", idx + 1))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let end = (start - 1).saturating_add(limit).min(total);
+        if end < total {
+            output.push_str(&format!(
+                "\n[lines {start}-{end} of {total}; use offset to read more]"
+            ));
+        }
+        Ok(ToolOutput::ok(output))
+    }
+
+    async fn write_file(&self, path: &str, content: &str) -> Result<ToolOutput, String> {
+        let full_path = resolve_path(&self.root, path)?;
+        if let Some(parent) = full_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create directories for {path}: {e}"))?;
+        }
+        tokio::fs::write(&full_path, content)
+            .await
+            .map_err(|e| format!("Failed to write {path}: {e}"))?;
+        Ok(ToolOutput::ok(format!(
+            "Wrote {} lines to {path}",
+            content.lines().count()
+        )))
+    }
+
+    async fn run_command(&self, command: &str) -> ToolOutput {
+        let (shell, shell_arg) = get_shell_command();
+        let mut cmd = self.env.command(shell);
+        cmd.kill_on_drop(true)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(&self.root)
+            .arg(shell_arg)
+            .arg(command);
+        self.env.apply_to_command(&mut cmd);
+
+        // The command runs in its own process group so a timeout also stops
+        // anything it started in the background
+        let mut child = match cmd.group_spawn() {
+            Ok(child) => child,
+            Err(e) => return ToolOutput::error(format!("Failed to run command: {e}")),
+        };
+        let stdout = child.inner().stdout.take();
+        let stderr = child.inner().stderr.take();
+        let finished = tokio::time::timeout(self.command_timeout, async {
+            tokio::try_join!(read_pipe(stdout), read_pipe(stderr), child.wait())
+        })
+        .await;
+
+        let (stdout, stderr, status) = match finished {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => return ToolOutput::error(format!("Failed to run command: {e}")),
+            Err(_) => {
+                if let Err(e) = child.kill().await {
+                    tracing::warn!("Failed to kill timed out command: {e}");
+                }
+                let _ = child.wait().await;
+                return ToolOutput::error(format!(
+                    "Command timed out after {} seconds",
+                    self.command_timeout.as_secs()
+                ));
+            }
+        };
+
+        let mut text = String::from_utf8_lossy(&stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&stderr);
+        if !stderr.trim().is_empty() {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&stderr);
+        }
+        ToolOutput {
+            output: truncate(text),
+            success: status.success(),
+            exit_code: status.code(),
+        }
+    }
+
+    async fn search(&self, pattern: &str, path: Option<&str>) -> Result<ToolOutput, String> {
+        let regex = RegexBuilder::new(pattern)
+            .build()
+            .map_err(|e| format!("Invalid pattern: {e}"))?;
+        let search_root = resolve_path(&self.root, path.unwrap_or("."))?;
+        let root = self.root.clone();
+
+        let matches = tokio::task::spawn_blocking(move || {
+            let mut matches = Vec::new();
+            for entry in ignore::WalkBuilder::new(&search_root).build().flatten() {
+                if !entry.file_type().is_some_and(|t| t.is_file()) {
+                    continue;
+                }
+                // Binary and non UTF-8 files are skipped
+                let Ok(content) = std::fs::read_to_string(entry.path()) else {
+                    continue;
+                };
+                let display = entry
+                    .path()
+                    .strip_prefix(&root)
+                    .unwrap_or(entry.path())
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                for (idx, line) in content.lines().enumerate() {
+                    if regex.is_match(line) {
+                        matches.push(format!("{display}:{}: {}", idx + 1, line.trim()));
+                        if matches.len() >= MAX_SEARCH_MATCHES {
+                            return matches;
+                        }
+                    }
+                }
+            }
+            matches
+        })
+        .await
+        .map_err(|e| format!("Search failed: {e}"))?;
+
+        Ok(ToolOutput::ok(match matches.len() {
+            0 => "No matches".to_string(),
+            MAX_SEARCH_MATCHES => format!(
+                "{}\n[stopped after {MAX_SEARCH_MATCHES} matches]",
+                matches.join("\n")
+            ),
+            _ => matches.join("\n"),
+        }))
+    }
+}
+
+async fn read_pipe(pipe: Option<impl AsyncRead + Unpin>) -> std::io::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    if let Some(mut pipe) = pipe {
+        pipe.read_to_end(&mut buffer).await?;
+    }
+    Ok(buffer)
+}
+
+/// Resolve `path` against `root`, refusing paths that leave it, including
+/// through symlinks
+pub fn resolve_path(root: &Path, path: &str) -> Result<PathBuf, String> {
+    let outside = || format!("{path} is outside the repository");
+    let requested = Path::new(path);
+    let relative = if requested.is_absolute() {
+        requested.strip_prefix(root).map_err(|_| outside())?
+    } else {
+        requested
+    };
+
+    let mut resolved = root.to_path_buf();
+    let mut depth = 0usize;
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => {
+                resolved.push(part);
+                depth += 1;
+            }
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => {
+                resolved.pop();
+                depth -= 1;
+            }
+            _ => return Err(outside()),
+        }
+    }
+
+    // The checks above are lexical, so a symlink inside the worktree could still
+    // point out of it. Follow the deepest part of the path that exists and make
+    // sure it really lives under the root.
+    let Ok(canonical_root) = root.canonicalize() else {
+        return Ok(resolved);
+    };
+    let existing = resolved
+        .ancestors()
+        .find(|ancestor| ancestor.symlink_metadata().is_ok())
+        .unwrap_or(root);
+    match existing.canonicalize() {
+        Ok(canonical) if canonical.starts_with(&canonical_root) => Ok(resolved),
+        _ => Err(outside()),
+    }
+}
+
+/// Keep the start and end of long output
+fn truncate(output: String) -> String {
+    if output.len() <= MAX_OUTPUT_BYTES {
+        return output;
+    }
+    let half = MAX_OUTPUT_BYTES / 2;
+    let mut head_end = half;
+    while !output.is_char_boundary(head_end) {
+        head_end -= 1;
+    }
+    let mut tail_start = output.len() - half;
+    while !output.is_char_boundary(tail_start) {
+        tail_start += 1;
+    }
+    format!(
+        "{}\n[{} bytes omitted]\n{}",
+        &output[..head_end],
+        tail_start - head_end,
+        &output[tail_start..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tool_arguments() {
+        assert_eq!(
+            ToolInput::parse("run_command", r#"{"command":"cargo test"}"#),
+            Ok(ToolInput::RunCommand {
+                command: "cargo test".to_string()
+            })
+        );
+        assert!(ToolInput::parse("write_file", r#"{"path":"a.txt"}"#).is_err());
+        assert!(ToolInput::parse("delete_everything", "{}").is_err());
+        assert!(ToolInput::parse("read_file", "not json").is_err());
+    }
+
+    #[test]
+    fn resolves_paths_inside_the_worktree_only() {
+        let root = Path::new("/work/repo");
+        assert_eq!(
+            resolve_path(root, "src/../lib.rs"),
+            Ok(PathBuf::from("/work/repo/lib.rs"))
+        );
+        assert_eq!(
+            resolve_path(root, "/work/repo/a/b.rs"),
+            Ok(PathBuf::from("/work/repo/a/b.rs"))
+        );
+        assert!(resolve_path(root, "../other/secret").is_err());
+        assert!(resolve_path(root, "/etc/passwd").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_symlinks_that_leave_the_worktree() {
+        use std::os::unix::fs::symlink;
+
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret"), "secret").unwrap();
+        std::fs::create_dir(root.path().join("src")).unwrap();
+        symlink(outside.path(), root.path().join("escape")).unwrap();
+        symlink(outside.path().join("missing"), root.path().join("dangling")).unwrap();
+        symlink(root.path().join("src"), root.path().join("inner")).unwrap();
+
+        assert!(resolve_path(root.path(), "escape/secret").is_err());
+        assert!(resolve_path(root.path(), "escape/new/file.rs").is_err());
+        assert!(resolve_path(root.path(), "dangling").is_err());
+        assert_eq!(
+            resolve_path(root.path(), "inner/lib.rs"),
+            Ok(root.path().join("inner/lib.rs"))
+        );
+        assert_eq!(
+            resolve_path(root.path(), "src/new/lib.rs"),
+            Ok(root.path().join("src/new/lib.rs"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn timed_out_commands_stop_their_background_jobs() {
+        let root = tempfile::tempdir().unwrap();
+        let runner = ToolRunner::new(
+            root.path().to_path_buf(),
+            ExecutionEnv::new(),
+            Duration::from_millis(300),
+        );
+
+        let output = runner
+            .run(&ToolInput::RunCommand {
+                command: "(sleep 1; touch marker) & sleep 30".to_string(),
+            })
+            .await;
+        assert!(!output.success);
+        assert!(output.output.contains("timed out"));
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!root.path().join("marker").exists());
+    }
+
+    #[test]
+    fn truncates_long_output_in_the_middle() {
+        let output = format!("{}{}", "a".repeat(MAX_OUTPUT_BYTES), "b".repeat(100));
+        let truncated = truncate(output);
+        assert!(truncated.starts_with('a'));
+        assert!(truncated.ends_with('b'));
+        assert!(truncated.contains("[100 bytes omitted]"));
+    }
+}
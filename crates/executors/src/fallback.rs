@@ -0,0 +1,289 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::profile::{ExecutorProfileId, canonical_variant_key};
+
+/// Lines at the end of a failed process' output searched for the failure cause.
+/// Earlier output is the agent's own work and may mention rate limits or auth.
+const CLASSIFY_TAIL_LINES: usize = 20;
+
+/// Cause of a coding agent failure that another attempt may not run into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentFailure {
+    RateLimited,
+    Overloaded,
+    Network,
+    /// The agent's login or API key stopped working. Retrying the same agent does
+    /// not help, but another agent may still run.
+    AuthExpired,
+}
+
+const AUTH_PATTERNS: &[&str] = &[
+    "authentication_error",
+    "invalid api key",
+    "invalid x-api-key",
+    "token has expired",
+    "token expired",
+    "invalid_grant",
+    "not logged in",
+    "please run /login",
+    "please log in",
+    "unauthorized",
+];
+const RATE_LIMIT_PATTERNS: &[&str] = &[
+    "rate limit",
+    "rate_limit",
+    "ratelimit",
+    "too many requests",
+    "resource_exhausted",
+    "quota exceeded",
+    "usage limit",
+];
+const OVERLOADED_PATTERNS: &[&str] = &[
+    "overloaded",
+    "service unavailable",
+    "bad gateway",
+    "gateway timeout",
+];
+const NETWORK_PATTERNS: &[&str] = &[
+    "econnreset",
+    "econnrefused",
+    "etimedout",
+    "enotfound",
+    "eai_again",
+    "socket hang up",
+    "fetch failed",
+    "network error",
+    "connection error",
+    "connection reset",
+    "connection refused",
+    "request timed out",
+];
+
+impl AgentFailure {
+    /// Classify a failed coding agent process from the end of its output
+    pub fn classify(output: &str) -> Option<Self> {
+        let lines: Vec<&str> = output.lines().collect();
+        let tail = lines[lines.len().saturating_sub(CLASSIFY_TAIL_LINES)..]
+            .join("\n")
+            .to_ascii_lowercase();
+        let matches = |patterns: &[&str], codes: &[u16]| {
+            patterns.iter().any(|pattern| tail.contains(pattern))
+                || codes.iter().any(|code| has_status_code(&tail, *code))
+        };
+
+        if matches(AUTH_PATTERNS, &[401]) {
+            Some(Self::AuthExpired)
+        } else if matches(RATE_LIMIT_PATTERNS, &[429]) {
+            Some(Self::RateLimited)
+        } else if matches(OVERLOADED_PATTERNS, &[502, 503, 504, 529]) {
+            Some(Self::Overloaded)
+        } else if matches(NETWORK_PATTERNS, &[]) {
+            Some(Self::Network)
+        } else {
+            None
+        }
+    }
+
+    /// Whether running the same profile again after a delay may succeed
+    pub fn is_transient(&self) -> bool {
+        !matches!(self, Self::AuthExpired)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::RateLimited => "hit a rate limit",
+            Self::Overloaded => "found its API overloaded",
+            Self::Network => "lost its network connection",
+            Self::AuthExpired => "is no longer logged in",
+        }
+    }
+}
+
+const STATUS_PREFIXES: &[&str] = &[
+    "error: ",
+    "error ",
+    "status: ",
+    "status ",
+    "status code ",
+    "http ",
+];
+
+/// HTTP status codes only count next to a word that marks them as one, so that
+/// line numbers and counts in the output are not mistaken for them
+fn has_status_code(text: &str, code: u16) -> bool {
+    STATUS_PREFIXES
+        .iter()
+        .any(|prefix| text.contains(&format!("{prefix}{code}")))
+        || text.contains(&format!("({code})"))
+}
+
+fn default_retries() -> u32 {
+    2
+}
+
+fn default_backoff_secs() -> u64 {
+    30
+}
+
+/// Profiles tried in order when a coding agent fails for a reason outside of its
+/// control, e.g. `CLAUDE_CODE:DEFAULT` → `CODEX:HIGH` → `GEMINI`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct ExecutorFallbackPolicy {
+    /// The profiles of the chain, starting with the preferred one
+    pub chain: Vec<ExecutorProfileId>,
+    /// How often a profile is retried after a transient failure before the next
+    /// profile of the chain takes over
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// Seconds to wait before the first retry of a profile; doubles with every retry
+    #[serde(default = "default_backoff_secs")]
+    #[ts(type = "number")]
+    pub backoff_secs: u64,
+}
+
+/// What to do after a coding agent failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum FallbackStep {
+    /// Run the same profile again once the delay has passed
+    Retry { delay: Duration },
+    /// Continue with the next profile of the chain
+    Switch(ExecutorProfileId),
+}
+
+impl ExecutorFallbackPolicy {
+    /// Position of a profile in the chain. A profile without variant is the same
+    /// as its DEFAULT variant.
+    pub fn position(&self, profile: &ExecutorProfileId) -> Option<usize> {
+        self.chain
+            .iter()
+            .position(|candidate| same_profile(candidate, profile))
+    }
+
+    /// Next step after `profile` failed with `failure`, having been retried
+    /// `retries_done` times in a row. None once the chain is exhausted.
+    pub fn next_step(
+        &self,
+        profile: &ExecutorProfileId,
+        failure: AgentFailure,
+        retries_done: u32,
+    ) -> Option<FallbackStep> {
+        let position = self.position(profile)?;
+        if failure.is_transient() && retries_done < self.retries {
+            let delay = self
+                .backoff_secs
+                .saturating_mul(2u64.saturating_pow(retries_done));
+            return Some(FallbackStep::Retry {
+                delay: Duration::from_secs(delay),
+            });
+        }
+        self.chain
+            .get(position + 1)
+            .cloned()
+            .map(FallbackStep::Switch)
+    }
+}
+
+fn same_profile(a: &ExecutorProfileId, b: &ExecutorProfileId) -> bool {
+    let variant =
+        |id: &ExecutorProfileId| canonical_variant_key(id.variant.as_deref().unwrap_or("DEFAULT"));
+    a.executor == b.executor && variant(a) == variant(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executors::BaseCodingAgent;
+
+    fn policy() -> ExecutorFallbackPolicy {
+        ExecutorFallbackPolicy {
+            chain: vec![
+                ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "DEFAULT".into()),
+                ExecutorProfileId::with_variant(BaseCodingAgent::Codex, "HIGH".into()),
+                ExecutorProfileId::new(BaseCodingAgent::Gemini),
+            ],
+            retries: 2,
+            backoff_secs: 10,
+        }
+    }
+
+    #[test]
+    fn classifies_the_end_of_the_output() {
+        assert_eq!(
+            AgentFailure::classify("working...\nAPI Error: 429 Too Many Requests"),
+            Some(AgentFailure::RateLimited)
+        );
+        assert_eq!(
+            AgentFailure::classify(r#"{"type":"error","error":{"type":"overloaded_error"}}"#),
+            Some(AgentFailure::Overloaded)
+        );
+        assert_eq!(
+            AgentFailure::classify("stream error: status 503"),
+            Some(AgentFailure::Overloaded)
+        );
+        assert_eq!(
+            AgentFailure::classify("Error: read ECONNRESET"),
+            Some(AgentFailure::Network)
+        );
+        assert_eq!(
+            AgentFailure::classify("OAuth token has expired. Please run /login"),
+            Some(AgentFailure::AuthExpired)
+        );
+        assert_eq!(
+            AgentFailure::classify("error[E0308]: mismatched types"),
+            None
+        );
+        assert_eq!(AgentFailure::classify("src/lib.rs:429: panicked"), None);
+    }
+
+    #[test]
+    fn ignores_causes_before_the_tail() {
+        let mut output = String::from("implementing a rate limit for the API\n");
+        output.push_str(&"compiling\n".repeat(CLASSIFY_TAIL_LINES));
+        output.push_str("process exited");
+        assert_eq!(AgentFailure::classify(&output), None);
+    }
+
+    #[test]
+    fn retries_with_backoff_then_switches() {
+        let policy = policy();
+        let claude = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+
+        assert_eq!(
+            policy.next_step(&claude, AgentFailure::RateLimited, 0),
+            Some(FallbackStep::Retry {
+                delay: Duration::from_secs(10)
+            })
+        );
+        assert_eq!(
+            policy.next_step(&claude, AgentFailure::RateLimited, 1),
+            Some(FallbackStep::Retry {
+                delay: Duration::from_secs(20)
+            })
+        );
+        assert_eq!(
+            policy.next_step(&claude, AgentFailure::RateLimited, 2),
+            Some(FallbackStep::Switch(policy.chain[1].clone()))
+        );
+    }
+
+    #[test]
+    fn auth_failures_switch_right_away() {
+        let policy = policy();
+        let codex = ExecutorProfileId::with_variant(BaseCodingAgent::Codex, "high".into());
+
+        assert_eq!(
+            policy.next_step(&codex, AgentFailure::AuthExpired, 0),
+            Some(FallbackStep::Switch(policy.chain[2].clone()))
+        );
+        let gemini = ExecutorProfileId::with_variant(BaseCodingAgent::Gemini, "DEFAULT".into());
+        assert_eq!(
+            policy.next_step(&gemini, AgentFailure::AuthExpired, 0),
+            None
+        );
+        let amp = ExecutorProfileId::new(BaseCodingAgent::Amp);
+        assert_eq!(policy.next_step(&amp, AgentFailure::Network, 0), None);
+    }
+}
@@ -10,8 +10,9 @@ use serde::{Deserialize, Deserializer, Serialize, de::Error as DeError};
 use thiserror::Error;
 use ts_rs::TS;
 
-use crate::executors::{
-    AvailabilityInfo, BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor,
+use crate::{
+    executors::{AvailabilityInfo, BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor},
+    fallback::ExecutorFallbackPolicy,
 };
 
 /// Return the canonical form for variant keys.
@@ -173,6 +174,9 @@ impl ExecutorConfig {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ExecutorConfigs {
     pub executors: HashMap<BaseCodingAgent, ExecutorConfig>,
+    /// Profiles to fall back to when a coding agent fails for a transient reason
+    #[serde(default)]
+    pub fallbacks: Vec<ExecutorFallbackPolicy>,
 }
 
 impl ExecutorConfigs {
@@ -267,6 +271,10 @@ impl ExecutorConfigs {
 
     /// Deep merge defaults with user overrides
     fn merge_with_defaults(mut defaults: Self, overrides: Self) -> Self {
+        // Fallback chains are replaced as a whole
+        if !overrides.fallbacks.is_empty() {
+            defaults.fallbacks = overrides.fallbacks;
+        }
         for (executor_key, override_profile) in overrides.executors {
             match defaults.executors.get_mut(&executor_key) {
                 Some(default_profile) => {
@@ -288,6 +296,11 @@ impl ExecutorConfigs {
     fn compute_overrides(defaults: &Self, current: &Self) -> Result<Self, ProfileError> {
         let mut overrides = Self {
             executors: HashMap::new(),
+            fallbacks: if current.fallbacks != defaults.fallbacks {
+                current.fallbacks.clone()
+            } else {
+                Vec::new()
+            },
         };
 
         // Fast scan for any illegal deletions BEFORE allocating/cloning
@@ -376,9 +389,34 @@ impl ExecutorConfigs {
                 }
             }
         }
+
+        for policy in &merged.fallbacks {
+            for (position, profile_id) in policy.chain.iter().enumerate() {
+                if merged.get_coding_agent(profile_id).is_none() {
+                    return Err(ProfileError::Validation(format!(
+                        "Fallback chain refers to unknown profile '{profile_id}'"
+                    )));
+                }
+                if policy.position(profile_id) != Some(position) {
+                    return Err(ProfileError::Validation(format!(
+                        "Fallback chain lists profile '{profile_id}' more than once"
+                    )));
+                }
+            }
+        }
         Ok(())
     }
 
+    /// Fallback policy whose chain contains the profile, if any
+    pub fn fallback_policy(
+        &self,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Option<&ExecutorFallbackPolicy> {
+        self.fallbacks
+            .iter()
+            .find(|policy| policy.position(executor_profile_id).is_some())
+    }
+
     /// Load from the new v3 defaults
     pub fn from_defaults() -> Self {
         serde_json::from_str(DEFAULT_PROFILES_JSON).unwrap_or_else(|e| {
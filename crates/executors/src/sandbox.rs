@@ -0,0 +1,508 @@
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use ts_rs::TS;
+use workspace_utils::shell::resolve_executable_path;
+
+use crate::executors::ExecutorError;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(use_ts_enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SandboxBackend {
+    #[default]
+    None,
+    Bubblewrap,
+}
+
+fn default_read_write_paths() -> Vec<String> {
+    [
+        "~/.npm",
+        "~/.cache",
+        "~/.claude",
+        "~/.claude.json",
+        "~/.codex",
+        "~/.gemini",
+        "~/.qwen",
+        "~/.cursor",
+        "~/.factory",
+        "~/.copilot",
+        "~/.config/amp",
+        "~/.local/share/amp",
+        "~/.config/opencode",
+        "~/.local/share/opencode",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// How executor processes (coding agents and scripts) are isolated from the host.
+///
+/// With a sandbox backend the filesystem is mounted read-only, the home directory and
+/// `/tmp` are replaced by empty tmpfs mounts, and only the workspace, the parts of its
+/// repositories' git directories that commits touch and `read_write_paths` stay writable.
+/// Git hooks and config stay read-only, since the host runs git in these repositories.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct SandboxConfig {
+    /// `BUBBLEWRAP` runs processes in Linux user namespaces via `bwrap`
+    pub backend: SandboxBackend,
+    /// Extra paths visible read-only inside the sandbox. `~` expands to the home directory.
+    #[serde(default)]
+    pub read_only_paths: Vec<String>,
+    /// Extra paths writable inside the sandbox, such as agent credentials and caches
+    #[serde(default = "default_read_write_paths")]
+    pub read_write_paths: Vec<String>,
+    /// Cut the sandbox off from the network. Coding agents need the network to reach
+    /// their model API, so this suits scripts and local models.
+    #[serde(default)]
+    pub deny_network: bool,
+    /// Data segment limit (RLIMIT_DATA) per process, in megabytes
+    #[serde(default)]
+    pub memory_limit_mb: Option<u32>,
+    /// CPU time limit (RLIMIT_CPU) per process, in seconds
+    #[serde(default)]
+    pub cpu_time_limit_secs: Option<u32>,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            backend: SandboxBackend::None,
+            read_only_paths: Vec::new(),
+            read_write_paths: default_read_write_paths(),
+            deny_network: false,
+            memory_limit_mb: None,
+            cpu_time_limit_secs: None,
+        }
+    }
+}
+
+/// The isolation applied to one execution process, as recorded on it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct SandboxPolicy {
+    pub backend: SandboxBackend,
+    pub read_only_paths: Vec<PathBuf>,
+    pub read_write_paths: Vec<PathBuf>,
+    /// Paths inside writable mounts that stay read-only, such as git hooks and config
+    #[serde(default)]
+    pub protected_paths: Vec<PathBuf>,
+    pub deny_network: bool,
+    pub memory_limit_mb: Option<u32>,
+    pub cpu_time_limit_secs: Option<u32>,
+}
+
+/// Git paths of one worktree. Committing needs `writable`. `protected` holds what
+/// git on the host reads to decide which code to run, so the sandbox must not change it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorktreeGitPaths {
+    pub writable: Vec<PathBuf>,
+    pub protected: Vec<PathBuf>,
+}
+
+impl WorktreeGitPaths {
+    /// Work out the git paths of `worktree`, a linked worktree of the repository at `repo`
+    pub async fn resolve(worktree: &Path, repo: &Path) -> Self {
+        let common_dir = repo.join(".git");
+        let dot_git = worktree.join(".git");
+        let git_dir = tokio::fs::read_to_string(&dot_git)
+            .await
+            .ok()
+            .and_then(|content| {
+                content
+                    .lines()
+                    .find_map(|line| line.strip_prefix("gitdir:"))
+                    .map(|path| worktree.join(path.trim()))
+            })
+            .or_else(|| {
+                worktree
+                    .file_name()
+                    .map(|name| common_dir.join("worktrees").join(name))
+            });
+
+        let mut writable = vec![
+            common_dir.join("objects"),
+            common_dir.join("refs"),
+            common_dir.join("logs"),
+        ];
+        let mut protected = vec![dot_git, common_dir.join("config"), common_dir.join("hooks")];
+        if let Some(git_dir) = git_dir {
+            // `commondir` and `gitdir` point git at the repository, so rewriting them
+            // could swap in a config of the sandbox's choosing
+            protected
+                .extend(["commondir", "gitdir", "config.worktree"].map(|file| git_dir.join(file)));
+            writable.insert(0, git_dir);
+        }
+
+        let mut existing = Vec::with_capacity(protected.len());
+        for path in protected {
+            if tokio::fs::symlink_metadata(&path).await.is_ok() {
+                existing.push(path);
+            }
+        }
+        Self {
+            writable,
+            protected: existing,
+        }
+    }
+}
+
+/// A resolved sandbox, ready to wrap executor commands
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    policy: SandboxPolicy,
+    bwrap: Option<PathBuf>,
+    prlimit: Option<PathBuf>,
+    home: Option<PathBuf>,
+}
+
+impl SandboxConfig {
+    fn has_limits(&self) -> bool {
+        self.memory_limit_mb.is_some() || self.cpu_time_limit_secs.is_some()
+    }
+
+    /// Tighten the resource limits for a single process. The lower limit wins.
+    pub fn with_limits(
+        mut self,
+        memory_limit_mb: Option<u32>,
+        cpu_time_limit_secs: Option<u32>,
+    ) -> Self {
+        self.memory_limit_mb = lowest(self.memory_limit_mb, memory_limit_mb);
+        self.cpu_time_limit_secs = lowest(self.cpu_time_limit_secs, cpu_time_limit_secs);
+        self
+    }
+
+    /// Resolve the config for a process that may write to `writable` (its workspace and
+    /// git directories) but never to `protected`. Returns `None` when neither isolation
+    /// nor limits are configured.
+    pub async fn prepare(
+        &self,
+        writable: Vec<PathBuf>,
+        protected: Vec<PathBuf>,
+    ) -> Result<Option<Sandbox>, ExecutorError> {
+        if self.backend == SandboxBackend::None && !self.has_limits() {
+            return Ok(None);
+        }
+        if !cfg!(target_os = "linux") {
+            return Err(ExecutorError::SandboxUnavailable(
+                "sandboxing and resource limits are only supported on Linux".to_string(),
+            ));
+        }
+
+        let bwrap = match self.backend {
+            SandboxBackend::None => None,
+            SandboxBackend::Bubblewrap => Some(required_tool("bwrap").await?),
+        };
+        let prlimit = if self.has_limits() {
+            Some(required_tool("prlimit").await?)
+        } else {
+            None
+        };
+
+        let home = dirs::home_dir();
+        let expand = |paths: &[String]| -> Vec<PathBuf> {
+            paths
+                .iter()
+                .map(|path| expand_home(path.trim(), home.as_deref()))
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect()
+        };
+        let mut read_write_paths = writable;
+        read_write_paths.extend(expand(&self.read_write_paths));
+
+        Ok(Some(Sandbox {
+            policy: SandboxPolicy {
+                backend: self.backend,
+                read_only_paths: expand(&self.read_only_paths),
+                read_write_paths,
+                protected_paths: protected,
+                deny_network: self.deny_network,
+                memory_limit_mb: self.memory_limit_mb,
+                cpu_time_limit_secs: self.cpu_time_limit_secs,
+            },
+            bwrap,
+            prlimit,
+            home,
+        }))
+    }
+}
+
+impl Sandbox {
+    pub fn policy(&self) -> &SandboxPolicy {
+        &self.policy
+    }
+
+    /// A command that runs `program` inside the sandbox. Arguments, stdio and the working
+    /// directory are set on the returned command as usual.
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        let program = Path::new(program.as_ref());
+        let mut argv: Vec<OsString> = Vec::new();
+        if let Some(bwrap) = &self.bwrap {
+            argv.push(bwrap.into());
+            argv.extend(self.bubblewrap_args(program));
+        }
+        if let Some(prlimit) = &self.prlimit {
+            argv.push(prlimit.into());
+            argv.extend(self.prlimit_args());
+        }
+        argv.push(program.into());
+
+        let mut command = Command::new(&argv[0]);
+        command.args(&argv[1..]);
+        command
+    }
+
+    fn prlimit_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        if let Some(mb) = self.policy.memory_limit_mb {
+            args.push(format!("--data={}", u64::from(mb) * 1024 * 1024).into());
+        }
+        if let Some(secs) = self.policy.cpu_time_limit_secs {
+            args.push(format!("--cpu={secs}").into());
+        }
+        args.push("--".into());
+        args
+    }
+
+    fn bubblewrap_args(&self, program: &Path) -> Vec<OsString> {
+        let mut args: Vec<OsString> = ["--die-with-parent", "--unshare-all"]
+            .into_iter()
+            .map(OsString::from)
+            .collect();
+        if !self.policy.deny_network {
+            args.push("--share-net".into());
+        }
+
+        args.extend(["--ro-bind", "/", "/"].map(OsString::from));
+        // Fresh /dev, /proc and /tmp
+        args.extend(["--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"].map(OsString::from));
+        if let Some(home) = &self.home {
+            args.push("--tmpfs".into());
+            args.push(home.into());
+        }
+
+        let mut mount = |flag: &str, path: &Path| {
+            args.push(flag.into());
+            args.push(path.into());
+            args.push(path.into());
+        };
+        // Tools installed under the home directory (nvm, cargo, ...) stay runnable
+        if let Some(home) = &self.home
+            && let Some(prefix) = program.parent().and_then(Path::parent)
+            && prefix.starts_with(home)
+            && prefix != home
+        {
+            mount("--ro-bind-try", prefix);
+        }
+        for path in &self.policy.read_only_paths {
+            mount("--ro-bind-try", path);
+        }
+        for path in &self.policy.read_write_paths {
+            mount("--bind-try", path);
+        }
+        // Mounted last so they cover the writable mounts they sit in
+        for path in &self.policy.protected_paths {
+            mount("--ro-bind", path);
+        }
+
+        args.push("--".into());
+        args
+    }
+}
+
+fn lowest(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+async fn required_tool(program: &str) -> Result<PathBuf, ExecutorError> {
+    resolve_executable_path(program).await.ok_or_else(|| {
+        ExecutorError::SandboxUnavailable(format!("`{program}` was not found in PATH"))
+    })
+}
+
+fn expand_home(path: &str, home: Option<&Path>) -> PathBuf {
+    match (path.strip_prefix("~"), home) {
+        (Some(""), Some(home)) => home.to_path_buf(),
+        (Some(rest), Some(home)) if rest.starts_with('/') => home.join(&rest[1..]),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandbox(policy: SandboxPolicy, prlimit: bool) -> Sandbox {
+        Sandbox {
+            bwrap: (policy.backend == SandboxBackend::Bubblewrap)
+                .then(|| PathBuf::from("/usr/bin/bwrap")),
+            prlimit: prlimit.then(|| PathBuf::from("/usr/bin/prlimit")),
+            home: Some(PathBuf::from("/home/dev")),
+            policy,
+        }
+    }
+
+    fn policy(backend: SandboxBackend) -> SandboxPolicy {
+        SandboxPolicy {
+            backend,
+            read_only_paths: vec![PathBuf::from("/opt/sdk")],
+            read_write_paths: vec![PathBuf::from("/home/dev/worktrees/vk-1")],
+            protected_paths: vec![],
+            deny_network: false,
+            memory_limit_mb: None,
+            cpu_time_limit_secs: None,
+        }
+    }
+
+    fn argv(command: &Command) -> Vec<String> {
+        let command = command.as_std();
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn expands_home() {
+        let home = Path::new("/home/dev");
+        assert_eq!(expand_home("~", Some(home)), PathBuf::from("/home/dev"));
+        assert_eq!(
+            expand_home("~/.claude", Some(home)),
+            PathBuf::from("/home/dev/.claude")
+        );
+        assert_eq!(expand_home("~other", Some(home)), PathBuf::from("~other"));
+        assert_eq!(expand_home("/srv", Some(home)), PathBuf::from("/srv"));
+    }
+
+    #[test]
+    fn bubblewrap_hides_home_and_binds_workspace() {
+        let sandbox = sandbox(policy(SandboxBackend::Bubblewrap), false);
+        let argv = argv(&sandbox.command("/home/dev/.nvm/versions/node/v22/bin/npx"));
+        let joined = argv.join(" ");
+
+        assert_eq!(argv[0], "/usr/bin/bwrap");
+        assert!(joined.contains("--share-net"));
+        assert!(joined.contains("--tmpfs /home/dev"));
+        assert!(joined.contains(
+            "--ro-bind-try /home/dev/.nvm/versions/node/v22 /home/dev/.nvm/versions/node/v22"
+        ));
+        assert!(joined.contains("--ro-bind-try /opt/sdk /opt/sdk"));
+        assert!(joined.contains("--bind-try /home/dev/worktrees/vk-1 /home/dev/worktrees/vk-1"));
+        // Writable mounts come after the tmpfs that would otherwise hide them
+        assert!(joined.find("--tmpfs /home/dev").unwrap() < joined.find("--bind-try").unwrap());
+        assert!(joined.ends_with("-- /home/dev/.nvm/versions/node/v22/bin/npx"));
+    }
+
+    #[test]
+    fn bubblewrap_keeps_git_hooks_and_config_read_only() {
+        let mut policy = policy(SandboxBackend::Bubblewrap);
+        policy.read_write_paths.extend([
+            PathBuf::from("/src/app/.git/worktrees/vk-1"),
+            PathBuf::from("/src/app/.git/objects"),
+            PathBuf::from("/src/app/.git/refs"),
+        ]);
+        policy.protected_paths = vec![
+            PathBuf::from("/src/app/.git/hooks"),
+            PathBuf::from("/src/app/.git/config"),
+        ];
+        let joined = argv(&sandbox(policy, false).command("/usr/bin/bash")).join(" ");
+
+        assert!(!joined.contains("--bind-try /src/app/.git /src/app/.git"));
+        assert!(joined.contains("--bind-try /src/app/.git/objects /src/app/.git/objects"));
+        let hooks = joined
+            .find("--ro-bind /src/app/.git/hooks /src/app/.git/hooks")
+            .expect("hooks are read-only");
+        let config = joined
+            .find("--ro-bind /src/app/.git/config /src/app/.git/config")
+            .expect("config is read-only");
+        let last_writable = joined.rfind("--bind-try").unwrap();
+        assert!(last_writable < hooks && last_writable < config);
+    }
+
+    #[tokio::test]
+    async fn worktree_git_paths_leave_hooks_and_config_out_of_reach() {
+        let root = tempfile::tempdir().unwrap();
+        let repo = root.path().join("app");
+        let common_dir = repo.join(".git");
+        let git_dir = common_dir.join("worktrees/vk-1");
+        for dir in [&git_dir, &common_dir.join("hooks")] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(common_dir.join("config"), "").unwrap();
+        std::fs::write(git_dir.join("commondir"), "../..").unwrap();
+        let worktree = root.path().join("worktrees/vk-1");
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", git_dir.display()),
+        )
+        .unwrap();
+
+        let paths = WorktreeGitPaths::resolve(&worktree, &repo).await;
+
+        assert!(!paths.writable.contains(&common_dir));
+        assert!(paths.writable.contains(&git_dir));
+        assert!(paths.writable.contains(&common_dir.join("objects")));
+        assert_eq!(
+            paths.protected,
+            vec![
+                worktree.join(".git"),
+                common_dir.join("config"),
+                common_dir.join("hooks"),
+                git_dir.join("commondir"),
+            ]
+        );
+    }
+
+    #[test]
+    fn per_process_limits_only_tighten() {
+        let config = SandboxConfig {
+            memory_limit_mb: Some(2048),
+            ..Default::default()
+        }
+        .with_limits(Some(4096), Some(30));
+
+        assert_eq!(config.memory_limit_mb, Some(2048));
+        assert_eq!(config.cpu_time_limit_secs, Some(30));
+        assert_eq!(
+            config.with_limits(Some(512), None).memory_limit_mb,
+            Some(512)
+        );
+    }
+
+    #[test]
+    fn deny_network_keeps_network_unshared() {
+        let mut policy = policy(SandboxBackend::Bubblewrap);
+        policy.deny_network = true;
+        let argv = argv(&sandbox(policy, false).command("/usr/bin/bash"));
+
+        assert!(argv.iter().any(|arg| arg == "--unshare-all"));
+        assert!(!argv.iter().any(|arg| arg == "--share-net"));
+    }
+
+    #[test]
+    fn limits_without_backend_use_prlimit_only() {
+        let mut policy = policy(SandboxBackend::None);
+        policy.memory_limit_mb = Some(512);
+        policy.cpu_time_limit_secs = Some(60);
+        let argv = argv(&sandbox(policy, true).command("/usr/bin/bash"));
+
+        assert_eq!(
+            argv,
+            vec![
+                "/usr/bin/prlimit",
+                "--data=536870912",
+                "--cpu=60",
+                "--",
+                "/usr/bin/bash"
+            ]
+        );
+    }
+}
@@ -13,12 +13,16 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::CodingAgentTurn,
+        conflict_resolution::ConflictResolution,
         execution_process::{
-            ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            ExecutionContext, ExecutionProcess, ExecutionProcessExitReason,
+            ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
+        pipeline::PipelineStepRun,
         project_repo::ProjectRepo,
         repo::Repo,
+        review_follow_up::ReviewFollowUp,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
         task::{Task, TaskStatus},
         workspace::Workspace,
@@ -31,19 +35,21 @@ use executors::{
         Executable, ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
+        script::{ScriptContext, ScriptRequest},
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorExitResult, ExecutorExitSignal, InterruptSender},
     logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
     profile::ExecutorProfileId,
+    sandbox::WorktreeGitPaths,
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
-    config::Config,
+    config::{Config, UsageConfig},
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
     git::{Commit, GitCli, GitService},
@@ -51,10 +57,11 @@ use services::services::{
     notification::NotificationService,
     queued_message::QueuedMessageService,
     share::SharePublisher,
+    webhooks::WebhookService,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
 use tokio::{sync::RwLock, task::JoinHandle};
-use tokio_util::io::ReaderStream;
+use tokio_util::{io::ReaderStream, sync::CancellationToken};
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
@@ -69,6 +76,7 @@ pub struct LocalContainerService {
     db: DBService,
     child_store: Arc<RwLock<HashMap<Uuid, Arc<RwLock<AsyncGroupChild>>>>>,
     interrupt_senders: Arc<RwLock<HashMap<Uuid, InterruptSender>>>,
+    budget_stops: Arc<RwLock<HashMap<Uuid, CancellationToken>>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
@@ -78,6 +86,7 @@ pub struct LocalContainerService {
     queued_message_service: QueuedMessageService,
     publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     notification_service: NotificationService,
+    webhooks: WebhookService,
 }
 
 impl LocalContainerService {
@@ -92,15 +101,18 @@ impl LocalContainerService {
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
         publisher: Result<SharePublisher, RemoteClientNotConfigured>,
+        webhooks: WebhookService,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
+        let budget_stops = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
 
         let container = LocalContainerService {
             db,
             child_store,
             interrupt_senders,
+            budget_stops,
             msg_stores,
             config,
             git,
@@ -110,6 +122,7 @@ impl LocalContainerService {
             queued_message_service,
             publisher,
             notification_service,
+            webhooks,
         };
 
         container.spawn_workspace_cleanup().await;
@@ -271,6 +284,9 @@ impl LocalContainerService {
             ExecutionProcessRunReason::CleanupScript => {
                 format!("Cleanup script changes for workspace {}", ctx.workspace.id)
             }
+            ExecutionProcessRunReason::PipelineStep => {
+                format!("Pipeline step changes for workspace {}", ctx.workspace.id)
+            }
             _ => format!(
                 "Changes from execution process {}",
                 ctx.execution_process.id
@@ -339,11 +355,14 @@ impl LocalContainerService {
     }
 
     /// Spawn a background task that polls the child process for completion and
-    /// cleans up the execution entry when it exits.
+    /// cleans up the execution entry when it exits. Cancelling `budget_stop` kills
+    /// the process once its project has spent its budget.
     pub fn spawn_exit_monitor(
         &self,
         exec_id: &Uuid,
         exit_signal: Option<ExecutorExitSignal>,
+        timeout: Option<Duration>,
+        budget_stop: Option<CancellationToken>,
     ) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
@@ -360,8 +379,17 @@ impl LocalContainerService {
             let mut exit_signal_future = exit_signal
                 .map(|rx| rx.boxed()) // wait for result
                 .unwrap_or_else(|| std::future::pending().boxed()); // no signal, stall forever
+            let timeout_future = match timeout {
+                Some(timeout) => tokio::time::sleep(timeout).boxed(),
+                None => std::future::pending().boxed(),
+            };
+            let budget_stop_future = match budget_stop {
+                Some(token) => async move { token.cancelled().await }.boxed(),
+                None => std::future::pending().boxed(),
+            };
 
             let status_result: std::io::Result<std::process::ExitStatus>;
+            let mut exit_reason = None;
 
             // Wait for process to exit, or exit signal from executor
             tokio::select! {
@@ -388,7 +416,30 @@ impl LocalContainerService {
                 exit_status_result = &mut process_exit_rx => {
                     status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
                 }
+                // Script timeout: kill the group and fail without an exit code
+                _ = timeout_future => {
+                    if let Some(child_lock) = child_store.read().await.get(&exec_id).cloned() {
+                        let mut child = child_lock.write().await;
+                        if let Err(err) = command::kill_process_group(&mut child).await {
+                            tracing::error!("Failed to kill process group after timeout: {} {}", exec_id, err);
+                        }
+                    }
+                    exit_reason = Some(ExecutionProcessExitReason::TimedOut);
+                    status_result = Err(std::io::Error::from(std::io::ErrorKind::TimedOut));
+                }
+                // Budget spent: kill the group and fail like a timed out script
+                _ = budget_stop_future => {
+                    if let Some(child_lock) = child_store.read().await.get(&exec_id).cloned() {
+                        let mut child = child_lock.write().await;
+                        if let Err(err) = command::kill_process_group(&mut child).await {
+                            tracing::error!("Failed to kill process group after budget stop: {} {}", exec_id, err);
+                        }
+                    }
+                    exit_reason = Some(ExecutionProcessExitReason::BudgetExceeded);
+                    status_result = Err(std::io::Error::other("budget exceeded"));
+                }
             }
+            container.budget_stops.write().await.remove(&exec_id);
 
             let (exit_code, status) = match status_result {
                 Ok(exit_status) => {
@@ -403,33 +454,87 @@ impl LocalContainerService {
                 Err(_) => (None, ExecutionProcessStatus::Failed),
             };
 
-            if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
-                && let Err(e) =
-                    ExecutionProcess::update_completion(&db.pool, exec_id, status, exit_code).await
-            {
-                tracing::error!("Failed to update execution process completion: {}", e);
+            // Update executor session summary if available. This happens before the
+            // completion is recorded so the execution webhooks can include it.
+            if let Err(e) = container.update_executor_session_summary(&exec_id).await {
+                tracing::warn!("Failed to update executor session summary: {}", e);
             }
 
-            if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
-                // Update executor session summary if available
-                if let Err(e) = container.update_executor_session_summary(&exec_id).await {
-                    tracing::warn!("Failed to update executor session summary: {}", e);
+            if !ExecutionProcess::was_stopped(&db.pool, exec_id).await {
+                if let Some(exit_reason) = &exit_reason
+                    && let Err(e) =
+                        ExecutionProcess::set_exit_reason(&db.pool, exec_id, exit_reason).await
+                {
+                    tracing::error!("Failed to record execution process exit reason: {}", e);
                 }
+                if let Err(e) =
+                    ExecutionProcess::update_completion(&db.pool, exec_id, status, exit_code).await
+                {
+                    tracing::error!("Failed to update execution process completion: {}", e);
+                }
+            }
 
+            if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
                 ) && exit_code == Some(0);
 
-                let cleanup_done = matches!(
+                let pipeline_step =
+                    match PipelineStepRun::find_by_execution_process_id(&db.pool, exec_id).await {
+                        Ok(step_run) => step_run,
+                        Err(e) => {
+                            tracing::error!("Failed to look up pipeline step run: {}", e);
+                            None
+                        }
+                    };
+
+                // Conflict resolution turns stop inside a rebase or merge, which is
+                // continued instead of committed
+                let conflict_resolution =
+                    match ConflictResolution::find_by_execution_process_id(&db.pool, exec_id).await
+                    {
+                        Ok(resolution) => resolution,
+                        Err(e) => {
+                            tracing::error!("Failed to look up conflict resolution: {}", e);
+                            None
+                        }
+                    };
+
+                // Review follow-up turns push their commit to the pull request branch
+                let review_follow_up =
+                    match ReviewFollowUp::find_by_execution_process_id(&db.pool, exec_id).await {
+                        Ok(follow_up) => follow_up,
+                        Err(e) => {
+                            tracing::error!("Failed to look up review follow-up: {}", e);
+                            None
+                        }
+                    };
+
+                // Verification leaves the workspace as the agent left it
+                let is_verification = matches!(
+                    ctx.execution_process
+                        .executor_action()
+                        .map(|action| action.typ()),
+                    Ok(ExecutorActionType::ScriptRequest(ScriptRequest {
+                        context: ScriptContext::Verification,
+                        ..
+                    }))
+                );
+
+                // Cleanup scripts and pipeline script steps keep their changes even when
+                // they fail
+                let script_done = matches!(
                     ctx.execution_process.run_reason,
                     ExecutionProcessRunReason::CleanupScript
+                        | ExecutionProcessRunReason::PipelineStep
                 ) && !matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Running
-                );
+                ) && !is_verification;
 
-                if success || cleanup_done {
+                let mut verification_running = false;
+                if (success || script_done) && conflict_resolution.is_none() {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -449,7 +554,28 @@ impl LocalContainerService {
                         true
                     };
 
-                    if should_start_next {
+                    // Review follow-ups push right away, without verification
+                    if pipeline_step.is_none()
+                        && review_follow_up.is_none()
+                        && matches!(
+                            ctx.execution_process.run_reason,
+                            ExecutionProcessRunReason::CodingAgent
+                        )
+                    {
+                        verification_running = container
+                            .try_start_verification(&ctx, should_start_next)
+                            .await
+                            .unwrap_or_else(|e| {
+                                tracing::error!("Failed to start verification: {}", e);
+                                false
+                            });
+                    }
+
+                    if pipeline_step.is_some() {
+                        // Pipeline steps have no next action; the pipeline decides below
+                    } else if verification_running {
+                        // The verification starts the next action once it passes
+                    } else if should_start_next {
                         // If the process exited successfully, start the next action
                         if let Err(e) = container.try_start_next_action(&ctx).await {
                             tracing::error!("Failed to start next action after completion: {}", e);
@@ -465,7 +591,84 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
+                let pipeline_running = match &pipeline_step {
+                    Some(step_run) => match container.advance_pipeline(&ctx, step_run).await {
+                        Ok(running) => running,
+                        Err(e) => {
+                            tracing::error!("Failed to advance pipeline run: {}", e);
+                            false
+                        }
+                    },
+                    None => false,
+                };
+
+                let fix_running = is_verification
+                    && container
+                        .finish_verification(&ctx)
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::error!("Failed to finish verification: {}", e);
+                            false
+                        });
+
+                let resolution_running = match &conflict_resolution {
+                    Some(resolution) => container
+                        .finish_conflict_resolution(&ctx, resolution)
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::error!("Failed to finish conflict resolution: {}", e);
+                            false
+                        }),
+                    None => false,
+                };
+
+                if let Some(follow_up) = &review_follow_up
+                    && let Err(e) = container.finish_review_follow_up(&ctx, follow_up).await
+                {
+                    tracing::error!("Failed to finish review follow-up: {}", e);
+                }
+
+                // Transient failures of a coding agent may be retried or handed to the
+                // next profile of its fallback chain after a backoff
+                let fallback = if pipeline_step.is_none()
+                    && conflict_resolution.is_none()
+                    && review_follow_up.is_none()
+                    && matches!(
+                        ctx.execution_process.run_reason,
+                        ExecutionProcessRunReason::CodingAgent
+                    ) {
+                    container.plan_fallback(&ctx).await.unwrap_or_else(|e| {
+                        tracing::error!("Failed to plan fallback: {}", e);
+                        None
+                    })
+                } else {
+                    None
+                };
+                let fallback_pending = fallback.is_some();
+                if let Some(plan) = fallback {
+                    let container = container.clone();
+                    let db = db.clone();
+                    let publisher = publisher.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(plan.delay).await;
+                        let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await
+                        else {
+                            return;
+                        };
+                        if let Err(e) = container.start_fallback(&ctx, &plan).await {
+                            tracing::error!("Failed to start fallback execution: {}", e);
+                            container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                        }
+                    });
+                }
+
+                if !pipeline_running
+                    && !verification_running
+                    && !fix_running
+                    && !resolution_running
+                    && !fallback_pending
+                    && container.should_finalize(&ctx)
+                {
                     // Only execute queued messages if the execution succeeded
                     // If it failed or was killed, just clear the queue and finalize
                     let should_execute_queued = !matches!(
@@ -952,6 +1155,14 @@ impl ContainerService for LocalContainerService {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn usage_config(&self) -> UsageConfig {
+        self.config.read().await.usage.clone()
+    }
+
+    async fn budget_stop(&self, execution_id: &Uuid) -> Option<CancellationToken> {
+        self.budget_stops.read().await.get(execution_id).cloned()
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -1125,11 +1336,13 @@ impl ContainerService for LocalContainerService {
                     | BaseCodingAgent::ClaudeCode
                     | BaseCodingAgent::Gemini
                     | BaseCodingAgent::QwenCode
-                    | BaseCodingAgent::Opencode,
+                    | BaseCodingAgent::Opencode
+                    | BaseCodingAgent::OpenaiCompatible,
                 ) => ExecutorApprovalBridge::new(
                     self.approvals.clone(),
                     self.db.clone(),
                     self.notification_service.clone(),
+                    self.webhooks.clone(),
                     execution_process.id,
                 ),
                 _ => Arc::new(NoopExecutorApprovalService {}),
@@ -1156,6 +1369,35 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
 
+        // Besides the workspace, git needs to write the worktree's index, refs and
+        // objects in each repo's .git directory. Hooks and config stay read-only.
+        let mut writable = vec![current_dir.clone()];
+        let mut protected = Vec::new();
+        for repo in WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await? {
+            let git_paths =
+                WorktreeGitPaths::resolve(&current_dir.join(&repo.name), &repo.path).await;
+            writable.extend(git_paths.writable);
+            protected.extend(git_paths.protected);
+        }
+        let mut sandbox_config = self.config.read().await.sandbox.clone();
+        let mut timeout = None;
+        if let ExecutorActionType::ScriptRequest(script) = executor_action.typ() {
+            sandbox_config = sandbox_config.with_limits(
+                script.options.memory_limit_mb,
+                script.options.cpu_time_limit_secs,
+            );
+            timeout = script
+                .options
+                .timeout_secs
+                .map(|secs| Duration::from_secs(secs.into()));
+        }
+        let sandbox = sandbox_config.prepare(writable, protected).await?;
+        if let Some(sandbox) = &sandbox {
+            ExecutionProcess::set_sandbox(&self.db.pool, execution_process.id, sandbox.policy())
+                .await?;
+        }
+        let env = env.with_sandbox(sandbox);
+
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
             Duration::from_secs(30),
@@ -1180,8 +1422,24 @@ impl ContainerService for LocalContainerService {
                 .await;
         }
 
+        // Coding agents can be stopped once their usage spends the project's budget
+        let budget_stop = executor_action
+            .base_executor()
+            .map(|_| CancellationToken::new());
+        if let Some(token) = &budget_stop {
+            self.budget_stops
+                .write()
+                .await
+                .insert(execution_process.id, token.clone());
+        }
+
         // Spawn unified exit monitor: watches OS exit and optional executor signal
-        let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
+        let _hn = self.spawn_exit_monitor(
+            &execution_process.id,
+            spawned.exit_signal,
+            timeout,
+            budget_stop,
+        );
 
         Ok(())
     }
@@ -1262,8 +1520,16 @@ impl ContainerService for LocalContainerService {
                 ExecutionProcessRunReason::DevServer
             )
         {
-            match Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await {
+            match Task::update_status_by_system(&self.db.pool, ctx.task.id, TaskStatus::InReview)
+                .await
+            {
                 Ok(_) => {
+                    self.record_task_status_change(
+                        ctx.task.id,
+                        &ctx.task.status,
+                        &TaskStatus::InReview,
+                    )
+                    .await;
                     if let Some(publisher) = self.share_publisher()
                         && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
                     {
@@ -1369,7 +1635,9 @@ impl ContainerService for LocalContainerService {
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
         if !matches!(
             ctx.execution_process.run_reason,
-            ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::CleanupScript,
+            ExecutionProcessRunReason::CodingAgent
+                | ExecutionProcessRunReason::CleanupScript
+                | ExecutionProcessRunReason::PipelineStep,
         ) {
             return Ok(false);
         }
@@ -6,7 +6,7 @@ use deployment::{Deployment, DeploymentError, RemoteClientNotConfigured};
 use executors::profile::ExecutorConfigs;
 use services::services::{
     analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
-    approvals::Approvals,
+    approvals::{Approvals, remote::RemoteApprovalSigner},
     auth::AuthContext,
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
@@ -21,12 +21,14 @@ use services::services::{
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
     share::{ShareConfig, SharePublisher},
+    stack::StackMonitorService,
+    task_scheduler::TaskSchedulerService,
     webhooks::WebhookService,
 };
 use tokio::sync::RwLock;
 use utils::{
     api::oauth::LoginStatus,
-    assets::{config_path, credentials_path},
+    assets::{approval_secret_path, config_path, credentials_path},
     msg_store::MsgStore,
 };
 use uuid::Uuid;
@@ -51,6 +53,7 @@ pub struct LocalDeployment {
     events: EventService,
     file_search_cache: Arc<FileSearchCache>,
     approvals: Approvals,
+    webhooks: WebhookService,
     queued_message_service: QueuedMessageService,
     share_publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     share_config: Option<ShareConfig>,
@@ -106,18 +109,18 @@ impl Deployment for LocalDeployment {
         let events_entry_count = Arc::new(RwLock::new(0));
 
         // Create DB with event hooks and webhook service
-        let db = {
+        let (db, webhooks) = {
             // Create a temporary DB service for the hook
             let temp_db = DBService::new().await?;
             // Create webhook service using the temp pool (same database)
-            let webhook_service = Arc::new(WebhookService::new(temp_db.pool.clone()));
+            let webhooks = WebhookService::new(temp_db.pool.clone());
             let hook = EventService::create_hook(
                 events_msg_store.clone(),
                 events_entry_count.clone(),
                 temp_db,
-                Some(webhook_service),
+                Some(Arc::new(webhooks.clone())),
             );
-            DBService::new_with_after_connect(hook).await?
+            (DBService::new_with_after_connect(hook).await?, webhooks)
         };
 
         let image = ImageService::new(db.clone().pool)?;
@@ -131,7 +134,12 @@ impl Deployment for LocalDeployment {
             });
         }
 
-        let approvals = Approvals::new(msg_stores.clone());
+        let remote_signer = RemoteApprovalSigner::load_or_create(&approval_secret_path())
+            .unwrap_or_else(|e| {
+                tracing::warn!(?e, "failed to persist the approval link secret");
+                RemoteApprovalSigner::new()
+            });
+        let approvals = Approvals::new(msg_stores.clone(), remote_signer);
         let queued_message_service = QueuedMessageService::new();
 
         let share_config = ShareConfig::from_env();
@@ -188,6 +196,7 @@ impl Deployment for LocalDeployment {
             approvals.clone(),
             queued_message_service.clone(),
             share_publisher.clone(),
+            webhooks.clone(),
         )
         .await;
 
@@ -209,6 +218,7 @@ impl Deployment for LocalDeployment {
             events,
             file_search_cache,
             approvals,
+            webhooks,
             queued_message_service,
             share_publisher,
             share_config: share_config.clone(),
@@ -272,6 +282,10 @@ impl Deployment for LocalDeployment {
         &self.approvals
     }
 
+    fn webhooks(&self) -> &WebhookService {
+        &self.webhooks
+    }
+
     fn queued_message_service(&self) -> &QueuedMessageService {
         &self.queued_message_service
     }
@@ -283,6 +297,14 @@ impl Deployment for LocalDeployment {
     fn auth_context(&self) -> &AuthContext {
         &self.auth_context
     }
+
+    async fn spawn_task_scheduler_service(&self) -> tokio::task::JoinHandle<()> {
+        TaskSchedulerService::spawn(self.container.clone(), self.config.clone()).await
+    }
+
+    async fn spawn_stack_monitor_service(&self) -> tokio::task::JoinHandle<()> {
+        StackMonitorService::spawn(self.container.clone()).await
+    }
 }
 
 impl LocalDeployment {
@@ -0,0 +1,2 @@
+-- Custom workflow state of a shared task, mirrored from the instance that shares it
+ALTER TABLE shared_tasks ADD COLUMN workflow_state TEXT;
@@ -49,6 +49,8 @@ pub struct SharedTask {
     pub title: String,
     pub description: Option<String>,
     pub status: TaskStatus,
+    /// Name of the project workflow state on the sharing instance, if any
+    pub workflow_state: Option<String>,
     pub deleted_at: Option<DateTime<Utc>>,
     pub shared_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -69,6 +71,7 @@ pub struct UpdateSharedTaskData {
     pub title: Option<String>,
     pub description: Option<String>,
     pub status: Option<TaskStatus>,
+    pub workflow_state: Option<String>,
     pub acting_user_id: Uuid,
 }
 
@@ -126,6 +129,7 @@ impl<'a> SharedTaskRepository<'a> {
                 title               AS "title!",
                 description         AS "description?",
                 status              AS "status!: TaskStatus",
+                workflow_state      AS "workflow_state?",
                 deleted_at          AS "deleted_at?",
                 shared_at           AS "shared_at?",
                 created_at          AS "created_at!",
@@ -189,6 +193,7 @@ impl<'a> SharedTaskRepository<'a> {
                       title              AS "title!",
                       description        AS "description?",
                       status             AS "status!: TaskStatus",
+                      workflow_state     AS "workflow_state?",
                       deleted_at         AS "deleted_at?",
                       shared_at          AS "shared_at?",
                       created_at         AS "created_at!",
@@ -227,6 +232,7 @@ impl<'a> SharedTaskRepository<'a> {
         SET title       = COALESCE($2, t.title),
             description = COALESCE($3, t.description),
             status      = COALESCE($4, t.status),
+            workflow_state = CASE WHEN $4::task_status IS NULL THEN t.workflow_state ELSE $6 END,
             updated_at  = NOW()
         WHERE t.id = $1
           AND t.assignee_user_id = $5
@@ -241,6 +247,7 @@ impl<'a> SharedTaskRepository<'a> {
             t.title             AS "title!",
             t.description       AS "description?",
             t.status            AS "status!: TaskStatus",
+            t.workflow_state    AS "workflow_state?",
             t.deleted_at        AS "deleted_at?",
             t.shared_at         AS "shared_at?",
             t.created_at        AS "created_at!",
@@ -250,7 +257,8 @@ impl<'a> SharedTaskRepository<'a> {
             data.title,
             data.description,
             data.status as Option<TaskStatus>,
-            data.acting_user_id
+            data.acting_user_id,
+            data.workflow_state
         )
         .fetch_optional(&mut *tx)
         .await?
@@ -292,6 +300,7 @@ impl<'a> SharedTaskRepository<'a> {
             t.title             AS "title!",
             t.description       AS "description?",
             t.status            AS "status!: TaskStatus",
+            t.workflow_state    AS "workflow_state?",
             t.deleted_at        AS "deleted_at?",
             t.shared_at         AS "shared_at?",
             t.created_at        AS "created_at!",
@@ -340,6 +349,7 @@ impl<'a> SharedTaskRepository<'a> {
             t.title             AS "title!",
             t.description       AS "description?",
             t.status            AS "status!: TaskStatus",
+            t.workflow_state    AS "workflow_state?",
             t.deleted_at        AS "deleted_at?",
             t.shared_at         AS "shared_at?",
             t.created_at        AS "created_at!",
@@ -180,6 +180,7 @@ pub async fn update_shared_task(
         title,
         description,
         status,
+        workflow_state,
     } = payload;
 
     let next_title = title.as_deref().unwrap_or(existing.title.as_str());
@@ -193,6 +194,7 @@ pub async fn update_shared_task(
         title,
         description,
         status,
+        workflow_state,
         acting_user_id: ctx.user.id,
     };
 
@@ -349,6 +351,9 @@ pub struct UpdateSharedTaskRequest {
     pub title: Option<String>,
     pub description: Option<String>,
     pub status: Option<TaskStatus>,
+    /// Travels with `status`; ignored when `status` is not set
+    #[serde(default)]
+    pub workflow_state: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,13 +24,40 @@ fn generate_types_content() -> String {
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
         db::models::project_repo::UpdateProjectRepo::decl(),
+        db::models::project_workflow::WorkflowState::decl(),
+        db::models::project_workflow::WorkflowTransition::decl(),
+        db::models::project_workflow::ProjectWorkflow::decl(),
+        db::models::project_workflow::CreateWorkflowState::decl(),
+        db::models::project_workflow::SetProjectWorkflow::decl(),
+        db::models::pipeline::ProjectPipeline::decl(),
+        db::models::pipeline::CreateProjectPipeline::decl(),
+        db::models::pipeline::UpdateProjectPipeline::decl(),
+        db::models::pipeline::PipelineRunStatus::decl(),
+        db::models::pipeline::PipelineRun::decl(),
+        db::models::pipeline::PipelineStepRun::decl(),
+        db::models::pipeline::PipelineRunWithSteps::decl(),
+        db::models::approval_rule::ApprovalRuleAction::decl(),
+        db::models::approval_rule::ApprovalRule::decl(),
+        db::models::approval_rule::CreateApprovalRule::decl(),
+        db::models::approval_rule::UpdateApprovalRule::decl(),
+        db::models::approval_decision::ApprovalDecisionStatus::decl(),
+        db::models::approval_decision::ApprovalDecider::decl(),
+        db::models::approval_decision::ApprovalDecision::decl(),
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
         db::models::tag::Tag::decl(),
         db::models::tag::CreateTag::decl(),
         db::models::tag::UpdateTag::decl(),
+        db::models::coding_agent_turn::VerificationStatus::decl(),
+        db::models::conflict_resolution::ConflictResolutionStatus::decl(),
+        db::models::conflict_resolution::ConflictResolution::decl(),
+        db::models::review_follow_up::ReviewFollowUpStatus::decl(),
+        db::models::review_follow_up::ReviewCommentType::decl(),
+        db::models::review_follow_up::ReviewFollowUp::decl(),
+        db::models::review_follow_up::ReviewFollowUpComment::decl(),
         db::models::task::TaskStatus::decl(),
+        db::models::task::TaskPriority::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
         db::models::task::TaskRelationships::decl(),
@@ -44,15 +71,38 @@ fn generate_types_content() -> String {
         db::models::scratch::Scratch::decl(),
         db::models::scratch::CreateScratch::decl(),
         db::models::scratch::UpdateScratch::decl(),
+        db::models::task_comment::TaskComment::decl(),
+        db::models::task_comment::CreateTaskComment::decl(),
+        db::models::task_comment::UpdateTaskComment::decl(),
+        db::models::task_comment::TaskCommentEdit::decl(),
+        db::models::task_comment::MentionType::decl(),
+        db::models::task_comment::TaskCommentMention::decl(),
         db::models::task_history::TaskHistory::decl(),
         db::models::task_history::CreateTaskHistory::decl(),
+        db::models::task_history::TaskActorType::decl(),
+        db::models::task_history::TaskActor::decl(),
+        db::models::task_history::TaskActivity::decl(),
+        db::models::task_history::TaskActivityFilters::decl(),
+        db::models::task_dependency::TaskDependency::decl(),
+        db::models::task_dependency::CreateTaskDependency::decl(),
+        db::models::task_dependency::TaskDependencies::decl(),
         db::models::webhook::Webhook::decl(),
         db::models::webhook::CreateWebhook::decl(),
         db::models::webhook::UpdateWebhook::decl(),
         db::models::webhook::WebhookEvent::decl(),
+        db::models::webhook::WebhookFilters::decl(),
         db::models::webhook_delivery::WebhookDelivery::decl(),
         db::models::webhook_delivery::CreateWebhookDelivery::decl(),
         db::models::webhook_delivery::DeliveryStatus::decl(),
+        db::models::webhook_history::WebhookHistoryAction::decl(),
+        db::models::webhook_history::WebhookHistory::decl(),
+        db::models::webhook_history::CreateWebhookHistory::decl(),
+        db::models::api_token::ApiTokenScope::decl(),
+        db::models::api_token::ApiToken::decl(),
+        db::models::api_token::CreateApiToken::decl(),
+        db::models::inbound_webhook::InboundWebhook::decl(),
+        db::models::inbound_webhook::InboundWebhookWithSecret::decl(),
+        db::models::inbound_webhook::CreateInboundWebhook::decl(),
         db::models::image::Image::decl(),
         db::models::image::CreateImage::decl(),
         db::models::workspace::Workspace::decl(),
@@ -60,11 +110,17 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process::ExecutionProcessExitReason::decl(),
+        db::models::execution_process_usage::ExecutionProcessUsage::decl(),
+        db::models::execution_process_usage::UsageTotals::decl(),
+        db::models::execution_process_usage::ExecutorUsage::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
         db::models::merge::MergeStatus::decl(),
+        db::models::merge::ForgeKind::decl(),
+        db::models::merge::MergeStrategy::decl(),
         db::models::merge::PullRequestInfo::decl(),
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
@@ -100,10 +156,13 @@ fn generate_types_content() -> String {
         utils::api::projects::RemoteProject::decl(),
         utils::api::projects::ListProjectsResponse::decl(),
         utils::api::projects::RemoteProjectMembersResponse::decl(),
+        server::routes::approvals::PendingApproval::decl(),
         server::routes::projects::CreateRemoteProjectRequest::decl(),
         server::routes::projects::LinkToExistingRequest::decl(),
+        server::routes::usage::UsageReport::decl(),
         server::routes::repo::RegisterRepoRequest::decl(),
         server::routes::repo::InitRepoRequest::decl(),
+        server::routes::repo::UpdateRepoForgeRequest::decl(),
         server::routes::tags::TagSearchParams::decl(),
         server::routes::oauth::TokenResponse::decl(),
         server::routes::config::UserSystemInfo::decl(),
@@ -116,6 +175,7 @@ fn generate_types_content() -> String {
         server::routes::config::CheckAgentAvailabilityQuery::decl(),
         server::routes::oauth::CurrentUserResponse::decl(),
         server::routes::sessions::CreateFollowUpAttempt::decl(),
+        server::routes::sessions::pipeline_runs::StartPipelineRunRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
         server::routes::task_attempts::MergeTaskAttemptRequest::decl(),
@@ -129,11 +189,27 @@ fn generate_types_content() -> String {
         server::routes::tasks::BulkUpdateTasksRequest::decl(),
         server::routes::tasks::BulkUpdateTasksResponse::decl(),
         server::routes::tasks::GetAgentMetadataResponse::decl(),
+        server::routes::tasks::UpdateTaskWorkflowState::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
         server::routes::webhooks::CreateWebhookRequest::decl(),
         server::routes::webhooks::UpdateWebhookRequest::decl(),
         server::routes::webhooks::WebhookResponse::decl(),
+        server::routes::webhooks::ReplayDeliveriesRequest::decl(),
+        server::routes::webhooks::ReplayDeliveriesResponse::decl(),
         server::routes::webhooks::TestWebhookResponse::decl(),
+        server::routes::api_tokens::CreateApiTokenRequest::decl(),
+        server::routes::api_tokens::ApiTokenResponse::decl(),
+        server::routes::api_tokens::CreateApiTokenResponse::decl(),
+        server::routes::inbound_webhooks::CreateInboundWebhookRequest::decl(),
+        server::routes::inbound_webhooks::UpdateInboundWebhookRequest::decl(),
+        server::routes::inbound_webhooks::InboundWebhookAction::decl(),
+        services::services::webhooks::payloads::ExecutionEventPayload::decl(),
+        services::services::webhooks::payloads::ApprovalRequestedPayload::decl(),
+        services::services::webhooks::payloads::ApprovalRespondedPayload::decl(),
+        services::services::webhooks::payloads::PullRequestEventPayload::decl(),
+        services::services::webhooks::payloads::TaskCommentAddedPayload::decl(),
+        services::services::webhooks::payloads::TaskCommentMentionedPayload::decl(),
+        services::services::webhooks::payloads::TaskAssignedPayload::decl(),
         server::routes::task_attempts::pr::CreateGitHubPrRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
@@ -144,7 +220,10 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::RunAgentSetupResponse::decl(),
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
+        services::services::stack::RestackStatus::decl(),
+        services::services::stack::RestackResult::decl(),
         server::routes::task_attempts::AbortConflictsRequest::decl(),
+        server::routes::task_attempts::ResolveConflictsRequest::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::PushError::decl(),
         server::routes::task_attempts::pr::CreatePrError::decl(),
@@ -155,6 +234,9 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::PrCommentsResponse::decl(),
         server::routes::task_attempts::pr::GetPrCommentsError::decl(),
         server::routes::task_attempts::pr::GetPrCommentsQuery::decl(),
+        server::routes::task_attempts::pr::AddressPrCommentsRequest::decl(),
+        server::routes::task_attempts::pr::AddressPrCommentsError::decl(),
+        server::routes::task_attempts::pr::ReviewFollowUpWithComments::decl(),
         services::services::github::UnifiedPrComment::decl(),
         server::routes::task_attempts::RepoBranchStatus::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
@@ -169,6 +251,11 @@ fn generate_types_content() -> String {
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
+        services::services::config::AutoSchedulerConfig::decl(),
+        services::services::config::UsageConfig::decl(),
+        services::services::config::ModelPrice::decl(),
+        services::services::task_scheduler::SchedulerQueueState::decl(),
+        services::services::task_scheduler::ProjectQueueState::decl(),
         services::services::git::GitBranch::decl(),
         services::services::share::SharedTaskDetails::decl(),
         services::services::queued_message::QueuedMessage::decl(),
@@ -180,13 +267,18 @@ fn generate_types_content() -> String {
         executors::actions::script::ScriptContext::decl(),
         executors::actions::script::ScriptRequest::decl(),
         executors::actions::script::ScriptRequestLanguage::decl(),
+        executors::actions::script::ScriptOptions::decl(),
         executors::executors::BaseCodingAgent::decl(),
         executors::executors::CodingAgent::decl(),
         executors::executors::AvailabilityInfo::decl(),
         executors::command::CommandBuilder::decl(),
+        executors::sandbox::SandboxBackend::decl(),
+        executors::sandbox::SandboxConfig::decl(),
+        executors::sandbox::SandboxPolicy::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
         executors::profile::ExecutorConfigs::decl(),
+        executors::fallback::ExecutorFallbackPolicy::decl(),
         executors::executors::BaseAgentCapability::decl(),
         executors::executors::claude::ClaudeCode::decl(),
         executors::executors::gemini::Gemini::decl(),
@@ -204,6 +296,11 @@ fn generate_types_content() -> String {
         executors::executors::droid::Droid::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::custom_cli::CustomCli::decl(),
+        executors::executors::custom_cli::OutputFormat::decl(),
+        executors::executors::custom_cli::JsonLinesMapping::decl(),
+        executors::executors::custom_cli::SessionIdExtraction::decl(),
+        executors::executors::openai_compatible::OpenaiCompatible::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
@@ -305,6 +402,14 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "custom_cli",
+            generate_json_schema::<executors::executors::custom_cli::CustomCli>()?,
+        ),
+        (
+            "openai_compatible",
+            generate_json_schema::<executors::executors::openai_compatible::OpenaiCompatible>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
@@ -0,0 +1,10 @@
+use clap::Parser;
+use server::cli::{self, Cli};
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = cli::run(Cli::parse()).await {
+        eprintln!("Error: {e:#}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,267 @@
+//! `vk`, a command line client for a running Vibe Kanban server.
+//!
+//! `vk approvals` lists the tool calls agents are waiting on and answers them, e.g.
+//! over SSH when the browser is out of reach. Packaged installs reach the same
+//! commands through `npx vibe-kanban approvals`.
+
+use anyhow::{Context, bail};
+use chrono::Utc;
+use clap::{Parser, Subcommand};
+use serde::de::DeserializeOwned;
+use services::services::approvals::policy::PolicyRequest;
+use utils::{
+    approvals::{ApprovalResponse, ApprovalStatus},
+    port_file::read_port_file,
+    response::ApiResponse,
+};
+
+use crate::routes::approvals::PendingApproval;
+
+/// Width of the request column in `vk approvals list`
+const REQUEST_COLUMN_WIDTH: usize = 48;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "vk",
+    version,
+    about = "Command line client for a running Vibe Kanban server"
+)]
+pub struct Cli {
+    /// Server URL. Defaults to the local server.
+    #[arg(long, env = "VIBE_BACKEND_URL", global = true)]
+    url: Option<String>,
+    /// API token with the `approvals:respond` scope, for servers that require one
+    #[arg(long, env = "VK_API_TOKEN", global = true, hide_env_values = true)]
+    token: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List and answer tool calls waiting for approval
+    Approvals {
+        #[command(subcommand)]
+        command: Option<ApprovalsCommand>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ApprovalsCommand {
+    /// List requests waiting for an answer (default)
+    List {
+        /// Print the requests as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Let the agent run the tool call
+    Approve {
+        /// Approval id, or a unique prefix of it
+        id: String,
+    },
+    /// Refuse the tool call
+    Deny {
+        /// Approval id, or a unique prefix of it
+        id: String,
+        /// Reason passed on to the agent
+        #[arg(long)]
+        reason: Option<String>,
+    },
+}
+
+pub async fn run(cli: Cli) -> anyhow::Result<()> {
+    let client = Client::new(cli.url, cli.token).await?;
+    match cli.command {
+        Command::Approvals { command } => {
+            match command.unwrap_or(ApprovalsCommand::List { json: false }) {
+                ApprovalsCommand::List { json } => list_approvals(&client, json).await,
+                ApprovalsCommand::Approve { id } => {
+                    respond(&client, &id, ApprovalStatus::Approved).await
+                }
+                ApprovalsCommand::Deny { id, reason } => {
+                    respond(&client, &id, ApprovalStatus::Denied { reason }).await
+                }
+            }
+        }
+    }
+}
+
+async fn list_approvals(client: &Client, json: bool) -> anyhow::Result<()> {
+    let pending: Vec<PendingApproval> = client.get("/api/approvals").await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&pending)?);
+        return Ok(());
+    }
+    if pending.is_empty() {
+        println!("No approvals pending");
+        return Ok(());
+    }
+
+    println!(
+        "{:<8}  {:<24}  {:<12}  {:<width$}  TIMES OUT",
+        "ID",
+        "TASK",
+        "TOOL",
+        "REQUEST",
+        width = REQUEST_COLUMN_WIDTH
+    );
+    for approval in &pending {
+        let minutes_left = (approval.request.timeout_at - Utc::now())
+            .num_minutes()
+            .max(0);
+        println!(
+            "{:<8}  {:<24}  {:<12}  {:<width$}  in {}m",
+            truncate(&approval.request.id, 8),
+            truncate(&approval.task_title, 24),
+            truncate(&approval.request.tool_name, 12),
+            truncate(&summary(approval), REQUEST_COLUMN_WIDTH),
+            minutes_left,
+            width = REQUEST_COLUMN_WIDTH
+        );
+    }
+    Ok(())
+}
+
+async fn respond(client: &Client, id: &str, status: ApprovalStatus) -> anyhow::Result<()> {
+    let pending: Vec<PendingApproval> = client.get("/api/approvals").await?;
+    let matches: Vec<&PendingApproval> = pending
+        .iter()
+        .filter(|approval| approval.request.id.starts_with(id))
+        .collect();
+    let approval = match matches.as_slice() {
+        [approval] => *approval,
+        [] => bail!("No pending approval matches '{id}'"),
+        _ => bail!(
+            "'{id}' matches {} approvals, use a longer id",
+            matches.len()
+        ),
+    };
+
+    let body = ApprovalResponse {
+        execution_process_id: approval.request.execution_process_id,
+        status,
+    };
+    let url = client.url(&format!("/api/approvals/{}/respond", approval.request.id));
+    let response = client
+        .authorized(client.http.post(&url))
+        .json(&body)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        bail!(
+            "Server refused the answer ({}). The request may have been answered already.",
+            response.status()
+        );
+    }
+    let status: ApprovalStatus = response.json().await?;
+    let outcome = match status {
+        ApprovalStatus::Approved => "Approved",
+        ApprovalStatus::Denied { .. } => "Denied",
+        ApprovalStatus::TimedOut => "Timed out",
+        ApprovalStatus::Pending => "Pending",
+    };
+    println!(
+        "{outcome}: {} for '{}'",
+        approval.request.tool_name, approval.task_title
+    );
+    Ok(())
+}
+
+/// The command or files of a request, falling back to its raw input
+fn summary(approval: &PendingApproval) -> String {
+    let request = PolicyRequest::new(
+        None,
+        &approval.request.tool_name,
+        &approval.request.tool_input,
+        None,
+    );
+    if let Some(command) = request.command {
+        return command.replace('\n', " ");
+    }
+    let paths: Vec<&str> = request
+        .paths
+        .iter()
+        .filter_map(|path| path.forms.first().map(String::as_str))
+        .collect();
+    if !paths.is_empty() {
+        return paths.join(", ");
+    }
+    approval.request.tool_input.to_string()
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl Client {
+    /// Use the given URL, or find the local server the same way the MCP server does
+    async fn new(url: Option<String>, token: Option<String>) -> anyhow::Result<Self> {
+        let base_url = match url {
+            Some(url) => url,
+            None => {
+                let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+                let port = match std::env::var("BACKEND_PORT").or_else(|_| std::env::var("PORT")) {
+                    Ok(port) => port
+                        .parse::<u16>()
+                        .with_context(|| format!("Invalid port value '{port}'"))?,
+                    Err(_) => read_port_file("vibe-kanban")
+                        .await
+                        .context("Vibe Kanban does not seem to be running; pass --url")?,
+                };
+                format!("http://{host}:{port}")
+            }
+        };
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.filter(|token| !token.trim().is_empty()),
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn authorized(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.token {
+            Some(token) => request.bearer_auth(token.trim()),
+            None => request,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        let response = self
+            .authorized(self.http.get(self.url(path)))
+            .send()
+            .await
+            .with_context(|| format!("Could not reach {}", self.base_url))?;
+        let body: ApiResponse<T> = response.json().await?;
+        if !body.is_success() {
+            bail!("{}", body.message().unwrap_or("Request failed"));
+        }
+        body.into_data().context("Response carried no data")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_by_characters() {
+        assert_eq!(truncate("cargo check", 20), "cargo check");
+        assert_eq!(truncate("cargo check --workspace", 12), "cargo check…");
+        assert_eq!(truncate("ümlaut", 3), "üm…");
+    }
+}
@@ -6,22 +6,28 @@ use axum::{
 };
 use db::models::{
     execution_process::ExecutionProcessError, project::ProjectError,
-    project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
+    project_repo::ProjectRepoError, project_workflow::WorkflowError, repo::RepoError,
+    scratch::ScratchError, session::SessionError, task_dependency::TaskDependencyError,
     workspace::WorkspaceError,
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
 use executors::executors::ExecutorError;
 use git2::Error as Git2Error;
 use services::services::{
+    approvals::policy::ApprovalPolicyError,
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
+    forge::ForgeError,
     git::GitServiceError,
     github::GitHubServiceError,
     image::ImageError,
+    pipeline::PipelineError,
     project::ProjectServiceError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
     share::ShareError,
+    usage::UsageError,
+    webhooks::WebhookError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
@@ -47,6 +53,8 @@ pub enum ApiError {
     #[error(transparent)]
     GitHubService(#[from] GitHubServiceError),
     #[error(transparent)]
+    Forge(#[from] ForgeError),
+    #[error(transparent)]
     Deployment(#[from] DeploymentError),
     #[error(transparent)]
     Container(#[from] ContainerError),
@@ -68,6 +76,8 @@ pub enum ApiError {
     EditorOpen(#[from] EditorOpenError),
     #[error(transparent)]
     RemoteClient(#[from] RemoteClientError),
+    #[error(transparent)]
+    Webhook(#[from] WebhookError),
     #[error("Unauthorized")]
     Unauthorized,
     #[error("Bad request: {0}")]
@@ -121,7 +131,17 @@ impl IntoResponse for ApiError {
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
             },
             ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
+            ApiError::Forge(ForgeError::GitHub(_)) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError")
+            }
+            ApiError::Forge(ForgeError::Repository(_) | ForgeError::MissingToken { .. }) => {
+                (StatusCode::BAD_REQUEST, "ForgeError")
+            }
+            ApiError::Forge(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ForgeError"),
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
+            ApiError::Container(ContainerError::Usage(UsageError::BudgetExceeded { .. })) => {
+                (StatusCode::CONFLICT, "BudgetExceeded")
+            }
             ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
             ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
@@ -173,6 +193,13 @@ impl IntoResponse for ApiError {
                     (StatusCode::BAD_REQUEST, "RemoteClientError")
                 }
             },
+            ApiError::Webhook(err) => match err {
+                WebhookError::NotFound(_) => (StatusCode::NOT_FOUND, "WebhookError"),
+                WebhookError::Template(_) | WebhookError::InvalidHeader(_) => {
+                    (StatusCode::BAD_REQUEST, "WebhookError")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "WebhookError"),
+            },
             ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
             ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BadRequest"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
@@ -249,10 +276,16 @@ impl IntoResponse for ApiError {
                 RemoteClientError::Serde(_) => "Unexpected response from remote service.".to_string(),
                 RemoteClientError::Url(_) => "Remote service URL is invalid.".to_string(),
             },
+            ApiError::Container(ContainerError::Usage(err @ UsageError::BudgetExceeded { .. })) => {
+                err.to_string()
+            }
             ApiError::Unauthorized => "Unauthorized. Please sign in again.".to_string(),
             ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
             ApiError::Forbidden(msg) => msg.clone(),
+            ApiError::Forge(err @ (ForgeError::Repository(_) | ForgeError::MissingToken { .. })) => {
+                err.to_string()
+            }
             _ => format!("{}: {}", error_type, self),
         };
         let response = ApiResponse::<()>::error(&error_message);
@@ -389,3 +422,49 @@ impl From<ProjectRepoError> for ApiError {
         }
     }
 }
+
+impl From<TaskDependencyError> for ApiError {
+    fn from(err: TaskDependencyError) -> Self {
+        match err {
+            TaskDependencyError::Database(db_err) => ApiError::Database(db_err),
+            TaskDependencyError::TaskNotFound => ApiError::BadRequest("Task not found".to_string()),
+            TaskDependencyError::SelfDependency => {
+                ApiError::BadRequest("A task cannot depend on itself".to_string())
+            }
+            TaskDependencyError::CrossProject => ApiError::BadRequest(
+                "Dependencies can only be created between tasks in the same project".to_string(),
+            ),
+            TaskDependencyError::AlreadyExists => {
+                ApiError::Conflict("Dependency already exists".to_string())
+            }
+            TaskDependencyError::CycleDetected => {
+                ApiError::Conflict("Adding this dependency would create a cycle".to_string())
+            }
+        }
+    }
+}
+
+impl From<WorkflowError> for ApiError {
+    fn from(err: WorkflowError) -> Self {
+        match err {
+            WorkflowError::Database(db_err) => ApiError::Database(db_err),
+            WorkflowError::TransitionNotAllowed { .. } => ApiError::Conflict(err.to_string()),
+            WorkflowError::EmptyStateName
+            | WorkflowError::DuplicateState(_)
+            | WorkflowError::UnknownState(_)
+            | WorkflowError::NoStateForStatus(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<PipelineError> for ApiError {
+    fn from(err: PipelineError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+impl From<ApprovalPolicyError> for ApiError {
+    fn from(err: ApprovalPolicyError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
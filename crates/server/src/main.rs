@@ -1,6 +1,11 @@
 use anyhow::{self, Error as AnyhowError};
+use clap::Parser;
 use deployment::{Deployment, DeploymentError};
-use server::{DeploymentImpl, routes};
+use server::{
+    DeploymentImpl,
+    cli::{self, Cli},
+    routes,
+};
 use services::services::container::ContainerService;
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
@@ -27,6 +32,13 @@ pub enum VibeKanbanError {
 
 #[tokio::main]
 async fn main() -> Result<(), VibeKanbanError> {
+    // `npx vibe-kanban approvals ...` runs the `vk` client against a running server
+    if std::env::args().nth(1).as_deref() == Some("approvals") {
+        let cli =
+            Cli::parse_from(std::iter::once("vk".to_string()).chain(std::env::args().skip(1)));
+        return Ok(cli::run(cli).await?);
+    }
+
     sentry_utils::init_once(SentrySource::Backend);
 
     let log_level = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
@@ -64,6 +76,8 @@ async fn main() -> Result<(), VibeKanbanError> {
         .map_err(DeploymentError::from)?;
     deployment.spawn_pr_monitor_service().await;
     deployment.spawn_webhook_worker_service().await;
+    deployment.spawn_task_scheduler_service().await;
+    deployment.spawn_stack_monitor_service().await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
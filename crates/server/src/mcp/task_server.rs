@@ -1,10 +1,18 @@
-use std::{future::Future, str::FromStr};
+use std::{
+    future::Future,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
 
 use db::models::{
     project::Project,
+    project_workflow::ProjectWorkflow,
     repo::Repo,
+    review_follow_up::ReviewFollowUp,
     tag::Tag,
-    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task::{CreateTask, Task, TaskPriority, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_comment::TaskComment,
+    task_dependency::{TaskDependencies, TaskDependency},
     workspace::{Workspace, WorkspaceContext},
 };
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
@@ -21,9 +29,9 @@ use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
 use uuid::Uuid;
 
-use crate::routes::{
-    containers::ContainerQuery,
-    task_attempts::WorkspaceRepoInput,
+use crate::{
+    middleware::AGENT_HEADER,
+    routes::{containers::ContainerQuery, task_attempts::WorkspaceRepoInput, usage::UsageReport},
 };
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -34,6 +42,14 @@ pub struct CreateTaskRequest {
     pub title: String,
     #[schemars(description = "Optional description of the task")]
     pub description: Option<String>,
+    #[schemars(
+        description = "Optional priority: 'low', 'medium', 'high', 'urgent' (default: 'medium')"
+    )]
+    pub priority: Option<String>,
+    #[schemars(description = "Optional due date (RFC3339 format)")]
+    pub due_date: Option<String>,
+    #[schemars(description = "Optional estimated effort in hours")]
+    pub estimate_hours: Option<f64>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -78,6 +94,37 @@ pub struct ListReposRequest {
     pub project_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetProjectWorkflowRequest {
+    #[schemars(description = "The ID of the project whose workflow to fetch")]
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WorkflowStateSummary {
+    #[schemars(description = "Name of the state")]
+    pub name: String,
+    #[schemars(description = "Built-in status the state counts as")]
+    pub category: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WorkflowTransitionSummary {
+    pub from_state: String,
+    pub to_state: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetProjectWorkflowResponse {
+    pub project_id: String,
+    #[schemars(
+        description = "States in board order. Empty when the project uses the built-in statuses."
+    )]
+    pub states: Vec<WorkflowStateSummary>,
+    #[schemars(description = "Allowed moves. When empty, every move between states is allowed.")]
+    pub transitions: Vec<WorkflowTransitionSummary>,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct ListReposResponse {
     pub repos: Vec<McpRepoSummary>,
@@ -113,6 +160,12 @@ pub struct ListTasksAdvancedRequest {
     pub statuses: Option<Vec<String>>,
     #[schemars(description = "Filter by assignee name (exact match)")]
     pub assignee: Option<String>,
+    #[schemars(description = "Filter by multiple priorities: 'low', 'medium', 'high', 'urgent'")]
+    pub priorities: Option<Vec<String>>,
+    #[schemars(description = "Filter tasks due after this timestamp (RFC3339 format)")]
+    pub due_after: Option<String>,
+    #[schemars(description = "Filter tasks due before this timestamp (RFC3339 format)")]
+    pub due_before: Option<String>,
     #[schemars(description = "Filter tasks created after this timestamp (RFC3339 format)")]
     pub created_after: Option<String>,
     #[schemars(description = "Filter tasks created before this timestamp (RFC3339 format)")]
@@ -125,7 +178,7 @@ pub struct ListTasksAdvancedRequest {
     pub limit: Option<u32>,
     #[schemars(description = "Number of tasks to skip for pagination (default: 0)")]
     pub offset: Option<u32>,
-    #[schemars(description = "Field to sort by: 'created_at', 'updated_at', 'title' (default: 'created_at')")]
+    #[schemars(description = "Field to sort by: 'created_at', 'updated_at', 'title', 'priority', 'due_date', 'estimate_hours' (default: 'created_at')")]
     pub sort_by: Option<String>,
     #[schemars(description = "Sort order: 'asc' or 'desc' (default: 'desc')")]
     pub sort_order: Option<String>,
@@ -139,6 +192,14 @@ pub struct TaskSummary {
     pub title: String,
     #[schemars(description = "Current status of the task")]
     pub status: String,
+    #[schemars(
+        description = "Project workflow state of the task, if the project defines a workflow"
+    )]
+    pub workflow_state: Option<String>,
+    #[schemars(description = "Priority of the task")]
+    pub priority: String,
+    #[schemars(description = "When the task is due")]
+    pub due_date: Option<String>,
     #[schemars(description = "When the task was created")]
     pub created_at: String,
     #[schemars(description = "When the task was last updated")]
@@ -147,6 +208,12 @@ pub struct TaskSummary {
     pub has_in_progress_attempt: Option<bool>,
     #[schemars(description = "Whether the last execution attempt failed")]
     pub last_attempt_failed: Option<bool>,
+    #[schemars(
+        description = "Result of the project's verification command after the latest verified agent turn ('passed' or 'failed')"
+    )]
+    pub last_verification_status: Option<String>,
+    #[schemars(description = "Whether the task is waiting on dependencies that are not done")]
+    pub blocked: Option<bool>,
 }
 
 impl TaskSummary {
@@ -155,10 +222,18 @@ impl TaskSummary {
             id: task.id.to_string(),
             title: task.title.to_string(),
             status: task.status.to_string(),
+            workflow_state: task.workflow_state.clone(),
+            priority: task.priority.to_string(),
+            due_date: task.due_date.map(|due_date| due_date.to_rfc3339()),
             created_at: task.created_at.to_rfc3339(),
             updated_at: task.updated_at.to_rfc3339(),
             has_in_progress_attempt: Some(task.has_in_progress_attempt),
             last_attempt_failed: Some(task.last_attempt_failed),
+            last_verification_status: task
+                .last_verification_status
+                .as_ref()
+                .map(|status| status.to_string()),
+            blocked: Some(task.blocked),
         }
     }
 }
@@ -173,8 +248,18 @@ pub struct TaskDetails {
     pub description: Option<String>,
     #[schemars(description = "Current status of the task")]
     pub status: String,
+    #[schemars(
+        description = "Project workflow state of the task, if the project defines a workflow"
+    )]
+    pub workflow_state: Option<String>,
     #[schemars(description = "The assignee of the task (agent or user name)")]
     pub assignee: Option<String>,
+    #[schemars(description = "Priority of the task")]
+    pub priority: String,
+    #[schemars(description = "When the task is due")]
+    pub due_date: Option<String>,
+    #[schemars(description = "Estimated effort in hours")]
+    pub estimate_hours: Option<f64>,
     #[schemars(description = "When the task was created")]
     pub created_at: String,
     #[schemars(description = "When the task was last updated")]
@@ -192,7 +277,11 @@ impl TaskDetails {
             title: task.title,
             description: task.description,
             status: task.status.to_string(),
+            workflow_state: task.workflow_state,
             assignee: task.assignee,
+            priority: task.priority.to_string(),
+            due_date: task.due_date.map(|due_date| due_date.to_rfc3339()),
+            estimate_hours: task.estimate_hours,
             created_at: task.created_at.to_rfc3339(),
             updated_at: task.updated_at.to_rfc3339(),
             has_in_progress_attempt: None,
@@ -227,6 +316,9 @@ pub struct ListTasksAdvancedResponse {
 pub struct ListTasksAdvancedFilters {
     pub statuses: Option<Vec<String>>,
     pub assignee: Option<String>,
+    pub priorities: Option<Vec<String>>,
+    pub due_after: Option<String>,
+    pub due_before: Option<String>,
     pub created_after: Option<String>,
     pub created_before: Option<String>,
     pub updated_after: Option<String>,
@@ -247,6 +339,20 @@ pub struct UpdateTaskRequest {
     pub description: Option<String>,
     #[schemars(description = "New status: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'")]
     pub status: Option<String>,
+    #[schemars(description = "New priority: 'low', 'medium', 'high', 'urgent'")]
+    pub priority: Option<String>,
+    #[schemars(description = "New due date (RFC3339 format)")]
+    pub due_date: Option<String>,
+    #[schemars(description = "New estimated effort in hours")]
+    pub estimate_hours: Option<f64>,
+    #[schemars(description = "Remove the due date")]
+    pub clear_due_date: Option<bool>,
+    #[schemars(description = "Remove the estimate")]
+    pub clear_estimate: Option<bool>,
+    #[schemars(
+        description = "Move the task to this state of the project workflow (see `get_project_workflow`). Cannot be combined with `status`."
+    )]
+    pub workflow_state: Option<String>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -334,6 +440,8 @@ pub struct AddTaskCommentRequest {
     pub content: String,
     #[schemars(description = "The author of the comment (e.g., agent name like 'Ferris', 'Bree', etc.)")]
     pub author: String,
+    #[schemars(description = "Optional ID of the comment this is a reply to")]
+    pub parent_comment_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -342,12 +450,30 @@ pub struct CommentSummary {
     pub id: String,
     #[schemars(description = "The ID of the task this comment belongs to")]
     pub task_id: String,
+    #[schemars(description = "The ID of the comment this is a reply to, if any")]
+    pub parent_comment_id: Option<String>,
     #[schemars(description = "The content of the comment")]
     pub content: String,
     #[schemars(description = "The author of the comment")]
     pub author: String,
     #[schemars(description = "When the comment was created")]
     pub created_at: String,
+    #[schemars(description = "When the comment was last edited, if ever")]
+    pub edited_at: Option<String>,
+}
+
+impl From<TaskComment> for CommentSummary {
+    fn from(comment: TaskComment) -> Self {
+        Self {
+            id: comment.id.to_string(),
+            task_id: comment.task_id.to_string(),
+            parent_comment_id: comment.parent_comment_id.map(|id| id.to_string()),
+            content: comment.content,
+            author: comment.author,
+            created_at: comment.created_at.to_rfc3339(),
+            edited_at: comment.edited_at.map(|at| at.to_rfc3339()),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -368,12 +494,41 @@ pub struct GetTaskCommentsResponse {
     pub task_id: String,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetUnreadCommentsRequest {
+    #[schemars(description = "The ID of the task to check for unread comments")]
+    pub task_id: Uuid,
+    #[schemars(
+        description = "Your agent name (e.g., 'Ferris'). Defaults to the name you last used with this server"
+    )]
+    pub agent_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetUnreadCommentsResponse {
+    pub comments: Vec<CommentSummary>,
+    pub count: usize,
+    pub task_id: String,
+    #[schemars(description = "The agent name the comments were addressed to")]
+    pub agent_name: String,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct GetTaskHistoryRequest {
     #[schemars(description = "The ID of the task to get change history for")]
     pub task_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetUsageRequest {
+    #[schemars(description = "The ID of the task to report usage for")]
+    pub task_id: Option<Uuid>,
+    #[schemars(
+        description = "The ID of the project to report usage for. Used when `task_id` is not given"
+    )]
+    pub project_id: Option<Uuid>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct AssignTaskRequest {
     #[schemars(description = "The ID of the task to assign. This is required!")]
@@ -388,6 +543,8 @@ pub struct SearchTasksRequest {
     pub project_id: Uuid,
     #[schemars(description = "The search query string to match against task titles and descriptions. This is required!")]
     pub query: String,
+    #[schemars(description = "Optional priority filter: 'low', 'medium', 'high', 'urgent'")]
+    pub priority: Option<String>,
     #[schemars(description = "Maximum number of tasks to return (default: 50, max: 500)")]
     pub limit: Option<u32>,
     #[schemars(description = "Number of tasks to skip for pagination (default: 0)")]
@@ -432,6 +589,40 @@ pub struct GetTaskRelationshipsResponse {
     pub relationships: TaskRelationshipsSummary,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AddDependencyRequest {
+    #[schemars(description = "The ID of the task that is blocked. This is required!")]
+    pub task_id: Uuid,
+    #[schemars(
+        description = "The ID of the task that must be done first (the blocker). This is required!"
+    )]
+    pub depends_on_task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AddDependencyResponse {
+    pub dependency_id: String,
+    pub task_id: String,
+    pub depends_on_task_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListBlockersRequest {
+    #[schemars(description = "The ID of the task to list blockers for. This is required!")]
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListBlockersResponse {
+    pub task_id: String,
+    #[schemars(description = "Whether any blocker is not yet done")]
+    pub blocked: bool,
+    #[schemars(description = "Tasks that must be done before this task can start")]
+    pub blocked_by: Vec<TaskDetails>,
+    #[schemars(description = "Tasks waiting on this task")]
+    pub blocks: Vec<TaskDetails>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct BulkUpdateTasksRequest {
     #[schemars(description = "Array of task IDs to update. This is required!")]
@@ -610,12 +801,42 @@ pub struct RefreshWorkspacePrStatusResponse {
     pub task_updated: bool,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AddressPrCommentsRequest {
+    #[schemars(
+        description = "The workspace ID whose pull request was reviewed. This is required!"
+    )]
+    pub workspace_id: Uuid,
+    #[schemars(description = "The repository ID of the pull request. This is required!")]
+    pub repo_id: Uuid,
+    #[schemars(
+        description = "Whether to reply to each addressed comment with the pushed commit. Default: false"
+    )]
+    pub reply_to_comments: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AddressPrCommentsResponse {
+    #[schemars(description = "The ID of the review follow-up")]
+    pub follow_up_id: String,
+    #[schemars(description = "The execution process running the agent turn")]
+    pub execution_process_id: Option<String>,
+    #[schemars(description = "The PR number whose comments are addressed")]
+    pub pr_number: i64,
+    #[schemars(
+        description = "Whether each addressed comment gets a reply once the commit is pushed"
+    )]
+    pub reply_to_comments: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
     base_url: String,
     tool_router: ToolRouter<TaskServer>,
     context: Option<McpContext>,
+    /// Name sent with every API call so task history can attribute changes
+    agent_name: Arc<RwLock<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
@@ -648,6 +869,9 @@ impl TaskServer {
             base_url: base_url.to_string(),
             tool_router: Self::tool_router(),
             context: None,
+            agent_name: Arc::new(RwLock::new(
+                std::env::var("VK_AGENT_NAME").unwrap_or_else(|_| "mcp".to_string()),
+            )),
         }
     }
 
@@ -722,6 +946,7 @@ struct ApiResponseEnvelope<T> {
     success: bool,
     data: Option<T>,
     message: Option<String>,
+    error_data: Option<serde_json::Value>,
 }
 
 impl TaskServer {
@@ -747,11 +972,29 @@ impl TaskServer {
         Self::err_value(v)
     }
 
+    fn agent_name(&self) -> String {
+        self.agent_name
+            .read()
+            .map(|name| name.clone())
+            .unwrap_or_else(|_| "mcp".to_string())
+    }
+
+    /// Attribute later calls to the agent name a tool was given
+    fn remember_agent_name(&self, name: &str) {
+        let name = name.trim();
+        if !name.is_empty()
+            && let Ok(mut agent_name) = self.agent_name.write()
+        {
+            *agent_name = name.to_string();
+        }
+    }
+
     async fn send_json<T: DeserializeOwned>(
         &self,
         rb: reqwest::RequestBuilder,
     ) -> Result<T, CallToolResult> {
         let resp = rb
+            .header(AGENT_HEADER, self.agent_name())
             .send()
             .await
             .map_err(|e| Self::err("Failed to connect to VK API", Some(&e.to_string())).unwrap())?;
@@ -768,8 +1011,12 @@ impl TaskServer {
         })?;
 
         if !api_response.success {
-            let msg = api_response.message.as_deref().unwrap_or("Unknown error");
-            return Err(Self::err("VK API returned error", Some(msg)).unwrap());
+            // Typed errors come without a message
+            let msg = api_response
+                .message
+                .or_else(|| api_response.error_data.map(|data| data.to_string()))
+                .unwrap_or_else(|| "Unknown error".to_string());
+            return Err(Self::err("VK API returned error", Some(&msg)).unwrap());
         }
 
         api_response
@@ -785,6 +1032,41 @@ impl TaskServer {
         )
     }
 
+    /// Parses the priority and RFC3339 due date accepted by create_task and update_task
+    fn parse_planning(
+        priority: Option<String>,
+        due_date: Option<String>,
+    ) -> Result<(Option<TaskPriority>, Option<chrono::DateTime<chrono::Utc>>), CallToolResult> {
+        let priority = match priority {
+            Some(priority_str) => match TaskPriority::from_str(&priority_str) {
+                Ok(priority) => Some(priority),
+                Err(_) => {
+                    return Err(Self::err(
+                        "Invalid priority. Valid values: 'low', 'medium', 'high', 'urgent'"
+                            .to_string(),
+                        Some(priority_str),
+                    )
+                    .unwrap());
+                }
+            },
+            None => None,
+        };
+        let due_date = match due_date {
+            Some(ts) => match chrono::DateTime::parse_from_rfc3339(&ts) {
+                Ok(due_date) => Some(due_date.with_timezone(&chrono::Utc)),
+                Err(_) => {
+                    return Err(Self::err(
+                        "Invalid due_date timestamp. Use RFC3339 format".to_string(),
+                        Some(ts),
+                    )
+                    .unwrap());
+                }
+            },
+            None => None,
+        };
+        Ok((priority, due_date))
+    }
+
     /// Expands @tagname references in text by replacing them with tag content.
     /// Returns the original text if expansion fails (e.g., network error).
     /// Unknown tags are left as-is (not expanded, not an error).
@@ -859,26 +1141,32 @@ impl TaskServer {
             project_id,
             title,
             description,
+            priority,
+            due_date,
+            estimate_hours,
         }): Parameters<CreateTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        let (priority, due_date) = match Self::parse_planning(priority, due_date) {
+            Ok(planning) => planning,
+            Err(e) => return Ok(e),
+        };
+
         // Expand @tagname references in description
         let expanded_description = match description {
             Some(desc) => Some(self.expand_tags(&desc).await),
             None => None,
         };
 
+        let mut create_task =
+            CreateTask::from_title_description(project_id, title, expanded_description);
+        create_task.priority = priority;
+        create_task.due_date = due_date;
+        create_task.estimate_hours = estimate_hours;
+
         let url = self.url("/api/tasks");
 
         let task: Task = match self
-            .send_json(
-                self.client
-                    .post(&url)
-                    .json(&CreateTask::from_title_description(
-                        project_id,
-                        title,
-                        expanded_description,
-                    )),
-            )
+            .send_json(self.client.post(&url).json(&create_task))
             .await
         {
             Ok(t) => t,
@@ -939,6 +1227,42 @@ impl TaskServer {
         TaskServer::success(&response)
     }
 
+    #[tool(
+        description = "Get the custom workflow of a project: its states, the built-in status each state counts as, and the allowed transitions. `project_id` is required!"
+    )]
+    async fn get_project_workflow(
+        &self,
+        Parameters(GetProjectWorkflowRequest { project_id }): Parameters<GetProjectWorkflowRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/projects/{}/workflow", project_id));
+        let workflow: ProjectWorkflow = match self.send_json(self.client.get(&url)).await {
+            Ok(w) => w,
+            Err(e) => return Ok(e),
+        };
+
+        let response = GetProjectWorkflowResponse {
+            project_id: project_id.to_string(),
+            states: workflow
+                .states
+                .into_iter()
+                .map(|state| WorkflowStateSummary {
+                    name: state.name,
+                    category: state.category.to_string(),
+                })
+                .collect(),
+            transitions: workflow
+                .transitions
+                .into_iter()
+                .map(|transition| WorkflowTransitionSummary {
+                    from_state: transition.from_state,
+                    to_state: transition.to_state,
+                })
+                .collect(),
+        };
+
+        TaskServer::success(&response)
+    }
+
     #[tool(
         description = "List all the task/tickets in a project with optional filtering and execution status. `project_id` is required!"
     )]
@@ -1008,6 +1332,9 @@ impl TaskServer {
             project_id,
             statuses,
             assignee,
+            priorities,
+            due_after,
+            due_before,
             created_after,
             created_before,
             updated_after,
@@ -1032,7 +1359,38 @@ impl TaskServer {
             }
         }
 
+        // Validate priorities
+        if let Some(ref priority_strs) = priorities {
+            for priority_str in priority_strs {
+                if TaskPriority::from_str(priority_str).is_err() {
+                    return Self::err(
+                        "Invalid priority value. Valid values: 'low', 'medium', 'high', 'urgent'"
+                            .to_string(),
+                        Some(priority_str.to_string()),
+                    );
+                }
+            }
+        }
+
         // Validate date filters
+        if let Some(ref ts) = due_after {
+            if DateTime::parse_from_rfc3339(ts).is_err() {
+                return Self::err(
+                    "Invalid due_after timestamp. Use RFC3339 format".to_string(),
+                    Some(ts.to_string()),
+                );
+            }
+        }
+
+        if let Some(ref ts) = due_before {
+            if DateTime::parse_from_rfc3339(ts).is_err() {
+                return Self::err(
+                    "Invalid due_before timestamp. Use RFC3339 format".to_string(),
+                    Some(ts.to_string()),
+                );
+            }
+        }
+
         if let Some(ref ts) = created_after {
             if DateTime::parse_from_rfc3339(ts).is_err() {
                 return Self::err(
@@ -1076,9 +1434,12 @@ impl TaskServer {
         let task_sort_order = sort_order.as_deref().unwrap_or("desc");
 
         // Validate sort_by
-        if !matches!(task_sort_by, "created_at" | "updated_at" | "title") {
+        if !matches!(
+            task_sort_by,
+            "created_at" | "updated_at" | "title" | "priority" | "due_date" | "estimate_hours"
+        ) {
             return Self::err(
-                "Invalid sort_by value. Valid values: 'created_at', 'updated_at', 'title'".to_string(),
+                "Invalid sort_by value. Valid values: 'created_at', 'updated_at', 'title', 'priority', 'due_date', 'estimate_hours'".to_string(),
                 Some(task_sort_by.to_string()),
             );
         }
@@ -1104,6 +1465,19 @@ impl TaskServer {
             query_params.push(("assignee", assignee_name.clone()));
         }
 
+        if let Some(ref priority_list) = priorities {
+            for priority in priority_list {
+                query_params.push(("priorities", priority.clone()));
+            }
+        }
+
+        if let Some(ref ts) = due_after {
+            query_params.push(("due_after", ts.clone()));
+        }
+        if let Some(ref ts) = due_before {
+            query_params.push(("due_before", ts.clone()));
+        }
+
         if let Some(ref ts) = created_after {
             query_params.push(("created_after", ts.clone()));
         }
@@ -1141,6 +1515,9 @@ impl TaskServer {
             applied_filters: ListTasksAdvancedFilters {
                 statuses: statuses.clone(),
                 assignee: assignee.clone(),
+                priorities: priorities.clone(),
+                due_after: due_after.clone(),
+                due_before: due_before.clone(),
                 created_after: created_after.clone(),
                 created_before: created_before.clone(),
                 updated_after: updated_after.clone(),
@@ -1175,6 +1552,9 @@ impl TaskServer {
                 None::<String>,
             );
         }
+        if let Some(name) = &agent_name {
+            self.remember_agent_name(name);
+        }
 
         // Validate and normalize mode
         let mode_str = mode.as_deref().unwrap_or("worktree").trim().to_lowercase();
@@ -1298,7 +1678,13 @@ impl TaskServer {
                     "summary": format!("Started workspace session with executor {} (mode: {})", executor_for_response, mode_str)
                 });
                 // Fire and forget - don't block on metadata logging
-                let _ = self.client.post(&metadata_url).json(&metadata_payload).send().await;
+                let _ = self
+                    .client
+                    .post(&metadata_url)
+                    .header(AGENT_HEADER, self.agent_name())
+                    .json(&metadata_payload)
+                    .send()
+                    .await;
             }
         }
 
@@ -1335,6 +1721,11 @@ impl TaskServer {
                     parent_workspace_id: None,
                     image_ids: None,
                     assignee: Some(trimmed_name.to_string()),
+                    priority: None,
+                    due_date: None,
+                    estimate_hours: None,
+                    clear_due_date: None,
+                    clear_estimate: None,
                 };
                 let assign_url = self.url(&format!("/api/tasks/{}", task_id));
                 // Fire and forget - don't block on assignment (best effort)
@@ -1396,7 +1787,7 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Update an existing task/ticket's title, description, or status. `project_id` and `task_id` are required! `title`, `description`, and `status` are optional."
+        description = "Update an existing task/ticket's title, description, status, priority, due date, or estimate. `project_id` and `task_id` are required! All other fields are optional."
     )]
     async fn update_task(
         &self,
@@ -1405,8 +1796,21 @@ impl TaskServer {
             title,
             description,
             status,
+            priority,
+            due_date,
+            estimate_hours,
+            clear_due_date,
+            clear_estimate,
+            workflow_state,
         }): Parameters<UpdateTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        if status.is_some() && workflow_state.is_some() {
+            return Self::err(
+                "Pass either status or workflow_state, not both".to_string(),
+                None::<String>,
+            );
+        }
+
         let status = if let Some(ref status_str) = status {
             match TaskStatus::from_str(status_str) {
                 Ok(s) => Some(s),
@@ -1420,6 +1824,10 @@ impl TaskServer {
         } else {
             None
         };
+        let (priority, due_date) = match Self::parse_planning(priority, due_date) {
+            Ok(planning) => planning,
+            Err(e) => return Ok(e),
+        };
 
         // Expand @tagname references in description
         let expanded_description = match description {
@@ -1434,12 +1842,33 @@ impl TaskServer {
             parent_workspace_id: None,
             image_ids: None,
             assignee: None,
+            priority,
+            due_date,
+            estimate_hours,
+            clear_due_date,
+            clear_estimate,
         };
         let url = self.url(&format!("/api/tasks/{}", task_id));
-        let updated_task: Task = match self.send_json(self.client.put(&url).json(&payload)).await {
-            Ok(t) => t,
-            Err(e) => return Ok(e),
-        };
+        let mut updated_task: Task =
+            match self.send_json(self.client.put(&url).json(&payload)).await {
+                Ok(t) => t,
+                Err(e) => return Ok(e),
+            };
+
+        if let Some(state) = workflow_state {
+            let url = self.url(&format!("/api/tasks/{}/workflow-state", task_id));
+            updated_task = match self
+                .send_json(
+                    self.client
+                        .put(&url)
+                        .json(&serde_json::json!({ "state": state })),
+                )
+                .await
+            {
+                Ok(t) => t,
+                Err(e) => return Ok(e),
+            };
+        }
 
         let details = TaskDetails::from_task(updated_task);
         let response = UpdateTaskResponse { task: details };
@@ -1496,6 +1925,7 @@ impl TaskServer {
             task_id,
             content,
             author,
+            parent_comment_id,
         }): Parameters<AddTaskCommentRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         // Validate inputs
@@ -1505,37 +1935,24 @@ impl TaskServer {
         if author.trim().is_empty() {
             return Self::err("Author cannot be empty".to_string(), None::<String>);
         }
+        self.remember_agent_name(&author);
 
         let url = self.url(&format!("/api/tasks/{}/comments", task_id));
         let payload = serde_json::json!({
             "task_id": task_id,
             "content": content,
-            "author": author
+            "author": author,
+            "parent_comment_id": parent_comment_id
         });
 
-        #[derive(Debug, Deserialize)]
-        struct ApiComment {
-            id: Uuid,
-            task_id: Uuid,
-            content: String,
-            author: String,
-            created_at: chrono::DateTime<chrono::Utc>,
-        }
-
-        let comment: ApiComment = match self.send_json(self.client.post(&url).json(&payload)).await
+        let comment: TaskComment = match self.send_json(self.client.post(&url).json(&payload)).await
         {
             Ok(c) => c,
             Err(e) => return Ok(e),
         };
 
         let response = AddTaskCommentResponse {
-            comment: CommentSummary {
-                id: comment.id.to_string(),
-                task_id: comment.task_id.to_string(),
-                content: comment.content,
-                author: comment.author,
-                created_at: comment.created_at.to_rfc3339(),
-            },
+            comment: CommentSummary::from(comment),
         };
 
         TaskServer::success(&response)
@@ -1550,40 +1967,104 @@ impl TaskServer {
     ) -> Result<CallToolResult, ErrorData> {
         let url = self.url(&format!("/api/tasks/{}/comments", task_id));
 
-        #[derive(Debug, Deserialize)]
-        struct ApiComment {
-            id: Uuid,
-            task_id: Uuid,
-            content: String,
-            author: String,
-            created_at: chrono::DateTime<chrono::Utc>,
+        let comments: Vec<TaskComment> = match self.send_json(self.client.get(&url)).await {
+            Ok(c) => c,
+            Err(e) => return Ok(e),
+        };
+
+        let comment_summaries: Vec<CommentSummary> =
+            comments.into_iter().map(CommentSummary::from).collect();
+
+        let response = GetTaskCommentsResponse {
+            count: comment_summaries.len(),
+            comments: comment_summaries,
+            task_id: task_id.to_string(),
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Get the comments on a task that are addressed to you and that you have not seen yet: comments that @mention you and replies to your comments, posted or edited since your last call. Call this at the start of each turn. Returned comments are marked as read. `task_id` is required!"
+    )]
+    async fn get_unread_comments(
+        &self,
+        Parameters(GetUnreadCommentsRequest {
+            task_id,
+            agent_name,
+        }): Parameters<GetUnreadCommentsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Some(name) = &agent_name {
+            self.remember_agent_name(name);
         }
+        let agent_name = self.agent_name();
 
-        let comments: Vec<ApiComment> = match self.send_json(self.client.get(&url)).await {
+        let url = self.url(&format!("/api/tasks/{}/comments/unread", task_id));
+        let comments: Vec<TaskComment> = match self
+            .send_json(self.client.get(&url).query(&[("reader", &agent_name)]))
+            .await
+        {
             Ok(c) => c,
             Err(e) => return Ok(e),
         };
 
-        let comment_summaries: Vec<CommentSummary> = comments
-            .into_iter()
-            .map(|c| CommentSummary {
-                id: c.id.to_string(),
-                task_id: c.task_id.to_string(),
-                content: c.content,
-                author: c.author,
-                created_at: c.created_at.to_rfc3339(),
-            })
-            .collect();
+        // Only mark what was returned as read, so comments posted in between are not lost
+        if let Some(read_at) = comments
+            .iter()
+            .map(|c| c.edited_at.unwrap_or(c.created_at))
+            .max()
+        {
+            let url = self.url(&format!("/api/tasks/{}/comments/read", task_id));
+            let payload = serde_json::json!({
+                "reader": agent_name,
+                "read_at": read_at
+            });
+            if let Err(e) = self
+                .send_json::<serde_json::Value>(self.client.post(&url).json(&payload))
+                .await
+            {
+                return Ok(e);
+            }
+        }
 
-        let response = GetTaskCommentsResponse {
+        let comment_summaries: Vec<CommentSummary> =
+            comments.into_iter().map(CommentSummary::from).collect();
+
+        let response = GetUnreadCommentsResponse {
             count: comment_summaries.len(),
             comments: comment_summaries,
             task_id: task_id.to_string(),
+            agent_name,
         };
 
         TaskServer::success(&response)
     }
 
+    #[tool(
+        description = "Get the tokens used and the estimated cost in USD of a task or a project, in total and per executor profile, with the project's budget if it has one. Pass `task_id` or `project_id`."
+    )]
+    async fn get_usage(
+        &self,
+        Parameters(GetUsageRequest {
+            task_id,
+            project_id,
+        }): Parameters<GetUsageRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = match (task_id, project_id) {
+            (Some(task_id), _) => self.url(&format!("/api/tasks/{}/usage", task_id)),
+            (None, Some(project_id)) => self.url(&format!("/api/projects/{}/usage", project_id)),
+            (None, None) => {
+                return Self::err("Either `task_id` or `project_id` is required", None);
+            }
+        };
+        let report: UsageReport = match self.send_json(self.client.get(&url)).await {
+            Ok(report) => report,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&report)
+    }
+
     #[tool(
         description = "Get the change history for a task. Returns all modifications made to the task including field changes, who made them, and when. `task_id` is required!"
     )]
@@ -1652,6 +2133,11 @@ impl TaskServer {
             parent_workspace_id: None,
             image_ids: None,
             assignee: assignee.clone(),
+            priority: None,
+            due_date: None,
+            estimate_hours: None,
+            clear_due_date: None,
+            clear_estimate: None,
         };
 
         let url = self.url(&format!("/api/tasks/{}", task_id));
@@ -1673,6 +2159,7 @@ impl TaskServer {
         Parameters(SearchTasksRequest {
             project_id,
             query,
+            priority,
             limit,
             offset,
         }): Parameters<SearchTasksRequest>,
@@ -1688,13 +2175,26 @@ impl TaskServer {
         let task_limit = limit.unwrap_or(50).max(1).min(500);
         let task_offset = offset.unwrap_or(0);
 
+        if let Some(ref priority_str) = priority
+            && TaskPriority::from_str(priority_str).is_err()
+        {
+            return Self::err(
+                "Invalid priority filter. Valid values: 'low', 'medium', 'high', 'urgent'"
+                    .to_string(),
+                Some(priority_str.to_string()),
+            );
+        }
+
         let url = self.url("/api/tasks/search");
-        let query_params = vec![
+        let mut query_params = vec![
             ("project_id", project_id.to_string()),
             ("q", search_query.to_string()),
             ("limit", task_limit.to_string()),
             ("offset", task_offset.to_string()),
         ];
+        if let Some(priority_str) = priority {
+            query_params.push(("priority", priority_str));
+        }
 
         let tasks: Vec<Task> = match self
             .send_json(self.client.get(&url).query(&query_params))
@@ -1761,6 +2261,66 @@ impl TaskServer {
         TaskServer::success(&response)
     }
 
+    #[tool(
+        description = "Mark a task as blocked by another task in the same project. The blocked task will not be auto-started until the blocker is done. Rejected if it would create a cycle. `task_id` and `depends_on_task_id` are required!"
+    )]
+    async fn add_dependency(
+        &self,
+        Parameters(AddDependencyRequest {
+            task_id,
+            depends_on_task_id,
+        }): Parameters<AddDependencyRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/tasks/{}/dependencies", task_id));
+        let payload = serde_json::json!({ "depends_on_task_id": depends_on_task_id });
+
+        let dependency: TaskDependency =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(d) => d,
+                Err(e) => return Ok(e),
+            };
+
+        let response = AddDependencyResponse {
+            dependency_id: dependency.id.to_string(),
+            task_id: dependency.task_id.to_string(),
+            depends_on_task_id: dependency.depends_on_task_id.to_string(),
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "List the tasks blocking a task and the tasks it blocks, plus whether it is currently blocked. `task_id` is required!"
+    )]
+    async fn list_blockers(
+        &self,
+        Parameters(ListBlockersRequest { task_id }): Parameters<ListBlockersRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/tasks/{}/dependencies", task_id));
+
+        let dependencies: TaskDependencies = match self.send_json(self.client.get(&url)).await {
+            Ok(d) => d,
+            Err(e) => return Ok(e),
+        };
+
+        let response = ListBlockersResponse {
+            task_id: dependencies.task_id.to_string(),
+            blocked: dependencies.blocked,
+            blocked_by: dependencies
+                .blocked_by
+                .into_iter()
+                .map(TaskDetails::from_task)
+                .collect(),
+            blocks: dependencies
+                .blocks
+                .into_iter()
+                .map(TaskDetails::from_task)
+                .collect(),
+        };
+
+        TaskServer::success(&response)
+    }
+
     #[tool(
         description = "Update the status of multiple tasks at once. `task_ids` (array) and `status` are required!"
     )]
@@ -1835,6 +2395,7 @@ impl TaskServer {
         if agent_name_trimmed.is_empty() {
             return Self::err("agent_name cannot be empty".to_string(), None::<String>);
         }
+        self.remember_agent_name(agent_name_trimmed);
 
         let action_trimmed = action.trim();
         if action_trimmed.is_empty() {
@@ -2271,12 +2832,48 @@ impl TaskServer {
 
         TaskServer::success(&response)
     }
+
+    #[tool(
+        description = "Address the pending review comments of a workspace's pull request. Comments written since the last push that are unresolved and not yet addressed are sent to the workspace's coding agent as a follow-up, and its commit is pushed to the PR branch when it finishes. Optionally replies to each comment with the commit. `workspace_id` and `repo_id` are required!"
+    )]
+    async fn address_pr_comments(
+        &self,
+        Parameters(AddressPrCommentsRequest {
+            workspace_id,
+            repo_id,
+            reply_to_comments,
+        }): Parameters<AddressPrCommentsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/task-attempts/{}/pr/comments/address",
+            workspace_id
+        ));
+        let payload = serde_json::json!({
+            "repo_id": repo_id,
+            "reply_to_comments": reply_to_comments.unwrap_or(false),
+        });
+
+        let follow_up: ReviewFollowUp =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(f) => f,
+                Err(e) => return Ok(e),
+            };
+
+        let response = AddressPrCommentsResponse {
+            follow_up_id: follow_up.id.to_string(),
+            execution_process_id: follow_up.execution_process_id.map(|id| id.to_string()),
+            pr_number: follow_up.pr_number,
+            reply_to_comments: follow_up.reply_to_comments,
+        };
+
+        TaskServer::success(&response)
+    }
 }
 
 #[tool_handler]
 impl ServerHandler for TaskServer {
     fn get_info(&self) -> ServerInfo {
-        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. For advanced filtering, sorting, and pagination, use `list_tasks_advanced`. Use `search_tasks` to find tasks by keyword in title or description. Use `get_task_relationships` to see parent/child task hierarchies. Use `add_agent_metadata` and `get_agent_metadata` to track which agents worked on a task. For Git/PR operations: use `push_workspace_branch` to push a workspace branch to GitHub, `create_workspace_pr` to create a pull request, `get_workspace_pr_status` to check PR status from the database, and `refresh_workspace_pr_status` to refresh PR status from GitHub (auto-updates task to 'done' when PR is merged and task was 'inreview'). TOOLS: 'list_projects', 'list_tasks', 'list_tasks_advanced', 'search_tasks', 'create_task', 'start_workspace_session', 'get_task', 'get_task_relationships', 'update_task', 'bulk_update_tasks', 'delete_task', 'list_repos', 'add_task_comment', 'get_task_comments', 'get_task_history', 'assign_task', 'add_agent_metadata', 'get_agent_metadata', 'push_workspace_branch', 'create_workspace_pr', 'get_workspace_pr_status', 'refresh_workspace_pr_status'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
+        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. For advanced filtering, sorting, and pagination, use `list_tasks_advanced`. Use `search_tasks` to find tasks by keyword in title or description. Use `get_task_relationships` to see parent/child task hierarchies. Use `add_dependency` to mark a task as blocked by another task and `list_blockers` to see what a task is waiting on. Use `add_agent_metadata` and `get_agent_metadata` to track which agents worked on a task. Use `get_usage` to see the tokens and estimated cost spent on a task or project. Projects can define custom workflow states: use `get_project_workflow` to see them and `update_task` with `workflow_state` to move a task between them. Use `get_unread_comments` at the start of each turn to read comments that @mention you or reply to you, and `add_task_comment` with `parent_comment_id` to reply. For Git/PR operations: use `push_workspace_branch` to push a workspace branch to GitHub, `create_workspace_pr` to create a pull request, `get_workspace_pr_status` to check PR status from the database, `refresh_workspace_pr_status` to refresh PR status from GitHub (auto-updates task to 'done' when PR is merged and task was 'inreview'), and `address_pr_comments` to have the workspace's agent address new review comments and push the result. TOOLS: 'list_projects', 'list_tasks', 'list_tasks_advanced', 'search_tasks', 'create_task', 'start_workspace_session', 'get_task', 'get_task_relationships', 'add_dependency', 'list_blockers', 'update_task', 'bulk_update_tasks', 'delete_task', 'list_repos', 'get_project_workflow', 'add_task_comment', 'get_task_comments', 'get_unread_comments', 'get_task_history', 'get_usage', 'assign_task', 'add_agent_metadata', 'get_agent_metadata', 'push_workspace_branch', 'create_workspace_pr', 'get_workspace_pr_status', 'refresh_workspace_pr_status', 'address_pr_comments'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
         if self.context.is_some() {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
@@ -0,0 +1,35 @@
+//! Identifies who is making a request, for task history.
+
+use std::convert::Infallible;
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+use db::models::{api_token::ApiToken, task_history::TaskActor};
+
+/// Header the MCP server sets to name the agent calling the API
+pub const AGENT_HEADER: &str = "X-Vibe-Kanban-Agent";
+
+/// The actor behind a request: an API token, an agent going through the MCP server,
+/// or otherwise the local user.
+pub struct RequestActor(pub TaskActor);
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestActor {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(api_token) = parts.extensions.get::<ApiToken>() {
+            return Ok(RequestActor(TaskActor::api_token(api_token.name.clone())));
+        }
+
+        let agent = parts
+            .headers
+            .get(AGENT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+
+        Ok(RequestActor(match agent {
+            Some(name) => TaskActor::agent(name),
+            None => TaskActor::user(),
+        }))
+    }
+}
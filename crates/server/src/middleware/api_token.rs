@@ -0,0 +1,417 @@
+//! Authentication for API requests from scripts and other machines.
+//!
+//! A request carrying `Authorization: Bearer <token>` is checked against the
+//! `api_tokens` table: the token must not be expired, must hold every scope the
+//! route needs and must belong to the project the request touches.
+//!
+//! Requests without credentials come from the UI or the MCP server. They are
+//! trusted unless `VK_REQUIRE_API_TOKEN` is set, in which case only loopback peers
+//! and the addresses listed in `VK_TRUSTED_NETWORKS` may skip the token. A reverse
+//! proxy on the same machine makes every request it forwards look like loopback,
+//! so with enforcement on, requests carrying forwarding headers always need a token.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::OnceLock,
+};
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::{ConnectInfo, Query, Request, State},
+    http::{Method, header::AUTHORIZATION},
+    middleware::Next,
+    response::Response,
+};
+use db::models::{
+    api_token::{ApiToken, ApiTokenScope},
+    execution_process::ExecutionProcess,
+    task::Task,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use ipnet::IpNet;
+use rand::{Rng, distributions::Alphanumeric};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Prefix of every generated token, to make leaked tokens easy to spot.
+pub const API_TOKEN_PREFIX: &str = "vk_";
+
+/// Largest request body inspected when checking which project a token request touches.
+const MAX_INSPECTED_BODY_BYTES: usize = 1024 * 1024;
+
+/// Generate a new random API token.
+pub fn generate_api_token() -> String {
+    let random: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(48)
+        .map(char::from)
+        .collect();
+    format!("{API_TOKEN_PREFIX}{random}")
+}
+
+/// Hash a token for storage and lookup. Tokens are high-entropy, so a plain
+/// SHA-256 is enough.
+pub fn hash_api_token(token: &str) -> String {
+    let mut output = String::with_capacity(64);
+    let digest = Sha256::digest(token.as_bytes());
+    for byte in digest {
+        use std::fmt::Write;
+        let _ = write!(output, "{:02x}", byte);
+    }
+    output
+}
+
+/// Scopes a token needs for a route, or `None` if the route is not available to tokens.
+fn required_scopes(method: &Method, path: &str) -> Option<&'static [ApiTokenScope]> {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match (segments.as_slice(), method) {
+        (["tasks", "create-and-start"], _) => {
+            Some(&[ApiTokenScope::TasksWrite, ApiTokenScope::WorkspacesStart])
+        }
+        (["tasks", ..], &Method::GET) => Some(&[ApiTokenScope::TasksRead]),
+        (["tasks", ..], _) => Some(&[ApiTokenScope::TasksWrite]),
+        (["task-attempts", ..], &Method::GET) => Some(&[ApiTokenScope::TasksRead]),
+        (["task-attempts"], &Method::POST) => Some(&[ApiTokenScope::WorkspacesStart]),
+        (["approvals"], &Method::GET) | (["approvals", _, "respond"], &Method::POST) => {
+            Some(&[ApiTokenScope::ApprovalsRespond])
+        }
+        _ => None,
+    }
+}
+
+/// Routes that authenticate themselves and are reachable without a token.
+fn is_public(path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    path == "/health"
+        || path.starts_with("/inbound/")
+        || matches!(segments.as_slice(), ["approvals", _, "remote", _])
+}
+
+fn bearer_token(request: &Request) -> Option<String> {
+    request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string())
+}
+
+/// Headers a reverse proxy adds to say the request came from somewhere else
+const FORWARDING_HEADERS: [&str; 3] = ["forwarded", "x-forwarded-for", "x-real-ip"];
+
+/// Which requests may skip the token check
+#[derive(Debug, Default)]
+struct TrustPolicy {
+    /// Only trusted peers may skip the token check
+    require_token: bool,
+    /// Peers trusted in addition to loopback
+    trusted_networks: Vec<IpNet>,
+}
+
+impl TrustPolicy {
+    fn from_env() -> Self {
+        Self::parse(
+            std::env::var("VK_REQUIRE_API_TOKEN").ok().as_deref(),
+            std::env::var("VK_TRUSTED_NETWORKS").ok().as_deref(),
+        )
+    }
+
+    /// `networks` is a comma-separated list of addresses and CIDR ranges
+    fn parse(require_token: Option<&str>, networks: Option<&str>) -> Self {
+        let require_token = require_token.is_some_and(|value| {
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes"
+            )
+        });
+        let trusted_networks = networks
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let network = entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from));
+                if network.is_err() {
+                    tracing::warn!("Ignoring invalid VK_TRUSTED_NETWORKS entry '{}'", entry);
+                }
+                network.ok()
+            })
+            .collect();
+        Self {
+            require_token,
+            trusted_networks,
+        }
+    }
+
+    /// True when a request without a token may go through. With enforcement on,
+    /// requests without a known peer address or relayed by a proxy are not trusted.
+    fn trusts(&self, request: &Request) -> bool {
+        if !self.require_token {
+            return true;
+        }
+        let forwarded = FORWARDING_HEADERS
+            .iter()
+            .any(|header| request.headers().contains_key(*header));
+        !forwarded
+            && request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .is_some_and(|ConnectInfo(addr)| {
+                    let ip = addr.ip().to_canonical();
+                    ip.is_loopback()
+                        || self
+                            .trusted_networks
+                            .iter()
+                            .any(|network| network.contains(&ip))
+                })
+    }
+}
+
+fn trust_policy() -> &'static TrustPolicy {
+    static POLICY: OnceLock<TrustPolicy> = OnceLock::new();
+    POLICY.get_or_init(TrustPolicy::from_env)
+}
+
+pub async fn api_token_middleware(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    // Nested routers see the path without the `/api` prefix
+    let path = request.uri().path();
+    let path = path.strip_prefix("/api").unwrap_or(path).to_string();
+
+    if is_public(&path) {
+        return Ok(next.run(request).await);
+    }
+
+    let Some(token) = bearer_token(&request) else {
+        if trust_policy().trusts(&request) {
+            return Ok(next.run(request).await);
+        }
+        return Err(ApiError::Unauthorized);
+    };
+
+    let pool = &deployment.db().pool;
+    let api_token = ApiToken::find_by_hash(pool, &hash_api_token(&token))
+        .await?
+        .filter(|api_token| !api_token.is_expired())
+        .ok_or(ApiError::Unauthorized)?;
+
+    let Some(scopes) = required_scopes(request.method(), &path) else {
+        return Err(ApiError::Forbidden(
+            "This endpoint is not available to API tokens".to_string(),
+        ));
+    };
+    if let Some(missing) = scopes.iter().find(|scope| !api_token.has_scope(**scope)) {
+        return Err(ApiError::Forbidden(format!(
+            "API token is missing the '{}' scope",
+            missing.as_str()
+        )));
+    }
+
+    // Buffer the body so the project it refers to can be checked, then restore it
+    let (parts, body) = request.into_parts();
+    let bytes = to_bytes(body, MAX_INSPECTED_BODY_BYTES)
+        .await
+        .map_err(|_| ApiError::BadRequest("Request body too large".to_string()))?;
+    let mut request = Request::from_parts(parts, Body::from(bytes.clone()));
+    let json_body = serde_json::from_slice::<serde_json::Value>(&bytes).ok();
+
+    let project_ids = request_project_ids(&deployment, &request, &path, json_body.as_ref()).await?;
+    // The pending approvals list is narrowed to the token's project by its handler
+    let lists_own_project = path == "/approvals";
+    if !lists_own_project
+        && (project_ids.is_empty() || project_ids.iter().any(|id| *id != api_token.project_id))
+    {
+        return Err(ApiError::Forbidden(
+            "API token is not valid for this project".to_string(),
+        ));
+    }
+
+    if let Err(e) = ApiToken::touch_last_used(pool, api_token.id).await {
+        tracing::warn!(
+            "Failed to update last use of API token {}: {}",
+            api_token.id,
+            e
+        );
+    }
+
+    request.extensions_mut().insert(api_token);
+    Ok(next.run(request).await)
+}
+
+/// Collect every project a request refers to through its path, query or JSON body.
+async fn request_project_ids(
+    deployment: &DeploymentImpl,
+    request: &Request,
+    path: &str,
+    body: Option<&serde_json::Value>,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let pool = &deployment.db().pool;
+    let mut project_ids = Vec::new();
+    let mut task_ids = Vec::new();
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        ["tasks", id, ..] => task_ids.extend(Uuid::parse_str(id).ok()),
+        ["task-attempts", id, ..] => {
+            if let Ok(workspace_id) = Uuid::parse_str(id)
+                && let Some(workspace) = Workspace::find_by_id(pool, workspace_id).await?
+            {
+                task_ids.push(workspace.task_id);
+            }
+        }
+        ["approvals", id, ..] => {
+            // Answered or unknown approvals are left for the handler to reject
+            if let Some(approval) = deployment
+                .approvals()
+                .pending_requests()
+                .into_iter()
+                .find(|approval| approval.id == *id)
+                && let Some(process) =
+                    ExecutionProcess::find_by_id(pool, approval.execution_process_id).await?
+                && let Some((workspace, _)) = process.parent_workspace_and_session(pool).await?
+            {
+                task_ids.push(workspace.task_id);
+            }
+        }
+        _ => {}
+    }
+
+    if let Ok(Query(query)) = Query::<HashMap<String, String>>::try_from_uri(request.uri()) {
+        project_ids.extend(
+            query
+                .get("project_id")
+                .and_then(|id| Uuid::parse_str(id).ok()),
+        );
+        task_ids.extend(query.get("task_id").and_then(|id| Uuid::parse_str(id).ok()));
+    }
+
+    if let Some(body) = body {
+        let uuid_at = |pointer: &str| {
+            body.pointer(pointer)
+                .and_then(|v| v.as_str())
+                .and_then(|id| Uuid::parse_str(id).ok())
+        };
+        project_ids.extend(uuid_at("/project_id"));
+        project_ids.extend(uuid_at("/task/project_id"));
+        task_ids.extend(uuid_at("/task_id"));
+        if let Some(ids) = body.get("task_ids").and_then(|v| v.as_array()) {
+            task_ids.extend(
+                ids.iter()
+                    .filter_map(|v| v.as_str())
+                    .filter_map(|id| Uuid::parse_str(id).ok()),
+            );
+        }
+    }
+
+    for task_id in task_ids {
+        // Unknown tasks are left for the handler to reject
+        if let Some(task) = Task::find_by_id(pool, task_id).await? {
+            project_ids.push(task.project_id);
+        }
+    }
+
+    Ok(project_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_tokens_are_unique_and_prefixed() {
+        let a = generate_api_token();
+        let b = generate_api_token();
+        assert!(a.starts_with(API_TOKEN_PREFIX));
+        assert_ne!(a, b);
+        assert_eq!(hash_api_token(&a), hash_api_token(&a));
+        assert_ne!(hash_api_token(&a), hash_api_token(&b));
+        assert_eq!(hash_api_token(&a).len(), 64);
+    }
+
+    #[test]
+    fn test_required_scopes() {
+        assert_eq!(
+            required_scopes(&Method::GET, "/tasks"),
+            Some(&[ApiTokenScope::TasksRead][..])
+        );
+        assert_eq!(
+            required_scopes(&Method::PUT, "/tasks/8c1c0b6e-0000-0000-0000-000000000000"),
+            Some(&[ApiTokenScope::TasksWrite][..])
+        );
+        assert_eq!(
+            required_scopes(&Method::POST, "/tasks/create-and-start"),
+            Some(&[ApiTokenScope::TasksWrite, ApiTokenScope::WorkspacesStart][..])
+        );
+        assert_eq!(
+            required_scopes(&Method::POST, "/task-attempts"),
+            Some(&[ApiTokenScope::WorkspacesStart][..])
+        );
+        assert_eq!(
+            required_scopes(&Method::POST, "/task-attempts/x/merge"),
+            None
+        );
+        assert_eq!(
+            required_scopes(&Method::POST, "/approvals/abc/respond"),
+            Some(&[ApiTokenScope::ApprovalsRespond][..])
+        );
+        assert_eq!(
+            required_scopes(&Method::GET, "/approvals"),
+            Some(&[ApiTokenScope::ApprovalsRespond][..])
+        );
+        assert_eq!(required_scopes(&Method::GET, "/config/info"), None);
+        assert_eq!(
+            required_scopes(&Method::POST, "/projects/x/api-tokens"),
+            None
+        );
+    }
+
+    fn request(peer: Option<&str>, forwarded: bool) -> Request {
+        let mut builder = Request::builder().uri("/api/tasks");
+        if forwarded {
+            builder = builder.header("X-Forwarded-For", "203.0.113.7");
+        }
+        if let Some(peer) = peer {
+            builder = builder.extension(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_ui_on_a_non_loopback_address_works_by_default() {
+        // The Docker image binds to 0.0.0.0, so the UI arrives from the bridge address
+        let policy = TrustPolicy::parse(None, None);
+        assert!(policy.trusts(&request(Some("172.17.0.1:5000"), false)));
+        assert!(policy.trusts(&request(Some("127.0.0.1:5000"), true)));
+        assert!(policy.trusts(&request(None, false)));
+    }
+
+    #[test]
+    fn test_required_tokens_only_trust_loopback_and_listed_networks() {
+        let policy = TrustPolicy::parse(Some("true"), Some("172.17.0.0/16, 10.0.0.5, bogus"));
+        assert_eq!(policy.trusted_networks.len(), 2);
+
+        assert!(policy.trusts(&request(Some("127.0.0.1:5000"), false)));
+        assert!(policy.trusts(&request(Some("[::1]:5000"), false)));
+        assert!(policy.trusts(&request(Some("172.17.0.1:5000"), false)));
+        assert!(policy.trusts(&request(Some("[::ffff:10.0.0.5]:5000"), false)));
+        assert!(!policy.trusts(&request(Some("192.168.1.20:5000"), false)));
+        assert!(!policy.trusts(&request(Some("127.0.0.1:5000"), true)));
+        assert!(!policy.trusts(&request(None, false)));
+    }
+
+    #[test]
+    fn test_signed_approval_links_are_public() {
+        assert!(is_public("/approvals/abc/remote/approve"));
+        assert!(!is_public("/approvals/abc/respond"));
+        assert!(!is_public("/approvals"));
+    }
+}
@@ -1,3 +1,7 @@
+pub mod actor;
+pub mod api_token;
 pub mod model_loaders;
 
+pub use actor::*;
+pub use api_token::*;
 pub use model_loaders::*;
@@ -9,6 +9,7 @@ use db::models::{
     webhook::Webhook, workspace::Workspace,
 };
 use deployment::Deployment;
+use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::DeploymentImpl;
@@ -40,9 +41,15 @@ pub async fn load_project_middleware(
     Ok(next.run(request).await)
 }
 
+/// Task routes may carry further path parameters after the task id
+#[derive(Deserialize)]
+pub struct TaskPath {
+    task_id: Uuid,
+}
+
 pub async fn load_task_middleware(
     State(deployment): State<DeploymentImpl>,
-    Path(task_id): Path<Uuid>,
+    Path(TaskPath { task_id }): Path<TaskPath>,
     request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
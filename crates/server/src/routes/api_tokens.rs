@@ -0,0 +1,153 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::api_token::{ApiToken, ApiTokenScope, CreateApiToken};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{generate_api_token, hash_api_token},
+};
+
+/// Number of leading token characters stored to tell tokens apart
+const TOKEN_PREFIX_LEN: usize = 10;
+
+/// Request body for creating an API token
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub scopes: Vec<ApiTokenScope>,
+    /// Optional expiry. Tokens without one stay valid until deleted.
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// API token with parsed scopes
+#[derive(Debug, Serialize, TS)]
+pub struct ApiTokenResponse {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ApiToken> for ApiTokenResponse {
+    fn from(api_token: ApiToken) -> Self {
+        ApiTokenResponse {
+            id: api_token.id,
+            project_id: api_token.project_id,
+            scopes: api_token.get_scopes(),
+            name: api_token.name,
+            token_prefix: api_token.token_prefix,
+            expires_at: api_token.expires_at,
+            last_used_at: api_token.last_used_at,
+            created_at: api_token.created_at,
+        }
+    }
+}
+
+/// Response for a newly created token
+#[derive(Debug, Serialize, TS)]
+pub struct CreateApiTokenResponse {
+    /// The plaintext token. It is only returned here and cannot be retrieved later.
+    pub token: String,
+    pub api_token: ApiTokenResponse,
+}
+
+/// Create an API token for a project
+/// POST /api/projects/{project_id}/api-tokens
+pub async fn create_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<CreateApiTokenRequest>,
+) -> Result<ResponseJson<ApiResponse<CreateApiTokenResponse>>, ApiError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest("Token name is required".to_string()));
+    }
+    if payload.scopes.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one scope must be specified".to_string(),
+        ));
+    }
+    if payload
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= chrono::Utc::now())
+    {
+        return Err(ApiError::BadRequest(
+            "Expiry must be in the future".to_string(),
+        ));
+    }
+
+    let token = generate_api_token();
+    let create_data = CreateApiToken {
+        project_id,
+        name: name.to_string(),
+        token_hash: hash_api_token(&token),
+        token_prefix: token.chars().take(TOKEN_PREFIX_LEN).collect(),
+        scopes: payload.scopes,
+        expires_at: payload.expires_at,
+    };
+
+    let api_token = ApiToken::create(&deployment.db().pool, &create_data).await?;
+
+    tracing::info!(
+        "Created API token {} for project {}",
+        api_token.id,
+        project_id
+    );
+
+    Ok(ResponseJson(ApiResponse::success(CreateApiTokenResponse {
+        token,
+        api_token: ApiTokenResponse::from(api_token),
+    })))
+}
+
+/// List API tokens for a project
+/// GET /api/projects/{project_id}/api-tokens
+pub async fn list_api_tokens(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApiTokenResponse>>>, ApiError> {
+    let api_tokens = ApiToken::find_by_project_id(&deployment.db().pool, project_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(
+        api_tokens.into_iter().map(ApiTokenResponse::from).collect(),
+    )))
+}
+
+/// Revoke an API token
+/// DELETE /api/api-tokens/{token_id}
+pub async fn delete_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Path(token_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ApiToken::delete(&deployment.db().pool, token_id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+
+    tracing::info!("Revoked API token {}", token_id);
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/api-tokens/{token_id}", delete(delete_api_token))
+}
+
+/// Build the project-scoped API token router (nested under /projects/{project_id})
+pub fn project_api_tokens_router() -> Router<DeploymentImpl> {
+    Router::new().route("/api-tokens", get(list_api_tokens).post(create_api_token))
+}
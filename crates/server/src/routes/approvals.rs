@@ -1,13 +1,54 @@
 use axum::{
-    Json, Router,
-    extract::{Path, State},
+    Extension, Json, Router,
+    extract::{Path, Query, State},
     http::StatusCode,
-    routing::post,
+    response::{Html, Json as ResponseJson},
+    routing::{get, post},
+};
+use db::models::{
+    api_token::ApiToken,
+    approval_decision::ApprovalDecision,
+    approval_rule::{ApprovalRule, CreateApprovalRule, UpdateApprovalRule},
+    execution_process::ExecutionProcess,
+    project::Project,
 };
 use deployment::Deployment;
-use utils::approvals::{ApprovalResponse, ApprovalStatus};
+use serde::{Deserialize, Serialize};
+use services::services::approvals::{ApprovalError, policy, remote::RemoteDecision};
+use ts_rs::TS;
+use utils::{
+    approvals::{ApprovalRequest, ApprovalResponse, ApprovalStatus},
+    response::ApiResponse,
+};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_DECISION_LIMIT: u32 = 100;
+const MAX_DECISION_LIMIT: u32 = 500;
+const APPROVAL_CLOSED: &str = "This request was already answered or has timed out.";
 
-use crate::DeploymentImpl;
+#[derive(Debug, Deserialize)]
+pub struct ApprovalDecisionQuery {
+    pub limit: Option<u32>,
+}
+
+/// A request waiting for an answer, with the task it holds up
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PendingApproval {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub request: ApprovalRequest,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RemoteLinkQuery {
+    pub expires: i64,
+    pub signature: String,
+}
 
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
@@ -39,6 +80,272 @@ pub async fn respond_to_approval(
     }
 }
 
+/// Requests waiting for an answer across all projects, oldest first. Requests made
+/// with an API token only see the token's project.
+/// GET /api/approvals
+pub async fn list_pending_approvals(
+    State(deployment): State<DeploymentImpl>,
+    api_token: Option<Extension<ApiToken>>,
+) -> Result<ResponseJson<ApiResponse<Vec<PendingApproval>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut pending = Vec::new();
+    for request in deployment.approvals().pending_requests() {
+        // The process may have been deleted while its approval was still open
+        let Ok(ctx) = ExecutionProcess::load_context(pool, request.execution_process_id).await
+        else {
+            continue;
+        };
+        if let Some(Extension(api_token)) = &api_token
+            && api_token.project_id != ctx.project.id
+        {
+            continue;
+        }
+        pending.push(PendingApproval {
+            request,
+            task_id: ctx.task.id,
+            task_title: ctx.task.title,
+            project_id: ctx.project.id,
+        });
+    }
+    Ok(ResponseJson(ApiResponse::success(pending)))
+}
+
+/// Confirmation page for a signed link, so that link previews in chat apps do not
+/// answer the request
+/// GET /api/approvals/{id}/remote/{decision}
+pub async fn show_remote_approval(
+    State(deployment): State<DeploymentImpl>,
+    Path((id, decision)): Path<(String, RemoteDecision)>,
+    Query(query): Query<RemoteLinkQuery>,
+) -> Result<Html<String>, ApiError> {
+    let approvals = deployment.approvals();
+    if !approvals.verify_remote_link(&id, decision, query.expires, &query.signature) {
+        return Err(ApiError::Unauthorized);
+    }
+    let Some(request) = approvals.pending_request(&id) else {
+        return Ok(remote_page(
+            "Approval closed",
+            &format!("<p>{APPROVAL_CLOSED}</p>"),
+        ));
+    };
+
+    let input = serde_json::to_string_pretty(&request.tool_input).unwrap_or_default();
+    let action = match decision {
+        RemoteDecision::Approve => "Approve",
+        RemoteDecision::Deny => "Deny",
+    };
+    Ok(remote_page(
+        &format!("{action} {}?", request.tool_name),
+        &format!(
+            "<pre>{}</pre><form method=\"post\"><button type=\"submit\">{action}</button></form>",
+            escape_html(&input)
+        ),
+    ))
+}
+
+/// Answer a request through a signed link
+/// POST /api/approvals/{id}/remote/{decision}
+pub async fn respond_to_remote_approval(
+    State(deployment): State<DeploymentImpl>,
+    Path((id, decision)): Path<(String, RemoteDecision)>,
+    Query(query): Query<RemoteLinkQuery>,
+) -> Result<Html<String>, ApiError> {
+    let approvals = deployment.approvals();
+    if !approvals.verify_remote_link(&id, decision, query.expires, &query.signature) {
+        return Err(ApiError::Unauthorized);
+    }
+    let request = approvals
+        .pending_request(&id)
+        .ok_or_else(|| ApiError::Conflict(APPROVAL_CLOSED.to_string()))?;
+
+    let response = ApprovalResponse {
+        execution_process_id: request.execution_process_id,
+        status: decision.status(),
+    };
+    let (status, context) = approvals
+        .respond(&deployment.db().pool, &id, response)
+        .await
+        .map_err(|e| match e {
+            ApprovalError::NotFound | ApprovalError::AlreadyCompleted => {
+                ApiError::Conflict(APPROVAL_CLOSED.to_string())
+            }
+            e => ApiError::BadRequest(e.to_string()),
+        })?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "approval_responded",
+            serde_json::json!({
+                "approval_id": &id,
+                "status": format!("{:?}", status),
+                "tool_name": context.tool_name,
+                "execution_process_id": context.execution_process_id.to_string(),
+                "remote": true,
+            }),
+        )
+        .await;
+
+    let title = match status {
+        ApprovalStatus::Approved => "Approved",
+        _ => "Denied",
+    };
+    Ok(remote_page(
+        title,
+        &format!(
+            "<p>{} was {}. You can close this page.</p>",
+            escape_html(&context.tool_name),
+            title.to_lowercase()
+        ),
+    ))
+}
+
+fn remote_page(title: &str, body: &str) -> Html<String> {
+    Html(format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width\">\
+         <title>{title}</title></head><body><h1>{title}</h1>{body}</body></html>",
+        title = escape_html(title)
+    ))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Approval rules of a project in creation order
+/// GET /api/projects/{id}/approval-rules
+pub async fn list_project_approval_rules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRule>>>, ApiError> {
+    let rules = ApprovalRule::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+/// POST /api/projects/{id}/approval-rules
+pub async fn create_project_approval_rule(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(mut payload): Json<CreateApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    normalize(&mut payload.tool_name);
+    normalize(&mut payload.command_regex);
+    normalize(&mut payload.path_glob);
+    normalize(&mut payload.description);
+    policy::validate_rule(
+        payload.command_regex.as_deref(),
+        payload.path_glob.as_deref(),
+    )?;
+
+    let rule = ApprovalRule::create(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "approval_rule_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "action": rule.action,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+/// GET /api/approval-rules/{rule_id}
+pub async fn get_approval_rule(
+    State(deployment): State<DeploymentImpl>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    let rule = ApprovalRule::find_by_id(&deployment.db().pool, rule_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+/// Replace a rule. Requests already waiting for a person are not re-evaluated.
+/// PUT /api/approval-rules/{rule_id}
+pub async fn update_approval_rule(
+    State(deployment): State<DeploymentImpl>,
+    Path(rule_id): Path<Uuid>,
+    Json(mut payload): Json<UpdateApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    normalize(&mut payload.tool_name);
+    normalize(&mut payload.command_regex);
+    normalize(&mut payload.path_glob);
+    normalize(&mut payload.description);
+    policy::validate_rule(
+        payload.command_regex.as_deref(),
+        payload.path_glob.as_deref(),
+    )?;
+
+    let rule = ApprovalRule::update(&deployment.db().pool, rule_id, &payload)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+/// DELETE /api/approval-rules/{rule_id}
+pub async fn delete_approval_rule(
+    State(deployment): State<DeploymentImpl>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ApprovalRule::delete(&deployment.db().pool, rule_id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Newest-first log of approval decisions made by rules, people and timeouts
+/// GET /api/projects/{id}/approval-decisions
+pub async fn list_project_approval_decisions(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ApprovalDecisionQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalDecision>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DECISION_LIMIT)
+        .min(MAX_DECISION_LIMIT);
+    let decisions =
+        ApprovalDecision::find_by_project_id(&deployment.db().pool, project.id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(decisions)))
+}
+
+/// Blank criteria match everything, so they are stored as missing
+fn normalize(value: &mut Option<String>) {
+    *value = value
+        .take()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+}
+
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/approvals/{id}/respond", post(respond_to_approval))
+    Router::new()
+        .route("/approvals", get(list_pending_approvals))
+        .route("/approvals/{id}/respond", post(respond_to_approval))
+        .route(
+            "/approvals/{id}/remote/{decision}",
+            get(show_remote_approval).post(respond_to_remote_approval),
+        )
+        .route(
+            "/approval-rules/{rule_id}",
+            get(get_approval_rule)
+                .put(update_approval_rule)
+                .delete(delete_approval_rule),
+        )
+}
+
+/// Build the project-scoped approval policy router (nested under /projects/{id})
+pub fn project_approvals_router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/approval-rules",
+            get(list_project_approval_rules).post(create_project_approval_rule),
+        )
+        .route("/approval-decisions", get(list_project_approval_decisions))
 }
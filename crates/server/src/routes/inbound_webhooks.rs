@@ -0,0 +1,315 @@
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::{Path, State},
+    http::HeaderMap,
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use chrono::Utc;
+use db::models::{
+    inbound_webhook::{CreateInboundWebhook, InboundWebhook, InboundWebhookWithSecret},
+    task::{CreateTask, Task, TaskStatus},
+    task_history::{TaskActor, TaskHistory},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::webhooks::WebhookService;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Header carrying the `sha256=<hex>` HMAC of `<timestamp>.<raw request body>`
+const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Header carrying the Unix time in seconds at which the caller signed the request
+const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+
+/// How far the signed timestamp may be from the server's clock
+const TIMESTAMP_TOLERANCE_SECS: i64 = 5 * 60;
+
+/// Request body for creating an inbound webhook
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateInboundWebhookRequest {
+    pub name: String,
+    /// Optional secret for verifying signatures. Auto-generated if not provided.
+    pub secret: Option<String>,
+}
+
+/// Request body for enabling or disabling an inbound webhook
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct UpdateInboundWebhookRequest {
+    pub is_active: bool,
+}
+
+/// Actions an external system can perform through an inbound webhook
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum InboundWebhookAction {
+    CreateTask {
+        title: String,
+        description: Option<String>,
+        status: Option<TaskStatus>,
+        assignee: Option<String>,
+    },
+    TransitionTask {
+        task_id: Uuid,
+        status: TaskStatus,
+    },
+}
+
+/// Create an inbound webhook for a project
+/// POST /api/projects/{project_id}/inbound-webhooks
+pub async fn create_inbound_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<CreateInboundWebhookRequest>,
+) -> Result<ResponseJson<ApiResponse<InboundWebhookWithSecret>>, ApiError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest("Webhook name is required".to_string()));
+    }
+
+    let secret = payload
+        .secret
+        .filter(|secret| !secret.trim().is_empty())
+        .unwrap_or_else(|| Uuid::new_v4().to_string().replace("-", ""));
+
+    let inbound_webhook = InboundWebhook::create(
+        &deployment.db().pool,
+        &CreateInboundWebhook {
+            project_id,
+            name: name.to_string(),
+            secret,
+        },
+    )
+    .await?;
+
+    tracing::info!(
+        "Created inbound webhook {} for project {}",
+        inbound_webhook.id,
+        project_id
+    );
+
+    Ok(ResponseJson(ApiResponse::success(
+        InboundWebhookWithSecret::from(inbound_webhook),
+    )))
+}
+
+/// List inbound webhooks for a project
+/// GET /api/projects/{project_id}/inbound-webhooks
+pub async fn list_inbound_webhooks(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<InboundWebhook>>>, ApiError> {
+    let inbound_webhooks =
+        InboundWebhook::find_by_project_id(&deployment.db().pool, project_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(inbound_webhooks)))
+}
+
+/// Enable or disable an inbound webhook
+/// PUT /api/inbound-webhooks/{inbound_webhook_id}
+pub async fn update_inbound_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(inbound_webhook_id): Path<Uuid>,
+    Json(payload): Json<UpdateInboundWebhookRequest>,
+) -> Result<ResponseJson<ApiResponse<InboundWebhook>>, ApiError> {
+    let inbound_webhook =
+        InboundWebhook::set_active(&deployment.db().pool, inbound_webhook_id, payload.is_active)
+            .await?
+            .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+    Ok(ResponseJson(ApiResponse::success(inbound_webhook)))
+}
+
+/// Delete an inbound webhook
+/// DELETE /api/inbound-webhooks/{inbound_webhook_id}
+pub async fn delete_inbound_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(inbound_webhook_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = InboundWebhook::delete(&deployment.db().pool, inbound_webhook_id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+
+    tracing::info!("Deleted inbound webhook {}", inbound_webhook_id);
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Receive a signed call from an external system
+/// POST /api/inbound/{inbound_webhook_id}
+pub async fn receive_inbound_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(inbound_webhook_id): Path<Uuid>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    // Unknown, disabled and badly signed calls all look the same to the caller
+    let inbound_webhook = InboundWebhook::find_by_id(pool, inbound_webhook_id)
+        .await?
+        .filter(|inbound_webhook| inbound_webhook.is_active)
+        .ok_or(ApiError::Unauthorized)?;
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(ApiError::Unauthorized)?;
+    let timestamp = headers
+        .get(TIMESTAMP_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .ok_or(ApiError::Unauthorized)?;
+    if !is_fresh(timestamp, Utc::now().timestamp())
+        || !WebhookService::verify_signature(
+            &inbound_webhook.secret,
+            &signed_payload(timestamp, &body),
+            signature,
+        )
+    {
+        return Err(ApiError::Unauthorized);
+    }
+    // A replayed call carries the same signature; keep receipts for as long as their
+    // timestamps can still pass the freshness check. Hex digits may differ in case.
+    let first_receipt = InboundWebhook::record_receipt(
+        pool,
+        inbound_webhook.id,
+        &signature.to_ascii_lowercase(),
+        2 * TIMESTAMP_TOLERANCE_SECS,
+    )
+    .await?;
+    if !first_receipt {
+        return Err(ApiError::Unauthorized);
+    }
+
+    let action: InboundWebhookAction = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid inbound webhook payload: {e}")))?;
+
+    let actor = TaskActor::system(format!("inbound_webhook:{}", inbound_webhook.name));
+    let task = match action {
+        InboundWebhookAction::CreateTask {
+            title,
+            description,
+            status,
+            assignee,
+        } => {
+            if title.trim().is_empty() {
+                return Err(ApiError::BadRequest("Task title is required".to_string()));
+            }
+            let mut create_data =
+                CreateTask::from_title_description(inbound_webhook.project_id, title, description);
+            if status.is_some() {
+                create_data.status = status;
+            }
+            let task = Task::create(pool, &create_data, Uuid::new_v4()).await?;
+            TaskHistory::record_created(pool, &task, &actor).await?;
+            match assignee.filter(|assignee| !assignee.trim().is_empty()) {
+                Some(assignee) => {
+                    let assigned = Task::update_assignee(pool, task.id, Some(assignee)).await?;
+                    TaskHistory::record_changes(pool, &task, &assigned, &actor).await?;
+                    assigned
+                }
+                None => task,
+            }
+        }
+        InboundWebhookAction::TransitionTask { task_id, status } => {
+            let task = Task::find_by_id(pool, task_id)
+                .await?
+                .filter(|task| task.project_id == inbound_webhook.project_id)
+                .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+            Task::update_status(pool, task.id, status.clone()).await?;
+            TaskHistory::record_status_change(pool, task.id, &task.status, &status, &actor).await?;
+            let task = Task::find_by_id(pool, task.id)
+                .await?
+                .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+            if task.shared_task_id.is_some()
+                && let Ok(publisher) = deployment.share_publisher()
+                && let Err(e) = publisher.update_shared_task(&task).await
+            {
+                tracing::warn!("Failed to sync shared task {}: {}", task.id, e);
+            }
+            task
+        }
+    };
+
+    tracing::info!(
+        "Inbound webhook {} applied to task {}",
+        inbound_webhook.id,
+        task.id
+    );
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+/// Bytes covered by the signature: the timestamp, a dot and the raw body
+fn signed_payload(timestamp: i64, body: &[u8]) -> Vec<u8> {
+    let mut payload = format!("{timestamp}.").into_bytes();
+    payload.extend_from_slice(body);
+    payload
+}
+
+fn is_fresh(timestamp: i64, now: i64) -> bool {
+    (now - timestamp).abs() <= TIMESTAMP_TOLERANCE_SECS
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/inbound-webhooks/{inbound_webhook_id}",
+            put(update_inbound_webhook).delete(delete_inbound_webhook),
+        )
+        .route(
+            "/inbound/{inbound_webhook_id}",
+            post(receive_inbound_webhook),
+        )
+}
+
+/// Build the project-scoped inbound webhook router (nested under /projects/{project_id})
+pub fn project_inbound_webhooks_router() -> Router<DeploymentImpl> {
+    Router::new().route(
+        "/inbound-webhooks",
+        get(list_inbound_webhooks).post(create_inbound_webhook),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_covers_timestamp_and_body() {
+        let body = br#"{"action":"transition_task"}"#;
+        let signature = WebhookService::sign_payload(
+            "secret",
+            &format!("1700000000.{}", std::str::from_utf8(body).unwrap()),
+        );
+
+        assert!(WebhookService::verify_signature(
+            "secret",
+            &signed_payload(1_700_000_000, body),
+            &signature
+        ));
+        // Re-dating a captured call breaks its signature
+        assert!(!WebhookService::verify_signature(
+            "secret",
+            &signed_payload(1_700_000_600, body),
+            &signature
+        ));
+    }
+
+    #[test]
+    fn test_timestamps_outside_the_tolerance_are_rejected() {
+        let now = 1_700_000_000;
+        assert!(is_fresh(now, now));
+        assert!(is_fresh(now - TIMESTAMP_TOLERANCE_SECS, now));
+        assert!(is_fresh(now + TIMESTAMP_TOLERANCE_SECS, now));
+        assert!(!is_fresh(now - TIMESTAMP_TOLERANCE_SECS - 1, now));
+        assert!(!is_fresh(now + TIMESTAMP_TOLERANCE_SECS + 1, now));
+    }
+}
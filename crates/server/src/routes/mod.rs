@@ -1,11 +1,14 @@
+use std::net::SocketAddr;
+
 use axum::{
-    Router,
-    routing::{IntoMakeService, get},
+    Router, extract::connect_info::IntoMakeServiceWithConnectInfo, middleware::from_fn_with_state,
+    routing::get,
 };
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, middleware::api_token_middleware};
 
 pub mod agents;
+pub mod api_tokens;
 pub mod approvals;
 pub mod config;
 pub mod containers;
@@ -16,10 +19,13 @@ pub mod execution_processes;
 pub mod frontend;
 pub mod health;
 pub mod images;
+pub mod inbound_webhooks;
 pub mod oauth;
 pub mod organizations;
+pub mod pipelines;
 pub mod projects;
 pub mod repo;
+pub mod scheduler;
 pub mod scratch;
 pub mod sessions;
 pub mod shared_tasks;
@@ -28,9 +34,10 @@ pub mod task_attempts;
 pub mod task_comments;
 pub mod task_history;
 pub mod tasks;
+pub mod usage;
 pub mod webhooks;
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
+pub fn router(deployment: DeploymentImpl) -> IntoMakeServiceWithConnectInfo<Router, SocketAddr> {
     // Create routers with different middleware layers
     let base_routes = Router::new()
         .route("/health", get(health::health_check))
@@ -44,7 +51,11 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(tags::router(&deployment))
         .merge(task_comments::router(&deployment))
         .merge(task_history::router(&deployment))
+        .merge(usage::router())
         .merge(webhooks::router(&deployment))
+        .merge(pipelines::router())
+        .merge(api_tokens::router())
+        .merge(inbound_webhooks::router())
         .merge(oauth::router())
         .merge(organizations::router())
         .merge(filesystem::router())
@@ -53,13 +64,15 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(approvals::router())
         .merge(agents::router())
         .merge(scratch::router(&deployment))
+        .merge(scheduler::router(&deployment))
         .merge(sessions::router(&deployment))
         .nest("/images", images::routes())
+        .layer(from_fn_with_state(deployment.clone(), api_token_middleware))
         .with_state(deployment);
 
     Router::new()
         .route("/", get(frontend::serve_frontend_root))
         .route("/{*path}", get(frontend::serve_frontend))
         .nest("/api", base_routes)
-        .into_make_service()
+        .into_make_service_with_connect_info::<SocketAddr>()
 }
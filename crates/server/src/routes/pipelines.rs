@@ -0,0 +1,152 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::{
+    pipeline::{CreateProjectPipeline, ProjectPipeline, UpdateProjectPipeline},
+    project::Project,
+};
+use deployment::Deployment;
+use services::services::pipeline::PipelineDefinition;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Pipelines of a project, by name
+/// GET /api/projects/{id}/pipelines
+pub async fn list_project_pipelines(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectPipeline>>>, ApiError> {
+    let pipelines = ProjectPipeline::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(pipelines)))
+}
+
+/// Create a pipeline from a TOML definition
+/// POST /api/projects/{id}/pipelines
+pub async fn create_project_pipeline(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(mut payload): Json<CreateProjectPipeline>,
+) -> Result<ResponseJson<ApiResponse<ProjectPipeline>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    payload.name = payload.name.trim().to_string();
+    if payload.name.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Pipeline name is required".to_string(),
+        ));
+    }
+    let definition = PipelineDefinition::parse(&payload.definition)?;
+    ensure_unique_name(&deployment, project.id, &payload.name, None).await?;
+
+    let pipeline = ProjectPipeline::create(pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_pipeline_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "step_count": definition.steps.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(pipeline)))
+}
+
+/// GET /api/pipelines/{pipeline_id}
+pub async fn get_pipeline(
+    State(deployment): State<DeploymentImpl>,
+    Path(pipeline_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<ProjectPipeline>>, ApiError> {
+    let pipeline = find_pipeline(&deployment, pipeline_id).await?;
+    Ok(ResponseJson(ApiResponse::success(pipeline)))
+}
+
+/// Rename a pipeline or replace its definition. Runs in flight keep the old one.
+/// PUT /api/pipelines/{pipeline_id}
+pub async fn update_pipeline(
+    State(deployment): State<DeploymentImpl>,
+    Path(pipeline_id): Path<Uuid>,
+    Json(mut payload): Json<UpdateProjectPipeline>,
+) -> Result<ResponseJson<ApiResponse<ProjectPipeline>>, ApiError> {
+    let existing = find_pipeline(&deployment, pipeline_id).await?;
+
+    if let Some(name) = payload.name.as_mut() {
+        *name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiError::BadRequest(
+                "Pipeline name is required".to_string(),
+            ));
+        }
+        ensure_unique_name(&deployment, existing.project_id, name, Some(existing.id)).await?;
+    }
+    if let Some(definition) = &payload.definition {
+        PipelineDefinition::parse(definition)?;
+    }
+
+    let pipeline = ProjectPipeline::update(&deployment.db().pool, pipeline_id, &payload)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(pipeline)))
+}
+
+/// DELETE /api/pipelines/{pipeline_id}
+pub async fn delete_pipeline(
+    State(deployment): State<DeploymentImpl>,
+    Path(pipeline_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectPipeline::delete(&deployment.db().pool, pipeline_id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+async fn find_pipeline(
+    deployment: &DeploymentImpl,
+    pipeline_id: Uuid,
+) -> Result<ProjectPipeline, ApiError> {
+    ProjectPipeline::find_by_id(&deployment.db().pool, pipeline_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))
+}
+
+async fn ensure_unique_name(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    name: &str,
+    except: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let pipelines = ProjectPipeline::find_by_project_id(&deployment.db().pool, project_id).await?;
+    if pipelines
+        .iter()
+        .any(|pipeline| pipeline.name == name && Some(pipeline.id) != except)
+    {
+        return Err(ApiError::Conflict(format!(
+            "A pipeline named {name} already exists"
+        )));
+    }
+    Ok(())
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route(
+        "/pipelines/{pipeline_id}",
+        get(get_pipeline)
+            .put(update_pipeline)
+            .delete(delete_pipeline),
+    )
+}
+
+/// Build the project-scoped pipeline router (nested under /projects/{id})
+pub fn project_pipelines_router() -> Router<DeploymentImpl> {
+    Router::new().route(
+        "/pipelines",
+        get(list_project_pipelines).post(create_project_pipeline),
+    )
+}
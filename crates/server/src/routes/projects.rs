@@ -10,14 +10,14 @@ use axum::{
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use db::models::{
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
     project_repo::{CreateProjectRepo, ProjectRepo, UpdateProjectRepo},
+    project_workflow::{ProjectWorkflow, SetProjectWorkflow},
     repo::Repo,
 };
-use super::webhooks;
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
@@ -32,6 +32,7 @@ use utils::{
 };
 use uuid::Uuid;
 
+use super::{api_tokens, approvals, inbound_webhooks, pipelines, task_history, usage, webhooks};
 use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
 
 #[derive(Deserialize, TS)]
@@ -421,6 +422,39 @@ pub async fn search_project_files(
     }
 }
 
+/// Custom states and allowed transitions of a project
+/// GET /api/projects/{id}/workflow
+pub async fn get_project_workflow(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectWorkflow>>, ApiError> {
+    let workflow = ProjectWorkflow::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(workflow)))
+}
+
+/// Replace the workflow of a project
+/// PUT /api/projects/{id}/workflow
+pub async fn set_project_workflow(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetProjectWorkflow>,
+) -> Result<ResponseJson<ApiResponse<ProjectWorkflow>>, ApiError> {
+    let workflow = ProjectWorkflow::set(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_workflow_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "state_count": workflow.states.len(),
+                "transition_count": workflow.transitions.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(workflow)))
+}
+
 pub async fn get_project_repositories(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -601,7 +635,17 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
         )
+        .route(
+            "/workflow",
+            get(get_project_workflow).put(set_project_workflow),
+        )
         .merge(webhooks::project_webhooks_router())
+        .merge(pipelines::project_pipelines_router())
+        .merge(approvals::project_approvals_router())
+        .merge(api_tokens::project_api_tokens_router())
+        .merge(inbound_webhooks::project_inbound_webhooks_router())
+        .merge(task_history::project_activity_router())
+        .merge(usage::project_usage_router())
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -1,10 +1,13 @@
 use axum::{
-    Router,
+    Json, Router,
     extract::{Path, State},
     response::Json as ResponseJson,
-    routing::{get, post},
+    routing::{get, post, put},
+};
+use db::models::{
+    merge::ForgeKind,
+    repo::{Repo, RepoError},
 };
-use db::models::repo::Repo;
 use deployment::Deployment;
 use serde::Deserialize;
 use services::services::git::GitBranch;
@@ -28,6 +31,15 @@ pub struct InitRepoRequest {
     pub folder_name: String,
 }
 
+/// Forge settings of a repo; None detects them from the remote URL
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateRepoForgeRequest {
+    pub forge: Option<ForgeKind>,
+    /// Base URL of a self-hosted forge, e.g. `https://git.example.com`
+    pub forge_url: Option<String>,
+}
+
 pub async fn register_repo(
     State(deployment): State<DeploymentImpl>,
     ResponseJson(payload): ResponseJson<RegisterRepoRequest>,
@@ -74,9 +86,34 @@ pub async fn get_repo_branches(
     Ok(ResponseJson(ApiResponse::success(branches)))
 }
 
+pub async fn update_repo_forge(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    Json(payload): Json<UpdateRepoForgeRequest>,
+) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
+    let forge_url = payload
+        .forge_url
+        .as_deref()
+        .map(|url| url.trim().trim_end_matches('/'))
+        .filter(|url| !url.is_empty());
+    if let Some(url) = forge_url
+        && !(url.starts_with("https://") || url.starts_with("http://"))
+    {
+        return Err(ApiError::BadRequest(
+            "Forge URL must start with http:// or https://".to_string(),
+        ));
+    }
+
+    let repo = Repo::update_forge(&deployment.db().pool, repo_id, payload.forge, forge_url)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    Ok(ResponseJson(ApiResponse::success(repo)))
+}
+
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/repos", post(register_repo))
         .route("/repos/init", post(init_repo))
         .route("/repos/{repo_id}/branches", get(get_repo_branches))
+        .route("/repos/{repo_id}/forge", put(update_repo_forge))
 }
@@ -0,0 +1,18 @@
+use axum::{Router, extract::State, response::Json as ResponseJson, routing::get};
+use deployment::Deployment;
+use services::services::task_scheduler::{self, SchedulerQueueState};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn get_queue_state(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<SchedulerQueueState>>, ApiError> {
+    let settings = deployment.config().read().await.auto_scheduler.clone();
+    let state = task_scheduler::queue_state(&deployment.db().pool, &settings).await?;
+    Ok(ResponseJson(ApiResponse::success(state)))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().route("/scheduler/queue", get(get_queue_state))
+}
@@ -1,3 +1,4 @@
+pub mod pipeline_runs;
 pub mod queue;
 
 use axum::{
@@ -246,7 +247,11 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let sessions_router = Router::new()
         .route("/", get(get_sessions).post(create_session))
         .nest("/{session_id}", session_id_router)
-        .nest("/{session_id}/queue", queue::router(deployment));
+        .nest("/{session_id}/queue", queue::router(deployment))
+        .nest(
+            "/{session_id}/pipeline-runs",
+            pipeline_runs::router(deployment),
+        );
 
     Router::new().nest("/sessions", sessions_router)
 }
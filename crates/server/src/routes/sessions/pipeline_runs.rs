@@ -0,0 +1,122 @@
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    execution_process::ExecutionProcess,
+    pipeline::{PipelineRun, PipelineRunWithSteps, ProjectPipeline},
+    session::Session,
+    workspace::{Workspace, WorkspaceError},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::Deserialize;
+use services::services::{container::ContainerService, pipeline::PipelineDefinition};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
+
+/// Request body for starting a pipeline in a session
+#[derive(Debug, Deserialize, TS)]
+pub struct StartPipelineRunRequest {
+    pub pipeline_id: Uuid,
+    /// Coding agent for agent steps. Defaults to the one last used in the session.
+    pub executor_profile_id: Option<ExecutorProfileId>,
+}
+
+/// Pipeline runs of a session with their step runs. Each step run names its
+/// execution process and the step run that started it.
+pub async fn get_pipeline_runs(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PipelineRunWithSteps>>>, ApiError> {
+    let runs =
+        PipelineRun::find_with_steps_by_session_id(&deployment.db().pool, session.id).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+/// Start a run of a project pipeline with its first step
+pub async fn start_pipeline_run(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<StartPipelineRunRequest>,
+) -> Result<ResponseJson<ApiResponse<PipelineRun>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let pipeline = ProjectPipeline::find_by_id(pool, payload.pipeline_id)
+        .await?
+        .filter(|pipeline| pipeline.project_id == task.project_id)
+        .ok_or(ApiError::BadRequest(
+            "Pipeline does not belong to this project".to_string(),
+        ))?;
+    // Definitions are validated when saved; parse again for a clear error
+    PipelineDefinition::parse(&pipeline.definition)?;
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running processes of this workspace to finish".to_string(),
+        ));
+    }
+
+    let executor_profile_id = match payload.executor_profile_id {
+        Some(executor_profile_id) => executor_profile_id,
+        None => ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+            .await
+            .map_err(|_| {
+                ApiError::BadRequest(
+                    "No coding agent has run in this session yet; pass executor_profile_id"
+                        .to_string(),
+                )
+            })?,
+    };
+
+    // Only ensure container exists for worktree-mode workspaces
+    if !workspace.is_branch_only() {
+        deployment
+            .container()
+            .ensure_container_exists(&workspace)
+            .await?;
+    }
+
+    let run = deployment
+        .container()
+        .start_pipeline(&workspace, &session, &pipeline, &executor_profile_id)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pipeline_run_started",
+            serde_json::json!({
+                "session_id": session.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "pipeline_id": pipeline.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(run)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/", get(get_pipeline_runs).post(start_pipeline_run))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_session_middleware,
+        ))
+}
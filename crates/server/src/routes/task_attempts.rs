@@ -22,20 +22,22 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    conflict_resolution::ConflictResolution,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
-    project_repo::ProjectRepo,
+    project_repo::{ProjectRepo, ProjectRepoWithName},
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskRelationships, TaskStatus},
-    workspace::{CreateWorkspace, Workspace, WorkspaceError},
+    task_history::TaskHistory,
+    workspace::{Workspace, WorkspaceError},
     workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
 };
 use deployment::Deployment;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
-        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+        script::{ScriptContext, ScriptOptions, ScriptRequest},
     },
     executors::{CodingAgent, ExecutorError},
     profile::{ExecutorConfigs, ExecutorProfileId},
@@ -44,8 +46,9 @@ use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
+    forge::{ForgeRepoInfo, forge_for},
     git::{ConflictOp, GitCliError, GitServiceError},
-    github::GitHubService,
+    stack::RestackResult,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -53,7 +56,9 @@ use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{RequestActor, load_workspace_middleware},
     routes::task_attempts::gh_cli_setup::GhCliSetupError,
 };
 
@@ -69,6 +74,11 @@ pub struct AbortConflictsRequest {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub repo_id: Uuid,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -122,6 +132,11 @@ pub struct CreateTaskAttemptBody {
     /// Used for ORCHESTRATOR_MANAGED mode where the actual executor is not a BaseCodingAgent.
     #[serde(default)]
     pub executor_name: Option<String>,
+    /// Build on this workspace's branch instead of the target branches. Repos the
+    /// workspace also uses start from its branch and follow it until it merges.
+    #[serde(default)]
+    #[ts(optional)]
+    pub stacked_on_workspace_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
@@ -156,51 +171,71 @@ pub async fn create_task_attempt(
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
-    let project = task
-        .parent_project(pool)
-        .await?
-        .ok_or(SqlxError::RowNotFound)?;
-
-    let agent_working_dir = project
-        .default_agent_working_dir
-        .as_ref()
-        .filter(|dir| !dir.is_empty())
-        .cloned();
+    let stack_parent = match payload.stacked_on_workspace_id {
+        Some(parent_id) => {
+            let parent = Workspace::find_by_id(pool, parent_id)
+                .await?
+                .ok_or_else(|| ApiError::BadRequest("Workspace to stack on not found".into()))?;
+            let parent_project_id = parent.parent_task(pool).await?.map(|t| t.project_id);
+            if parent_project_id != Some(task.project_id) {
+                return Err(ApiError::BadRequest(
+                    "Can only stack on a workspace of the same project".to_string(),
+                ));
+            }
+            Some(parent)
+        }
+        None => None,
+    };
 
-    let attempt_id = Uuid::new_v4();
-    let git_branch_name = deployment
-        .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
-        .await;
+    let mut workspace_repos: Vec<CreateWorkspaceRepo> = Vec::with_capacity(payload.repos.len());
+    for r in &payload.repos {
+        let stacked_on = match &stack_parent {
+            Some(parent) => {
+                WorkspaceRepo::find_by_workspace_and_repo_id(pool, parent.id, r.repo_id)
+                    .await?
+                    .map(|_| parent)
+            }
+            None => None,
+        };
+        workspace_repos.push(match stacked_on {
+            // Start from the parent's branch and remember where it was
+            Some(parent) => {
+                let repo = Repo::find_by_id(pool, r.repo_id)
+                    .await?
+                    .ok_or(RepoError::NotFound)?;
+                CreateWorkspaceRepo {
+                    repo_id: r.repo_id,
+                    target_branch: parent.branch.clone(),
+                    stack_base_commit: Some(
+                        deployment
+                            .git()
+                            .get_branch_oid(&repo.path, &parent.branch)?,
+                    ),
+                }
+            }
+            None => CreateWorkspaceRepo {
+                repo_id: r.repo_id,
+                target_branch: r.target_branch.clone(),
+                stack_base_commit: None,
+            },
+        });
+    }
 
     // Convert mode to string for storage
     let workspace_mode_str = match payload.mode {
-        WorkspaceMode::Worktree => "worktree".to_string(),
-        WorkspaceMode::Branch => "branch".to_string(),
+        WorkspaceMode::Worktree => "worktree",
+        WorkspaceMode::Branch => "branch",
     };
 
-    let workspace = Workspace::create(
-        pool,
-        &CreateWorkspace {
-            branch: git_branch_name.clone(),
-            agent_working_dir,
-            workspace_mode: workspace_mode_str,
-        },
-        attempt_id,
-        payload.task_id,
-    )
-    .await?;
-
-    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
-        .repos
-        .iter()
-        .map(|r| CreateWorkspaceRepo {
-            repo_id: r.repo_id,
-            target_branch: r.target_branch.clone(),
-        })
-        .collect();
-
-    WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
+    let workspace = deployment
+        .container()
+        .create_workspace(
+            &task,
+            workspace_mode_str,
+            stack_parent.as_ref().map(|parent| parent.id),
+            &workspace_repos,
+        )
+        .await?;
 
     // Handle mode-specific behavior
     let workspace = match payload.mode {
@@ -220,7 +255,7 @@ pub async fn create_task_attempt(
         }
         WorkspaceMode::Branch => {
             // Branch-only mode: create git branches (not worktrees), set setup_completed_at immediately
-            for repo_input in &payload.repos {
+            for repo_input in &workspace_repos {
                 let repo = Repo::find_by_id(pool, repo_input.repo_id)
                     .await?
                     .ok_or(RepoError::NotFound)?;
@@ -229,12 +264,12 @@ pub async fn create_task_attempt(
                 // Use git branch command to create branch from target branch
                 if let Err(e) = deployment.git().create_branch(
                     &repo.path,
-                    &git_branch_name,
+                    &workspace.branch,
                     &repo_input.target_branch,
                 ) {
                     tracing::warn!(
                         "Failed to create branch '{}' in repo '{}': {}. Branch may already exist.",
-                        git_branch_name,
+                        workspace.branch,
                         repo.name,
                         e
                     );
@@ -277,6 +312,7 @@ pub async fn create_task_attempt(
                 "workspace_id": workspace.id.to_string(),
                 "repository_count": payload.repos.len(),
                 "mode": format!("{:?}", payload.mode).to_lowercase(),
+                "stacked": stack_parent.is_some(),
             }),
         )
         .await;
@@ -398,6 +434,7 @@ pub struct PushTaskAttemptRequest {
 pub async fn merge_task_attempt(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Json(request): Json<MergeTaskAttemptRequest>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let pool = &deployment.db().pool;
@@ -442,12 +479,20 @@ pub async fn merge_task_attempt(
         commit_message.push_str(description);
     }
 
-    let merge_commit_id = deployment.git().merge_changes(
+    let (merge_strategy, rebase_before_merge) =
+        ProjectRepo::find_by_project_and_repo(pool, task.project_id, repo.id)
+            .await?
+            .map(|pr| (pr.merge_strategy, pr.rebase_before_merge))
+            .unwrap_or_default();
+
+    let merge_commit_id = deployment.git().merge_changes_with_strategy(
         &repo.path,
         &worktree_path,
         &workspace.branch,
         &workspace_repo.target_branch,
         &commit_message,
+        merge_strategy,
+        rebase_before_merge,
     )?;
 
     Merge::create_direct(
@@ -456,9 +501,15 @@ pub async fn merge_task_attempt(
         workspace_repo.repo_id,
         &workspace_repo.target_branch,
         &merge_commit_id,
+        merge_strategy,
     )
     .await?;
-    Task::update_status(pool, task.id, TaskStatus::Done).await?;
+    Task::update_status_by_system(pool, task.id, TaskStatus::Done).await?;
+    TaskHistory::record_status_change(pool, task.id, &task.status, &TaskStatus::Done, &actor)
+        .await?;
+
+    // Workspaces stacked on this one move onto the target branch
+    deployment.container().restack_children(&workspace).await;
 
     // Stop any running dev servers for this workspace
     let dev_servers =
@@ -507,6 +558,7 @@ pub async fn merge_task_attempt(
             serde_json::json!({
                 "task_id": task.id.to_string(),
                 "workspace_id": workspace.id.to_string(),
+                "merge_strategy": merge_strategy.to_string(),
             }),
         )
         .await;
@@ -521,9 +573,6 @@ pub async fn push_task_attempt_branch(
 ) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
     let pool = &deployment.db().pool;
 
-    let github_service = GitHubService::new()?;
-    github_service.check_token().await?;
-
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -533,6 +582,9 @@ pub async fn push_task_attempt_branch(
         .await?
         .ok_or(RepoError::NotFound)?;
 
+    let repo_info = ForgeRepoInfo::for_repo(&repo, &deployment.git().get_remote_url(&repo.path)?)?;
+    forge_for(&repo_info)?.check_auth().await?;
+
     // Determine the path to push from based on workspace mode
     // Use explicit workspace_mode field rather than container_ref check
     let push_path = if workspace.is_branch_only() {
@@ -567,9 +619,6 @@ pub async fn force_push_task_attempt_branch(
 ) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
     let pool = &deployment.db().pool;
 
-    let github_service = GitHubService::new()?;
-    github_service.check_token().await?;
-
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -579,6 +628,9 @@ pub async fn force_push_task_attempt_branch(
         .await?
         .ok_or(RepoError::NotFound)?;
 
+    let repo_info = ForgeRepoInfo::for_repo(&repo, &deployment.git().get_remote_url(&repo.path)?)?;
+    forge_for(&repo_info)?.check_auth().await?;
+
     // Determine the path to push from based on workspace mode
     // Use explicit workspace_mode field rather than container_ref check
     let push_path = if workspace.is_branch_only() {
@@ -1216,6 +1268,8 @@ pub async fn rebase_task_attempt(
         };
     }
 
+    deployment.container().restack_children(&workspace).await;
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_rebased",
@@ -1229,6 +1283,40 @@ pub async fn rebase_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+pub async fn restack_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<RestackResult>>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if workspace.stacked_on_workspace_id.is_none() {
+        return Err(ApiError::BadRequest(
+            "This attempt is not stacked on another workspace".to_string(),
+        ));
+    }
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running processes of this workspace to finish".to_string(),
+        ));
+    }
+
+    let results = deployment.container().restack_workspace(&workspace).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_restacked",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repository_count": results.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(results)))
+}
+
 #[axum::debug_handler]
 pub async fn abort_conflicts_task_attempt(
     Extension(workspace): Extension<Workspace>,
@@ -1260,6 +1348,90 @@ pub async fn abort_conflicts_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+#[axum::debug_handler]
+pub async fn resolve_conflicts_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<ConflictResolution>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let worktree_path = if workspace.is_branch_only() {
+        Path::new(&repo.path).to_path_buf()
+    } else {
+        let container_ref = deployment
+            .container()
+            .ensure_container_exists(&workspace)
+            .await?;
+        Path::new(&container_ref).join(&repo.name)
+    };
+    let op = deployment.git().detect_conflict_op(&worktree_path)?;
+    let conflicted_files = deployment.git().get_conflicted_files(&worktree_path)?;
+    if op.is_none() || conflicted_files.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "No conflicts to resolve in {}",
+            repo.name
+        )));
+    }
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running processes of this workspace to finish".to_string(),
+        ));
+    }
+
+    let session = Session::find_latest_by_workspace_id(pool, workspace.id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest("The attempt has no session to resolve conflicts in".to_string())
+        })?;
+    // The container may have been recreated above
+    let workspace = Workspace::find_by_id(pool, workspace.id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let (execution_process, conflicted_files) = deployment
+        .container()
+        .start_conflict_resolution_turn(&workspace, &session, &repo)
+        .await?;
+    let resolution = ConflictResolution::create(
+        pool,
+        workspace.id,
+        repo.id,
+        execution_process.id,
+        &conflicted_files,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_conflicts_resolve_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+                "conflicted_files": conflicted_files.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(resolution)))
+}
+
+pub async fn get_conflict_resolutions(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ConflictResolution>>>, ApiError> {
+    let resolutions =
+        ConflictResolution::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(resolutions)))
+}
+
 #[axum::debug_handler]
 pub async fn start_dev_server(
     Extension(workspace): Extension<Workspace>,
@@ -1327,12 +1499,15 @@ pub async fn start_dev_server(
         .filter(|dir| !dir.is_empty())
         .cloned();
 
+    let project_repos = ProjectRepo::find_by_project_id_with_names(pool, project.id).await?;
+    let options = ProjectRepoWithName::dev_script_options(&project_repos, working_dir.as_deref());
+
     let executor_action = ExecutorAction::new(
         ExecutorActionType::ScriptRequest(ScriptRequest {
             script: dev_script,
-            language: ScriptRequestLanguage::Bash,
             context: ScriptContext::DevServer,
             working_dir,
+            options,
         }),
         None,
     );
@@ -1679,10 +1854,15 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/push", post(push_task_attempt_branch))
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
+        .route("/restack", post(restack_task_attempt))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
+        .route("/conflicts/resolve", post(resolve_conflicts_task_attempt))
+        .route("/conflicts/resolutions", get(get_conflict_resolutions))
         .route("/pr", post(pr::create_github_pr))
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
+        .route("/pr/comments/address", post(pr::address_pr_comments))
+        .route("/pr/review-follow-ups", get(pr::get_review_follow_ups))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/children", get(get_task_attempt_children))
         .route("/stop", post(stop_task_attempt_execution))
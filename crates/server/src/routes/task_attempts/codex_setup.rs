@@ -7,7 +7,7 @@ use deployment::Deployment;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
-        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+        script::{ScriptContext, ScriptOptions, ScriptRequest},
     },
     command::{CommandBuilder, apply_overrides},
     executors::{ExecutorError, codex::Codex},
@@ -94,9 +94,9 @@ async fn get_setup_helper_action(codex: &Codex) -> Result<ExecutorAction, ApiErr
     let login_script = format!("{} {}", program_path.to_string_lossy(), args.join(" "));
     let login_request = ScriptRequest {
         script: login_script,
-        language: ScriptRequestLanguage::Bash,
         context: ScriptContext::ToolInstallScript,
         working_dir: None,
+        options: ScriptOptions::default(),
     };
 
     Ok(ExecutorAction::new(
@@ -9,7 +9,7 @@ use executors::actions::ExecutorAction;
 use executors::{
     actions::{
         ExecutorActionType,
-        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+        script::{ScriptContext, ScriptOptions, ScriptRequest},
     },
     executors::cursor::CursorAgent,
 };
@@ -118,9 +118,9 @@ fi"#
 
         let install_request = ScriptRequest {
             script: install_script,
-            language: ScriptRequestLanguage::Bash,
             context: ScriptContext::ToolInstallScript,
             working_dir: None,
+            options: ScriptOptions::default(),
         };
         // Second action (chained): Login
         let login_script = format!(
@@ -132,9 +132,9 @@ export PATH="$HOME/.local/bin:$PATH"
         );
         let login_request = ScriptRequest {
             script: login_script,
-            language: ScriptRequestLanguage::Bash,
             context: ScriptContext::ToolInstallScript,
             working_dir: None,
+            options: ScriptOptions::default(),
         };
 
         // Chain them: install → login
@@ -9,7 +9,7 @@ use executors::actions::ExecutorAction;
 use executors::{
     actions::{
         ExecutorActionType,
-        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+        script::{ScriptContext, ScriptOptions, ScriptRequest},
     },
     executors::ExecutorError,
 };
@@ -100,9 +100,9 @@ fi"#
 
         let install_request = ScriptRequest {
             script: install_script,
-            language: ScriptRequestLanguage::Bash,
             context: ScriptContext::ToolInstallScript,
             working_dir: None,
+            options: ScriptOptions::default(),
         };
 
         // Auth script
@@ -115,9 +115,9 @@ gh auth login --web --git-protocol https --skip-ssh-key
 
         let auth_request = ScriptRequest {
             script: auth_script,
-            language: ScriptRequestLanguage::Bash,
             context: ScriptContext::ToolInstallScript,
             working_dir: None,
+            options: ScriptOptions::default(),
         };
 
         // Chain them: install → auth
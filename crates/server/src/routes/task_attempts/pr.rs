@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{collections::HashSet, path::PathBuf};
 
 use axum::{
     Extension, Json,
@@ -7,10 +7,12 @@ use axum::{
 };
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
-    merge::{Merge, MergeStatus},
+    merge::{ForgeKind, Merge, MergeStatus, PullRequestInfo},
     repo::{Repo, RepoError},
+    review_follow_up::{ReviewFollowUp, ReviewFollowUpComment},
     session::{CreateSession, Session},
     task::{Task, TaskStatus},
+    task_history::TaskHistory,
     workspace::{Workspace, WorkspaceError},
     workspace_repo::WorkspaceRepo,
 };
@@ -23,14 +25,16 @@ use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
+    forge::{ForgeError, ForgeRepoInfo, forge_for},
     git::{GitCliError, GitServiceError},
-    github::{CreatePrRequest, GitHubService, GitHubServiceError, UnifiedPrComment},
+    github::{CreatePrRequest, GitHubServiceError, UnifiedPrComment},
+    review_follow_up,
 };
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::RequestActor};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct CreateGitHubPrRequest {
@@ -52,6 +56,7 @@ pub enum CreatePrError {
     GitCliNotLoggedIn,
     GitCliNotInstalled,
     TargetBranchNotFound { branch: String },
+    ForgeNotLoggedIn { forge: ForgeKind, message: String },
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -79,6 +84,7 @@ pub enum GetPrCommentsError {
     NoPrAttached,
     GithubCliNotInstalled,
     GithubCliNotLoggedIn,
+    ForgeNotLoggedIn { forge: ForgeKind, message: String },
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -86,6 +92,34 @@ pub struct GetPrCommentsQuery {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct AddressPrCommentsRequest {
+    pub repo_id: Uuid,
+    /// Answer every addressed comment with the pushed commit
+    #[serde(default)]
+    #[ts(optional)]
+    pub reply_to_comments: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum AddressPrCommentsError {
+    NoPrAttached,
+    NoPendingComments,
+    GithubCliNotInstalled,
+    GithubCliNotLoggedIn,
+    ForgeNotLoggedIn { forge: ForgeKind, message: String },
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ReviewFollowUpWithComments {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub follow_up: ReviewFollowUp,
+    pub comments: Vec<ReviewFollowUpComment>,
+}
+
 pub const DEFAULT_PR_DESCRIPTION_PROMPT: &str = r#"Update the GitHub PR that was just created with a better title and description.
 The PR number is #{pr_number} and the URL is {pr_url}.
 
@@ -102,8 +136,7 @@ Use `gh pr edit` to update the PR."#;
 async fn trigger_pr_description_follow_up(
     deployment: &DeploymentImpl,
     workspace: &Workspace,
-    pr_number: i64,
-    pr_url: &str,
+    pr_info: &PullRequestInfo,
 ) -> Result<(), ApiError> {
     // Get the custom prompt from config, or use default
     let config = deployment.config().read().await;
@@ -113,9 +146,15 @@ async fn trigger_pr_description_follow_up(
         .unwrap_or(DEFAULT_PR_DESCRIPTION_PROMPT);
 
     // Replace placeholders in prompt
-    let prompt = prompt_template
-        .replace("{pr_number}", &pr_number.to_string())
-        .replace("{pr_url}", pr_url);
+    let mut prompt = prompt_template
+        .replace("{pr_number}", &pr_info.number.to_string())
+        .replace("{pr_url}", &pr_info.url);
+    if pr_info.forge != ForgeKind::GitHub {
+        prompt.push_str(&format!(
+            "\n\nThe repository is hosted on {forge}, not GitHub. Update it through the {forge} API or CLI instead of `gh`.",
+            forge = pr_info.forge.display_name()
+        ));
+    }
 
     drop(config); // Release the lock before async operations
 
@@ -200,6 +239,7 @@ pub async fn create_github_pr(
         .ok_or(RepoError::NotFound)?;
 
     let repo_path = repo.path.clone();
+    let repo_info = ForgeRepoInfo::for_repo(&repo, &deployment.git().get_remote_url(&repo_path)?)?;
     let target_branch = if let Some(branch) = request.target_branch {
         branch
     } else {
@@ -217,9 +257,21 @@ pub async fn create_github_pr(
             .ensure_container_exists(&workspace)
             .await?;
         let workspace_path = PathBuf::from(&container_ref);
-        workspace_path.join(repo.name)
+        workspace_path.join(&repo.name)
     };
 
+    // A stacked attempt targets its parent's branch until the parent lands, so that
+    // branch has to be on the remote as well
+    if let Some(parent_id) = workspace.stacked_on_workspace_id
+        && let Some(parent) = Workspace::find_by_id(pool, parent_id).await?
+        && parent.branch == target_branch
+        && let Err(e) = deployment
+            .git()
+            .push_to_github(&repo_path, &parent.branch, false)
+    {
+        tracing::warn!("Failed to push parent branch '{}': {}", parent.branch, e);
+    }
+
     match deployment
         .git()
         .check_remote_branch_exists(&repo_path, &target_branch)
@@ -245,12 +297,12 @@ pub async fn create_github_pr(
         Ok(true) => {}
     }
 
-    // Push the branch to GitHub first
+    // Push the branch to the remote first
     if let Err(e) = deployment
         .git()
         .push_to_github(&worktree_path, &workspace.branch, false)
     {
-        tracing::error!("Failed to push branch to GitHub: {}", e);
+        tracing::error!("Failed to push branch to remote: {}", e);
         match e {
             GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => {
                 return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -285,7 +337,7 @@ pub async fn create_github_pr(
     } else {
         target_branch
     };
-    // Create the PR using GitHub service
+    // Create the PR on the repo's forge
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
         body: request.body.clone(),
@@ -293,12 +345,13 @@ pub async fn create_github_pr(
         base_branch: norm_target_branch_name.clone(),
         draft: request.draft,
     };
-    // Use GitService to get the remote URL, then create GitHubRepoInfo
-    let repo_info = deployment.git().get_github_repo_info(&repo_path)?;
-
-    // Use GitHubService to create the PR
-    let github_service = GitHubService::new()?;
-    match github_service.create_pr(&repo_info, &pr_request).await {
+    let result = async {
+        forge_for(&repo_info)?
+            .create_pr(&repo_info, &pr_request)
+            .await
+    }
+    .await;
+    match result {
         Ok(pr_info) => {
             // Update the workspace with PR information
             if let Err(e) = Merge::create_pr(
@@ -306,8 +359,7 @@ pub async fn create_github_pr(
                 workspace.id,
                 workspace_repo.repo_id,
                 &norm_target_branch_name,
-                pr_info.number,
-                &pr_info.url,
+                &pr_info,
             )
             .await
             {
@@ -323,19 +375,15 @@ pub async fn create_github_pr(
                     "github_pr_created",
                     serde_json::json!({
                         "workspace_id": workspace.id.to_string(),
+                        "forge": pr_info.forge,
                     }),
                 )
                 .await;
 
             // Trigger auto-description follow-up if enabled
             if request.auto_generate_description
-                && let Err(e) = trigger_pr_description_follow_up(
-                    &deployment,
-                    &workspace,
-                    pr_info.number,
-                    &pr_info.url,
-                )
-                .await
+                && let Err(e) =
+                    trigger_pr_description_follow_up(&deployment, &workspace, &pr_info).await
             {
                 tracing::warn!(
                     "Failed to trigger PR description follow-up for attempt {}: {}",
@@ -348,18 +396,25 @@ pub async fn create_github_pr(
         }
         Err(e) => {
             tracing::error!(
-                "Failed to create GitHub PR for attempt {}: {}",
+                "Failed to create {} PR for attempt {}: {}",
+                repo_info.forge.display_name(),
                 workspace.id,
                 e
             );
             match &e {
-                GitHubServiceError::GhCliNotInstalled(_) => Ok(ResponseJson(
+                ForgeError::GitHub(GitHubServiceError::GhCliNotInstalled(_)) => Ok(ResponseJson(
                     ApiResponse::error_with_data(CreatePrError::GithubCliNotInstalled),
                 )),
-                GitHubServiceError::AuthFailed(_) => Ok(ResponseJson(
+                ForgeError::GitHub(GitHubServiceError::AuthFailed(_)) => Ok(ResponseJson(
                     ApiResponse::error_with_data(CreatePrError::GithubCliNotLoggedIn),
                 )),
-                _ => Err(ApiError::GitHubService(e)),
+                _ if e.is_auth_error() => Ok(ResponseJson(ApiResponse::error_with_data(
+                    CreatePrError::ForgeNotLoggedIn {
+                        forge: repo_info.forge,
+                        message: e.to_string(),
+                    },
+                ))),
+                _ => Err(ApiError::Forge(e)),
             }
         }
     }
@@ -368,6 +423,7 @@ pub async fn create_github_pr(
 pub async fn attach_existing_pr(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Json(request): Json<AttachExistingPrRequest>,
 ) -> Result<ResponseJson<ApiResponse<AttachPrResponse>>, ApiError> {
     let pool = &deployment.db().pool;
@@ -397,12 +453,11 @@ pub async fn attach_existing_pr(
         })));
     }
 
-    let github_service = GitHubService::new()?;
-    let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
+    let repo_info = ForgeRepoInfo::for_repo(&repo, &deployment.git().get_remote_url(&repo.path)?)?;
 
     // List all PRs for branch (open, closed, and merged)
-    let prs = github_service
-        .list_all_prs_for_branch(&repo_info, &workspace.branch)
+    let prs = forge_for(&repo_info)?
+        .list_prs_for_branch(&repo_info, &workspace.branch)
         .await?;
 
     // Take the first PR (prefer open, but also accept merged/closed)
@@ -413,8 +468,7 @@ pub async fn attach_existing_pr(
             workspace.id,
             workspace_repo.repo_id,
             &workspace_repo.target_branch,
-            pr_info.number,
-            &pr_info.url,
+            &pr_info,
         )
         .await?;
 
@@ -431,7 +485,15 @@ pub async fn attach_existing_pr(
 
         // If PR is merged, mark task as done
         if matches!(pr_info.status, MergeStatus::Merged) {
-            Task::update_status(pool, task.id, TaskStatus::Done).await?;
+            Task::update_status_by_system(pool, task.id, TaskStatus::Done).await?;
+            TaskHistory::record_status_change(
+                pool,
+                task.id,
+                &task.status,
+                &TaskStatus::Done,
+                &actor,
+            )
+            .await?;
 
             // Try broadcast update to other users in organization
             if let Ok(publisher) = deployment.share_publisher() {
@@ -496,14 +558,21 @@ pub async fn get_pr_comments(
         }
     };
 
-    let github_service = GitHubService::new()?;
-    let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
-
-    // Fetch comments from GitHub
-    match github_service
-        .get_pr_comments(&repo_info, pr_info.number)
-        .await
-    {
+    // PRs recorded before other forges were supported are on GitHub
+    let repo_info = ForgeRepoInfo::from_url(
+        &deployment.git().get_remote_url(&repo.path)?,
+        Some(pr_info.forge),
+        repo.forge_url.as_deref(),
+    )?;
+
+    // Fetch comments from the forge
+    let result = async {
+        forge_for(&repo_info)?
+            .get_pr_comments(&repo_info, pr_info.number)
+            .await
+    }
+    .await;
+    match result {
         Ok(comments) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
             comments,
         }))),
@@ -515,14 +584,170 @@ pub async fn get_pr_comments(
                 e
             );
             match &e {
-                GitHubServiceError::GhCliNotInstalled(_) => Ok(ResponseJson(
+                ForgeError::GitHub(GitHubServiceError::GhCliNotInstalled(_)) => Ok(ResponseJson(
                     ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotInstalled),
                 )),
-                GitHubServiceError::AuthFailed(_) => Ok(ResponseJson(
+                ForgeError::GitHub(GitHubServiceError::AuthFailed(_)) => Ok(ResponseJson(
                     ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotLoggedIn),
                 )),
-                _ => Err(ApiError::GitHubService(e)),
+                _ if e.is_auth_error() => Ok(ResponseJson(ApiResponse::error_with_data(
+                    GetPrCommentsError::ForgeNotLoggedIn {
+                        forge: repo_info.forge,
+                        message: e.to_string(),
+                    },
+                ))),
+                _ => Err(ApiError::Forge(e)),
             }
         }
     }
 }
+
+/// Start an agent turn addressing the review comments of the attempt's pull request
+/// that are still pending: written after the last push, unresolved and not handled
+/// by an earlier follow-up. The turn's commit is pushed once it ends.
+pub async fn address_pr_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<AddressPrCommentsRequest>,
+) -> Result<ResponseJson<ApiResponse<ReviewFollowUp, AddressPrCommentsError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id).await?;
+    let pr_info = match merges.into_iter().next() {
+        Some(Merge::Pr(pr_merge)) => pr_merge.pr_info,
+        _ => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                AddressPrCommentsError::NoPrAttached,
+            )));
+        }
+    };
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running processes of this workspace to finish".to_string(),
+        ));
+    }
+
+    let repo_info = ForgeRepoInfo::from_url(
+        &deployment.git().get_remote_url(&repo.path)?,
+        Some(pr_info.forge),
+        repo.forge_url.as_deref(),
+    )?;
+    let result = async {
+        let forge = forge_for(&repo_info)?;
+        let comments = forge.get_pr_comments(&repo_info, pr_info.number).await?;
+        let resolved_ids = forge
+            .get_resolved_comment_ids(&repo_info, pr_info.number)
+            .await?;
+        Ok::<_, ForgeError>((comments, resolved_ids))
+    }
+    .await;
+    let (comments, resolved_ids) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch PR comments for attempt {}, PR #{}: {}",
+                workspace.id,
+                pr_info.number,
+                e
+            );
+            let error = match &e {
+                ForgeError::GitHub(GitHubServiceError::GhCliNotInstalled(_)) => {
+                    AddressPrCommentsError::GithubCliNotInstalled
+                }
+                ForgeError::GitHub(GitHubServiceError::AuthFailed(_)) => {
+                    AddressPrCommentsError::GithubCliNotLoggedIn
+                }
+                _ if e.is_auth_error() => AddressPrCommentsError::ForgeNotLoggedIn {
+                    forge: repo_info.forge,
+                    message: e.to_string(),
+                },
+                _ => return Err(ApiError::Forge(e)),
+            };
+            return Ok(ResponseJson(ApiResponse::error_with_data(error)));
+        }
+    };
+
+    // The tracking branch lives in the main repository, shared by all worktrees
+    let last_push = deployment
+        .git()
+        .last_push_time(&repo.path, &workspace.branch)
+        .unwrap_or_else(|e| {
+            tracing::warn!(
+                "Failed to read the last push of {}: {}",
+                workspace.branch,
+                e
+            );
+            None
+        });
+    let last_follow_up =
+        ReviewFollowUp::find_latest_pushed(pool, workspace.id, repo.id, pr_info.number).await?;
+    let addressed: HashSet<_> =
+        ReviewFollowUp::find_addressed_comment_ids(pool, workspace.id, repo.id, pr_info.number)
+            .await?
+            .into_iter()
+            .collect();
+    let pending = review_follow_up::pending_comments(
+        comments,
+        review_follow_up::comment_cutoff(last_push, last_follow_up.as_ref()),
+        &resolved_ids,
+        &addressed,
+    );
+    if pending.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            AddressPrCommentsError::NoPendingComments,
+        )));
+    }
+
+    let follow_up = deployment
+        .container()
+        .start_review_follow_up(
+            &workspace,
+            &repo,
+            &pr_info,
+            &pending,
+            request.reply_to_comments,
+        )
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_comments_addressed",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "forge": pr_info.forge.display_name(),
+                "comment_count": pending.len(),
+                "reply_to_comments": request.reply_to_comments,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(follow_up)))
+}
+
+/// Review follow-ups of the attempt, newest first, with the comments each addressed
+pub async fn get_review_follow_ups(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ReviewFollowUpWithComments>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut follow_ups = Vec::new();
+    for follow_up in ReviewFollowUp::find_by_workspace_id(pool, workspace.id).await? {
+        let comments =
+            ReviewFollowUpComment::find_by_review_follow_up_id(pool, follow_up.id).await?;
+        follow_ups.push(ReviewFollowUpWithComments {
+            follow_up,
+            comments,
+        });
+    }
+    Ok(ResponseJson(ApiResponse::success(follow_ups)))
+}
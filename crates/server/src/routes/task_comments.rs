@@ -1,15 +1,38 @@
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Json as ResponseJson,
-    routing::get,
+    routing::{get, post, put},
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    task::Task,
+    task_comment::{
+        CreateTaskComment, MentionType, TaskComment, TaskCommentEdit, UpdateTaskComment,
+        parse_mentions, resolve_mentions,
+    },
 };
-use db::models::task_comment::{CreateTaskComment, TaskComment};
 use deployment::Deployment;
+use serde::Deserialize;
+use services::services::container::ContainerService;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::RequestActor};
+
+/// Query parameters for fetching unread comments
+#[derive(Debug, Deserialize)]
+pub struct UnreadCommentsQuery {
+    pub reader: String,
+}
+
+/// Request body for moving a reader's read marker forward
+#[derive(Debug, Deserialize)]
+pub struct MarkCommentsReadRequest {
+    pub reader: String,
+    /// Defaults to now
+    pub read_at: Option<DateTime<Utc>>,
+}
 
 pub async fn get_task_comments(
     State(deployment): State<DeploymentImpl>,
@@ -22,18 +45,175 @@ pub async fn get_task_comments(
 pub async fn create_task_comment(
     State(deployment): State<DeploymentImpl>,
     Path(task_id): Path<Uuid>,
+    RequestActor(actor): RequestActor,
     Json(mut payload): Json<CreateTaskComment>,
 ) -> Result<ResponseJson<ApiResponse<TaskComment>>, ApiError> {
+    let pool = &deployment.db().pool;
+
     // Ensure task_id in path matches payload
     payload.task_id = task_id;
 
-    let comment = TaskComment::create(&deployment.db().pool, &payload).await?;
+    if let Some(parent_comment_id) = payload.parent_comment_id {
+        let parent = TaskComment::find_by_id(pool, parent_comment_id).await?;
+        if parent.is_none_or(|parent| parent.task_id != task_id) {
+            return Err(ApiError::BadRequest(
+                "Parent comment does not belong to this task".to_string(),
+            ));
+        }
+    }
+
+    let comment = TaskComment::create(pool, &payload, &actor.actor_type).await?;
+
+    if let Some(task) = Task::find_by_id(pool, task_id).await? {
+        record_mentions(&deployment, &task, &comment).await?;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+/// Edit a comment. The previous content is kept in its edit history.
+/// PUT /api/tasks/{task_id}/comments/{comment_id}
+pub async fn update_task_comment(
+    State(deployment): State<DeploymentImpl>,
+    Path((task_id, comment_id)): Path<(Uuid, Uuid)>,
+    RequestActor(actor): RequestActor,
+    Json(payload): Json<UpdateTaskComment>,
+) -> Result<ResponseJson<ApiResponse<TaskComment>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if payload.content.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Comment content is required".to_string(),
+        ));
+    }
+    find_task_comment(&deployment, task_id, comment_id).await?;
+
+    let comment = TaskComment::update(pool, comment_id, &payload.content, &actor.name)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+    if let Some(task) = Task::find_by_id(pool, task_id).await? {
+        record_mentions(&deployment, &task, &comment).await?;
+    }
+
     Ok(ResponseJson(ApiResponse::success(comment)))
 }
 
+/// Delete a comment and its replies
+/// DELETE /api/tasks/{task_id}/comments/{comment_id}
+pub async fn delete_task_comment(
+    State(deployment): State<DeploymentImpl>,
+    Path((task_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    find_task_comment(&deployment, task_id, comment_id).await?;
+    TaskComment::delete(&deployment.db().pool, comment_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Previous versions of a comment, oldest first
+/// GET /api/tasks/{task_id}/comments/{comment_id}/edits
+pub async fn get_task_comment_edits(
+    State(deployment): State<DeploymentImpl>,
+    Path((task_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskCommentEdit>>>, ApiError> {
+    find_task_comment(&deployment, task_id, comment_id).await?;
+    let edits = TaskComment::find_edits(&deployment.db().pool, comment_id).await?;
+    Ok(ResponseJson(ApiResponse::success(edits)))
+}
+
+/// Comments that mention `reader` or reply to it, since it last marked the task as read
+/// GET /api/tasks/{task_id}/comments/unread?reader=
+pub async fn get_unread_task_comments(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+    Query(query): Query<UnreadCommentsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskComment>>>, ApiError> {
+    let reader = query.reader.trim();
+    if reader.is_empty() {
+        return Err(ApiError::BadRequest("reader is required".to_string()));
+    }
+    let comments = TaskComment::find_unread_for(&deployment.db().pool, task_id, reader).await?;
+    Ok(ResponseJson(ApiResponse::success(comments)))
+}
+
+/// Mark everything up to `read_at` as read for `reader`, returning that time
+/// POST /api/tasks/{task_id}/comments/read
+pub async fn mark_task_comments_read(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<MarkCommentsReadRequest>,
+) -> Result<ResponseJson<ApiResponse<DateTime<Utc>>>, ApiError> {
+    let reader = payload.reader.trim();
+    if reader.is_empty() {
+        return Err(ApiError::BadRequest("reader is required".to_string()));
+    }
+    let read_at = payload.read_at.unwrap_or_else(Utc::now);
+    TaskComment::mark_read(&deployment.db().pool, task_id, reader, read_at).await?;
+    Ok(ResponseJson(ApiResponse::success(read_at)))
+}
+
+async fn find_task_comment(
+    deployment: &DeploymentImpl,
+    task_id: Uuid,
+    comment_id: Uuid,
+) -> Result<TaskComment, ApiError> {
+    TaskComment::find_by_id(&deployment.db().pool, comment_id)
+        .await?
+        .filter(|comment| comment.task_id == task_id)
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))
+}
+
+/// Store the mentions in a comment and notify the people newly mentioned. Agents
+/// pick their mentions up through the MCP server, and the webhook for each new
+/// mention is queued when its row is stored.
+async fn record_mentions(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    comment: &TaskComment,
+) -> Result<(), ApiError> {
+    let resolved = resolve_mentions(task, &parse_mentions(&comment.content));
+    if resolved.is_empty() {
+        return Ok(());
+    }
+    let mentions = TaskComment::add_mentions(&deployment.db().pool, comment.id, &resolved).await?;
+    if mentions.is_empty() {
+        return Ok(());
+    }
+
+    let people: Vec<String> = mentions
+        .iter()
+        .filter(|mention| mention.mention_type == MentionType::User)
+        .map(|mention| format!("@{}", mention.mention))
+        .collect();
+    if !people.is_empty() {
+        let title = format!("Mentioned on: {}", task.title);
+        let message = format!(
+            "{} mentioned {}\n{}",
+            comment.author,
+            people.join(", "),
+            comment.content
+        );
+        deployment
+            .container()
+            .notification_service()
+            .notify(&title, &message)
+            .await;
+    }
+
+    Ok(())
+}
+
 pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new().nest(
         "/tasks/{task_id}/comments",
-        Router::new().route("/", get(get_task_comments).post(create_task_comment)),
+        Router::new()
+            .route("/", get(get_task_comments).post(create_task_comment))
+            .route("/unread", get(get_unread_task_comments))
+            .route("/read", post(mark_task_comments_read))
+            .route(
+                "/{comment_id}",
+                put(update_task_comment).delete(delete_task_comment),
+            )
+            .route("/{comment_id}/edits", get(get_task_comment_edits)),
     )
 }
@@ -1,16 +1,34 @@
 use axum::{
     Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Json as ResponseJson,
     routing::get,
 };
-use db::models::task_history::TaskHistory;
+use chrono::{DateTime, Utc};
+use db::models::task_history::{TaskActivity, TaskActivityFilters, TaskActorType, TaskHistory};
 use deployment::Deployment;
+use serde::Deserialize;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
 
+const DEFAULT_ACTIVITY_LIMIT: u32 = 50;
+const MAX_ACTIVITY_LIMIT: u32 = 500;
+
+/// Query parameters for the project activity feed
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    pub task_id: Option<Uuid>,
+    pub actor_type: Option<TaskActorType>,
+    pub changed_by: Option<String>,
+    pub field: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
 pub async fn get_task_history(
     State(deployment): State<DeploymentImpl>,
     Path(task_id): Path<Uuid>,
@@ -19,6 +37,42 @@ pub async fn get_task_history(
     Ok(ResponseJson(ApiResponse::success(history)))
 }
 
+/// Newest-first history of every task in a project
+/// GET /api/projects/{project_id}/activity
+pub async fn get_project_activity(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    Query(query): Query<ActivityQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskActivity>>>, ApiError> {
+    let filters = TaskActivityFilters {
+        task_id: query.task_id,
+        actor_type: query.actor_type,
+        changed_by: query.changed_by.filter(|s| !s.is_empty()),
+        field_changed: query.field.filter(|s| !s.is_empty()),
+        since: query.since,
+        until: query.until,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_ACTIVITY_LIMIT)
+        .min(MAX_ACTIVITY_LIMIT);
+
+    let activity = TaskHistory::find_by_project_id(
+        &deployment.db().pool,
+        project_id,
+        &filters,
+        limit,
+        query.offset.unwrap_or(0),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(activity)))
+}
+
 pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new().route("/tasks/{task_id}/history", get(get_task_history))
 }
+
+/// Build the project-scoped activity router (nested under /projects/{project_id})
+pub fn project_activity_router() -> Router<DeploymentImpl> {
+    Router::new().route("/activity", get(get_project_activity))
+}
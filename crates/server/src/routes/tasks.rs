@@ -1,10 +1,10 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use anyhow;
 use axum::{
     Extension, Json, Router,
     extract::{
-        Query, State,
+        Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
     http::StatusCode,
@@ -14,10 +14,11 @@ use axum::{
 };
 use db::models::{
     image::TaskImage,
-    project::{Project, ProjectError},
     repo::Repo,
-    task::{AgentMetadataEntry, CreateTask, Task, TaskRelationshipsSimple, TaskWithAttemptStatus, UpdateTask},
-    workspace::{CreateWorkspace, Workspace},
+    task::{AgentMetadataEntry, CreateTask, Task, TaskPriority, TaskRelationshipsSimple, TaskWithAttemptStatus, UpdateTask},
+    task_dependency::{CreateTaskDependency, TaskDependencies, TaskDependency},
+    task_history::{TaskActor, TaskHistory},
+    workspace::Workspace,
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
 use deployment::Deployment;
@@ -33,7 +34,9 @@ use utils::{api::oauth::LoginStatus, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{RequestActor, load_task_middleware},
     routes::task_attempts::WorkspaceRepoInput,
 };
 
@@ -47,6 +50,9 @@ pub struct TaskQueryAdvanced {
     pub project_id: Uuid,
     pub statuses: Option<Vec<String>>,
     pub assignee: Option<String>,
+    pub priorities: Option<Vec<String>>,
+    pub due_after: Option<String>,
+    pub due_before: Option<String>,
     pub created_after: Option<String>,
     pub created_before: Option<String>,
     pub updated_after: Option<String>,
@@ -61,6 +67,7 @@ pub struct TaskQueryAdvanced {
 pub struct TaskSearchQuery {
     pub project_id: Uuid,
     pub q: String,
+    pub priority: Option<TaskPriority>,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
 }
@@ -107,6 +114,58 @@ pub async fn get_tasks_advanced(
         None
     };
 
+    // Parse priorities
+    let priority_filters = if let Some(ref priority_strs) = query.priorities {
+        let mut parsed = Vec::new();
+        for s in priority_strs {
+            match TaskPriority::from_str(s) {
+                Ok(priority) => parsed.push(priority),
+                Err(_) => {
+                    return Err(ApiError::BadRequest(format!(
+                        "Invalid priority: '{}'. Valid values: 'low', 'medium', 'high', 'urgent'",
+                        s
+                    )));
+                }
+            }
+        }
+        if parsed.is_empty() {
+            None
+        } else {
+            Some(parsed)
+        }
+    } else {
+        None
+    };
+
+    // Parse due date filters
+    let due_after = if let Some(ref ts) = query.due_after {
+        match DateTime::parse_from_rfc3339(ts) {
+            Ok(dt) => Some(dt.with_timezone(&chrono::Utc)),
+            Err(_) => {
+                return Err(ApiError::BadRequest(format!(
+                    "Invalid due_after timestamp: '{}'. Use RFC3339 format",
+                    ts
+                )));
+            }
+        }
+    } else {
+        None
+    };
+
+    let due_before = if let Some(ref ts) = query.due_before {
+        match DateTime::parse_from_rfc3339(ts) {
+            Ok(dt) => Some(dt.with_timezone(&chrono::Utc)),
+            Err(_) => {
+                return Err(ApiError::BadRequest(format!(
+                    "Invalid due_before timestamp: '{}'. Use RFC3339 format",
+                    ts
+                )));
+            }
+        }
+    } else {
+        None
+    };
+
     // Parse date filters
     let created_after = if let Some(ref ts) = query.created_after {
         match DateTime::parse_from_rfc3339(ts) {
@@ -170,9 +229,12 @@ pub async fn get_tasks_advanced(
     let sort_by = query.sort_by.as_deref().unwrap_or("created_at");
     let sort_order = query.sort_order.as_deref().unwrap_or("desc");
 
-    if !matches!(sort_by, "created_at" | "updated_at" | "title") {
+    if !matches!(
+        sort_by,
+        "created_at" | "updated_at" | "title" | "priority" | "due_date" | "estimate_hours"
+    ) {
         return Err(ApiError::BadRequest(format!(
-            "Invalid sort_by: '{}'. Valid values: 'created_at', 'updated_at', 'title'",
+            "Invalid sort_by: '{}'. Valid values: 'created_at', 'updated_at', 'title', 'priority', 'due_date', 'estimate_hours'",
             sort_by
         )));
     }
@@ -189,6 +251,9 @@ pub async fn get_tasks_advanced(
         query.project_id,
         status_filters,
         query.assignee.as_deref(),
+        priority_filters,
+        due_after,
+        due_before,
         created_after,
         created_before,
         updated_after,
@@ -221,6 +286,7 @@ pub async fn search_tasks(
         &deployment.db().pool,
         query.project_id,
         search_query,
+        query.priority,
         limit,
         offset,
     )
@@ -285,9 +351,11 @@ pub async fn get_task(
 
 pub async fn create_task(
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Json(payload): Json<CreateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
     let id = Uuid::new_v4();
+    validate_estimate(payload.estimate_hours)?;
 
     tracing::debug!(
         "Creating task '{}' in project {}",
@@ -296,6 +364,7 @@ pub async fn create_task(
     );
 
     let task = Task::create(&deployment.db().pool, &payload, id).await?;
+    TaskHistory::record_created(&deployment.db().pool, &task, &actor).await?;
 
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
@@ -325,6 +394,7 @@ pub struct CreateAndStartTaskRequest {
 
 pub async fn create_task_and_start(
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Json(payload): Json<CreateAndStartTaskRequest>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
     if payload.repos.is_empty() {
@@ -332,11 +402,13 @@ pub async fn create_task_and_start(
             "At least one repository is required".to_string(),
         ));
     }
+    validate_estimate(payload.task.estimate_hours)?;
 
     let pool = &deployment.db().pool;
 
     let task_id = Uuid::new_v4();
     let task = Task::create(pool, &payload.task, task_id).await?;
+    TaskHistory::record_created(pool, &task, &actor).await?;
 
     if let Some(image_ids) = &payload.task.image_ids {
         TaskImage::associate_many_dedup(pool, task.id, image_ids).await?;
@@ -354,43 +426,19 @@ pub async fn create_task_and_start(
         )
         .await;
 
-    let project = Project::find_by_id(pool, task.project_id)
-        .await?
-        .ok_or(ProjectError::ProjectNotFound)?;
-
-    let attempt_id = Uuid::new_v4();
-    let git_branch_name = deployment
-        .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
-        .await;
-
-    let agent_working_dir = project
-        .default_agent_working_dir
-        .as_ref()
-        .filter(|dir: &&String| !dir.is_empty())
-        .cloned();
-
-    let workspace = Workspace::create(
-        pool,
-        &CreateWorkspace {
-            branch: git_branch_name,
-            agent_working_dir,
-            workspace_mode: "worktree".to_string(),
-        },
-        attempt_id,
-        task.id,
-    )
-    .await?;
-
     let workspace_repos: Vec<CreateWorkspaceRepo> = payload
         .repos
         .iter()
         .map(|r| CreateWorkspaceRepo {
             repo_id: r.repo_id,
             target_branch: r.target_branch.clone(),
+            stack_base_commit: None,
         })
         .collect();
-    WorkspaceRepo::create_many(&deployment.db().pool, workspace.id, &workspace_repos).await?;
+    let workspace = deployment
+        .container()
+        .create_workspace(&task, "worktree", None, &workspace_repos)
+        .await?;
 
     let is_attempt_running = deployment
         .container()
@@ -419,17 +467,22 @@ pub async fn create_task_and_start(
         task,
         has_in_progress_attempt: is_attempt_running,
         last_attempt_failed: false,
+        last_verification_status: None,
         executor: payload.executor_profile_id.executor.to_string(),
+        blocked: false,
     })))
 }
 
 pub async fn update_task(
     Extension(existing_task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
-
+    RequestActor(actor): RequestActor,
     Json(payload): Json<UpdateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
     ensure_shared_task_auth(&existing_task, &deployment).await?;
+    validate_estimate(payload.estimate_hours)?;
+
+    let previous_task = existing_task.clone();
 
     // Use existing values if not provided in update
     let title = payload.title.unwrap_or(existing_task.title);
@@ -438,11 +491,16 @@ pub async fn update_task(
         Some(s) => Some(s),                     // Non-empty string = update description
         None => existing_task.description,      // Field omitted = keep existing
     };
-    let status = payload.status.unwrap_or(existing_task.status);
+    let status = payload.status.unwrap_or(existing_task.status.clone());
     let parent_workspace_id = payload
         .parent_workspace_id
         .or(existing_task.parent_workspace_id);
 
+    // Status changes go through the project's workflow first
+    if status != existing_task.status {
+        Task::update_status(&deployment.db().pool, existing_task.id, status.clone()).await?;
+    }
+
     let mut task = Task::update(
         &deployment.db().pool,
         existing_task.id,
@@ -465,6 +523,38 @@ pub async fn update_task(
         task = Task::update_assignee(&deployment.db().pool, task.id, new_assignee).await?;
     }
 
+    // Handle planning fields separately. Omitted fields keep their value.
+    let clear_due_date = payload.clear_due_date.unwrap_or(false);
+    let clear_estimate = payload.clear_estimate.unwrap_or(false);
+    if payload.priority.is_some()
+        || payload.due_date.is_some()
+        || payload.estimate_hours.is_some()
+        || clear_due_date
+        || clear_estimate
+    {
+        let priority = payload.priority.clone().unwrap_or(task.priority.clone());
+        let due_date = if clear_due_date {
+            None
+        } else {
+            payload.due_date.or(task.due_date)
+        };
+        let estimate_hours = if clear_estimate {
+            None
+        } else {
+            payload.estimate_hours.or(task.estimate_hours)
+        };
+        task = Task::update_planning(
+            &deployment.db().pool,
+            task.id,
+            priority,
+            due_date,
+            estimate_hours,
+        )
+        .await?;
+    }
+
+    TaskHistory::record_changes(&deployment.db().pool, &previous_task, &task, &actor).await?;
+
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::delete_by_task_id(&deployment.db().pool, task.id).await?;
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
@@ -481,6 +571,15 @@ pub async fn update_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+fn validate_estimate(estimate_hours: Option<f64>) -> Result<(), ApiError> {
+    match estimate_hours {
+        Some(hours) if !hours.is_finite() || hours < 0.0 => Err(ApiError::BadRequest(
+            "estimate_hours must be a non-negative number".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
 async fn ensure_shared_task_auth(
     existing_task: &Task,
     deployment: &local_deployment::LocalDeployment,
@@ -499,6 +598,7 @@ async fn ensure_shared_task_auth(
 pub async fn delete_task(
     Extension(task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
 ) -> Result<(StatusCode, ResponseJson<ApiResponse<()>>), ApiError> {
     ensure_shared_task_auth(&task, &deployment).await?;
 
@@ -536,6 +636,12 @@ pub async fn delete_task(
         publisher.delete_shared_task(shared_task_id).await?;
     }
 
+    // Snapshot child tasks so their detachment shows up in their history
+    let mut children = Vec::new();
+    for attempt in &attempts {
+        children.extend(Task::find_children_by_workspace_id(pool, attempt.id).await?);
+    }
+
     // Use a transaction to ensure atomicity: either all operations succeed or all are rolled back
     let mut tx = pool.begin().await?;
 
@@ -558,6 +664,14 @@ pub async fn delete_task(
     // Commit the transaction - if this fails, all changes are rolled back
     tx.commit().await?;
 
+    for child in &children {
+        let detached = Task {
+            parent_workspace_id: None,
+            ..child.clone()
+        };
+        TaskHistory::record_changes(pool, child, &detached, &actor).await?;
+    }
+
     if total_children_affected > 0 {
         tracing::info!(
             "Nullified {} child task references before deleting task {}",
@@ -662,6 +776,7 @@ pub async fn share_task(
 
 pub async fn bulk_update_tasks(
     State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
     Json(payload): Json<BulkUpdateTasksRequest>,
 ) -> Result<ResponseJson<ApiResponse<BulkUpdateTasksResponse>>, ApiError> {
     use db::models::task::TaskStatus;
@@ -683,8 +798,22 @@ pub async fn bulk_update_tasks(
         }
     };
 
-    let updated_tasks =
-        Task::bulk_update_status(&deployment.db().pool, &payload.task_ids, status).await?;
+    let pool = &deployment.db().pool;
+    let mut previous_statuses = HashMap::new();
+    for task_id in &payload.task_ids {
+        if let Some(task) = Task::find_by_id(pool, *task_id).await? {
+            previous_statuses.insert(task.id, task.status);
+        }
+    }
+
+    let updated_tasks = Task::bulk_update_status(pool, &payload.task_ids, status).await?;
+
+    for task in &updated_tasks {
+        if let Some(previous_status) = previous_statuses.get(&task.id) {
+            TaskHistory::record_status_change(pool, task.id, previous_status, &task.status, &actor)
+                .await?;
+        }
+    }
 
     let count = updated_tasks.len();
 
@@ -714,6 +843,60 @@ pub async fn get_task_relationships(
     Ok(ResponseJson(ApiResponse::success(relationships)))
 }
 
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let dependencies =
+        TaskDependency::find_dependencies_for_task(&deployment.db().pool, task.id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+pub async fn add_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskDependency>,
+) -> Result<ResponseJson<ApiResponse<TaskDependency>>, ApiError> {
+    let dependency = TaskDependency::create(&deployment.db().pool, task.id, &payload).await?;
+
+    // The blocked flag lives outside the tasks table, so refresh the task stream manually
+    if let Err(e) = deployment.events().push_task_update(task.id).await {
+        tracing::warn!("Failed to push task update after adding dependency: {}", e);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_dependency_added",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "depends_on_task_id": payload.depends_on_task_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(dependency)))
+}
+
+pub async fn remove_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, depends_on_task_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected =
+        TaskDependency::delete(&deployment.db().pool, task.id, depends_on_task_id).await?;
+
+    if rows_affected == 0 {
+        return Err(ApiError::Database(SqlxError::RowNotFound));
+    }
+
+    if let Err(e) = deployment.events().push_task_update(task.id).await {
+        tracing::warn!("Failed to push task update after removing dependency: {}", e);
+    }
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AppendAgentMetadataRequest {
     pub agent_name: String,
@@ -743,6 +926,43 @@ pub async fn append_agent_metadata(
     );
 
     let updated_task = Task::append_agent_metadata(&deployment.db().pool, task.id, entry).await?;
+    TaskHistory::record_changes(
+        &deployment.db().pool,
+        &task,
+        &updated_task,
+        &TaskActor::agent(agent_name),
+    )
+    .await?;
+
+    Ok(ResponseJson(ApiResponse::success(updated_task)))
+}
+
+/// Request body for moving a task to a named state of its project's workflow
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct UpdateTaskWorkflowState {
+    pub state: String,
+}
+
+/// Move a task to a workflow state. Its status follows the state's category.
+/// PUT /api/tasks/{task_id}/workflow-state
+pub async fn update_task_workflow_state(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    RequestActor(actor): RequestActor,
+    Json(payload): Json<UpdateTaskWorkflowState>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    ensure_shared_task_auth(&task, &deployment).await?;
+    let pool = &deployment.db().pool;
+
+    let updated_task = Task::update_workflow_state(pool, task.id, &payload.state).await?;
+    TaskHistory::record_changes(pool, &task, &updated_task, &actor).await?;
+
+    if updated_task.shared_task_id.is_some() {
+        let Ok(publisher) = deployment.share_publisher() else {
+            return Err(ShareError::MissingConfig("share publisher unavailable").into());
+        };
+        publisher.update_shared_task(&updated_task).await?;
+    }
 
     Ok(ResponseJson(ApiResponse::success(updated_task)))
 }
@@ -774,6 +994,15 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", delete(delete_task))
         .route("/share", post(share_task))
         .route("/relationships", get(get_task_relationships))
+        .route("/workflow-state", put(update_task_workflow_state))
+        .route(
+            "/dependencies",
+            get(get_task_dependencies).post(add_task_dependency),
+        )
+        .route(
+            "/dependencies/{depends_on_task_id}",
+            delete(remove_task_dependency),
+        )
         .route("/agent-metadata", get(get_agent_metadata).post(append_agent_metadata));
 
     let task_id_router = Router::new()
@@ -0,0 +1,75 @@
+use axum::{
+    Extension, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::{
+    execution_process_usage::{ExecutionProcessUsage, ExecutorUsage, UsageTotals},
+    project::Project,
+    task::Task,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Token usage and estimated cost of a task or project
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct UsageReport {
+    pub totals: UsageTotals,
+    /// Usage per executor profile, most expensive first
+    pub by_executor: Vec<ExecutorUsage>,
+    /// Budget of the project the usage counts towards, if it has one
+    pub budget_usd: Option<f64>,
+}
+
+/// GET /api/tasks/{task_id}/usage
+pub async fn get_task_usage(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<UsageReport>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let totals = ExecutionProcessUsage::totals_for_task(pool, task_id).await?;
+    let by_executor = ExecutionProcessUsage::by_executor_for_task(pool, task_id).await?;
+    let budget_usd = match Task::find_by_id(pool, task_id).await? {
+        Some(task) => Project::find_by_id(pool, task.project_id)
+            .await?
+            .and_then(|project| project.usage_budget_usd),
+        None => None,
+    };
+
+    Ok(ResponseJson(ApiResponse::success(UsageReport {
+        totals,
+        by_executor,
+        budget_usd,
+    })))
+}
+
+/// GET /api/projects/{project_id}/usage
+pub async fn get_project_usage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UsageReport>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let totals = ExecutionProcessUsage::totals_for_project(pool, project.id).await?;
+    let by_executor = ExecutionProcessUsage::by_executor_for_project(pool, project.id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(UsageReport {
+        totals,
+        by_executor,
+        budget_usd: project.usage_budget_usd,
+    })))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/tasks/{task_id}/usage", get(get_task_usage))
+}
+
+/// Build the project-scoped usage router (nested under /projects/{project_id})
+pub fn project_usage_router() -> Router<DeploymentImpl> {
+    Router::new().route("/usage", get(get_project_usage))
+}
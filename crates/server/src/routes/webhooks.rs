@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use axum::{
     Extension, Json, Router,
     extract::{Path, Query, State},
@@ -7,11 +9,13 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
-    webhook::{CreateWebhook, UpdateWebhook, Webhook, WebhookEvent},
+    webhook::{CreateWebhook, UpdateWebhook, Webhook, WebhookEvent, WebhookFilters},
     webhook_delivery::WebhookDelivery,
+    webhook_history::{CreateWebhookHistory, WebhookHistory, WebhookHistoryAction},
 };
 use deployment::Deployment;
 use serde::{Deserialize, Serialize};
+use services::services::webhooks::WebhookService;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -27,15 +31,25 @@ pub struct CreateWebhookRequest {
     pub events: Vec<WebhookEvent>,
     /// Optional secret for signing payloads. Auto-generated if not provided.
     pub secret: Option<String>,
+    /// Optional MiniJinja template for the request body, e.g. for Slack-style endpoints
+    pub payload_template: Option<String>,
+    /// Extra headers sent with every delivery
+    pub headers: Option<HashMap<String, String>>,
+    /// Only deliver events matching these filters
+    pub filters: Option<WebhookFilters>,
 }
 
-/// Request body for updating a webhook
+/// Request body for updating a webhook. An empty template, header map or filter set
+/// clears the stored value.
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct UpdateWebhookRequest {
     pub url: Option<String>,
     pub secret: Option<String>,
     pub events: Option<Vec<WebhookEvent>>,
     pub is_active: Option<bool>,
+    pub payload_template: Option<String>,
+    pub headers: Option<HashMap<String, String>>,
+    pub filters: Option<WebhookFilters>,
 }
 
 /// Response containing webhook data with parsed events
@@ -47,6 +61,10 @@ pub struct WebhookResponse {
     pub secret: String,
     pub events: Vec<WebhookEvent>,
     pub is_active: bool,
+    pub payload_template: Option<String>,
+    pub headers: HashMap<String, String>,
+    pub filters: WebhookFilters,
+    pub consecutive_failures: i64,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -60,6 +78,10 @@ impl From<Webhook> for WebhookResponse {
             secret: webhook.secret.clone(),
             events: webhook.get_events(),
             is_active: webhook.is_active,
+            headers: webhook.get_headers(),
+            filters: webhook.get_filters(),
+            consecutive_failures: webhook.consecutive_failures,
+            payload_template: webhook.payload_template,
             created_at: webhook.created_at,
             updated_at: webhook.updated_at,
         }
@@ -79,6 +101,20 @@ pub struct DeliveryListQuery {
     pub offset: Option<u32>,
 }
 
+/// Request body for replaying failed deliveries. Bounds are inclusive and optional.
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ReplayDeliveriesRequest {
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Response for the replay endpoint
+#[derive(Debug, Serialize, TS)]
+pub struct ReplayDeliveriesResponse {
+    /// Number of failed deliveries queued for another attempt
+    pub requeued: u64,
+}
+
 /// Response for the test webhook endpoint
 #[derive(Debug, Serialize, TS)]
 pub struct TestWebhookResponse {
@@ -109,6 +145,22 @@ fn validate_webhook_url(url: &str) -> Result<(), ApiError> {
     }
 }
 
+/// Validates the optional payload template and custom headers
+fn validate_webhook_options(
+    payload_template: Option<&str>,
+    headers: Option<&HashMap<String, String>>,
+) -> Result<(), ApiError> {
+    if let Some(template) = payload_template {
+        WebhookService::validate_template(template)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid payload template: {}", e)))?;
+    }
+    if let Some(headers) = headers {
+        WebhookService::validate_headers(headers)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+    Ok(())
+}
+
 /// Generates a random secret for webhook signing
 fn generate_webhook_secret() -> String {
     // Generate a UUID and convert to hex string for the secret
@@ -132,6 +184,11 @@ pub async fn create_webhook(
         ));
     }
 
+    validate_webhook_options(
+        payload.payload_template.as_deref(),
+        payload.headers.as_ref(),
+    )?;
+
     // Generate secret if not provided
     let secret = payload.secret.unwrap_or_else(generate_webhook_secret);
 
@@ -140,6 +197,9 @@ pub async fn create_webhook(
         url: payload.url.trim().to_string(),
         secret,
         events: payload.events,
+        payload_template: payload.payload_template,
+        headers: payload.headers,
+        filters: payload.filters,
     };
 
     let webhook = Webhook::create(&deployment.db().pool, &create_data).await?;
@@ -196,17 +256,42 @@ pub async fn update_webhook(
         ));
     }
 
+    validate_webhook_options(
+        payload.payload_template.as_deref(),
+        payload.headers.as_ref(),
+    )?;
+
     let update_data = UpdateWebhook {
         url: payload.url.map(|u| u.trim().to_string()),
         secret: payload.secret,
         events: payload.events,
         is_active: payload.is_active,
+        payload_template: payload.payload_template,
+        headers: payload.headers,
+        filters: payload.filters,
     };
 
     let updated_webhook = Webhook::update(&deployment.db().pool, existing_webhook.id, &update_data)
         .await?
         .ok_or_else(|| ApiError::Database(sqlx::Error::RowNotFound))?;
 
+    if updated_webhook.is_active != existing_webhook.is_active {
+        let action = if updated_webhook.is_active {
+            WebhookHistoryAction::Enabled
+        } else {
+            WebhookHistoryAction::Disabled
+        };
+        WebhookHistory::create(
+            &deployment.db().pool,
+            &CreateWebhookHistory {
+                webhook_id: updated_webhook.id,
+                action,
+                reason: None,
+            },
+        )
+        .await?;
+    }
+
     tracing::info!("Updated webhook {}", existing_webhook.id);
 
     Ok(ResponseJson(ApiResponse::success(WebhookResponse::from(updated_webhook))))
@@ -262,6 +347,77 @@ pub async fn list_webhook_deliveries(
     Ok(ResponseJson(ApiResponse::success(deliveries)))
 }
 
+/// Send a single delivery again, regardless of its current status
+/// POST /api/webhooks/{webhook_id}/deliveries/{delivery_id}/redeliver
+pub async fn redeliver_webhook_delivery(
+    State(deployment): State<DeploymentImpl>,
+    Path((webhook_id, delivery_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<WebhookDelivery>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let webhook = Webhook::find_by_id(pool, webhook_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    if !webhook.is_active {
+        return Err(ApiError::BadRequest(
+            "Cannot redeliver to an inactive webhook. Please activate it first.".to_string(),
+        ));
+    }
+
+    // Only allow redelivering deliveries that belong to this webhook
+    match WebhookDelivery::find_by_id(pool, delivery_id).await? {
+        Some(delivery) if delivery.webhook_id == webhook.id => {}
+        _ => return Err(ApiError::Database(sqlx::Error::RowNotFound)),
+    }
+
+    let delivery = deployment
+        .webhooks()
+        .redeliver(&webhook, delivery_id)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(delivery)))
+}
+
+/// Requeue failed deliveries created within a time range
+/// POST /api/webhooks/{webhook_id}/deliveries/replay
+pub async fn replay_webhook_deliveries(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReplayDeliveriesRequest>,
+) -> Result<ResponseJson<ApiResponse<ReplayDeliveriesResponse>>, ApiError> {
+    if !webhook.is_active {
+        return Err(ApiError::BadRequest(
+            "Cannot replay deliveries for an inactive webhook. Please activate it first."
+                .to_string(),
+        ));
+    }
+    if let (Some(from), Some(to)) = (payload.from, payload.to)
+        && from > to
+    {
+        return Err(ApiError::BadRequest(
+            "`from` must not be later than `to`".to_string(),
+        ));
+    }
+
+    let requeued = deployment
+        .webhooks()
+        .replay_failed(webhook.id, payload.from, payload.to)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(
+        ReplayDeliveriesResponse { requeued },
+    )))
+}
+
+/// List activation history for a webhook, including automatic deactivations
+/// GET /api/webhooks/{webhook_id}/history
+pub async fn list_webhook_history(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookHistory>>>, ApiError> {
+    let history = WebhookHistory::find_by_webhook_id(&deployment.db().pool, webhook.id).await?;
+    Ok(ResponseJson(ApiResponse::success(history)))
+}
+
 /// Send a test webhook (placeholder implementation)
 /// POST /api/webhooks/{webhook_id}/test
 pub async fn test_webhook(
@@ -294,11 +450,17 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let webhook_id_router = Router::new()
         .route("/", get(get_webhook).put(update_webhook).delete(delete_webhook))
         .route("/deliveries", get(list_webhook_deliveries))
+        .route("/deliveries/replay", post(replay_webhook_deliveries))
+        .route("/history", get(list_webhook_history))
         .route("/test", post(test_webhook))
         .layer(from_fn_with_state(deployment.clone(), load_webhook_middleware));
 
     // Top-level webhook routes (for accessing webhooks by ID)
     let webhooks_router = Router::new()
+        .route(
+            "/{webhook_id}/deliveries/{delivery_id}/redeliver",
+            post(redeliver_webhook_delivery),
+        )
         .nest("/{webhook_id}", webhook_id_router);
 
     Router::new().nest("/webhooks", webhooks_router)
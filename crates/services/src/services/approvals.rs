@@ -1,4 +1,6 @@
 pub mod executor_approvals;
+pub mod policy;
+pub mod remote;
 
 use std::{collections::HashMap, sync::Arc, time::Duration as StdDuration};
 
@@ -6,6 +8,7 @@ use dashmap::DashMap;
 use db::models::{
     execution_process::ExecutionProcess,
     task::{Task, TaskStatus},
+    task_history::{TaskActor, TaskHistory},
 };
 use executors::{
     approvals::ToolCallMetadata,
@@ -25,12 +28,15 @@ use utils::{
 };
 use uuid::Uuid;
 
+use crate::services::approvals::remote::{
+    RemoteApprovalLinks, RemoteApprovalSigner, RemoteDecision,
+};
+
 #[derive(Debug)]
 struct PendingApproval {
     entry_index: usize,
     entry: NormalizedEntry,
-    execution_process_id: Uuid,
-    tool_name: String,
+    request: ApprovalRequest,
     response_tx: oneshot::Sender<ApprovalStatus>,
 }
 
@@ -47,6 +53,7 @@ pub struct Approvals {
     pending: Arc<DashMap<String, PendingApproval>>,
     completed: Arc<DashMap<String, ApprovalStatus>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+    remote: RemoteApprovalSigner,
 }
 
 #[derive(Debug, Error)]
@@ -66,14 +73,51 @@ pub enum ApprovalError {
 }
 
 impl Approvals {
-    pub fn new(msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
+    pub fn new(
+        msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+        remote: RemoteApprovalSigner,
+    ) -> Self {
         Self {
             pending: Arc::new(DashMap::new()),
             completed: Arc::new(DashMap::new()),
             msg_stores,
+            remote,
         }
     }
 
+    /// Requests waiting for an answer, oldest first
+    pub fn pending_requests(&self) -> Vec<ApprovalRequest> {
+        let mut requests: Vec<ApprovalRequest> = self
+            .pending
+            .iter()
+            .map(|pending| pending.request.clone())
+            .collect();
+        requests.sort_by_key(|request| request.created_at);
+        requests
+    }
+
+    pub fn pending_request(&self, id: &str) -> Option<ApprovalRequest> {
+        self.pending.get(id).map(|pending| pending.request.clone())
+    }
+
+    /// Signed approve and deny links for a request. None unless a public URL is
+    /// configured.
+    pub fn remote_links(&self, request: &ApprovalRequest) -> Option<RemoteApprovalLinks> {
+        let base_url = remote::public_url()?;
+        Some(self.remote.links(&base_url, request))
+    }
+
+    /// Whether a remote link was signed by this process and has not expired
+    pub fn verify_remote_link(
+        &self,
+        id: &str,
+        decision: RemoteDecision,
+        expires: i64,
+        signature: &str,
+    ) -> bool {
+        self.remote.verify(id, decision, expires, signature)
+    }
+
     pub async fn create_with_waiter(
         &self,
         request: ApprovalRequest,
@@ -104,8 +148,7 @@ impl Approvals {
                     PendingApproval {
                         entry_index: idx,
                         entry: matching_tool,
-                        execution_process_id: request.execution_process_id,
-                        tool_name: request.tool_name.clone(),
+                        request: request.clone(),
                         response_tx: tx,
                     },
                 );
@@ -144,7 +187,7 @@ impl Approvals {
             self.completed.insert(id.to_string(), req.status.clone());
             let _ = p.response_tx.send(req.status.clone());
 
-            if let Some(store) = self.msg_store_by_id(&p.execution_process_id).await {
+            if let Some(store) = self.msg_store_by_id(&p.request.execution_process_id).await {
                 let status = ToolStatus::from_approval_status(&req.status).ok_or(
                     ApprovalError::Custom(anyhow::anyhow!("Invalid approval status")),
                 )?;
@@ -157,13 +200,13 @@ impl Approvals {
             } else {
                 tracing::warn!(
                     "No msg_store found for execution_process_id: {}",
-                    p.execution_process_id
+                    p.request.execution_process_id
                 );
             }
 
             let tool_ctx = ToolContext {
-                tool_name: p.tool_name,
-                execution_process_id: p.execution_process_id,
+                tool_name: p.request.tool_name,
+                execution_process_id: p.request.execution_process_id,
             };
 
             // If approved or denied, and task is still InReview, move back to InProgress
@@ -173,12 +216,8 @@ impl Approvals {
             ) && let Ok(ctx) =
                 ExecutionProcess::load_context(pool, tool_ctx.execution_process_id).await
                 && ctx.task.status == TaskStatus::InReview
-                && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
             {
-                tracing::warn!(
-                    "Failed to update task status to InProgress after approval response: {}",
-                    e
-                );
+                set_task_status(pool, &ctx.task, TaskStatus::InProgress).await;
             }
 
             Ok((req.status, tool_ctx))
@@ -224,7 +263,8 @@ impl Approvals {
 
                 let store = {
                     let map = msg_stores.read().await;
-                    map.get(&pending_approval.execution_process_id).cloned()
+                    map.get(&pending_approval.request.execution_process_id)
+                        .cloned()
                 };
 
                 if let Some(store) = store {
@@ -245,7 +285,7 @@ impl Approvals {
                 } else {
                     tracing::warn!(
                         "No msg_store found for execution_process_id: {}",
-                        pending_approval.execution_process_id
+                        pending_approval.request.execution_process_id
                     );
                 }
             }
@@ -261,12 +301,31 @@ impl Approvals {
 pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_id: Uuid) {
     if let Ok(ctx) = ExecutionProcess::load_context(pool, execution_process_id).await
         && ctx.task.status == TaskStatus::InProgress
-        && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InReview).await
     {
+        set_task_status(pool, &ctx.task, TaskStatus::InReview).await;
+    }
+}
+
+/// Move a task between InProgress and InReview as approvals come and go
+async fn set_task_status(pool: &SqlitePool, task: &Task, status: TaskStatus) {
+    if let Err(e) = Task::update_status_by_system(pool, task.id, status.clone()).await {
         tracing::warn!(
-            "Failed to update task status to InReview for approval request: {}",
+            "Failed to update task status to {} for approval: {}",
+            status,
             e
         );
+        return;
+    }
+    if let Err(e) = TaskHistory::record_status_change(
+        pool,
+        task.id,
+        &task.status,
+        &status,
+        &TaskActor::system("approvals"),
+    )
+    .await
+    {
+        tracing::warn!("Failed to record history for task {}: {}", task.id, e);
     }
 }
 
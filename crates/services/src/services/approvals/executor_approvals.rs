@@ -1,18 +1,39 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use async_trait::async_trait;
-use db::{self, DBService};
+use db::{
+    self, DBService,
+    models::{
+        approval_decision::{
+            ApprovalDecider, ApprovalDecision, ApprovalDecisionStatus, CreateApprovalDecision,
+        },
+        approval_rule::{ApprovalRule, ApprovalRuleAction},
+        execution_process::{ExecutionContext, ExecutionProcess},
+        webhook::WebhookEvent,
+    },
+};
 use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
 use serde_json::Value;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::{
+        Approvals,
+        policy::{self, PolicyRequest},
+    },
+    notification::NotificationService,
+    webhooks::{
+        WebhookService,
+        payloads::{ApprovalRequestedPayload, ApprovalRespondedPayload},
+    },
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
     db: DBService,
     notification_service: NotificationService,
+    webhooks: WebhookService,
     execution_process_id: Uuid,
 }
 
@@ -21,15 +42,106 @@ impl ExecutorApprovalBridge {
         approvals: Approvals,
         db: DBService,
         notification_service: NotificationService,
+        webhooks: WebhookService,
         execution_process_id: Uuid,
     ) -> Arc<Self> {
         Arc::new(Self {
             approvals,
             db,
             notification_service,
+            webhooks,
             execution_process_id,
         })
     }
+
+    /// Decide the request with the project's approval rules. None when a person
+    /// has to decide.
+    async fn apply_policy(
+        &self,
+        ctx: &ExecutionContext,
+        tool_name: &str,
+        tool_input: &Value,
+        tool_call_id: &str,
+    ) -> Option<ApprovalStatus> {
+        let rules = ApprovalRule::find_by_project_id(&self.db.pool, ctx.project.id)
+            .await
+            .inspect_err(|e| tracing::warn!("Failed to load approval rules: {}", e))
+            .ok()?;
+        if rules.is_empty() {
+            return None;
+        }
+
+        let executor = ctx
+            .execution_process
+            .executor_action()
+            .ok()
+            .and_then(|action| action.base_executor());
+        let request = PolicyRequest::new(
+            executor,
+            tool_name,
+            tool_input,
+            ctx.workspace.container_ref.as_deref().map(Path::new),
+        );
+        let rule = policy::evaluate(&rules, &request)?;
+        let status = match rule.action {
+            ApprovalRuleAction::Allow => ApprovalStatus::Approved,
+            ApprovalRuleAction::Deny => ApprovalStatus::Denied {
+                reason: Some(match &rule.description {
+                    Some(description) => format!("Denied by approval rule: {description}"),
+                    None => "Denied by an approval rule of the project".to_string(),
+                }),
+            },
+            ApprovalRuleAction::Ask => return None,
+        };
+
+        tracing::info!(
+            "Approval rule {} decided tool '{}' for execution process {}: {:?}",
+            rule.id,
+            tool_name,
+            self.execution_process_id,
+            status
+        );
+        self.record_decision(
+            tool_call_id,
+            tool_name,
+            tool_input,
+            &status,
+            ApprovalDecider::Rule,
+            Some(rule.id),
+        )
+        .await;
+        Some(status)
+    }
+
+    async fn record_decision(
+        &self,
+        approval_id: &str,
+        tool_name: &str,
+        tool_input: &Value,
+        status: &ApprovalStatus,
+        decided_by: ApprovalDecider,
+        rule_id: Option<Uuid>,
+    ) {
+        let (status, reason) = match status {
+            ApprovalStatus::Approved => (ApprovalDecisionStatus::Approved, None),
+            ApprovalStatus::Denied { reason } => (ApprovalDecisionStatus::Denied, reason.clone()),
+            ApprovalStatus::TimedOut => (ApprovalDecisionStatus::TimedOut, None),
+            ApprovalStatus::Pending => return,
+        };
+        let data = CreateApprovalDecision {
+            approval_id: approval_id.to_string(),
+            execution_process_id: self.execution_process_id,
+            tool_name: tool_name.to_string(),
+            tool_input: tool_input.clone(),
+            status,
+            reason,
+            decided_by,
+            rule_id,
+        };
+        if let Err(e) = ApprovalDecision::create(&self.db.pool, &data).await {
+            tracing::error!("Failed to record approval decision: {}", e);
+        }
+    }
 }
 
 #[async_trait]
@@ -40,6 +152,19 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_input: Value,
         tool_call_id: &str,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
+        let ctx = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id)
+            .await
+            .inspect_err(|e| tracing::warn!("Failed to load context for approval request: {}", e))
+            .ok();
+
+        if let Some(ctx) = &ctx
+            && let Some(status) = self
+                .apply_policy(ctx, tool_name, &tool_input, tool_call_id)
+                .await
+        {
+            return Ok(status);
+        }
+
         super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
 
         let request = ApprovalRequest::from_create(
@@ -51,7 +176,7 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             self.execution_process_id,
         );
 
-        let (_, waiter) = self
+        let (request, waiter) = self
             .approvals
             .create_with_waiter(request)
             .await
@@ -65,6 +190,23 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             )
             .await;
 
+        if let Some(ctx) = &ctx
+            && let Err(e) = self
+                .webhooks
+                .trigger_payload(
+                    ctx.project.id,
+                    &WebhookEvent::ApprovalRequested,
+                    &ApprovalRequestedPayload::new(
+                        &request,
+                        ctx,
+                        self.approvals.remote_links(&request),
+                    ),
+                )
+                .await
+        {
+            tracing::error!("Failed to trigger ApprovalRequested webhook: {}", e);
+        }
+
         let status = waiter.clone().await;
 
         if matches!(status, ApprovalStatus::Pending) {
@@ -73,6 +215,33 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             ));
         }
 
+        let decided_by = match status {
+            ApprovalStatus::TimedOut => ApprovalDecider::Timeout,
+            _ => ApprovalDecider::User,
+        };
+        self.record_decision(
+            &request.id,
+            tool_name,
+            &request.tool_input,
+            &status,
+            decided_by,
+            None,
+        )
+        .await;
+
+        if let Some(ctx) = &ctx
+            && let Err(e) = self
+                .webhooks
+                .trigger_payload(
+                    ctx.project.id,
+                    &WebhookEvent::ApprovalResponded,
+                    &ApprovalRespondedPayload::new(&request, ctx, status.clone()),
+                )
+                .await
+        {
+            tracing::error!("Failed to trigger ApprovalResponded webhook: {}", e);
+        }
+
         Ok(status)
     }
 }
@@ -0,0 +1,220 @@
+//! Signed links for answering approval requests away from the local UI.
+//!
+//! Every pending request gets an approve and a deny link, signed with a secret kept
+//! in the asset directory and valid until the request times out. The links are
+//! sent with the `approval_requested` webhook, so chat integrations can offer them
+//! as buttons. A link works once: an answered request cannot be answered again.
+
+use std::{path::Path, sync::Arc};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+use utils::approvals::{ApprovalRequest, ApprovalStatus};
+use uuid::Uuid;
+
+use crate::services::webhooks::WebhookService;
+
+/// Base URL under which this server is reachable from other machines, e.g.
+/// `https://vk.example.com`. Remote links are only created when it is set.
+pub const PUBLIC_URL_ENV: &str = "VIBE_PUBLIC_URL";
+
+pub fn public_url() -> Option<String> {
+    std::env::var(PUBLIC_URL_ENV)
+        .ok()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum RemoteDecision {
+    Approve,
+    Deny,
+}
+
+impl RemoteDecision {
+    pub fn status(self) -> ApprovalStatus {
+        match self {
+            Self::Approve => ApprovalStatus::Approved,
+            Self::Deny => ApprovalStatus::Denied {
+                reason: Some("Denied through a remote approval link".to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoteApprovalLinks {
+    pub approve_url: String,
+    pub deny_url: String,
+}
+
+#[derive(Clone)]
+pub struct RemoteApprovalSigner {
+    secret: Arc<str>,
+}
+
+impl Default for RemoteApprovalSigner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RemoteApprovalSigner {
+    /// Signer with a fresh secret that only lives in this process
+    pub fn new() -> Self {
+        Self {
+            secret: Self::generate_secret().into(),
+        }
+    }
+
+    /// Signer with the secret stored at `path`, so links sent out before a restart
+    /// keep working. A missing or empty file gets a new secret.
+    pub fn load_or_create(path: &Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(secret) if !secret.trim().is_empty() => {
+                return Ok(Self {
+                    secret: secret.trim().into(),
+                });
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        let secret = Self::generate_secret();
+        let tmp = path.with_extension("tmp");
+        {
+            let mut opts = std::fs::OpenOptions::new();
+            opts.create(true).truncate(true).write(true);
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                opts.mode(0o600);
+            }
+
+            let mut file = opts.open(&tmp)?;
+            std::io::Write::write_all(&mut file, secret.as_bytes())?;
+            file.sync_all()?;
+        }
+        std::fs::rename(&tmp, path)?;
+
+        Ok(Self {
+            secret: secret.into(),
+        })
+    }
+
+    fn generate_secret() -> String {
+        format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+    }
+
+    fn message(id: &str, decision: RemoteDecision, expires: i64) -> String {
+        format!("{id}.{decision}.{expires}")
+    }
+
+    /// Hex-encoded HMAC-SHA256 of the approval, decision and expiry
+    pub fn sign(&self, id: &str, decision: RemoteDecision, expires: i64) -> String {
+        let signature =
+            WebhookService::sign_payload(&self.secret, &Self::message(id, decision, expires));
+        signature
+            .strip_prefix("sha256=")
+            .unwrap_or(&signature)
+            .to_string()
+    }
+
+    pub fn verify(
+        &self,
+        id: &str,
+        decision: RemoteDecision,
+        expires: i64,
+        signature: &str,
+    ) -> bool {
+        expires >= Utc::now().timestamp()
+            && WebhookService::verify_signature(
+                &self.secret,
+                Self::message(id, decision, expires).as_bytes(),
+                &format!("sha256={signature}"),
+            )
+    }
+
+    pub fn links(&self, base_url: &str, request: &ApprovalRequest) -> RemoteApprovalLinks {
+        let expires = request.timeout_at.timestamp();
+        let link = |decision| {
+            format!(
+                "{base_url}/api/approvals/{}/remote/{decision}?expires={expires}&signature={}",
+                request.id,
+                self.sign(&request.id, decision, expires)
+            )
+        };
+        RemoteApprovalLinks {
+            approve_url: link(RemoteDecision::Approve),
+            deny_url: link(RemoteDecision::Deny),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::approvals::CreateApprovalRequest;
+
+    use super::*;
+
+    #[test]
+    fn links_verify_only_for_their_decision() {
+        let signer = RemoteApprovalSigner::new();
+        let request = ApprovalRequest::from_create(
+            CreateApprovalRequest {
+                tool_name: "Bash".to_string(),
+                tool_input: serde_json::json!({ "command": "git push" }),
+                tool_call_id: "call-1".to_string(),
+            },
+            Uuid::new_v4(),
+        );
+        let expires = request.timeout_at.timestamp();
+        let links = signer.links("https://vk.example.com", &request);
+        let signature = links
+            .approve_url
+            .split("signature=")
+            .nth(1)
+            .expect("link carries a signature");
+
+        assert!(links.approve_url.starts_with(&format!(
+            "https://vk.example.com/api/approvals/{}/remote/approve?",
+            request.id
+        )));
+        assert!(signer.verify(&request.id, RemoteDecision::Approve, expires, signature));
+        assert!(!signer.verify(&request.id, RemoteDecision::Deny, expires, signature));
+        assert!(!signer.verify(
+            &request.id,
+            RemoteDecision::Approve,
+            expires + 60,
+            signature
+        ));
+        assert!(!RemoteApprovalSigner::new().verify(
+            &request.id,
+            RemoteDecision::Approve,
+            expires,
+            signature
+        ));
+
+        let expired = Utc::now().timestamp() - 1;
+        let signature = signer.sign(&request.id, RemoteDecision::Approve, expired);
+        assert!(!signer.verify(&request.id, RemoteDecision::Approve, expired, &signature));
+    }
+
+    #[test]
+    fn stored_secret_survives_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("approval_secret");
+        let expires = Utc::now().timestamp() + 60;
+
+        let signer = RemoteApprovalSigner::load_or_create(&path).unwrap();
+        let signature = signer.sign("approval-1", RemoteDecision::Approve, expires);
+
+        let restarted = RemoteApprovalSigner::load_or_create(&path).unwrap();
+        assert!(restarted.verify("approval-1", RemoteDecision::Approve, expires, &signature));
+    }
+}
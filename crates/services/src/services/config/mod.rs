@@ -26,6 +26,10 @@ pub type EditorType = versions::v8::EditorType;
 pub type GitHubConfig = versions::v8::GitHubConfig;
 pub type UiLanguage = versions::v8::UiLanguage;
 pub type ShowcaseState = versions::v8::ShowcaseState;
+pub type AutoSchedulerConfig = versions::v8::AutoSchedulerConfig;
+pub type UsageConfig = versions::v8::UsageConfig;
+pub type ModelPrice = versions::v8::ModelPrice;
+pub type SandboxConfig = executors::sandbox::SandboxConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
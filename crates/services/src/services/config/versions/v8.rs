@@ -1,5 +1,5 @@
 use anyhow::Error;
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId, sandbox::SandboxConfig};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 pub use v7::{
@@ -17,6 +17,104 @@ fn default_pr_auto_description_enabled() -> bool {
     true
 }
 
+/// Settings for the background scheduler that auto-starts ready tasks
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[serde(default)]
+pub struct AutoSchedulerConfig {
+    pub enabled: bool,
+    /// Maximum coding agents running at once across all projects
+    pub max_concurrent_agents: u32,
+    /// Maximum coding agents running at once within a single project
+    pub max_concurrent_agents_per_project: u32,
+}
+
+impl Default for AutoSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_concurrent_agents: 3,
+            max_concurrent_agents_per_project: 1,
+        }
+    }
+}
+
+/// Price of a model in USD per million tokens
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq)]
+pub struct ModelPrice {
+    /// Matched against the start of the model name reported by the agent; the
+    /// longest match wins
+    pub model: String,
+    pub input: f64,
+    pub output: f64,
+    #[serde(default)]
+    pub cache_read: f64,
+    #[serde(default)]
+    pub cache_write: f64,
+}
+
+impl ModelPrice {
+    fn new(model: &str, input: f64, output: f64, cache_read: f64, cache_write: f64) -> Self {
+        Self {
+            model: model.to_string(),
+            input,
+            output,
+            cache_read,
+            cache_write,
+        }
+    }
+}
+
+/// Prices used to estimate the cost of recorded token usage. Only the user's
+/// own entries are saved, so the built-in prices stay current across updates.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct UsageConfig {
+    /// Replace the built-in price of the same model, or add a new model
+    #[serde(default)]
+    pub price_overrides: Vec<ModelPrice>,
+}
+
+impl UsageConfig {
+    /// Built-in prices. Smaller variants get their own entry because the longest
+    /// prefix wins, so "o3" alone would also price "o3-mini".
+    pub fn default_prices() -> Vec<ModelPrice> {
+        vec![
+            ModelPrice::new("claude-opus-4-5", 5.0, 25.0, 0.5, 6.25),
+            ModelPrice::new("claude-opus-4", 15.0, 75.0, 1.5, 18.75),
+            ModelPrice::new("claude-sonnet-4", 3.0, 15.0, 0.3, 3.75),
+            ModelPrice::new("claude-haiku-4-5", 1.0, 5.0, 0.1, 1.25),
+            ModelPrice::new("claude-3-5-haiku", 0.8, 4.0, 0.08, 1.0),
+            ModelPrice::new("gpt-5", 1.25, 10.0, 0.125, 0.0),
+            ModelPrice::new("gpt-5-mini", 0.25, 2.0, 0.025, 0.0),
+            ModelPrice::new("gpt-5-nano", 0.05, 0.4, 0.005, 0.0),
+            ModelPrice::new("gpt-4.1", 2.0, 8.0, 0.5, 0.0),
+            ModelPrice::new("gpt-4.1-mini", 0.4, 1.6, 0.1, 0.0),
+            ModelPrice::new("gpt-4.1-nano", 0.1, 0.4, 0.025, 0.0),
+            ModelPrice::new("o3", 2.0, 8.0, 0.5, 0.0),
+            ModelPrice::new("o3-mini", 1.1, 4.4, 0.55, 0.0),
+            ModelPrice::new("o3-pro", 20.0, 80.0, 0.0, 0.0),
+            ModelPrice::new("o4-mini", 1.1, 4.4, 0.275, 0.0),
+            ModelPrice::new("gemini-2.5-pro", 1.25, 10.0, 0.31, 0.0),
+            ModelPrice::new("gemini-2.5-flash", 0.3, 2.5, 0.075, 0.0),
+            ModelPrice::new("gemini-2.5-flash-lite", 0.1, 0.4, 0.025, 0.0),
+        ]
+    }
+
+    /// The built-in prices with the user's overrides applied
+    pub fn prices(&self) -> Vec<ModelPrice> {
+        let mut prices = Self::default_prices();
+        for price in &self.price_overrides {
+            match prices
+                .iter_mut()
+                .find(|existing| existing.model.eq_ignore_ascii_case(&price.model))
+            {
+                Some(existing) => *existing = price.clone(),
+                None => prices.push(price.clone()),
+            }
+        }
+        prices
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -41,6 +139,12 @@ pub struct Config {
     pub pr_auto_description_enabled: bool,
     #[serde(default)]
     pub pr_auto_description_prompt: Option<String>,
+    #[serde(default)]
+    pub auto_scheduler: AutoSchedulerConfig,
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    #[serde(default)]
+    pub usage: UsageConfig,
 }
 
 impl Config {
@@ -66,6 +170,9 @@ impl Config {
             showcases: old_config.showcases,
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            auto_scheduler: AutoSchedulerConfig::default(),
+            sandbox: SandboxConfig::default(),
+            usage: UsageConfig::default(),
         }
     }
 
@@ -116,6 +223,9 @@ impl Default for Config {
             showcases: ShowcaseState::default(),
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            auto_scheduler: AutoSchedulerConfig::default(),
+            sandbox: SandboxConfig::default(),
+            usage: UsageConfig::default(),
         }
     }
 }
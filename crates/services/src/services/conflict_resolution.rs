@@ -0,0 +1,258 @@
+//! Prompts for resolving the conflicts of an interrupted rebase or merge with the
+//! workspace's coding agent.
+//!
+//! The agent gets the conflicted files with both sides of every conflict. Once its
+//! turn ends, the files are checked for leftover conflict markers: a clean tree is
+//! staged and the operation continued, anything else aborts the operation.
+
+use crate::services::git::ConflictOp;
+
+/// Agent turns per resolution; a rebase can stop at several conflicting commits
+pub const MAX_ROUNDS: i64 = 5;
+
+/// Files listed in full in the prompt; the rest are only counted
+pub const MAX_PROMPT_FILES: usize = 12;
+
+/// Conflicts shown per file
+const MAX_HUNKS_PER_FILE: usize = 5;
+
+/// Lines shown per side of a conflict
+const MAX_SIDE_LINES: usize = 40;
+
+/// One `<<<<<<<` ... `>>>>>>>` block of a conflicted file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictHunk {
+    /// Line of the `<<<<<<<` marker, starting at 1
+    pub line: usize,
+    pub ours_label: String,
+    pub ours: Vec<String>,
+    pub theirs_label: String,
+    pub theirs: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub hunks: Vec<ConflictHunk>,
+}
+
+fn marker_label<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.is_empty() {
+        Some("")
+    } else {
+        rest.strip_prefix(' ')
+    }
+}
+
+/// Conflict blocks of a file, in order. A diff3 base section (`|||||||`) is skipped.
+pub fn parse_conflict_hunks(content: &str) -> Vec<ConflictHunk> {
+    enum Section {
+        Outside,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut hunks = Vec::new();
+    let mut section = Section::Outside;
+    let mut current: Option<ConflictHunk> = None;
+    for (index, line) in content.lines().enumerate() {
+        match section {
+            Section::Outside => {
+                if let Some(label) = marker_label(line, "<<<<<<<") {
+                    current = Some(ConflictHunk {
+                        line: index + 1,
+                        ours_label: label.to_string(),
+                        ours: Vec::new(),
+                        theirs_label: String::new(),
+                        theirs: Vec::new(),
+                    });
+                    section = Section::Ours;
+                }
+            }
+            Section::Ours | Section::Base => {
+                if marker_label(line, "|||||||").is_some() {
+                    section = Section::Base;
+                } else if line == "=======" {
+                    section = Section::Theirs;
+                } else if let (Section::Ours, Some(hunk)) = (&section, current.as_mut()) {
+                    hunk.ours.push(line.to_string());
+                }
+            }
+            Section::Theirs => {
+                if let Some(label) = marker_label(line, ">>>>>>>") {
+                    if let Some(mut hunk) = current.take() {
+                        hunk.theirs_label = label.to_string();
+                        hunks.push(hunk);
+                    }
+                    section = Section::Outside;
+                } else if let Some(hunk) = current.as_mut() {
+                    hunk.theirs.push(line.to_string());
+                }
+            }
+        }
+    }
+    hunks
+}
+
+/// Whether a file still holds a complete conflict block
+pub fn has_conflict_markers(content: &str) -> bool {
+    !parse_conflict_hunks(content).is_empty()
+}
+
+fn side_block(lines: &[String]) -> String {
+    if lines.is_empty() {
+        return "(empty)".to_string();
+    }
+    let mut block = lines
+        .iter()
+        .take(MAX_SIDE_LINES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if lines.len() > MAX_SIDE_LINES {
+        block.push_str(&format!("\n[{} more lines]", lines.len() - MAX_SIDE_LINES));
+    }
+    block
+}
+
+/// Prompt asking the agent to resolve the conflicts of `op` in `repo_name`
+pub fn resolve_prompt(
+    op: &ConflictOp,
+    repo_name: &str,
+    task_branch: &str,
+    base_branch: &str,
+    files: &[ConflictedFile],
+) -> String {
+    let (situation, ours, theirs, continue_command) = match op {
+        ConflictOp::Rebase => (
+            format!("Rebasing '{task_branch}' onto '{base_branch}'"),
+            format!("'{base_branch}' plus the commits already rebased"),
+            "the commit of this task being replayed".to_string(),
+            "git rebase --continue",
+        ),
+        ConflictOp::Merge => (
+            format!("Merging into '{task_branch}'"),
+            format!("'{task_branch}'"),
+            "the branch being merged".to_string(),
+            "git merge --continue",
+        ),
+        ConflictOp::CherryPick => (
+            format!("Cherry-picking onto '{task_branch}'"),
+            format!("'{task_branch}'"),
+            "the commit being picked".to_string(),
+            "git cherry-pick --continue",
+        ),
+        ConflictOp::Revert => (
+            format!("Reverting on '{task_branch}'"),
+            format!("'{task_branch}'"),
+            "the revert".to_string(),
+            "git revert --continue",
+        ),
+    };
+
+    let mut prompt = format!(
+        "{situation} in repository '{repo_name}' stopped with conflicts in {} file(s). \
+         In each conflict, the first side ({ours}) is marked `<<<<<<<` and the second \
+         side ({theirs}) ends with `>>>>>>>`.\n",
+        files.len()
+    );
+    for file in files.iter().take(MAX_PROMPT_FILES) {
+        prompt.push_str(&format!("\n## {}\n", file.path));
+        if file.hunks.is_empty() {
+            prompt.push_str(
+                "\nNo conflict markers in the file; it was changed differently on both \
+                 sides, e.g. deleted on one of them.\n",
+            );
+        }
+        for hunk in file.hunks.iter().take(MAX_HUNKS_PER_FILE) {
+            prompt.push_str(&format!(
+                "\nLine {}, first side ({}):\n```\n{}\n```\nSecond side ({}):\n```\n{}\n```\n",
+                hunk.line,
+                hunk.ours_label,
+                side_block(&hunk.ours),
+                hunk.theirs_label,
+                side_block(&hunk.theirs),
+            ));
+        }
+        if file.hunks.len() > MAX_HUNKS_PER_FILE {
+            prompt.push_str(&format!(
+                "\n[{} more conflicts in this file]\n",
+                file.hunks.len() - MAX_HUNKS_PER_FILE
+            ));
+        }
+    }
+    if files.len() > MAX_PROMPT_FILES {
+        prompt.push_str(&format!(
+            "\n[{} more conflicted files; list them with `git diff --name-only --diff-filter=U`]\n",
+            files.len() - MAX_PROMPT_FILES
+        ));
+    }
+    prompt.push_str(&format!(
+        "\nResolve every conflict so that the result keeps the intent of both sides, \
+         remove all conflict markers and stage the files with `git add`. Do not run \
+         `{continue_command}`, abort the operation or commit: once your turn ends, the \
+         conflicts are checked and the operation is continued for you. If markers are \
+         left, the operation is aborted."
+    ));
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFLICTED: &str = "fn main() {\n\
+        <<<<<<< HEAD\n\
+        \x20   println!(\"base\");\n\
+        ||||||| parent of 1a2b3c4 (Say hello)\n\
+        \x20   println!(\"old\");\n\
+        =======\n\
+        \x20   println!(\"hello\");\n\
+        \x20   greet();\n\
+        >>>>>>> 1a2b3c4 (Say hello)\n\
+        }\n";
+
+    #[test]
+    fn parses_both_sides_and_skips_base() {
+        let hunks = parse_conflict_hunks(CONFLICTED);
+        assert_eq!(
+            hunks,
+            vec![ConflictHunk {
+                line: 2,
+                ours_label: "HEAD".to_string(),
+                ours: vec!["    println!(\"base\");".to_string()],
+                theirs_label: "1a2b3c4 (Say hello)".to_string(),
+                theirs: vec![
+                    "    println!(\"hello\");".to_string(),
+                    "    greet();".to_string()
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn detects_leftover_markers_only_in_complete_blocks() {
+        assert!(has_conflict_markers(CONFLICTED));
+        assert!(!has_conflict_markers("fn main() {}\n"));
+        // Markdown underlines and lone separators are not conflicts
+        assert!(!has_conflict_markers("Title\n=======\n\ntext\n"));
+        assert!(!has_conflict_markers("<<<<<<<< not a marker\n"));
+    }
+
+    #[test]
+    fn prompt_lists_files_and_hunks() {
+        let files = vec![ConflictedFile {
+            path: "src/main.rs".to_string(),
+            hunks: parse_conflict_hunks(CONFLICTED),
+        }];
+        let prompt = resolve_prompt(&ConflictOp::Rebase, "app", "vk/feature", "main", &files);
+
+        assert!(prompt.starts_with("Rebasing 'vk/feature' onto 'main' in repository 'app'"));
+        assert!(prompt.contains("## src/main.rs"));
+        assert!(prompt.contains("Line 2, first side (HEAD)"));
+        assert!(prompt.contains("    greet();"));
+        assert!(prompt.contains("Do not run `git rebase --continue`"));
+    }
+}
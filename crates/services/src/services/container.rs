@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
@@ -9,31 +10,40 @@ use async_trait::async_trait;
 use db::{
     DBService,
     models::{
-        coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn, VerificationStatus},
+        conflict_resolution::{ConflictResolution, ConflictResolutionStatus},
         execution_process::{
-            CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
-            ExecutionProcessStatus,
+            CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessExitReason,
+            ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_logs::ExecutionProcessLogs,
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        merge::{Merge, MergeStatus, PullRequestInfo},
+        pipeline::{PipelineRun, PipelineRunStatus, PipelineStepRun, ProjectPipeline},
         project::{Project, UpdateProject},
         project_repo::{ProjectRepo, ProjectRepoWithName},
         repo::Repo,
+        review_follow_up::{
+            CreateReviewFollowUp, ReviewFollowUp, ReviewFollowUpComment, ReviewFollowUpStatus,
+        },
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
-        workspace::{Workspace, WorkspaceError},
-        workspace_repo::WorkspaceRepo,
+        task_history::{TaskActor, TaskHistory},
+        workspace::{CreateWorkspace, Workspace, WorkspaceError},
+        workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
     },
 };
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
-        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+        script::{ScriptContext, ScriptRequest},
     },
     executors::{ExecutorError, StandardCodingAgentExecutor},
+    fallback::{AgentFailure, FallbackStep},
     logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
@@ -41,6 +51,7 @@ use futures::{StreamExt, future};
 use sqlx::Error as SqlxError;
 use thiserror::Error;
 use tokio::{sync::RwLock, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
@@ -49,14 +60,35 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
+    config::UsageConfig,
+    conflict_resolution::{self, ConflictedFile},
+    fallback::{self, FallbackPlan},
+    forge::{ForgeRepoInfo, forge_for},
     git::{GitService, GitServiceError},
+    github::UnifiedPrComment,
     notification::NotificationService,
+    pipeline::{
+        PipelineDefinition, PipelineError, PipelineStep, PipelineStepAction, PipelineStepOutcome,
+        StepResult, output_tail,
+    },
+    review_follow_up,
     share::SharePublisher,
+    stack::{self, RestackPlan, RestackResult, RestackStatus},
+    usage::{self, UsageError, UsageTracking},
+    verification,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
 
+/// Pipeline step an execution process is started for
+#[derive(Debug, Clone)]
+pub struct PipelineStepLink {
+    pub pipeline_run_id: Uuid,
+    pub step_id: String,
+    pub triggered_by_id: Option<Uuid>,
+}
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -73,6 +105,10 @@ pub enum ContainerError {
     WorkspaceManager(#[from] WorkspaceManagerError),
     #[error(transparent)]
     Session(#[from] SessionError),
+    #[error(transparent)]
+    Pipeline(#[from] PipelineError),
+    #[error(transparent)]
+    Usage(#[from] UsageError),
     #[error("Io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
@@ -149,6 +185,15 @@ pub trait ContainerService {
             return false;
         }
 
+        // Pipeline steps never carry a next action. The exit handler only asks once
+        // the step's run has finished, and then the task is done either way.
+        if matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::PipelineStep
+        ) {
+            return true;
+        }
+
         // Always finalize failed or killed executions, regardless of next action
         if matches!(
             ctx.execution_process.status,
@@ -161,14 +206,42 @@ pub trait ContainerService {
         action.next_action.is_none()
     }
 
+    /// Record a task status change made while running executions
+    async fn record_task_status_change(
+        &self,
+        task_id: Uuid,
+        old_status: &TaskStatus,
+        new_status: &TaskStatus,
+    ) {
+        if let Err(e) = TaskHistory::record_status_change(
+            &self.db().pool,
+            task_id,
+            old_status,
+            new_status,
+            &TaskActor::system("container"),
+        )
+        .await
+        {
+            tracing::warn!("Failed to record history for task {}: {}", task_id, e);
+        }
+    }
+
     /// Finalize task execution by updating status to InReview and sending notifications
     async fn finalize_task(
         &self,
         share_publisher: Option<&SharePublisher>,
         ctx: &ExecutionContext,
     ) {
-        match Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await {
+        match Task::update_status_by_system(&self.db().pool, ctx.task.id, TaskStatus::InReview)
+            .await
+        {
             Ok(_) => {
+                self.record_task_status_change(
+                    ctx.task.id,
+                    &ctx.task.status,
+                    &TaskStatus::InReview,
+                )
+                .await;
                 if let Some(publisher) = share_publisher
                     && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
                 {
@@ -268,14 +341,23 @@ pub trait ContainerService {
                 ExecutionProcessRunReason::CodingAgent
                     | ExecutionProcessRunReason::SetupScript
                     | ExecutionProcessRunReason::CleanupScript
+                    | ExecutionProcessRunReason::PipelineStep
             ) && let Ok(Some(session)) =
                 Session::find_by_id(&self.db().pool, process.session_id).await
                 && let Ok(Some(workspace)) =
                     Workspace::find_by_id(&self.db().pool, session.workspace_id).await
                 && let Ok(Some(task)) = workspace.parent_task(&self.db().pool).await
             {
-                match Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await {
+                match Task::update_status_by_system(&self.db().pool, task.id, TaskStatus::InReview)
+                    .await
+                {
                     Ok(_) => {
+                        self.record_task_status_change(
+                            task.id,
+                            &task.status,
+                            &TaskStatus::InReview,
+                        )
+                        .await;
                         if let Some(publisher) = self.share_publisher()
                             && let Err(err) = publisher.update_shared_task_by_id(task.id).await
                         {
@@ -413,6 +495,10 @@ pub trait ContainerService {
                                 } else {
                                     project.default_agent_working_dir.clone()
                                 },
+                                verification_command: None,
+                                verification_working_dir: None,
+                                verification_max_retries: None,
+                                usage_budget_usd: None,
                             },
                         )
                         .await?;
@@ -439,9 +525,9 @@ pub trait ContainerService {
         let mut root_action = ExecutorAction::new(
             ExecutorActionType::ScriptRequest(ScriptRequest {
                 script: first.cleanup_script.clone().unwrap(),
-                language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::CleanupScript,
                 working_dir: Some(first.repo_name.clone()),
+                options: first.cleanup_script_options.0.clone(),
             }),
             None,
         );
@@ -450,9 +536,9 @@ pub trait ContainerService {
             root_action = root_action.append_action(ExecutorAction::new(
                 ExecutorActionType::ScriptRequest(ScriptRequest {
                     script: repo.cleanup_script.clone().unwrap(),
-                    language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::CleanupScript,
                     working_dir: Some(repo.repo_name.clone()),
+                    options: repo.cleanup_script_options.0.clone(),
                 }),
                 None,
             ));
@@ -473,9 +559,9 @@ pub trait ContainerService {
         let mut root_action = ExecutorAction::new(
             ExecutorActionType::ScriptRequest(ScriptRequest {
                 script: first.setup_script.clone().unwrap(),
-                language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::SetupScript,
                 working_dir: Some(first.repo_name.clone()),
+                options: first.setup_script_options.0.clone(),
             }),
             None,
         );
@@ -484,9 +570,9 @@ pub trait ContainerService {
             root_action = root_action.append_action(ExecutorAction::new(
                 ExecutorActionType::ScriptRequest(ScriptRequest {
                     script: repo.setup_script.clone().unwrap(),
-                    language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
                     working_dir: Some(repo.repo_name.clone()),
+                    options: repo.setup_script_options.0.clone(),
                 }),
                 None,
             ));
@@ -500,9 +586,9 @@ pub trait ContainerService {
             ExecutorAction::new(
                 ExecutorActionType::ScriptRequest(ScriptRequest {
                     script: script.clone(),
-                    language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
                     working_dir: Some(repo.repo_name.clone()),
+                    options: repo.setup_script_options.0.clone(),
                 }),
                 None,
             )
@@ -519,9 +605,9 @@ pub trait ContainerService {
                 chained = ExecutorAction::new(
                     ExecutorActionType::ScriptRequest(ScriptRequest {
                         script: script.clone(),
-                        language: ScriptRequestLanguage::Bash,
                         context: ScriptContext::SetupScript,
                         working_dir: Some(repo.repo_name.clone()),
+                        options: repo.setup_script_options.0.clone(),
                     }),
                     Some(Box::new(chained)),
                 );
@@ -609,6 +695,13 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    /// Price table used to cost the token usage of new executions
+    async fn usage_config(&self) -> UsageConfig;
+
+    /// Token that stops a running execution once cancelled, with the exit reason
+    /// `BudgetExceeded`
+    async fn budget_stop(&self, execution_id: &Uuid) -> Option<CancellationToken>;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -804,7 +897,14 @@ pub trait ContainerService {
         }
     }
 
-    fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
+    /// Persist raw logs of an execution, and the token usage reported by coding
+    /// agents when `usage_tracking` is given. A usage report that brings the project
+    /// to its budget stops the execution.
+    fn spawn_stream_raw_logs_to_db(
+        &self,
+        execution_id: &Uuid,
+        usage_tracking: Option<UsageTracking>,
+    ) -> JoinHandle<()> {
         let execution_id = *execution_id;
         let msg_stores = self.msg_stores().clone();
         let db = self.db().clone();
@@ -868,6 +968,50 @@ pub trait ContainerService {
                                 );
                             }
                         }
+                        LogMsg::Usage(token_usage) => {
+                            let Some(tracking) = &usage_tracking else {
+                                continue;
+                            };
+                            if let Err(e) = usage::record_usage(
+                                &db.pool,
+                                execution_id,
+                                &tracking.executor_profile_id,
+                                &tracking.config,
+                                token_usage,
+                            )
+                            .await
+                            {
+                                tracing::error!(
+                                    "Failed to record token usage for execution process {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                                continue;
+                            }
+                            let Some(budget_stop) = &tracking.budget_stop else {
+                                continue;
+                            };
+                            match usage::ensure_project_within_budget(&db.pool, tracking.project_id)
+                                .await
+                            {
+                                Ok(()) => {}
+                                Err(e @ UsageError::BudgetExceeded { .. }) => {
+                                    tracing::info!(
+                                        "Stopping execution process {}: {}",
+                                        execution_id,
+                                        e
+                                    );
+                                    budget_stop.cancel();
+                                }
+                                Err(e) => {
+                                    tracing::error!(
+                                        "Failed to check the budget for execution process {}: {}",
+                                        execution_id,
+                                        e
+                                    );
+                                }
+                            }
+                        }
                         LogMsg::Finished => {
                             break;
                         }
@@ -878,6 +1022,46 @@ pub trait ContainerService {
         })
     }
 
+    /// Create a workspace for `task` on `repos`, named after the task and using the
+    /// project's default agent working directory. The workspace is not started.
+    async fn create_workspace(
+        &self,
+        task: &Task,
+        workspace_mode: &str,
+        stacked_on_workspace_id: Option<Uuid>,
+        repos: &[CreateWorkspaceRepo],
+    ) -> Result<Workspace, ContainerError> {
+        let pool = &self.db().pool;
+        let project = task
+            .parent_project(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let workspace_id = Uuid::new_v4();
+        let branch = self
+            .git_branch_from_workspace(&workspace_id, &task.title)
+            .await;
+        let agent_working_dir = project
+            .default_agent_working_dir
+            .filter(|dir| !dir.is_empty());
+
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch,
+                agent_working_dir,
+                workspace_mode: workspace_mode.to_string(),
+                stacked_on_workspace_id,
+            },
+            workspace_id,
+            task.id,
+        )
+        .await?;
+        WorkspaceRepo::create_many(pool, workspace.id, repos).await?;
+
+        Ok(workspace)
+    }
+
     async fn start_workspace(
         &self,
         workspace: &Workspace,
@@ -986,16 +1170,47 @@ pub trait ContainerService {
         session: &Session,
         executor_action: &ExecutorAction,
         run_reason: &ExecutionProcessRunReason,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        self.start_linked_execution(workspace, session, executor_action, run_reason, None, None)
+            .await
+    }
+
+    /// Start an execution, recording it as a pipeline step run before the process is
+    /// spawned so its exit is always seen as the end of that step. A notice becomes
+    /// the first entry of the process' conversation.
+    async fn start_linked_execution(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        executor_action: &ExecutorAction,
+        run_reason: &ExecutionProcessRunReason,
+        pipeline_step: Option<&PipelineStepLink>,
+        notice: Option<&str>,
     ) -> Result<ExecutionProcess, ContainerError> {
         // Update task status to InProgress when starting an execution
         let task = workspace
             .parent_task(&self.db().pool)
             .await?
             .ok_or(SqlxError::RowNotFound)?;
+
+        // Coding agent turns stop once the project has spent its budget. Scripts that
+        // lead up to an agent are refused too, rather than failing halfway.
+        let runs_agent =
+            std::iter::successors(Some(executor_action), |action| action.next_action())
+                .any(|action| action.base_executor().is_some());
+        if runs_agent {
+            let project = Project::find_by_id(&self.db().pool, task.project_id)
+                .await?
+                .ok_or(SqlxError::RowNotFound)?;
+            usage::ensure_within_budget(&self.db().pool, &project).await?;
+        }
+
         if task.status != TaskStatus::InProgress
             && run_reason != &ExecutionProcessRunReason::DevServer
         {
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
+            Task::update_status_by_system(&self.db().pool, task.id, TaskStatus::InProgress).await?;
+            self.record_task_status_change(task.id, &task.status, &TaskStatus::InProgress)
+                .await;
 
             if let Some(publisher) = self.share_publisher()
                 && let Err(err) = publisher.update_shared_task_by_id(task.id).await
@@ -1048,6 +1263,17 @@ pub trait ContainerService {
         )
         .await?;
 
+        if let Some(link) = pipeline_step {
+            PipelineStepRun::create(
+                &self.db().pool,
+                link.pipeline_run_id,
+                &link.step_id,
+                execution_process.id,
+                link.triggered_by_id,
+            )
+            .await?;
+        }
+
         if let Some(prompt) = match executor_action.typ() {
             ExecutorActionType::CodingAgentInitialRequest(coding_agent_request) => {
                 Some(coding_agent_request.prompt.clone())
@@ -1091,7 +1317,15 @@ pub trait ContainerService {
                     update_error
                 );
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            Task::update_status_by_system(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            // The status was moved to InProgress above unless this is a dev server
+            let status_before_failure = if run_reason == &ExecutionProcessRunReason::DevServer {
+                &task.status
+            } else {
+                &TaskStatus::InProgress
+            };
+            self.record_task_status_change(task.id, status_before_failure, &TaskStatus::InReview)
+                .await;
 
             // Emit stderr error message
             let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
@@ -1130,17 +1364,31 @@ pub trait ContainerService {
             return Err(start_error);
         }
 
+        // Added before normalization starts, so the agent's entries follow the notice
+        if let Some(notice) = notice {
+            let entry = NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::SystemMessage,
+                content: notice.to_string(),
+                metadata: None,
+            };
+            let patch = LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(0, entry));
+            if let Ok(json_line) = serde_json::to_string(&patch) {
+                let _ = ExecutionProcessLogs::append_log_line(
+                    &self.db().pool,
+                    execution_process.id,
+                    &format!("{json_line}\n"),
+                )
+                .await;
+            }
+            if let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await {
+                msg_store.push(patch);
+            }
+        }
+
         // Start processing normalised logs for executor requests and follow ups
         if let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await
-            && let Some(executor_profile_id) = match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    Some(&request.executor_profile_id)
-                }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    Some(&request.executor_profile_id)
-                }
-                _ => None,
-            }
+            && let Some(executor_profile_id) = executor_action.executor_profile_id()
         {
             if let Some(executor) =
                 ExecutorConfigs::get_cached().get_coding_agent(executor_profile_id)
@@ -1154,7 +1402,16 @@ pub trait ContainerService {
             }
         }
 
-        self.spawn_stream_raw_logs_to_db(&execution_process.id);
+        let usage_tracking = match executor_action.executor_profile_id() {
+            Some(executor_profile_id) => Some(UsageTracking {
+                project_id: task.project_id,
+                executor_profile_id: executor_profile_id.clone(),
+                config: self.usage_config().await,
+                budget_stop: self.budget_stop(&execution_process.id).await,
+            }),
+            None => None,
+        };
+        self.spawn_stream_raw_logs_to_db(&execution_process.id, usage_tracking);
         Ok(execution_process)
     }
 
@@ -1169,6 +1426,14 @@ pub trait ContainerService {
 
         // Determine the run reason of the next action
         let next_run_reason = match (action.typ(), next_action.typ()) {
+            // Verification stands between a coding agent and its cleanup scripts
+            (
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    context: ScriptContext::Verification,
+                    ..
+                }),
+                ExecutorActionType::ScriptRequest(_),
+            ) => ExecutionProcessRunReason::CleanupScript,
             (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(_)) => {
                 ExecutionProcessRunReason::SetupScript
             }
@@ -1190,4 +1455,1091 @@ pub trait ContainerService {
         tracing::debug!("Started next action: {:?}", next_action);
         Ok(())
     }
+
+    /// Start a run of a project pipeline in a session, beginning with its first step
+    async fn start_pipeline(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        pipeline: &ProjectPipeline,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Result<PipelineRun, ContainerError> {
+        let definition = PipelineDefinition::parse(&pipeline.definition)?;
+        let run =
+            PipelineRun::create(&self.db().pool, session.id, pipeline, executor_profile_id).await?;
+
+        if let Err(e) = self
+            .start_pipeline_step(workspace, session, &run, definition.first_step(), None)
+            .await
+        {
+            PipelineRun::finish(&self.db().pool, run.id, PipelineRunStatus::Failed).await?;
+            return Err(e);
+        }
+        Ok(run)
+    }
+
+    async fn start_pipeline_step(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        run: &PipelineRun,
+        step: &PipelineStep,
+        previous: Option<(&PipelineStepRun, &StepResult)>,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let (action_type, run_reason) = match &step.action {
+            PipelineStepAction::Agent(agent) => {
+                let task = workspace
+                    .parent_task(pool)
+                    .await?
+                    .ok_or(SqlxError::RowNotFound)?;
+                let prompt = agent.render_prompt(
+                    &task.title,
+                    task.description.as_deref(),
+                    &task.to_prompt(),
+                    previous.map(|(_, result)| result),
+                )?;
+                let executor_profile_id = run.executor_profile_id.0.clone();
+                let working_dir = workspace
+                    .agent_working_dir
+                    .as_ref()
+                    .filter(|dir| !dir.is_empty())
+                    .cloned();
+                let latest_agent_session_id =
+                    ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id)
+                        .await?;
+                let action_type = match latest_agent_session_id {
+                    Some(session_id) => {
+                        ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                            prompt,
+                            session_id,
+                            executor_profile_id,
+                            working_dir,
+                        })
+                    }
+                    None => {
+                        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                            prompt,
+                            executor_profile_id,
+                            working_dir,
+                        })
+                    }
+                };
+                (action_type, ExecutionProcessRunReason::CodingAgent)
+            }
+            PipelineStepAction::Script(script) => (
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: script.script.clone(),
+                    context: ScriptContext::PipelineStep,
+                    working_dir: script.working_dir.clone(),
+                    options: script.options.clone(),
+                }),
+                ExecutionProcessRunReason::PipelineStep,
+            ),
+        };
+
+        let link = PipelineStepLink {
+            pipeline_run_id: run.id,
+            step_id: step.id.clone(),
+            triggered_by_id: previous.map(|(step_run, _)| step_run.id),
+        };
+        self.start_linked_execution(
+            workspace,
+            session,
+            &ExecutorAction::new(action_type, None),
+            &run_reason,
+            Some(&link),
+            None,
+        )
+        .await
+    }
+
+    /// Tail of the stdout and stderr of a finished process, as seen by pipeline
+    /// conditions and verification follow-ups
+    async fn process_output_tail(&self, execution_process_id: &Uuid) -> String {
+        let Some(store) = self.get_msg_store_by_id(execution_process_id).await else {
+            return String::new();
+        };
+        let output: String = store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(text) | LogMsg::Stderr(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        output_tail(&output).to_string()
+    }
+
+    /// Successor of [`Self::try_start_next_action`] for processes started by a pipeline.
+    /// Follows the transitions of the finished step and finishes the run once no step
+    /// is left running. Returns true while the pipeline still owns the session, in
+    /// which case the caller must not finalize the task.
+    async fn advance_pipeline(
+        &self,
+        ctx: &ExecutionContext,
+        step_run: &PipelineStepRun,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        let Some(run) = PipelineRun::find_by_id(pool, step_run.pipeline_run_id).await? else {
+            return Ok(false);
+        };
+        if run.status != PipelineRunStatus::Running {
+            // Whoever ended the run has finalized the task already
+            return Ok(true);
+        }
+        if ctx.execution_process.status == ExecutionProcessStatus::Killed {
+            let finished = PipelineRun::finish(pool, run.id, PipelineRunStatus::Cancelled).await?;
+            return Ok(!finished);
+        }
+
+        let definition = PipelineDefinition::parse(&run.definition)?;
+        let outcome = if ctx.execution_process.status == ExecutionProcessStatus::Completed
+            && ctx.execution_process.exit_code == Some(0)
+        {
+            PipelineStepOutcome::Success
+        } else {
+            PipelineStepOutcome::Failure
+        };
+        let result = StepResult {
+            step: step_run.step_id.clone(),
+            outcome,
+            exit_code: ctx.execution_process.exit_code,
+            output: self.process_output_tail(&ctx.execution_process.id).await,
+        };
+
+        let history = PipelineStepRun::find_by_run_id(pool, run.id).await?;
+        let times_taken = |to: &str| {
+            history
+                .iter()
+                .filter(|s| s.step_id == to)
+                .filter_map(|s| s.triggered_by_id)
+                .filter_map(|id| history.iter().find(|s| s.id == id))
+                .filter(|s| s.step_id == result.step)
+                .count() as u32
+        };
+        // A step that cannot start, for example because the budget is spent, fails
+        // the run instead of leaving it to look complete
+        let mut start_failed = false;
+        for step in definition.next_steps(&result, times_taken) {
+            let running = |id: &str| {
+                history
+                    .iter()
+                    .any(|s| s.step_id == id && s.status == ExecutionProcessStatus::Running)
+            };
+            // Joins start once every step they need has run and none is still running;
+            // the branch that finishes last starts them
+            let waiting = step
+                .needs
+                .iter()
+                .any(|need| running(need) || !history.iter().any(|s| &s.step_id == need));
+            if waiting || running(&step.id) {
+                continue;
+            }
+            if let Err(e) = self
+                .start_pipeline_step(
+                    &ctx.workspace,
+                    &ctx.session,
+                    &run,
+                    step,
+                    Some((step_run, &result)),
+                )
+                .await
+            {
+                tracing::error!("Failed to start pipeline step {}: {}", step.id, e);
+                start_failed = true;
+            }
+        }
+
+        let steps = PipelineStepRun::find_by_run_id(pool, run.id).await?;
+        if steps
+            .iter()
+            .any(|s| s.status == ExecutionProcessStatus::Running)
+        {
+            return Ok(true);
+        }
+        // The run failed if any branch ended on a step that did not succeed
+        let failed = start_failed
+            || steps.iter().any(|s| {
+                (s.status != ExecutionProcessStatus::Completed || s.exit_code != Some(0))
+                    && !steps.iter().any(|next| next.triggered_by_id == Some(s.id))
+            });
+        let status = if failed {
+            PipelineRunStatus::Failed
+        } else {
+            PipelineRunStatus::Completed
+        };
+        let finished = PipelineRun::finish(pool, run.id, status).await?;
+        Ok(!finished)
+    }
+
+    /// Run the project's verification command after a successful coding agent turn.
+    /// The agent's next action (its cleanup scripts) follows the verification when
+    /// `start_next` is set. Returns whether verification started.
+    async fn try_start_verification(
+        &self,
+        ctx: &ExecutionContext,
+        start_next: bool,
+    ) -> Result<bool, ContainerError> {
+        let Some(command) = ctx.project.verification_command.clone() else {
+            return Ok(false);
+        };
+        let action = ctx.execution_process.executor_action()?;
+        let next_action = if start_next {
+            action.next_action.clone()
+        } else {
+            None
+        };
+        let working_dir = ctx
+            .project
+            .verification_working_dir
+            .clone()
+            .or_else(|| ctx.workspace.agent_working_dir.clone())
+            .filter(|dir| !dir.is_empty());
+        let verification = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: command,
+                context: ScriptContext::Verification,
+                working_dir,
+                options: Default::default(),
+            }),
+            next_action,
+        );
+
+        self.start_execution(
+            &ctx.workspace,
+            &ctx.session,
+            &verification,
+            &ExecutionProcessRunReason::CleanupScript,
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Record the result of a verification on the turn it checked. A passing
+    /// verification continues with its next action; a failing one sends the failure
+    /// back to the agent while retries are left. Returns true when a fix turn was
+    /// started, in which case the caller must not finalize the task.
+    async fn finish_verification(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        let status = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed if ctx.execution_process.exit_code == Some(0) => {
+                VerificationStatus::Passed
+            }
+            ExecutionProcessStatus::Killed | ExecutionProcessStatus::Running => return Ok(false),
+            _ => VerificationStatus::Failed,
+        };
+        let Some(turn) = CodingAgentTurn::find_latest_by_session_id(pool, ctx.session.id).await?
+        else {
+            return Ok(false);
+        };
+        CodingAgentTurn::set_verification_status(pool, turn.execution_process_id, &status).await?;
+
+        if status == VerificationStatus::Passed {
+            self.try_start_next_action(ctx).await?;
+            return Ok(false);
+        }
+        if turn.verification_retry >= ctx.project.verification_max_retries {
+            tracing::info!(
+                "Verification still failing after {} fix attempts in session {}",
+                turn.verification_retry,
+                ctx.session.id
+            );
+            return Ok(false);
+        }
+        let Some(agent_session_id) =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, ctx.session.id)
+                .await?
+        else {
+            return Ok(false);
+        };
+
+        let action = ctx.execution_process.executor_action()?;
+        let command = match action.typ() {
+            ExecutorActionType::ScriptRequest(request) => request.script.clone(),
+            _ => return Ok(false),
+        };
+        let prompt = verification::fix_prompt(
+            &command,
+            ctx.execution_process.exit_code,
+            &self.process_output_tail(&ctx.execution_process.id).await,
+        );
+        let executor_profile_id =
+            ExecutionProcess::latest_executor_profile_for_session(pool, ctx.session.id)
+                .await
+                .map_err(|e| anyhow!(e))?;
+        let working_dir = ctx
+            .workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+        let fix = ExecutorAction::new(
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt,
+                session_id: agent_session_id,
+                executor_profile_id,
+                working_dir,
+            }),
+            action.next_action.clone(),
+        );
+
+        let execution_process = match self
+            .start_execution(
+                &ctx.workspace,
+                &ctx.session,
+                &fix,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await
+        {
+            Ok(execution_process) => execution_process,
+            // The verification failure stands; the task is finalized as usual
+            Err(ContainerError::Usage(e @ UsageError::BudgetExceeded { .. })) => {
+                tracing::info!(
+                    "Skipping verification fix in session {}: {}",
+                    ctx.session.id,
+                    e
+                );
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
+        CodingAgentTurn::set_verification_retry(
+            pool,
+            execution_process.id,
+            turn.verification_retry + 1,
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Path of `repo` inside the workspace; branch-only workspaces work in the repo
+    /// itself.
+    fn workspace_repo_path(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+    ) -> Result<PathBuf, ContainerError> {
+        if workspace.is_branch_only() {
+            return Ok(repo.path.clone());
+        }
+        let container_ref = workspace
+            .container_ref
+            .as_ref()
+            .ok_or_else(|| anyhow!("Container ref not found for workspace {}", workspace.id))?;
+        Ok(PathBuf::from(container_ref).join(&repo.name))
+    }
+
+    /// Start an agent turn on `session` that resolves the conflicts of the
+    /// interrupted rebase or merge in `repo`. Returns the turn and the files it
+    /// has to resolve.
+    async fn start_conflict_resolution_turn(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        repo: &Repo,
+    ) -> Result<(ExecutionProcess, Vec<String>), ContainerError> {
+        let pool = &self.db().pool;
+        let worktree_path = self.workspace_repo_path(workspace, repo)?;
+        let op = self
+            .git()
+            .detect_conflict_op(&worktree_path)?
+            .ok_or_else(|| anyhow!("No rebase or merge in progress in {}", repo.name))?;
+        let conflicted_files = self.git().get_conflicted_files(&worktree_path)?;
+        if conflicted_files.is_empty() {
+            return Err(anyhow!("No conflicted files in {}", repo.name).into());
+        }
+        let Some(agent_session_id) =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?
+        else {
+            return Err(anyhow!("The session has no agent conversation to continue").into());
+        };
+        let target_branch =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo.id)
+                .await?
+                .map(|workspace_repo| workspace_repo.target_branch)
+                .unwrap_or_default();
+
+        let files = conflicted_files
+            .iter()
+            .map(|path| ConflictedFile {
+                path: path.clone(),
+                // Deleted on one side, or not text
+                hunks: std::fs::read_to_string(worktree_path.join(path))
+                    .map(|content| conflict_resolution::parse_conflict_hunks(&content))
+                    .unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        let prompt = conflict_resolution::resolve_prompt(
+            &op,
+            &repo.name,
+            &workspace.branch,
+            &target_branch,
+            &files,
+        );
+        let executor_profile_id =
+            ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                .await
+                .map_err(|e| anyhow!(e))?;
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+        // No cleanup script: the operation is continued once the turn ends
+        let action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt,
+                session_id: agent_session_id,
+                executor_profile_id,
+                working_dir,
+            }),
+            None,
+        );
+
+        let execution_process = self
+            .start_execution(
+                workspace,
+                session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?;
+        Ok((execution_process, conflicted_files))
+    }
+
+    /// Check the files of a finished conflict resolution turn and continue the
+    /// interrupted operation. Leftover conflict markers abort the operation; new
+    /// conflicts at a later rebased commit start another round while rounds are
+    /// left. Returns true when another round was started, in which case the caller
+    /// must not finalize the task.
+    async fn finish_conflict_resolution(
+        &self,
+        ctx: &ExecutionContext,
+        resolution: &ConflictResolution,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        let Some(repo) = ctx.repos.iter().find(|repo| repo.id == resolution.repo_id) else {
+            ConflictResolution::finish(
+                pool,
+                resolution.id,
+                ConflictResolutionStatus::Failed,
+                Some("The repository is no longer part of the workspace"),
+            )
+            .await?;
+            return Ok(false);
+        };
+        let worktree_path = self.workspace_repo_path(&ctx.workspace, repo)?;
+
+        let succeeded = ctx.execution_process.status == ExecutionProcessStatus::Completed
+            && ctx.execution_process.exit_code == Some(0);
+        if !succeeded {
+            // Leave the conflicts in place so they can be resolved by hand
+            ConflictResolution::finish(
+                pool,
+                resolution.id,
+                ConflictResolutionStatus::Failed,
+                Some("The agent turn did not complete; the conflicts are left as they were"),
+            )
+            .await?;
+            return Ok(false);
+        }
+
+        // Staged files no longer show up as conflicted, so check every file of the
+        // round as well as any still unmerged
+        let mut files = resolution.conflicted_files.0.clone();
+        for path in self
+            .git()
+            .get_conflicted_files(&worktree_path)
+            .unwrap_or_default()
+        {
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+        let leftover = files
+            .iter()
+            .filter(|path| {
+                std::fs::read_to_string(worktree_path.join(path))
+                    .map(|content| conflict_resolution::has_conflict_markers(&content))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if !leftover.is_empty() {
+            self.git().abort_conflicts(&worktree_path)?;
+            ConflictResolution::finish(
+                pool,
+                resolution.id,
+                ConflictResolutionStatus::Aborted,
+                Some(&format!(
+                    "Conflict markers were left in {}; the operation was aborted",
+                    leftover.join(", ")
+                )),
+            )
+            .await?;
+            return Ok(false);
+        }
+
+        let continued = self.git().continue_conflicts(&worktree_path);
+        if self.git().detect_conflict_op(&worktree_path)?.is_none() {
+            ConflictResolution::finish(
+                pool,
+                resolution.id,
+                ConflictResolutionStatus::Resolved,
+                None,
+            )
+            .await?;
+            return Ok(false);
+        }
+
+        let conflicted_files = self.git().get_conflicted_files(&worktree_path)?;
+        if conflicted_files.is_empty() {
+            // Stopped for another reason, e.g. a failing hook
+            let reason = continued
+                .err()
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "The operation stopped without conflicts".to_string());
+            self.git().abort_conflicts(&worktree_path)?;
+            ConflictResolution::finish(
+                pool,
+                resolution.id,
+                ConflictResolutionStatus::Failed,
+                Some(&format!("{reason}; the operation was aborted")),
+            )
+            .await?;
+            return Ok(false);
+        }
+        if resolution.rounds >= conflict_resolution::MAX_ROUNDS {
+            self.git().abort_conflicts(&worktree_path)?;
+            ConflictResolution::finish(
+                pool,
+                resolution.id,
+                ConflictResolutionStatus::Aborted,
+                Some(&format!(
+                    "Still conflicting after {} rounds; the operation was aborted",
+                    resolution.rounds
+                )),
+            )
+            .await?;
+            return Ok(false);
+        }
+
+        let (execution_process, conflicted_files) = self
+            .start_conflict_resolution_turn(&ctx.workspace, &ctx.session, repo)
+            .await?;
+        ConflictResolution::start_round(
+            pool,
+            resolution.id,
+            execution_process.id,
+            &conflicted_files,
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Move a stacked workspace onto its parent's current branch, or onto the
+    /// parent's target branch once the parent has merged. Conflicting rebases are
+    /// aborted and reported, leaving the branch as it was. The workspace leaves the
+    /// stack once none of its repos follow the parent any more.
+    async fn restack_workspace(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<Vec<RestackResult>, ContainerError> {
+        let pool = &self.db().pool;
+        let Some(parent_id) = workspace.stacked_on_workspace_id else {
+            return Ok(Vec::new());
+        };
+        let Some(parent) = Workspace::find_by_id(pool, parent_id).await? else {
+            Workspace::clear_stacked_on(pool, workspace.id).await?;
+            return Ok(Vec::new());
+        };
+
+        let mut results = Vec::new();
+        let mut still_stacked = false;
+        for workspace_repo in WorkspaceRepo::find_by_workspace_id(pool, workspace.id).await? {
+            let Some(base_commit) = workspace_repo.stack_base_commit.clone() else {
+                continue;
+            };
+            let Some(repo) = Repo::find_by_id(pool, workspace_repo.repo_id).await? else {
+                continue;
+            };
+            let parent_merged = Merge::find_by_workspace_and_repo_id(pool, parent.id, repo.id)
+                .await?
+                .iter()
+                .any(|merge| match merge {
+                    Merge::Direct(_) => true,
+                    Merge::Pr(pr) => matches!(pr.pr_info.status, MergeStatus::Merged),
+                });
+            let parent_tip = self.git().get_branch_oid(&repo.path, &parent.branch).ok();
+
+            let mut result = RestackResult {
+                repo_id: repo.id,
+                status: RestackStatus::UpToDate,
+                base_branch: parent.branch.clone(),
+                message: None,
+            };
+            let (new_base, next_base_commit) =
+                match stack::plan_restack(&base_commit, parent_tip.as_deref(), parent_merged) {
+                    RestackPlan::UpToDate => {
+                        still_stacked = true;
+                        results.push(result);
+                        continue;
+                    }
+                    RestackPlan::ParentMissing => {
+                        still_stacked = true;
+                        result.status = RestackStatus::Skipped;
+                        result.message = Some(format!(
+                            "Branch '{}' of the parent workspace no longer exists",
+                            parent.branch
+                        ));
+                        results.push(result);
+                        continue;
+                    }
+                    RestackPlan::OntoParent { parent_tip } => {
+                        (parent.branch.clone(), Some(parent_tip))
+                    }
+                    RestackPlan::OntoTarget => {
+                        match WorkspaceRepo::find_by_workspace_and_repo_id(pool, parent.id, repo.id)
+                            .await?
+                        {
+                            Some(parent_repo) => (parent_repo.target_branch, None),
+                            None => (workspace_repo.target_branch.clone(), None),
+                        }
+                    }
+                };
+            result.base_branch = new_base.clone();
+
+            let worktree_path = if workspace.is_branch_only() {
+                repo.path.clone()
+            } else {
+                PathBuf::from(self.ensure_container_exists(workspace).await?).join(&repo.name)
+            };
+            match self.git().rebase_branch(
+                &repo.path,
+                &worktree_path,
+                &new_base,
+                &base_commit,
+                &workspace.branch,
+            ) {
+                Ok(_) => {
+                    WorkspaceRepo::update_stack_base_commit(
+                        pool,
+                        workspace.id,
+                        repo.id,
+                        next_base_commit.as_deref(),
+                    )
+                    .await?;
+                    if next_base_commit.is_some() {
+                        still_stacked = true;
+                        result.status = RestackStatus::Rebased;
+                    } else {
+                        WorkspaceRepo::update_target_branch(pool, workspace.id, repo.id, &new_base)
+                            .await?;
+                        result.status = RestackStatus::Unstacked;
+                    }
+                }
+                Err(GitServiceError::MergeConflicts(message)) => {
+                    still_stacked = true;
+                    if let Err(e) = self.git().abort_conflicts(&worktree_path) {
+                        tracing::error!("Failed to abort conflicting restack: {}", e);
+                    }
+                    result.status = RestackStatus::Conflicts;
+                    result.message = Some(message);
+                }
+                Err(e) => {
+                    still_stacked = true;
+                    result.status = RestackStatus::Skipped;
+                    result.message = Some(e.to_string());
+                }
+            }
+            tracing::info!(
+                "Restacked {} of workspace {} onto '{}': {:?}",
+                repo.name,
+                workspace.id,
+                new_base,
+                result.status
+            );
+            results.push(result);
+        }
+
+        if !still_stacked {
+            Workspace::clear_stacked_on(pool, workspace.id).await?;
+        }
+        Ok(results)
+    }
+
+    /// Restack the idle workspaces stacked on `parent`, e.g. right after it was
+    /// rebased or merged. Failures are logged; the stack monitor retries later.
+    async fn restack_children(&self, parent: &Workspace) {
+        let pool = &self.db().pool;
+        let children = match Workspace::find_stacked_on(pool, parent.id).await {
+            Ok(children) => children,
+            Err(e) => {
+                tracing::error!("Failed to find workspaces stacked on {}: {}", parent.id, e);
+                return;
+            }
+        };
+        for child in children {
+            match ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                pool, child.id,
+            )
+            .await
+            {
+                Ok(false) => {}
+                Ok(true) => continue,
+                Err(e) => {
+                    tracing::error!("Failed to check processes of workspace {}: {}", child.id, e);
+                    continue;
+                }
+            }
+            if let Err(e) = self.restack_workspace(&child).await {
+                tracing::error!("Failed to restack workspace {}: {}", child.id, e);
+            }
+        }
+    }
+
+    /// Start an agent turn on the workspace's latest session that addresses
+    /// `comments` of pull request `pr_info` in `repo`. Once the turn ends, its commit
+    /// is pushed and, with `reply_to_comments`, every comment is answered with it.
+    async fn start_review_follow_up(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+        pr_info: &PullRequestInfo,
+        comments: &[UnifiedPrComment],
+        reply_to_comments: bool,
+    ) -> Result<ReviewFollowUp, ContainerError> {
+        let pool = &self.db().pool;
+        let worktree_path = self.workspace_repo_path(workspace, repo)?;
+        let base_commit = self.git().get_head_info(&worktree_path)?.oid;
+        let session = Session::find_latest_by_workspace_id(pool, workspace.id)
+            .await?
+            .ok_or_else(|| anyhow!("The workspace has no session to continue"))?;
+        let executor_profile_id =
+            ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                .await
+                .map_err(|e| anyhow!(e))?;
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let prompt = review_follow_up::follow_up_prompt(pr_info.number, &pr_info.url, comments);
+        let agent_session_id =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?;
+        let action_type = match agent_session_id {
+            Some(agent_session_id) => {
+                ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                    prompt,
+                    session_id: agent_session_id,
+                    executor_profile_id,
+                    working_dir,
+                })
+            }
+            None => ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt,
+                executor_profile_id,
+                working_dir,
+            }),
+        };
+        // No cleanup script: the commit is pushed once the turn ends
+        let action = ExecutorAction::new(action_type, None);
+
+        let execution_process = self
+            .start_execution(
+                workspace,
+                &session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?;
+        let follow_up = ReviewFollowUp::create(
+            pool,
+            &CreateReviewFollowUp {
+                workspace_id: workspace.id,
+                repo_id: repo.id,
+                pr_number: pr_info.number,
+                execution_process_id: execution_process.id,
+                base_commit: Some(base_commit),
+                reply_to_comments,
+                // Comments past the prompt's limit are left for the next follow-up
+                comments: comments
+                    .iter()
+                    .take(review_follow_up::MAX_PROMPT_COMMENTS)
+                    .map(review_follow_up::create_comment)
+                    .collect(),
+            },
+        )
+        .await?;
+        Ok(follow_up)
+    }
+
+    /// Push the commit of a finished review follow-up turn to the pull request
+    /// branch and answer the addressed comments when asked to. Failed replies are
+    /// noted on the follow-up but do not fail it.
+    async fn finish_review_follow_up(
+        &self,
+        ctx: &ExecutionContext,
+        follow_up: &ReviewFollowUp,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let Some(repo) = ctx.repos.iter().find(|repo| repo.id == follow_up.repo_id) else {
+            ReviewFollowUp::finish(
+                pool,
+                follow_up.id,
+                ReviewFollowUpStatus::Failed,
+                None,
+                Some("The repository is no longer part of the workspace"),
+            )
+            .await?;
+            return Ok(());
+        };
+        let succeeded = ctx.execution_process.status == ExecutionProcessStatus::Completed
+            && ctx.execution_process.exit_code == Some(0);
+        if !succeeded {
+            ReviewFollowUp::finish(
+                pool,
+                follow_up.id,
+                ReviewFollowUpStatus::Failed,
+                None,
+                Some("The agent turn did not complete; nothing was pushed"),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let worktree_path = self.workspace_repo_path(&ctx.workspace, repo)?;
+        let head = self.git().get_head_info(&worktree_path)?.oid;
+        if follow_up.base_commit.as_deref() == Some(head.as_str()) {
+            ReviewFollowUp::finish(
+                pool,
+                follow_up.id,
+                ReviewFollowUpStatus::NoChanges,
+                None,
+                Some("The agent made no changes"),
+            )
+            .await?;
+            return Ok(());
+        }
+        if let Err(e) = self
+            .git()
+            .push_to_github(&worktree_path, &ctx.workspace.branch, false)
+        {
+            ReviewFollowUp::finish(
+                pool,
+                follow_up.id,
+                ReviewFollowUpStatus::Failed,
+                Some(&head),
+                Some(&format!("Pushing {} failed: {e}", ctx.workspace.branch)),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let message = if follow_up.reply_to_comments {
+            self.reply_to_review_comments(repo, follow_up, &head)
+                .await
+                .err()
+                .map(|e| format!("Pushed, but replying to the comments failed: {e}"))
+        } else {
+            None
+        };
+        ReviewFollowUp::finish(
+            pool,
+            follow_up.id,
+            ReviewFollowUpStatus::Pushed,
+            Some(&head),
+            message.as_deref(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Answer every comment addressed by `follow_up` with the commit that addressed
+    /// it. Comments deleted in the meantime are skipped.
+    async fn reply_to_review_comments(
+        &self,
+        repo: &Repo,
+        follow_up: &ReviewFollowUp,
+        commit_sha: &str,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        // PRs recorded before other forges were supported are on GitHub
+        let merges =
+            Merge::find_by_workspace_and_repo_id(pool, follow_up.workspace_id, follow_up.repo_id)
+                .await?;
+        let forge_kind = merges.into_iter().find_map(|merge| match merge {
+            Merge::Pr(pr) if pr.pr_info.number == follow_up.pr_number => Some(pr.pr_info.forge),
+            _ => None,
+        });
+        let repo_info = ForgeRepoInfo::from_url(
+            &self.git().get_remote_url(&repo.path)?,
+            forge_kind,
+            repo.forge_url.as_deref(),
+        )
+        .map_err(|e| anyhow!(e))?;
+        let forge = forge_for(&repo_info).map_err(|e| anyhow!(e))?;
+        let comments = forge
+            .get_pr_comments(&repo_info, follow_up.pr_number)
+            .await
+            .map_err(|e| anyhow!(e))?;
+
+        let body = review_follow_up::reply_body(commit_sha);
+        for addressed in
+            ReviewFollowUpComment::find_by_review_follow_up_id(pool, follow_up.id).await?
+        {
+            let Some(comment) = comments.iter().find(|comment| {
+                review_follow_up::comment_ref(comment)
+                    == (addressed.comment_type, addressed.comment_id.clone())
+            }) else {
+                continue;
+            };
+            forge
+                .reply_to_comment(&repo_info, follow_up.pr_number, comment, &body)
+                .await
+                .map_err(|e| anyhow!(e))?;
+            ReviewFollowUpComment::mark_replied(pool, addressed.id).await?;
+        }
+        Ok(())
+    }
+
+    /// Decide how a failed coding agent turn continues under the fallback policy of
+    /// its profile. None when the failure is not transient, the profile has no
+    /// policy or its chain is exhausted.
+    async fn plan_fallback(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<FallbackPlan>, ContainerError> {
+        // Turns stopped for the budget would only be refused again
+        if ctx.execution_process.status != ExecutionProcessStatus::Failed
+            || ctx.execution_process.exit_reason == Some(ExecutionProcessExitReason::BudgetExceeded)
+        {
+            return Ok(None);
+        }
+        let action = ctx.execution_process.executor_action()?;
+        let (prompt, profile, working_dir) = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => (
+                &request.prompt,
+                &request.executor_profile_id,
+                &request.working_dir,
+            ),
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => (
+                &request.prompt,
+                &request.executor_profile_id,
+                &request.working_dir,
+            ),
+            ExecutorActionType::ScriptRequest(_) => return Ok(None),
+        };
+        let configs = ExecutorConfigs::get_cached();
+        let Some(policy) = configs.fallback_policy(profile) else {
+            return Ok(None);
+        };
+        let output = self.process_output_tail(&ctx.execution_process.id).await;
+        let Some(failure) = AgentFailure::classify(&output) else {
+            return Ok(None);
+        };
+        let Some(turn) = CodingAgentTurn::find_by_execution_process_id(
+            &self.db().pool,
+            ctx.execution_process.id,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let retries_done = u32::try_from(turn.fallback_retry).unwrap_or(u32::MAX);
+        let plan = match policy.next_step(profile, failure, retries_done) {
+            Some(FallbackStep::Retry { delay }) => {
+                let fallback_retry = turn.fallback_retry + 1;
+                FallbackPlan {
+                    action: action.clone(),
+                    delay,
+                    fallback_retry,
+                    verification_retry: turn.verification_retry,
+                    notice: fallback::retry_notice(
+                        profile,
+                        failure,
+                        delay,
+                        fallback_retry,
+                        policy.retries,
+                    ),
+                }
+            }
+            Some(FallbackStep::Switch(next)) => FallbackPlan {
+                // The next agent cannot resume another agent's session
+                action: ExecutorAction::new(
+                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                        prompt: fallback::handover_prompt(prompt),
+                        executor_profile_id: next.clone(),
+                        working_dir: working_dir.clone(),
+                    }),
+                    action.next_action.clone(),
+                ),
+                delay: Duration::ZERO,
+                fallback_retry: 0,
+                verification_retry: turn.verification_retry,
+                notice: fallback::switch_notice(profile, &next, failure),
+            },
+            None => {
+                tracing::info!(
+                    "No fallback left for {} after it {} in session {}",
+                    profile,
+                    failure.description(),
+                    ctx.session.id
+                );
+                return Ok(None);
+            }
+        };
+        Ok(Some(plan))
+    }
+
+    /// Start a planned retry or fallback once its delay has passed. Returns false
+    /// without starting anything when the session moved on in the meantime.
+    async fn start_fallback(
+        &self,
+        ctx: &ExecutionContext,
+        plan: &FallbackPlan,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        let latest = ExecutionProcess::find_latest_by_session_and_run_reason(
+            pool,
+            ctx.session.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+        if latest.map(|process| process.id) != Some(ctx.execution_process.id)
+            || ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                pool,
+                ctx.workspace.id,
+            )
+            .await?
+        {
+            return Ok(false);
+        }
+
+        let execution_process = self
+            .start_linked_execution(
+                &ctx.workspace,
+                &ctx.session,
+                &plan.action,
+                &ExecutionProcessRunReason::CodingAgent,
+                None,
+                Some(&plan.notice),
+            )
+            .await?;
+        CodingAgentTurn::set_fallback_retry(pool, execution_process.id, plan.fallback_retry)
+            .await?;
+        if plan.verification_retry > 0 {
+            CodingAgentTurn::set_verification_retry(
+                pool,
+                execution_process.id,
+                plan.verification_retry,
+            )
+            .await?;
+        }
+        Ok(true)
+    }
 }
@@ -1,12 +1,10 @@
-use std::{collections::HashMap, str::FromStr, sync::Arc};
+use std::{str::FromStr, sync::Arc};
 
 use db::{
     DBService,
     models::{
-        execution_process::ExecutionProcess, project::Project, scratch::Scratch,
-        task::{Task, TaskStatus},
-        webhook::WebhookEvent,
-        workspace::Workspace,
+        execution_process::ExecutionProcess, project::Project, scratch::Scratch, task::Task,
+        task_dependency::TaskDependency, workspace::Workspace,
     },
 };
 use serde_json::json;
@@ -23,6 +21,8 @@ pub mod patches;
 mod streams;
 #[path = "events/types.rs"]
 pub mod types;
+#[path = "events/webhook_events.rs"]
+mod webhook_events;
 
 pub use patches::{
     execution_process_patch, project_patch, scratch_patch, task_patch, workspace_patch,
@@ -83,10 +83,9 @@ impl EventService {
 
     /// Creates the hook function that should be used with DBService::new_with_after_connect
     ///
-    /// If `webhook_service` is provided, webhooks will be triggered on database changes:
-    /// - Task insert -> TaskCreated
-    /// - Task update -> TaskUpdated (and TaskCompleted if status changes to Done)
-    /// - Workspace insert -> WorkspaceStarted
+    /// If `webhook_service` is provided, every webhook event implied by a row change
+    /// (tasks, workspaces, execution processes, pull requests, comments and mentions)
+    /// is queued from here, whichever code path made the change.
     pub fn create_hook(
         msg_store: Arc<MsgStore>,
         entry_count: Arc<RwLock<usize>>,
@@ -99,42 +98,29 @@ impl EventService {
     > + Send
     + Sync
     + 'static {
-        // Shared map to track old task status for detecting status changes
-        // Key: task_id, Value: (project_id, old_status)
-        let old_task_status_map: Arc<std::sync::RwLock<HashMap<Uuid, (Uuid, String)>>> =
-            Arc::new(std::sync::RwLock::new(HashMap::new()));
+        // Old column values of updated rows, so webhooks can tell what changed
+        let previous_values = Arc::new(webhook_events::PreviousValues::default());
 
         move |conn: &mut sqlx::sqlite::SqliteConnection| {
             let msg_store_for_hook = msg_store.clone();
             let entry_count_for_hook = entry_count.clone();
             let db_for_hook = db_service.clone();
             let webhook_service_for_hook = webhook_service.clone();
-            let old_status_map_for_hook = old_task_status_map.clone();
+            let previous_values_for_hook = previous_values.clone();
             Box::pin(async move {
                 let mut handle = conn.lock_handle().await?;
                 let runtime_handle = tokio::runtime::Handle::current();
 
                 // Clone for preupdate hook
-                let old_status_map_for_preupdate = old_status_map_for_hook.clone();
+                let previous_values_for_preupdate = previous_values_for_hook.clone();
+                let track_previous_values = webhook_service_for_hook.is_some();
 
                 handle.set_preupdate_hook({
                     let msg_store_for_preupdate = msg_store_for_hook.clone();
                     move |preupdate: sqlx::sqlite::PreupdateHookResult<'_>| {
-                        // For task updates, capture the old status before it changes
-                        if preupdate.operation == SqliteOperation::Update
-                            && preupdate.table == "tasks"
-                            && let Ok(id_val) = preupdate.get_old_column_value(0)
-                            && let Ok(task_id) = <Uuid as Decode<Sqlite>>::decode(id_val)
-                            && let Ok(project_id_val) = preupdate.get_old_column_value(1)
-                            && let Ok(project_id) =
-                                <Uuid as Decode<Sqlite>>::decode(project_id_val)
-                            && let Ok(status_val) = preupdate.get_old_column_value(4)
-                            && let Ok(old_status) =
-                                <String as Decode<Sqlite>>::decode(status_val)
-                            && let Ok(mut map) = old_status_map_for_preupdate.write()
-                        {
-                            // Column indices: 0=id, 1=project_id, 4=status
-                            map.insert(task_id, (project_id, old_status));
+                        // For updates, capture the old values before they change
+                        if track_previous_values {
+                            previous_values_for_preupdate.record(&preupdate);
                         }
 
                         // Handle deletes as before
@@ -198,8 +184,27 @@ impl EventService {
                     let entry_count_for_hook = entry_count_for_hook.clone();
                     let msg_store_for_hook = msg_store_for_hook.clone();
                     let db = db_for_hook.clone();
-                    let webhook_service = webhook_service_for_hook.clone();
-                    let old_status_map = old_status_map_for_hook.clone();
+
+                    if let Some(webhook_service) = webhook_service_for_hook.clone()
+                        && webhook_events::watches(hook.table)
+                    {
+                        let pool = db.pool.clone();
+                        let previous = previous_values_for_hook.take(hook.table, hook.rowid);
+                        let table = hook.table.to_string();
+                        let operation = hook.operation.clone();
+                        let rowid = hook.rowid;
+                        runtime_handle.spawn(async move {
+                            webhook_events::emit(
+                                &webhook_service,
+                                &pool,
+                                &table,
+                                operation,
+                                rowid,
+                                previous,
+                            )
+                            .await;
+                        });
+                    }
 
                     if let Ok(table) = HookTables::from_str(hook.table) {
                         let rowid = hook.rowid;
@@ -320,81 +325,26 @@ impl EventService {
                                         };
                                         msg_store_for_hook.push_patch(patch);
 
-                                        // Trigger webhooks for task changes
-                                        if let Some(ref webhook_svc) = webhook_service {
-                                            let task_data = serde_json::to_value(task).unwrap_or_default();
-
-                                            match hook.operation {
-                                                SqliteOperation::Insert => {
-                                                    // Task created
-                                                    if let Err(e) = webhook_svc
-                                                        .trigger_event(
-                                                            task.project_id,
-                                                            &WebhookEvent::TaskCreated,
-                                                            task_data,
-                                                        )
-                                                        .await
-                                                    {
-                                                        tracing::error!(
-                                                            "Failed to trigger TaskCreated webhook: {:?}",
-                                                            e
-                                                        );
-                                                    }
-                                                }
-                                                SqliteOperation::Update => {
-                                                    // Check if status changed to Done (task completed)
-                                                    let old_status_opt = old_status_map
-                                                        .read()
-                                                        .ok()
-                                                        .and_then(|map| map.get(&task.id).cloned());
-
-                                                    // Clean up the old status entry
-                                                    if let Ok(mut map) = old_status_map.write() {
-                                                        map.remove(&task.id);
-                                                    }
-
-                                                    // Trigger TaskUpdated
-                                                    if let Err(e) = webhook_svc
-                                                        .trigger_event(
-                                                            task.project_id,
-                                                            &WebhookEvent::TaskUpdated,
-                                                            task_data.clone(),
-                                                        )
-                                                        .await
-                                                    {
-                                                        tracing::error!(
-                                                            "Failed to trigger TaskUpdated webhook: {:?}",
-                                                            e
-                                                        );
-                                                    }
-
-                                                    // Check if task was completed (status changed to Done)
-                                                    // Only trigger TaskCompleted if old status was not Done
-                                                    let was_already_done = old_status_opt
-                                                        .as_ref()
-                                                        .is_some_and(|(_, old_status)| {
-                                                            old_status == "done"
-                                                        });
-
-                                                    if task.status == TaskStatus::Done
-                                                        && !was_already_done
-                                                    {
-                                                        if let Err(e) = webhook_svc
-                                                            .trigger_event(
-                                                                task.project_id,
-                                                                &WebhookEvent::TaskCompleted,
-                                                                task_data,
-                                                            )
-                                                            .await
-                                                        {
-                                                            tracing::error!(
-                                                                "Failed to trigger TaskCompleted webhook: {:?}",
-                                                                e
-                                                            );
-                                                        }
-                                                    }
+                                        // Dependents' blocked flag may have changed with this task's status
+                                        if matches!(hook.operation, SqliteOperation::Update)
+                                            && let Ok(dependents) =
+                                                TaskDependency::find_dependents(&db.pool, task.id)
+                                                    .await
+                                        {
+                                            for dependent in dependents {
+                                                if let Err(e) = Self::push_task_update_for_task(
+                                                    &db.pool,
+                                                    msg_store_for_hook.clone(),
+                                                    dependent.id,
+                                                )
+                                                .await
+                                                {
+                                                    tracing::error!(
+                                                        "Failed to push update for dependent task {}: {:?}",
+                                                        dependent.id,
+                                                        e
+                                                    );
                                                 }
-                                                _ => {}
                                             }
                                         }
 
@@ -452,27 +402,6 @@ impl EventService {
                                         let patch = task_patch::replace(&task_with_status);
                                         msg_store_for_hook.push_patch(patch);
 
-                                        // Trigger WorkspaceStarted webhook on workspace insert
-                                        if hook.operation == SqliteOperation::Insert
-                                            && let Some(ref webhook_svc) = webhook_service
-                                        {
-                                            let workspace_data =
-                                                serde_json::to_value(workspace).unwrap_or_default();
-                                            if let Err(e) = webhook_svc
-                                                .trigger_event(
-                                                    task.project_id,
-                                                    &WebhookEvent::WorkspaceStarted,
-                                                    workspace_data,
-                                                )
-                                                .await
-                                            {
-                                                tracing::error!(
-                                                    "Failed to trigger WorkspaceStarted webhook: {:?}",
-                                                    e
-                                                );
-                                            }
-                                        }
-
                                         return;
                                     }
                                 }
@@ -584,6 +513,11 @@ impl EventService {
         }
     }
 
+    /// Push a fresh task_patch for a task whose derived state changed outside the tasks table
+    pub async fn push_task_update(&self, task_id: Uuid) -> Result<(), SqlxError> {
+        Self::push_task_update_for_task(&self.db.pool, self.msg_store.clone(), task_id).await
+    }
+
     pub fn msg_store(&self) -> &Arc<MsgStore> {
         &self.msg_store
     }
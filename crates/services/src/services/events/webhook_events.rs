@@ -0,0 +1,351 @@
+//! Webhook events that follow from database changes.
+//!
+//! Every event that a row change implies is queued here, from the same hooks that
+//! feed the event stream. Tasks, comments, pull requests and processes changed
+//! through the UI, the MCP server, the scheduler or an automation therefore all
+//! notify the same way. Approval events have no row to hook and are queued by the
+//! approvals service.
+
+use std::{collections::HashMap, str::FromStr, sync::Mutex};
+
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    merge::{Merge, MergeStatus},
+    task::{Task, TaskStatus},
+    task_comment::TaskComment,
+    webhook::WebhookEvent,
+    workspace::Workspace,
+};
+use sqlx::{
+    Decode, Sqlite, SqlitePool, ValueRef,
+    sqlite::{PreupdateHookResult, SqliteOperation},
+};
+
+use crate::services::webhooks::{
+    WebhookService,
+    payloads::{
+        ExecutionEventPayload, PullRequestEventPayload, TaskAssignedPayload,
+        TaskCommentAddedPayload, TaskCommentMentionedPayload,
+    },
+};
+
+/// Tables whose changes can trigger a webhook
+const WATCHED_TABLES: [&str; 6] = [
+    "tasks",
+    "workspaces",
+    "execution_processes",
+    "merges",
+    "task_comments",
+    "task_comment_mentions",
+];
+
+pub fn watches(table: &str) -> bool {
+    WATCHED_TABLES.contains(&table)
+}
+
+/// Column values of a row before an update
+pub enum Previous {
+    Task {
+        status: String,
+        assignee: Option<String>,
+    },
+    ProcessStatus(String),
+    PrStatus(Option<String>),
+}
+
+/// Old values of rows being updated, captured by the preupdate hook and taken by
+/// the update hook that follows for the same row, so webhooks can tell what changed.
+#[derive(Default)]
+pub struct PreviousValues {
+    rows: Mutex<HashMap<(&'static str, i64), Previous>>,
+}
+
+fn decode_text(preupdate: &PreupdateHookResult<'_>, column: i32) -> Option<Option<String>> {
+    let value = preupdate.get_old_column_value(column).ok()?;
+    if value.is_null() {
+        return Some(None);
+    }
+    <String as Decode<Sqlite>>::decode(value).ok().map(Some)
+}
+
+impl PreviousValues {
+    /// Remember the old values of an updated row. Column indices follow the table
+    /// definitions after all migrations.
+    pub fn record(&self, preupdate: &PreupdateHookResult<'_>) {
+        if preupdate.operation != SqliteOperation::Update {
+            return;
+        }
+        let Ok(rowid) = preupdate.get_old_row_id() else {
+            return;
+        };
+        let (table, previous) = match preupdate.table {
+            // 4=status, 9=assignee
+            "tasks" => {
+                let (Some(Some(status)), Some(assignee)) =
+                    (decode_text(preupdate, 4), decode_text(preupdate, 9))
+                else {
+                    return;
+                };
+                ("tasks", Previous::Task { status, assignee })
+            }
+            // 4=status
+            "execution_processes" => {
+                let Some(Some(status)) = decode_text(preupdate, 4) else {
+                    return;
+                };
+                ("execution_processes", Previous::ProcessStatus(status))
+            }
+            // 6=pr_status
+            "merges" => {
+                let Some(status) = decode_text(preupdate, 6) else {
+                    return;
+                };
+                ("merges", Previous::PrStatus(status))
+            }
+            _ => return,
+        };
+        if let Ok(mut rows) = self.rows.lock() {
+            rows.insert((table, rowid), previous);
+        }
+    }
+
+    /// Take the old values recorded for a row, if the change was an update
+    pub fn take(&self, table: &str, rowid: i64) -> Option<Previous> {
+        let table = WATCHED_TABLES.iter().find(|watched| **watched == table)?;
+        self.rows.lock().ok()?.remove(&(*table, rowid))
+    }
+}
+
+/// Queue the webhooks implied by a change to a watched table
+pub async fn emit(
+    webhooks: &WebhookService,
+    pool: &SqlitePool,
+    table: &str,
+    operation: SqliteOperation,
+    rowid: i64,
+    previous: Option<Previous>,
+) {
+    let result = match table {
+        "tasks" => task_changed(webhooks, pool, operation, rowid, previous).await,
+        "workspaces" if operation == SqliteOperation::Insert => {
+            workspace_created(webhooks, pool, rowid).await
+        }
+        "execution_processes" if operation == SqliteOperation::Update => {
+            process_updated(webhooks, pool, rowid, previous).await
+        }
+        "merges" => merge_changed(webhooks, pool, operation, rowid, previous).await,
+        "task_comments" if operation == SqliteOperation::Insert => {
+            comment_created(webhooks, pool, rowid).await
+        }
+        "task_comment_mentions" if operation == SqliteOperation::Insert => {
+            mention_created(webhooks, pool, rowid).await
+        }
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        tracing::error!("Failed to queue webhooks for a change to {}: {}", table, e);
+    }
+}
+
+type EmitResult = Result<(), anyhow::Error>;
+
+async fn task_changed(
+    webhooks: &WebhookService,
+    pool: &SqlitePool,
+    operation: SqliteOperation,
+    rowid: i64,
+    previous: Option<Previous>,
+) -> EmitResult {
+    let Some(task) = Task::find_by_rowid(pool, rowid).await? else {
+        return Ok(());
+    };
+    match operation {
+        SqliteOperation::Insert => {
+            webhooks
+                .trigger_task_event(&WebhookEvent::TaskCreated, &task, None)
+                .await?;
+        }
+        SqliteOperation::Update => {
+            let (previous_status, previous_assignee) = match previous {
+                Some(Previous::Task { status, assignee }) => {
+                    (TaskStatus::from_str(&status).ok(), Some(assignee))
+                }
+                _ => (None, None),
+            };
+
+            webhooks
+                .trigger_task_event(&WebhookEvent::TaskUpdated, &task, previous_status.clone())
+                .await?;
+
+            if task.status == TaskStatus::Done && previous_status != Some(TaskStatus::Done) {
+                webhooks
+                    .trigger_task_event(&WebhookEvent::TaskCompleted, &task, previous_status)
+                    .await?;
+            }
+
+            if let Some(previous_assignee) = previous_assignee
+                && previous_assignee != task.assignee
+            {
+                let payload = TaskAssignedPayload {
+                    task_id: task.id,
+                    project_id: task.project_id,
+                    previous_assignee,
+                    assignee: task.assignee.clone(),
+                    task: task.clone(),
+                };
+                webhooks
+                    .trigger_payload(task.project_id, &WebhookEvent::TaskAssigned, &payload)
+                    .await?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+async fn workspace_created(webhooks: &WebhookService, pool: &SqlitePool, rowid: i64) -> EmitResult {
+    let Some(workspace) = Workspace::find_by_rowid(pool, rowid).await? else {
+        return Ok(());
+    };
+    let Some(task) = Task::find_by_id(pool, workspace.task_id).await? else {
+        return Ok(());
+    };
+    webhooks
+        .trigger_event(
+            task.project_id,
+            &WebhookEvent::WorkspaceStarted,
+            serde_json::to_value(&workspace)?,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Processes that leave `running` as completed or failed. Processes stopped by the
+/// user are reported as killed and are skipped.
+async fn process_updated(
+    webhooks: &WebhookService,
+    pool: &SqlitePool,
+    rowid: i64,
+    previous: Option<Previous>,
+) -> EmitResult {
+    if !matches!(&previous, Some(Previous::ProcessStatus(status)) if status == "running") {
+        return Ok(());
+    }
+    let Some(process) = ExecutionProcess::find_by_rowid(pool, rowid).await? else {
+        return Ok(());
+    };
+    let event = match process.status {
+        ExecutionProcessStatus::Completed => WebhookEvent::ExecutionCompleted,
+        ExecutionProcessStatus::Failed => WebhookEvent::ExecutionFailed,
+        _ => return Ok(()),
+    };
+
+    let ctx = ExecutionProcess::load_context(pool, process.id).await?;
+    let summary = CodingAgentTurn::find_by_execution_process_id(pool, process.id)
+        .await?
+        .and_then(|turn| turn.summary);
+    let payload = ExecutionEventPayload::from_context(&ctx, summary);
+    webhooks
+        .trigger_payload(ctx.project.id, &event, &payload)
+        .await?;
+    Ok(())
+}
+
+/// Pull requests that are recorded, merged or closed
+async fn merge_changed(
+    webhooks: &WebhookService,
+    pool: &SqlitePool,
+    operation: SqliteOperation,
+    rowid: i64,
+    previous: Option<Previous>,
+) -> EmitResult {
+    let Some(Merge::Pr(pr_merge)) = Merge::find_by_rowid(pool, rowid).await? else {
+        return Ok(());
+    };
+    let status = match pr_merge.pr_info.status {
+        MergeStatus::Open => "open",
+        MergeStatus::Merged => "merged",
+        MergeStatus::Closed => "closed",
+        MergeStatus::Unknown => "unknown",
+    };
+    // Updates only count when the status itself changed
+    let changed = match operation {
+        SqliteOperation::Insert => true,
+        SqliteOperation::Update => match previous {
+            Some(Previous::PrStatus(previous)) => previous.as_deref() != Some(status),
+            _ => false,
+        },
+        _ => false,
+    };
+    let event = match status {
+        _ if !changed => return Ok(()),
+        "open" if operation == SqliteOperation::Insert => WebhookEvent::PrCreated,
+        "merged" => WebhookEvent::PrMerged,
+        "closed" => WebhookEvent::PrClosed,
+        _ => return Ok(()),
+    };
+
+    let Some(workspace) = Workspace::find_by_id(pool, pr_merge.workspace_id).await? else {
+        return Ok(());
+    };
+    let Some(task) = workspace.parent_task(pool).await? else {
+        return Ok(());
+    };
+    let payload = PullRequestEventPayload::new(
+        &task,
+        pr_merge.workspace_id,
+        pr_merge.repo_id,
+        &pr_merge.target_branch_name,
+        &pr_merge.pr_info,
+    );
+    webhooks
+        .trigger_payload(task.project_id, &event, &payload)
+        .await?;
+    Ok(())
+}
+
+async fn comment_created(webhooks: &WebhookService, pool: &SqlitePool, rowid: i64) -> EmitResult {
+    let Some(comment) = TaskComment::find_by_rowid(pool, rowid).await? else {
+        return Ok(());
+    };
+    let Some(task) = Task::find_by_id(pool, comment.task_id).await? else {
+        return Ok(());
+    };
+    let payload = TaskCommentAddedPayload {
+        task_id: task.id,
+        project_id: task.project_id,
+        comment,
+    };
+    webhooks
+        .trigger_payload(task.project_id, &WebhookEvent::TaskCommentAdded, &payload)
+        .await?;
+    Ok(())
+}
+
+/// Mentions are stored once per comment and name, so every inserted row is new
+async fn mention_created(webhooks: &WebhookService, pool: &SqlitePool, rowid: i64) -> EmitResult {
+    let Some(mention) = TaskComment::find_mention_by_rowid(pool, rowid).await? else {
+        return Ok(());
+    };
+    let Some(comment) = TaskComment::find_by_id(pool, mention.comment_id).await? else {
+        return Ok(());
+    };
+    let Some(task) = Task::find_by_id(pool, comment.task_id).await? else {
+        return Ok(());
+    };
+    let payload = TaskCommentMentionedPayload {
+        task_id: task.id,
+        project_id: task.project_id,
+        comment,
+        mentions: vec![mention],
+    };
+    webhooks
+        .trigger_payload(
+            task.project_id,
+            &WebhookEvent::TaskCommentMentioned,
+            &payload,
+        )
+        .await?;
+    Ok(())
+}
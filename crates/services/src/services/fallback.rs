@@ -0,0 +1,90 @@
+//! Automatic retries and executor fallbacks after transient agent failures.
+//!
+//! When a coding agent turn fails and the end of its output points at a rate limit,
+//! an overloaded API, a network error or an expired login, the fallback policy of
+//! its profile decides whether the same profile runs again after a backoff or the
+//! next profile of the chain takes over in the same workspace.
+
+use std::time::Duration;
+
+use executors::{actions::ExecutorAction, fallback::AgentFailure, profile::ExecutorProfileId};
+
+/// A retry or switch decided when a coding agent turn failed, started once its
+/// delay has passed
+#[derive(Debug, Clone)]
+pub struct FallbackPlan {
+    pub action: ExecutorAction,
+    pub delay: Duration,
+    /// Retries of the action's profile in a row, recorded on the new turn
+    pub fallback_retry: i64,
+    /// Carried over so a retried verification fix keeps its place in the budget
+    pub verification_retry: i64,
+    /// Conversation entry explaining why the turn runs
+    pub notice: String,
+}
+
+/// Prompt for the profile taking over from a failed one. The new agent has no
+/// access to the previous agent's conversation, only to its changes.
+pub fn handover_prompt(prompt: &str) -> String {
+    format!(
+        "Another coding agent started working on the request below but stopped because \
+         of a service failure. Any changes it made are already in the working tree; \
+         review them and continue from there.\n\n{prompt}"
+    )
+}
+
+/// Notice for running the same profile again
+pub fn retry_notice(
+    profile: &ExecutorProfileId,
+    failure: AgentFailure,
+    delay: Duration,
+    retry: i64,
+    max_retries: u32,
+) -> String {
+    format!(
+        "{profile} {}. Retried after {}s (retry {retry} of {max_retries}).",
+        failure.description(),
+        delay.as_secs()
+    )
+}
+
+/// Notice for switching to the next profile of the chain
+pub fn switch_notice(
+    previous: &ExecutorProfileId,
+    next: &ExecutorProfileId,
+    failure: AgentFailure,
+) -> String {
+    format!(
+        "{previous} {}. Falling back to {next}, which continues in the same workspace.",
+        failure.description()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+
+    #[test]
+    fn notices_name_profiles_and_cause() {
+        let claude = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+        let codex = ExecutorProfileId::with_variant(BaseCodingAgent::Codex, "HIGH".into());
+
+        assert_eq!(
+            retry_notice(
+                &claude,
+                AgentFailure::RateLimited,
+                Duration::from_secs(60),
+                2,
+                3
+            ),
+            "CLAUDE_CODE hit a rate limit. Retried after 60s (retry 2 of 3)."
+        );
+        assert_eq!(
+            switch_notice(&claude, &codex, AgentFailure::AuthExpired),
+            "CLAUDE_CODE is no longer logged in. Falling back to CODEX:HIGH, which continues in the same workspace."
+        );
+        assert!(handover_prompt("Fix the login form").ends_with("\n\nFix the login form"));
+    }
+}
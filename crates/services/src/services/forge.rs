@@ -0,0 +1,530 @@
+//! Pull and merge requests across forges.
+//!
+//! The [`Forge`] trait covers what the PR routes and the PR monitor need from a
+//! forge. GitHub goes through the `gh` CLI ([`GitHubService`]); GitLab and Gitea
+//! are called over their REST APIs with a personal access token taken from
+//! `GITLAB_TOKEN` or `GITEA_TOKEN`. Which forge hosts a repo comes from the repo's
+//! settings, or is guessed from the host name of its remote URL.
+
+use std::{collections::HashSet, time::Duration};
+
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+use db::models::{
+    merge::{ForgeKind, PullRequestInfo},
+    repo::Repo,
+};
+use reqwest::{Method, StatusCode};
+use serde::{Serialize, de::DeserializeOwned};
+use thiserror::Error;
+use url::Url;
+
+use crate::services::github::{
+    CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError, UnifiedPrComment,
+};
+
+mod gitea;
+mod gitlab;
+
+pub use gitea::{GITEA_TOKEN_ENV, GiteaForge};
+pub use gitlab::{GITLAB_TOKEN_ENV, GitLabForge};
+
+#[derive(Debug, Error)]
+pub enum ForgeError {
+    #[error(transparent)]
+    GitHub(#[from] GitHubServiceError),
+    #[error("Repository error: {0}")]
+    Repository(String),
+    #[error("No {} token found. Set {env_var} to a personal access token.", .forge.display_name())]
+    MissingToken {
+        forge: ForgeKind,
+        env_var: &'static str,
+    },
+    #[error("{} authentication failed: {message}", .forge.display_name())]
+    AuthFailed { forge: ForgeKind, message: String },
+    #[error("{} API error ({status}): {message}", .forge.display_name())]
+    Api {
+        forge: ForgeKind,
+        status: StatusCode,
+        message: String,
+    },
+    #[error("{} request failed: {source}", .forge.display_name())]
+    Transport {
+        forge: ForgeKind,
+        #[source]
+        source: reqwest::Error,
+    },
+}
+
+impl ForgeError {
+    pub fn should_retry(&self) -> bool {
+        match self {
+            ForgeError::GitHub(err) => err.should_retry(),
+            ForgeError::Api { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            ForgeError::Transport { .. } => true,
+            ForgeError::Repository(_)
+            | ForgeError::MissingToken { .. }
+            | ForgeError::AuthFailed { .. } => false,
+        }
+    }
+
+    /// The access token is missing or was rejected
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self,
+            ForgeError::MissingToken { .. } | ForgeError::AuthFailed { .. }
+        )
+    }
+}
+
+/// A repository on a forge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeRepoInfo {
+    pub forge: ForgeKind,
+    /// Web root of the forge, e.g. `https://gitlab.example.com`
+    pub base_url: String,
+    /// Owner or, on GitLab, the full namespace path
+    pub owner: String,
+    pub repo_name: String,
+}
+
+impl ForgeRepoInfo {
+    /// Forge repository of a local repo, from its settings and remote URL
+    pub fn for_repo(repo: &Repo, remote_url: &str) -> Result<Self, ForgeError> {
+        Self::from_url(remote_url, repo.forge, repo.forge_url.as_deref())
+    }
+
+    /// Parse a remote or pull request URL. SSH, scp-like and HTTP(S) remotes are
+    /// supported, as are pull request URLs of each forge.
+    pub fn from_url(
+        url: &str,
+        forge: Option<ForgeKind>,
+        forge_url: Option<&str>,
+    ) -> Result<Self, ForgeError> {
+        let (host, web_root, path) = split_url(url)?;
+        let forge = match forge {
+            Some(forge) => forge,
+            None => detect_forge(&host).ok_or_else(|| {
+                ForgeError::Repository(format!(
+                    "Could not tell which forge hosts {url}. Choose one in the repository settings."
+                ))
+            })?,
+        };
+        let base_url = forge_url
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+            .unwrap_or(web_root);
+
+        // Forges installed under a sub path have it in front of the repo path
+        let mut path = path.as_str();
+        if let Some(prefix) = Url::parse(&base_url)
+            .ok()
+            .map(|url| url.path().trim_matches('/').to_string())
+            .filter(|prefix| !prefix.is_empty())
+        {
+            path = path.strip_prefix(&format!("{prefix}/")).unwrap_or(path);
+        }
+
+        let segments: Vec<&str> = strip_pr_suffix(path)
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let (owner, repo_name) = match (forge, segments.as_slice()) {
+            // GitLab projects can sit in nested groups
+            (ForgeKind::GitLab, [namespace @ .., repo_name]) if !namespace.is_empty() => {
+                (namespace.join("/"), repo_name.to_string())
+            }
+            (ForgeKind::GitHub | ForgeKind::Gitea, [owner, repo_name, ..]) => {
+                (owner.to_string(), repo_name.to_string())
+            }
+            _ => {
+                return Err(ForgeError::Repository(format!(
+                    "Invalid {} repository URL: {url}",
+                    forge.display_name()
+                )));
+            }
+        };
+
+        Ok(Self {
+            forge,
+            base_url,
+            owner,
+            repo_name,
+        })
+    }
+
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.repo_name)
+    }
+}
+
+/// Host, web root and repository path of a remote or web URL
+fn split_url(url: &str) -> Result<(String, String, String), ForgeError> {
+    let url = url.trim();
+    let invalid = || ForgeError::Repository(format!("Invalid remote URL: {url}"));
+
+    if url.contains("://") {
+        let parsed = Url::parse(url).map_err(|_| invalid())?;
+        let host = parsed.host_str().ok_or_else(invalid)?.to_string();
+        let web_root = match parsed.scheme() {
+            "http" | "https" => match parsed.port() {
+                Some(port) => format!("{}://{host}:{port}", parsed.scheme()),
+                None => format!("{}://{host}", parsed.scheme()),
+            },
+            // SSH ports say nothing about the web port
+            _ => format!("https://{host}"),
+        };
+        return Ok((host, web_root, parsed.path().trim_matches('/').to_string()));
+    }
+
+    // scp-like syntax: [user@]host:path
+    let (authority, path) = url.split_once(':').ok_or_else(invalid)?;
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    if host.is_empty() || path.is_empty() {
+        return Err(invalid());
+    }
+    Ok((
+        host.to_string(),
+        format!("https://{host}"),
+        path.trim_matches('/').to_string(),
+    ))
+}
+
+/// Drop the pull request part of a web URL, e.g. `/-/merge_requests/12`
+fn strip_pr_suffix(path: &str) -> &str {
+    ["/-/", "/pull/", "/pulls/"]
+        .iter()
+        .find_map(|marker| path.find(marker).map(|index| &path[..index]))
+        .unwrap_or(path)
+}
+
+fn detect_forge(host: &str) -> Option<ForgeKind> {
+    let host = host.to_ascii_lowercase();
+    if host.contains("github") {
+        Some(ForgeKind::GitHub)
+    } else if host.contains("gitlab") {
+        Some(ForgeKind::GitLab)
+    } else if ["gitea", "forgejo", "codeberg"]
+        .iter()
+        .any(|name| host.contains(name))
+    {
+        Some(ForgeKind::Gitea)
+    } else {
+        None
+    }
+}
+
+/// Operations on pull requests (merge requests on GitLab)
+#[async_trait]
+pub trait Forge: Send + Sync {
+    fn kind(&self) -> ForgeKind;
+
+    /// Check the forge can be used, e.g. before pushing a branch
+    async fn check_auth(&self) -> Result<(), ForgeError>;
+
+    async fn create_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError>;
+
+    /// Current state of a pull request
+    async fn get_pr_status(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<PullRequestInfo, ForgeError>;
+
+    /// All pull requests from a branch, including closed and merged ones
+    async fn list_prs_for_branch(
+        &self,
+        repo: &ForgeRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError>;
+
+    /// Conversation and inline review comments, oldest first
+    async fn get_pr_comments(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError>;
+
+    /// Ids of the comments in resolved threads, as given by `get_pr_comments`.
+    /// Forges without resolvable threads report none.
+    async fn get_resolved_comment_ids(
+        &self,
+        _repo: &ForgeRepoInfo,
+        _number: i64,
+    ) -> Result<HashSet<String>, ForgeError> {
+        Ok(HashSet::new())
+    }
+
+    /// Answer a comment, in its thread where the forge allows it
+    async fn reply_to_comment(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+        comment: &UnifiedPrComment,
+        body: &str,
+    ) -> Result<(), ForgeError>;
+}
+
+/// Client for the forge hosting `repo`
+pub fn forge_for(repo: &ForgeRepoInfo) -> Result<Box<dyn Forge>, ForgeError> {
+    Ok(match repo.forge {
+        ForgeKind::GitHub => Box::new(GitHubService::new()?),
+        ForgeKind::GitLab => Box::new(GitLabForge::new(&repo.base_url)?),
+        ForgeKind::Gitea => Box::new(GiteaForge::new(&repo.base_url)?),
+    })
+}
+
+#[async_trait]
+impl Forge for GitHubService {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::GitHub
+    }
+
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        Ok(self.check_token().await?)
+    }
+
+    async fn create_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        Ok(GitHubService::create_pr(self, &repo.into(), request).await?)
+    }
+
+    async fn get_pr_status(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        Ok(self.update_pr_status(&repo.into(), number).await?)
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        repo: &ForgeRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        Ok(self.list_all_prs_for_branch(&repo.into(), branch).await?)
+    }
+
+    async fn get_pr_comments(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError> {
+        Ok(GitHubService::get_pr_comments(self, &repo.into(), number).await?)
+    }
+
+    async fn get_resolved_comment_ids(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<HashSet<String>, ForgeError> {
+        Ok(self
+            .get_resolved_review_comment_ids(&repo.into(), number)
+            .await?
+            .into_iter()
+            .map(|id| id.to_string())
+            .collect())
+    }
+
+    async fn reply_to_comment(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+        comment: &UnifiedPrComment,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        Ok(GitHubService::reply_to_comment(self, &repo.into(), number, comment, body).await?)
+    }
+}
+
+impl From<&ForgeRepoInfo> for GitHubRepoInfo {
+    fn from(repo: &ForgeRepoInfo) -> Self {
+        Self {
+            owner: repo.owner.clone(),
+            repo_name: repo.repo_name.clone(),
+        }
+    }
+}
+
+/// JSON client shared by the REST forges
+#[derive(Debug, Clone)]
+struct ForgeHttpClient {
+    forge: ForgeKind,
+    client: reqwest::Client,
+    api_url: String,
+    auth_header: &'static str,
+    auth_value: String,
+}
+
+impl ForgeHttpClient {
+    fn new(
+        forge: ForgeKind,
+        api_url: String,
+        token_env: &'static str,
+        auth_header: &'static str,
+        auth_scheme: &str,
+    ) -> Result<Self, ForgeError> {
+        let token = std::env::var(token_env)
+            .ok()
+            .filter(|token| !token.trim().is_empty())
+            .ok_or(ForgeError::MissingToken {
+                forge,
+                env_var: token_env,
+            })?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|source| ForgeError::Transport { forge, source })?;
+        Ok(Self {
+            forge,
+            client,
+            api_url,
+            auth_header,
+            auth_value: format!("{auth_scheme}{}", token.trim()),
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ForgeError> {
+        self.send(Method::GET, path, None::<&()>).await
+    }
+
+    async fn post<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ForgeError> {
+        self.send(Method::POST, path, Some(body)).await
+    }
+
+    async fn send<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, ForgeError> {
+        let forge = self.forge;
+        (|| async {
+            let mut request = self
+                .client
+                .request(method.clone(), format!("{}{path}", self.api_url))
+                .header(self.auth_header, &self.auth_value);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|source| ForgeError::Transport { forge, source })?;
+
+            let status = response.status();
+            if status.is_success() {
+                return response
+                    .json::<T>()
+                    .await
+                    .map_err(|source| ForgeError::Transport { forge, source });
+            }
+            let message = response.text().await.unwrap_or_default();
+            Err(match status {
+                StatusCode::UNAUTHORIZED => ForgeError::AuthFailed { forge, message },
+                _ => ForgeError::Api {
+                    forge,
+                    status,
+                    message,
+                },
+            })
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|err: &ForgeError| err.should_retry())
+        .notify(|err: &ForgeError, dur: Duration| {
+            tracing::warn!(
+                "{} API call failed, retrying after {:.2}s: {}",
+                forge.display_name(),
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> ForgeRepoInfo {
+        ForgeRepoInfo::from_url(url, None, None).unwrap()
+    }
+
+    #[test]
+    fn detects_forge_from_remote_url() {
+        let github = parse("git@github.com:owner/repo.git");
+        assert_eq!(github.forge, ForgeKind::GitHub);
+        assert_eq!(github.full_name(), "owner/repo");
+
+        let gitlab = parse("https://gitlab.example.com/group/sub/project.git");
+        assert_eq!(gitlab.forge, ForgeKind::GitLab);
+        assert_eq!(gitlab.base_url, "https://gitlab.example.com");
+        assert_eq!(gitlab.owner, "group/sub");
+        assert_eq!(gitlab.repo_name, "project");
+
+        let gitea = parse("ssh://git@codeberg.org:2222/owner/repo.git");
+        assert_eq!(gitea.forge, ForgeKind::Gitea);
+        assert_eq!(gitea.base_url, "https://codeberg.org");
+        assert_eq!(gitea.full_name(), "owner/repo");
+
+        assert!(ForgeRepoInfo::from_url("git@git.example.com:owner/repo.git", None, None).is_err());
+    }
+
+    #[test]
+    fn parses_pull_request_urls() {
+        let gitlab = parse("https://gitlab.com/group/sub/project/-/merge_requests/12");
+        assert_eq!(gitlab.owner, "group/sub");
+        assert_eq!(gitlab.repo_name, "project");
+
+        let gitea = parse("https://gitea.com/owner/repo/pulls/7");
+        assert_eq!(gitea.full_name(), "owner/repo");
+
+        let github = parse("https://github.com/owner/repo/pull/123");
+        assert_eq!(github.full_name(), "owner/repo");
+    }
+
+    #[test]
+    fn repo_settings_override_detection() {
+        let info = ForgeRepoInfo::from_url(
+            "git@git.example.com:team/project.git",
+            Some(ForgeKind::Gitea),
+            Some("https://git.example.com/gitea/"),
+        )
+        .unwrap();
+        assert_eq!(info.forge, ForgeKind::Gitea);
+        assert_eq!(info.base_url, "https://git.example.com/gitea");
+        assert_eq!(info.full_name(), "team/project");
+
+        // Web URLs of a forge under a sub path carry the prefix
+        let pr = ForgeRepoInfo::from_url(
+            "https://git.example.com/gitea/team/project/pulls/3",
+            Some(ForgeKind::Gitea),
+            Some("https://git.example.com/gitea"),
+        )
+        .unwrap();
+        assert_eq!(pr.full_name(), "team/project");
+    }
+}
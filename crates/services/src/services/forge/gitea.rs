@@ -0,0 +1,280 @@
+//! Gitea (and Forgejo) pull requests over the REST API (v1).
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{ForgeKind, MergeStatus, PullRequestInfo};
+use serde::{Deserialize, Serialize};
+
+use super::{Forge, ForgeError, ForgeHttpClient, ForgeRepoInfo};
+use crate::services::github::{CreatePrRequest, UnifiedPrComment};
+
+pub const GITEA_TOKEN_ENV: &str = "GITEA_TOKEN";
+
+/// Page size for listing pull requests; the largest Gitea allows by default
+const PAGE_LIMIT: u32 = 50;
+
+/// Gitea has no author association; comments are reported like GitHub's `NONE`
+const NO_ASSOCIATION: &str = "NONE";
+
+#[derive(Debug, Serialize)]
+struct CreatePullRequest<'a> {
+    head: &'a str,
+    base: &'a str,
+    title: String,
+    body: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestBranch {
+    #[serde(rename = "ref")]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequest {
+    number: i64,
+    html_url: String,
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    head: PullRequestBranch,
+}
+
+impl From<PullRequest> for PullRequestInfo {
+    fn from(pr: PullRequest) -> Self {
+        PullRequestInfo {
+            number: pr.number,
+            url: pr.html_url,
+            status: if pr.merged {
+                MergeStatus::Merged
+            } else {
+                match pr.state.as_str() {
+                    "open" => MergeStatus::Open,
+                    "closed" => MergeStatus::Closed,
+                    _ => MergeStatus::Unknown,
+                }
+            },
+            merged_at: pr.merged_at,
+            merge_commit_sha: pr.merge_commit_sha,
+            forge: ForgeKind::Gitea,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueComment {
+    id: i64,
+    user: User,
+    body: String,
+    created_at: DateTime<Utc>,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Review {
+    id: i64,
+    #[serde(default)]
+    comments_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewComment {
+    id: i64,
+    user: User,
+    body: String,
+    created_at: DateTime<Utc>,
+    html_url: String,
+    path: String,
+    #[serde(default)]
+    diff_hunk: String,
+    /// Line in the new file; zero for comments on removed lines
+    #[serde(default)]
+    position: i64,
+    #[serde(default)]
+    original_position: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateComment<'a> {
+    body: &'a str,
+}
+
+#[derive(Debug, Clone)]
+pub struct GiteaForge {
+    http: ForgeHttpClient,
+}
+
+impl GiteaForge {
+    pub fn new(base_url: &str) -> Result<Self, ForgeError> {
+        Ok(Self {
+            http: ForgeHttpClient::new(
+                ForgeKind::Gitea,
+                format!("{base_url}/api/v1"),
+                GITEA_TOKEN_ENV,
+                "Authorization",
+                "token ",
+            )?,
+        })
+    }
+
+    fn repo_path(repo: &ForgeRepoInfo) -> String {
+        format!("/repos/{}/{}", repo.owner, repo.repo_name)
+    }
+}
+
+#[async_trait]
+impl Forge for GiteaForge {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::Gitea
+    }
+
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        self.http
+            .get::<serde_json::Value>("/user")
+            .await
+            .map(|_| ())
+    }
+
+    async fn create_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        // Gitea marks pull requests as work in progress by their title
+        let title = if request.draft.unwrap_or(false) {
+            format!("WIP: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let body = CreatePullRequest {
+            head: &request.head_branch,
+            base: &request.base_branch,
+            title,
+            body: request.body.as_deref().unwrap_or(""),
+        };
+        let pr: PullRequest = self
+            .http
+            .post(&format!("{}/pulls", Self::repo_path(repo)), &body)
+            .await?;
+
+        tracing::info!(
+            "Created Gitea PR #{} for branch {} in {}",
+            pr.number,
+            request.head_branch,
+            repo.full_name()
+        );
+        Ok(pr.into())
+    }
+
+    async fn get_pr_status(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        let pr: PullRequest = self
+            .http
+            .get(&format!("{}/pulls/{number}", Self::repo_path(repo)))
+            .await?;
+        Ok(pr.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        repo: &ForgeRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        // The list endpoint cannot filter by head branch, so check the most
+        // recently updated pull requests
+        let prs: Vec<PullRequest> = self
+            .http
+            .get(&format!(
+                "{}/pulls?state=all&sort=recentupdate&limit={PAGE_LIMIT}",
+                Self::repo_path(repo)
+            ))
+            .await?;
+        Ok(prs
+            .into_iter()
+            .filter(|pr| pr.head.name == branch)
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError> {
+        let repo_path = Self::repo_path(repo);
+        let (issue_comments, reviews) = tokio::join!(
+            self.http
+                .get::<Vec<IssueComment>>(&format!("{repo_path}/issues/{number}/comments")),
+            self.http
+                .get::<Vec<Review>>(&format!("{repo_path}/pulls/{number}/reviews")),
+        );
+
+        let mut unified: Vec<UnifiedPrComment> = issue_comments?
+            .into_iter()
+            .map(|c| UnifiedPrComment::General {
+                id: c.id.to_string(),
+                author: c.user.login,
+                author_association: NO_ASSOCIATION.to_string(),
+                body: c.body,
+                created_at: c.created_at,
+                url: c.html_url,
+            })
+            .collect();
+
+        for review in reviews?.into_iter().filter(|r| r.comments_count > 0) {
+            let comments: Vec<ReviewComment> = self
+                .http
+                .get(&format!(
+                    "{repo_path}/pulls/{number}/reviews/{}/comments",
+                    review.id
+                ))
+                .await?;
+            unified.extend(comments.into_iter().map(|c| {
+                UnifiedPrComment::Review {
+                    id: c.id,
+                    author: c.user.login,
+                    author_association: NO_ASSOCIATION.to_string(),
+                    body: c.body,
+                    created_at: c.created_at,
+                    url: c.html_url,
+                    path: c.path,
+                    line: [c.position, c.original_position]
+                        .into_iter()
+                        .find(|line| *line > 0),
+                    diff_hunk: c.diff_hunk,
+                }
+            }));
+        }
+
+        unified.sort_by_key(|c| c.created_at());
+        Ok(unified)
+    }
+
+    async fn reply_to_comment(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+        comment: &UnifiedPrComment,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        // The API cannot answer inside a review thread
+        let body = comment.standalone_reply(body);
+        self.http
+            .post::<_, serde_json::Value>(
+                &format!("{}/issues/{number}/comments", Self::repo_path(repo)),
+                &CreateComment { body: &body },
+            )
+            .await
+            .map(|_| ())
+    }
+}
@@ -0,0 +1,268 @@
+//! GitLab merge requests over the REST API (v4).
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{ForgeKind, MergeStatus, PullRequestInfo};
+use serde::{Deserialize, Serialize};
+
+use super::{Forge, ForgeError, ForgeHttpClient, ForgeRepoInfo};
+use crate::services::github::{CreatePrRequest, UnifiedPrComment};
+
+pub const GITLAB_TOKEN_ENV: &str = "GITLAB_TOKEN";
+
+/// GitLab has no author association; comments are reported like GitHub's `NONE`
+const NO_ASSOCIATION: &str = "NONE";
+
+#[derive(Debug, Serialize)]
+struct CreateMergeRequest<'a> {
+    source_branch: &'a str,
+    target_branch: &'a str,
+    title: String,
+    description: &'a str,
+    remove_source_branch: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeRequest {
+    iid: i64,
+    web_url: String,
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+}
+
+impl From<MergeRequest> for PullRequestInfo {
+    fn from(mr: MergeRequest) -> Self {
+        PullRequestInfo {
+            number: mr.iid,
+            url: mr.web_url,
+            status: match mr.state.as_str() {
+                "opened" | "locked" => MergeStatus::Open,
+                "merged" => MergeStatus::Merged,
+                "closed" => MergeStatus::Closed,
+                _ => MergeStatus::Unknown,
+            },
+            merged_at: mr.merged_at,
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+            forge: ForgeKind::GitLab,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NoteAuthor {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotePosition {
+    new_path: Option<String>,
+    old_path: Option<String>,
+    new_line: Option<i64>,
+    old_line: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    id: i64,
+    body: String,
+    author: NoteAuthor,
+    created_at: DateTime<Utc>,
+    /// Notes GitLab writes itself, e.g. "added 1 commit"
+    system: bool,
+    /// Set on notes of a resolved thread
+    #[serde(default)]
+    resolved: bool,
+    position: Option<NotePosition>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateNote<'a> {
+    body: &'a str,
+}
+
+#[derive(Debug, Clone)]
+pub struct GitLabForge {
+    http: ForgeHttpClient,
+}
+
+impl GitLabForge {
+    pub fn new(base_url: &str) -> Result<Self, ForgeError> {
+        Ok(Self {
+            http: ForgeHttpClient::new(
+                ForgeKind::GitLab,
+                format!("{base_url}/api/v4"),
+                GITLAB_TOKEN_ENV,
+                "PRIVATE-TOKEN",
+                "",
+            )?,
+        })
+    }
+
+    fn project_path(repo: &ForgeRepoInfo) -> String {
+        format!("/projects/{}", repo.full_name().replace('/', "%2F"))
+    }
+
+    async fn get_notes(&self, repo: &ForgeRepoInfo, number: i64) -> Result<Vec<Note>, ForgeError> {
+        self.http
+            .get(&format!(
+                "{}/merge_requests/{number}/notes?sort=asc&order_by=created_at&per_page=100",
+                Self::project_path(repo)
+            ))
+            .await
+    }
+}
+
+#[async_trait]
+impl Forge for GitLabForge {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::GitLab
+    }
+
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        self.http
+            .get::<serde_json::Value>("/user")
+            .await
+            .map(|_| ())
+    }
+
+    async fn create_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        let title = if request.draft.unwrap_or(false) {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let body = CreateMergeRequest {
+            source_branch: &request.head_branch,
+            target_branch: &request.base_branch,
+            title,
+            description: request.body.as_deref().unwrap_or(""),
+            remove_source_branch: false,
+        };
+        let mr: MergeRequest = self
+            .http
+            .post(
+                &format!("{}/merge_requests", Self::project_path(repo)),
+                &body,
+            )
+            .await?;
+
+        tracing::info!(
+            "Created GitLab MR !{} for branch {} in {}",
+            mr.iid,
+            request.head_branch,
+            repo.full_name()
+        );
+        Ok(mr.into())
+    }
+
+    async fn get_pr_status(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        let mr: MergeRequest = self
+            .http
+            .get(&format!(
+                "{}/merge_requests/{number}",
+                Self::project_path(repo)
+            ))
+            .await?;
+        Ok(mr.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        repo: &ForgeRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        let mrs: Vec<MergeRequest> = self
+            .http
+            .get(&format!(
+                "{}/merge_requests?state=all&source_branch={}",
+                Self::project_path(repo),
+                url::form_urlencoded::byte_serialize(branch.as_bytes()).collect::<String>()
+            ))
+            .await?;
+        Ok(mrs.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError> {
+        let mr_path = format!("{}/merge_requests/{number}", Self::project_path(repo));
+        let mr: MergeRequest = self.http.get(&mr_path).await?;
+        let notes = self.get_notes(repo, number).await?;
+
+        Ok(notes
+            .into_iter()
+            .filter(|note| !note.system)
+            .map(|note| {
+                let url = format!("{}#note_{}", mr.web_url, note.id);
+                match note.position {
+                    Some(position) => UnifiedPrComment::Review {
+                        id: note.id,
+                        author: note.author.username,
+                        author_association: NO_ASSOCIATION.to_string(),
+                        body: note.body,
+                        created_at: note.created_at,
+                        url,
+                        path: position.new_path.or(position.old_path).unwrap_or_default(),
+                        line: position.new_line.or(position.old_line),
+                        // GitLab does not send the hunk with the note
+                        diff_hunk: String::new(),
+                    },
+                    None => UnifiedPrComment::General {
+                        id: note.id.to_string(),
+                        author: note.author.username,
+                        author_association: NO_ASSOCIATION.to_string(),
+                        body: note.body,
+                        created_at: note.created_at,
+                        url,
+                    },
+                }
+            })
+            .collect())
+    }
+
+    async fn get_resolved_comment_ids(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<HashSet<String>, ForgeError> {
+        Ok(self
+            .get_notes(repo, number)
+            .await?
+            .into_iter()
+            .filter(|note| note.resolved)
+            .map(|note| note.id.to_string())
+            .collect())
+    }
+
+    async fn reply_to_comment(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+        comment: &UnifiedPrComment,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        // Answering inside a thread needs its discussion id, which notes do not carry
+        let body = comment.standalone_reply(body);
+        self.http
+            .post::<_, serde_json::Value>(
+                &format!("{}/merge_requests/{number}/notes", Self::project_path(repo)),
+                &CreateNote { body: &body },
+            )
+            .await
+            .map(|_| ())
+    }
+}
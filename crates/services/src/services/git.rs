@@ -1,6 +1,7 @@
 use std::{collections::HashMap, path::Path};
 
 use chrono::{DateTime, Utc};
+use db::models::merge::MergeStrategy;
 use git2::{
     BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, Reference, Remote,
     Repository, Sort,
@@ -790,21 +791,56 @@ impl GitService {
         base_branch_name: &str,
         commit_message: &str,
     ) -> Result<String, GitServiceError> {
-        // Open the repositories
-        let task_repo = self.open_repo(task_worktree_path)?;
-        let base_repo = self.open_repo(base_worktree_path)?;
+        self.merge_changes_with_strategy(
+            base_worktree_path,
+            task_worktree_path,
+            task_branch_name,
+            base_branch_name,
+            commit_message,
+            MergeStrategy::Squash,
+            false,
+        )
+    }
 
+    /// Merge the task branch into the base branch and return the new tip of the base
+    /// branch. When the base branch has moved ahead, the task branch is rebased onto
+    /// it first if `rebase_if_behind` is set or the strategy rebases anyway;
+    /// otherwise the merge is refused.
+    #[allow(clippy::too_many_arguments)]
+    pub fn merge_changes_with_strategy(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+        strategy: MergeStrategy,
+        rebase_if_behind: bool,
+    ) -> Result<String, GitServiceError> {
         // Check if base branch is ahead of task branch - this indicates the base has moved
-        // ahead since the task was created, which should block the merge
+        // ahead since the task was created
         let (_, task_behind) =
             self.get_branch_status(base_worktree_path, task_branch_name, base_branch_name)?;
 
         if task_behind > 0 {
-            return Err(GitServiceError::BranchesDiverged(format!(
-                "Cannot merge: base branch '{base_branch_name}' is {task_behind} commits ahead of task branch '{task_branch_name}'. The base branch has moved forward since the task was created.",
-            )));
+            if !rebase_if_behind && strategy != MergeStrategy::Rebase {
+                return Err(GitServiceError::BranchesDiverged(format!(
+                    "Cannot merge: base branch '{base_branch_name}' is {task_behind} commits ahead of task branch '{task_branch_name}'. The base branch has moved forward since the task was created.",
+                )));
+            }
+            self.rebase_branch(
+                base_worktree_path,
+                task_worktree_path,
+                base_branch_name,
+                base_branch_name,
+                task_branch_name,
+            )?;
         }
 
+        // Open the repositories
+        let task_repo = self.open_repo(task_worktree_path)?;
+        let base_repo = self.open_repo(base_worktree_path)?;
+
         // Check where base branch is checked out (if anywhere)
         match self.find_checkout_path_for_branch(base_worktree_path, base_branch_name)? {
             Some(base_checkout_path) => {
@@ -826,16 +862,28 @@ impl GitService {
 
                 // Use CLI merge in base context
                 self.ensure_cli_commit_identity(&base_checkout_path)?;
-                let sha = git_cli
-                    .merge_squash_commit(
+                let sha = match strategy {
+                    MergeStrategy::Squash => git_cli.merge_squash_commit(
                         &base_checkout_path,
                         base_branch_name,
                         task_branch_name,
                         commit_message,
-                    )
-                    .map_err(|e| {
-                        GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
-                    })?;
+                    ),
+                    MergeStrategy::Rebase => git_cli.merge_fast_forward(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                    ),
+                    MergeStrategy::MergeCommit => git_cli.merge_no_ff_commit(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                        commit_message,
+                    ),
+                }
+                .map_err(|e| {
+                    GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
+                })?;
 
                 // Update task branch ref for continuity
                 let task_refname = format!("refs/heads/{task_branch_name}");
@@ -856,28 +904,44 @@ impl GitService {
                 let base_commit = base_branch.get().peel_to_commit()?;
                 let task_commit = task_branch.get().peel_to_commit()?;
 
-                // Create the squash commit in-memory (no checkout) and update the base branch ref
+                // Create the commit in-memory (no checkout) and update the base branch ref
                 let signature = self.signature_with_fallback(&task_repo)?;
-                let squash_commit_id = self.perform_squash_merge(
-                    &task_repo,
-                    &base_commit,
-                    &task_commit,
-                    &signature,
-                    commit_message,
-                    base_branch_name,
-                )?;
+                let merged_commit_id = match strategy {
+                    MergeStrategy::Squash => self.perform_squash_merge(
+                        &task_repo,
+                        &base_commit,
+                        &task_commit,
+                        &signature,
+                        commit_message,
+                        base_branch_name,
+                    )?,
+                    MergeStrategy::Rebase => {
+                        // The task branch contains the base branch at this point
+                        let refname = format!("refs/heads/{base_branch_name}");
+                        task_repo.reference(&refname, task_commit.id(), true, "Fast-forward")?;
+                        task_commit.id()
+                    }
+                    MergeStrategy::MergeCommit => self.perform_merge_commit(
+                        &task_repo,
+                        &base_commit,
+                        &task_commit,
+                        &signature,
+                        commit_message,
+                        base_branch_name,
+                    )?,
+                };
 
-                // Update the task branch to the new squash commit so follow-up
+                // Update the task branch to the new commit so follow-up
                 // work can continue from the merged state without conflicts.
                 let task_refname = format!("refs/heads/{task_branch_name}");
                 base_repo.reference(
                     &task_refname,
-                    squash_commit_id,
+                    merged_commit_id,
                     true,
-                    "Reset task branch after squash merge",
+                    "Reset task branch after merge",
                 )?;
 
-                Ok(squash_commit_id.to_string())
+                Ok(merged_commit_id.to_string())
             }
         }
     }
@@ -1055,6 +1119,22 @@ impl GitService {
         Ok(oid)
     }
 
+    /// When the remote tracking branch of `branch_name` last moved, from its reflog:
+    /// the last push, or a fetch that brought new commits. None when the branch was
+    /// never pushed or fetched from this clone.
+    pub fn last_push_time(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<Option<DateTime<Utc>>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let remote_name = self.default_remote_name(&repo);
+        let reflog = repo.reflog(&format!("refs/remotes/{remote_name}/{branch_name}"))?;
+        Ok(reflog
+            .get(0)
+            .and_then(|entry| DateTime::from_timestamp(entry.committer().when().seconds(), 0)))
+    }
+
     /// Get the subject/summary line for a given commit OID
     pub fn get_commit_subject(
         &self,
@@ -1272,16 +1352,13 @@ impl GitService {
         Ok(branches)
     }
 
-    /// Perform a squash merge of task branch into base branch, but fail on conflicts
-    fn perform_squash_merge(
+    /// Merge the task commit into the base commit in memory, failing on conflicts
+    fn merged_tree<'r>(
         &self,
-        repo: &Repository,
+        repo: &'r Repository,
         base_commit: &git2::Commit,
         task_commit: &git2::Commit,
-        signature: &git2::Signature,
-        commit_message: &str,
-        base_branch_name: &str,
-    ) -> Result<git2::Oid, GitServiceError> {
+    ) -> Result<git2::Tree<'r>, GitServiceError> {
         // In-memory merge to detect conflicts without touching the working tree
         let mut merge_opts = git2::MergeOptions::new();
         // Safety and correctness options
@@ -1298,7 +1375,20 @@ impl GitService {
 
         // Write the merged tree back to the repository
         let tree_id = index.write_tree_to(repo)?;
-        let tree = repo.find_tree(tree_id)?;
+        Ok(repo.find_tree(tree_id)?)
+    }
+
+    /// Perform a squash merge of task branch into base branch, but fail on conflicts
+    fn perform_squash_merge(
+        &self,
+        repo: &Repository,
+        base_commit: &git2::Commit,
+        task_commit: &git2::Commit,
+        signature: &git2::Signature,
+        commit_message: &str,
+        base_branch_name: &str,
+    ) -> Result<git2::Oid, GitServiceError> {
+        let tree = self.merged_tree(repo, base_commit, task_commit)?;
 
         // Create a squash commit: use merged tree with base_commit as sole parent
         let squash_commit_id = repo.commit(
@@ -1317,6 +1407,33 @@ impl GitService {
         Ok(squash_commit_id)
     }
 
+    /// Create a merge commit of task branch into base branch, but fail on conflicts
+    fn perform_merge_commit(
+        &self,
+        repo: &Repository,
+        base_commit: &git2::Commit,
+        task_commit: &git2::Commit,
+        signature: &git2::Signature,
+        commit_message: &str,
+        base_branch_name: &str,
+    ) -> Result<git2::Oid, GitServiceError> {
+        let tree = self.merged_tree(repo, base_commit, task_commit)?;
+
+        let merge_commit_id = repo.commit(
+            None,
+            signature,
+            signature,
+            commit_message,
+            &tree,
+            &[base_commit, task_commit],
+        )?;
+
+        let refname = format!("refs/heads/{base_branch_name}");
+        repo.reference(&refname, merge_commit_id, true, "Merge commit")?;
+
+        Ok(merge_commit_id)
+    }
+
     /// Rebase a worktree branch onto a new base
     pub fn rebase_branch(
         &self,
@@ -1590,6 +1707,26 @@ impl GitService {
         })
     }
 
+    /// Stage the worktree and continue the interrupted rebase, merge, cherry-pick or
+    /// revert (no-op if none). A rebase may stop again at a later conflicting commit.
+    pub fn continue_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(());
+        };
+        // Continuing commits the resolution
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let git = GitCli::new();
+        let (result, command) = match op {
+            ConflictOp::Rebase => (git.continue_rebase(worktree_path), "rebase"),
+            ConflictOp::Merge => (git.continue_merge(worktree_path), "merge"),
+            ConflictOp::CherryPick => (git.continue_cherry_pick(worktree_path), "cherry-pick"),
+            ConflictOp::Revert => (git.continue_revert(worktree_path), "revert"),
+        };
+        result.map_err(|e| {
+            GitServiceError::InvalidRepository(format!("git {command} --continue failed: {e}"))
+        })
+    }
+
     pub fn abort_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
         let git = GitCli::new();
         if git.is_rebase_in_progress(worktree_path).unwrap_or(false) {
@@ -1644,21 +1781,27 @@ impl GitService {
         }
     }
 
-    /// Extract GitHub owner and repo name from git repo path
-    pub fn get_github_repo_info(
-        &self,
-        repo_path: &Path,
-    ) -> Result<GitHubRepoInfo, GitServiceError> {
+    /// URL of the default remote of a git repo
+    pub fn get_remote_url(&self, repo_path: &Path) -> Result<String, GitServiceError> {
         let repo = self.open_repo(repo_path)?;
         let remote_name = self.default_remote_name(&repo);
         let remote = repo.find_remote(&remote_name).map_err(|_| {
             GitServiceError::InvalidRepository(format!("No '{remote_name}' remote found"))
         })?;
 
-        let url = remote
+        remote
             .url()
-            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
-        GitHubRepoInfo::from_remote_url(url).map_err(|e| {
+            .map(|url| url.to_string())
+            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))
+    }
+
+    /// Extract GitHub owner and repo name from git repo path
+    pub fn get_github_repo_info(
+        &self,
+        repo_path: &Path,
+    ) -> Result<GitHubRepoInfo, GitServiceError> {
+        let url = self.get_remote_url(repo_path)?;
+        GitHubRepoInfo::from_remote_url(&url).map_err(|e| {
             GitServiceError::InvalidRepository(format!("Failed to parse remote URL: {e}"))
         })
     }
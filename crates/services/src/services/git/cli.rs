@@ -594,6 +594,44 @@ impl GitCli {
         Ok(sha)
     }
 
+    /// Checkout base branch and fast-forward it to from_branch. Returns new HEAD sha.
+    pub fn merge_fast_forward(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--ff-only", from_branch])
+            .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
+    /// Checkout base branch and merge from_branch with a merge commit, even when a
+    /// fast-forward is possible. Aborts the merge on failure. Returns new HEAD sha.
+    pub fn merge_no_ff_commit(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        if let Err(e) = self.git(repo_path, ["merge", "--no-ff", "-m", message, from_branch]) {
+            let _ = self.abort_merge(repo_path);
+            return Err(e);
+        }
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
     /// Update a ref to a specific sha in the repo.
     pub fn update_ref(
         &self,
@@ -627,6 +665,32 @@ impl GitCli {
         self.git(worktree_path, ["revert", "--abort"]).map(|_| ())
     }
 
+    /// Continue an in-progress rebase after its conflicts were resolved and staged,
+    /// without opening an editor. A commit that became empty is skipped.
+    pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        match self.continue_op(worktree_path, "rebase") {
+            Err(GitCliError::CommandFailed(msg))
+                if msg.contains("nothing to commit") || msg.contains("No changes") =>
+            {
+                self.git_with_env(worktree_path, ["rebase", "--skip"], &Self::no_editor_env())
+                    .map(|_| ())
+            }
+            other => other,
+        }
+    }
+
+    pub fn continue_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.continue_op(worktree_path, "merge")
+    }
+
+    pub fn continue_cherry_pick(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.continue_op(worktree_path, "cherry-pick")
+    }
+
+    pub fn continue_revert(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.continue_op(worktree_path, "revert")
+    }
+
     /// List files currently in a conflicted (unmerged) state in the worktree.
     pub fn get_conflicted_files(&self, worktree_path: &Path) -> Result<Vec<String>, GitCliError> {
         // `--diff-filter=U` lists paths with unresolved conflicts
@@ -662,6 +726,22 @@ impl GitCli {
         }
     }
 
+    /// Environment that keeps `--continue` from waiting on an editor
+    fn no_editor_env() -> [(OsString, OsString); 1] {
+        [(OsString::from("GIT_EDITOR"), OsString::from("true"))]
+    }
+
+    /// Stage everything and run `git <command> --continue`
+    fn continue_op(&self, worktree_path: &Path, command: &str) -> Result<(), GitCliError> {
+        self.add_all(worktree_path)?;
+        self.git_with_env(
+            worktree_path,
+            [command, "--continue"],
+            &Self::no_editor_env(),
+        )
+        .map(|_| ())
+    }
+
     /// Ensure `git` is available on PATH
     fn ensure_available(&self) -> Result<(), GitCliError> {
         let git = resolve_executable_path_blocking("git").ok_or(GitCliError::NotAvailable)?;
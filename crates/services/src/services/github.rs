@@ -44,12 +44,31 @@ pub enum UnifiedPrComment {
 }
 
 impl UnifiedPrComment {
-    fn created_at(&self) -> DateTime<Utc> {
+    pub(crate) fn created_at(&self) -> DateTime<Utc> {
         match self {
             UnifiedPrComment::General { created_at, .. } => *created_at,
             UnifiedPrComment::Review { created_at, .. } => *created_at,
         }
     }
+
+    pub(crate) fn body(&self) -> &str {
+        match self {
+            UnifiedPrComment::General { body, .. } => body,
+            UnifiedPrComment::Review { body, .. } => body,
+        }
+    }
+
+    pub(crate) fn url(&self) -> &str {
+        match self {
+            UnifiedPrComment::General { url, .. } => url,
+            UnifiedPrComment::Review { url, .. } => url,
+        }
+    }
+
+    /// Body of a conversation comment answering this comment outside its thread
+    pub(crate) fn standalone_reply(&self, body: &str) -> String {
+        format!("In reply to {}:\n\n{body}", self.url())
+    }
 }
 
 #[derive(Debug, Error)]
@@ -371,6 +390,76 @@ impl GitHubService {
         Ok(unified)
     }
 
+    /// Ids of the inline review comments in resolved threads
+    pub async fn get_resolved_review_comment_ids(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<i64>, GitHubServiceError> {
+        (|| async {
+            let owner = repo_info.owner.clone();
+            let repo = repo_info.repo_name.clone();
+            let cli = self.gh_cli.clone();
+            let ids = task::spawn_blocking(move || {
+                cli.get_resolved_review_comment_ids(&owner, &repo, pr_number)
+            })
+            .await
+            .map_err(|err| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for fetching PR #{pr_number} review threads: {err}"
+                ))
+            })?;
+            ids.map_err(GitHubServiceError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHubServiceError| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    /// Reply to a comment: in the thread of an inline review comment, otherwise as
+    /// a new conversation comment. Not retried, so a reply is never posted twice.
+    pub async fn reply_to_comment(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        comment: &UnifiedPrComment,
+        body: &str,
+    ) -> Result<(), GitHubServiceError> {
+        let owner = repo_info.owner.clone();
+        let repo = repo_info.repo_name.clone();
+        let cli = self.gh_cli.clone();
+        let (review_comment_id, body) = match comment {
+            UnifiedPrComment::Review { id, .. } => (Some(*id), body.to_string()),
+            UnifiedPrComment::General { .. } => (None, comment.standalone_reply(body)),
+        };
+        task::spawn_blocking(move || match review_comment_id {
+            Some(comment_id) => {
+                cli.reply_to_review_comment(&owner, &repo, pr_number, comment_id, &body)
+            }
+            None => cli.comment_on_pr(&owner, &repo, pr_number, &body),
+        })
+        .await
+        .map_err(|err| {
+            GitHubServiceError::PullRequest(format!(
+                "Failed to execute GitHub CLI for replying on PR #{pr_number}: {err}"
+            ))
+        })?
+        .map_err(GitHubServiceError::from)
+    }
+
     async fn fetch_general_comments(
         &self,
         repo_info: &GitHubRepoInfo,
@@ -10,7 +10,7 @@ use std::{
 };
 
 use chrono::{DateTime, Utc};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use db::models::merge::{ForgeKind, MergeStatus, PullRequestInfo};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
@@ -58,6 +58,20 @@ pub struct PrReviewComment {
     pub author_association: String,
 }
 
+/// Review threads of a pull request with the ids of their comments
+const REVIEW_THREADS_QUERY: &str = r#"query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          isResolved
+          comments(first: 100) { nodes { databaseId } }
+        }
+      }
+    }
+  }
+}"#;
+
 /// High-level errors originating from the GitHub CLI.
 #[derive(Debug, Error)]
 pub enum GhCliError {
@@ -245,6 +259,68 @@ impl GhCli {
         ])?;
         Self::parse_pr_review_comments(&raw)
     }
+
+    /// Ids of the review comments in resolved threads of a pull request.
+    pub fn get_resolved_review_comment_ids(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<i64>, GhCliError> {
+        let raw = self.run([
+            "api",
+            "graphql",
+            "-f",
+            &format!("owner={owner}"),
+            "-f",
+            &format!("repo={repo}"),
+            "-F",
+            &format!("number={pr_number}"),
+            "-f",
+            &format!("query={REVIEW_THREADS_QUERY}"),
+        ])?;
+        Self::parse_resolved_review_comment_ids(&raw)
+    }
+
+    /// Reply in the thread of an inline review comment.
+    pub fn reply_to_review_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        self.run([
+            "api",
+            "--method",
+            "POST",
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}/comments/{comment_id}/replies"),
+            "-f",
+            &format!("body={body}"),
+        ])?;
+        Ok(())
+    }
+
+    /// Add a conversation comment to a pull request.
+    pub fn comment_on_pr(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        self.run([
+            "pr",
+            "comment",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
+            "--body",
+            body,
+        ])?;
+        Ok(())
+    }
 }
 
 impl GhCli {
@@ -285,6 +361,7 @@ impl GhCli {
             status: MergeStatus::Open,
             merged_at: None,
             merge_commit_sha: None,
+            forge: ForgeKind::GitHub,
         })
     }
 
@@ -357,6 +434,30 @@ impl GhCli {
         })
     }
 
+    fn parse_resolved_review_comment_ids(raw: &str) -> Result<Vec<i64>, GhCliError> {
+        let value: Value = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse review threads response: {err}; raw: {raw}"
+            ))
+        })?;
+        let threads = value
+            .pointer("/data/repository/pullRequest/reviewThreads/nodes")
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Review threads response missing 'reviewThreads': {value:#?}"
+                ))
+            })?;
+
+        Ok(threads
+            .iter()
+            .filter(|thread| thread.get("isResolved").and_then(Value::as_bool) == Some(true))
+            .filter_map(|thread| thread.pointer("/comments/nodes").and_then(Value::as_array))
+            .flatten()
+            .filter_map(|comment| comment.get("databaseId").and_then(Value::as_i64))
+            .collect())
+    }
+
     fn extract_pr_info(value: &Value) -> Option<PullRequestInfo> {
         let number = value.get("number")?.as_i64()?;
         let url = value.get("url")?.as_str()?.to_string();
@@ -386,6 +487,7 @@ impl GhCli {
             },
             merged_at,
             merge_commit_sha,
+            forge: ForgeKind::GitHub,
         })
     }
 }
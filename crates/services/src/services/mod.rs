@@ -2,24 +2,33 @@ pub mod analytics;
 pub mod approvals;
 pub mod auth;
 pub mod config;
+pub mod conflict_resolution;
 pub mod container;
 pub mod diff_stream;
 pub mod events;
+pub mod fallback;
 pub mod file_ranker;
 pub mod file_search_cache;
 pub mod filesystem;
 pub mod filesystem_watcher;
+pub mod forge;
 pub mod git;
 pub mod github;
 pub mod image;
 pub mod notification;
 pub mod oauth_credentials;
+pub mod pipeline;
 pub mod pr_monitor;
 pub mod project;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
+pub mod review_follow_up;
 pub mod share;
+pub mod stack;
+pub mod task_scheduler;
+pub mod usage;
+pub mod verification;
 pub mod webhook_worker;
 pub mod webhooks;
 pub mod workspace_manager;
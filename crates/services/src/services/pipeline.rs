@@ -0,0 +1,617 @@
+//! Declarative pipelines: named graphs of agent and script steps that a project can
+//! run in a session, with conditional transitions, bounded loops and parallel branches.
+//!
+//! Definitions are stored as TOML. Every step either prompts the session's coding
+//! agent or runs a script; the first step starts the run. When a step finishes, every
+//! transition in its `next` list whose `when` condition holds is followed, so several
+//! matching transitions start parallel branches. A transition back to an earlier step
+//! forms a loop and must set `max`, the number of times it may be followed per run.
+//! Steps with `needs` wait until all of those steps have finished, joining branches.
+//! Agent steps all prompt the session's one coding agent, so at most one branch of a
+//! fork may lead to agent steps before the branches join.
+//!
+//! ```toml
+//! [[steps]]
+//! id = "implement"
+//! agent = {}
+//! next = [{ to = "test" }]
+//!
+//! [[steps]]
+//! id = "test"
+//! script = { script = "cargo test", timeout_secs = 900 }
+//! next = [{ to = "fix", when = { outcome = "failure" }, max = 3 }]
+//!
+//! [[steps]]
+//! id = "fix"
+//! agent = { prompt = "The tests failed:\n\n{{ output }}\n\nPlease fix them." }
+//! next = [{ to = "test" }]
+//! ```
+//!
+//! Agent prompts are MiniJinja templates. They see the step that led to them as
+//! `step`, `outcome`, `exit_code` and `output` (the tail of its stdout and stderr),
+//! and the task as `task.title` and `task.description`. An agent step without a
+//! prompt sends the task prompt.
+
+use std::collections::{HashMap, HashSet};
+
+use executors::actions::script::ScriptOptions;
+use minijinja::Environment;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// How much of a step's output conditions and templates see, from the end
+pub const OUTPUT_TAIL_BYTES: usize = 16 * 1024;
+
+#[derive(Debug, Error)]
+pub enum PipelineError {
+    #[error("Invalid pipeline definition: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("Pipeline has no steps")]
+    NoSteps,
+    #[error("Pipeline step ids cannot be empty")]
+    EmptyStepId,
+    #[error("Duplicate pipeline step: {0}")]
+    DuplicateStep(String),
+    #[error("Step {from} refers to unknown step {to}")]
+    UnknownStep { from: String, to: String },
+    #[error("Script of step {0} is empty")]
+    EmptyScript(String),
+    #[error("Invalid output pattern in step {step}: {message}")]
+    InvalidPattern { step: String, message: String },
+    #[error("Invalid prompt template in step {step}: {message}")]
+    InvalidTemplate { step: String, message: String },
+    #[error("Loop through step {0} has no transition with a max count")]
+    UnboundedLoop(String),
+    #[error("Step {step} can run agent steps {first} and {second} at the same time")]
+    ParallelAgentSteps {
+        step: String,
+        first: String,
+        second: String,
+    },
+    #[error("Failed to render prompt: {0}")]
+    Render(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineDefinition {
+    pub steps: Vec<PipelineStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineStep {
+    pub id: String,
+    #[serde(flatten)]
+    pub action: PipelineStepAction,
+    /// Steps that must all have finished before this one starts
+    #[serde(default)]
+    pub needs: Vec<String>,
+    /// Transitions followed when this step finishes
+    #[serde(default)]
+    pub next: Vec<PipelineTransition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStepAction {
+    /// Prompt the session's coding agent, as a follow-up once it has run
+    Agent(AgentStep),
+    /// Run a script in the workspace
+    Script(ScriptStep),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentStep {
+    /// MiniJinja template; defaults to the task prompt
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptStep {
+    pub script: String,
+    /// Directory relative to the workspace root, e.g. a repository name
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    #[serde(flatten)]
+    pub options: ScriptOptions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineTransition {
+    pub to: String,
+    /// Always followed when empty
+    #[serde(default)]
+    pub when: PipelineCondition,
+    /// How many times this transition may be followed in one run
+    #[serde(default)]
+    pub max: Option<u32>,
+}
+
+/// Every field that is set must match
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PipelineCondition {
+    #[serde(default)]
+    pub outcome: Option<PipelineStepOutcome>,
+    #[serde(default)]
+    pub exit_code: Option<i64>,
+    #[serde(default)]
+    pub output_contains: Option<String>,
+    /// Regular expression searched in the output
+    #[serde(default)]
+    pub output_matches: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PipelineStepOutcome {
+    Success,
+    Failure,
+}
+
+/// How a finished step ended, as seen by conditions and prompt templates
+#[derive(Debug, Clone, Serialize)]
+pub struct StepResult {
+    pub step: String,
+    pub outcome: PipelineStepOutcome,
+    pub exit_code: Option<i64>,
+    pub output: String,
+}
+
+impl PipelineDefinition {
+    /// Parse and validate a TOML definition
+    pub fn parse(source: &str) -> Result<Self, PipelineError> {
+        let definition: Self = toml::from_str(source)?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    pub fn validate(&self) -> Result<(), PipelineError> {
+        if self.steps.is_empty() {
+            return Err(PipelineError::NoSteps);
+        }
+
+        let mut ids = HashSet::new();
+        for step in &self.steps {
+            if step.id.trim().is_empty() {
+                return Err(PipelineError::EmptyStepId);
+            }
+            if !ids.insert(step.id.as_str()) {
+                return Err(PipelineError::DuplicateStep(step.id.clone()));
+            }
+        }
+
+        for step in &self.steps {
+            let targets = step
+                .needs
+                .iter()
+                .chain(step.next.iter().map(|transition| &transition.to));
+            for target in targets {
+                if !ids.contains(target.as_str()) {
+                    return Err(PipelineError::UnknownStep {
+                        from: step.id.clone(),
+                        to: target.clone(),
+                    });
+                }
+            }
+
+            match &step.action {
+                PipelineStepAction::Agent(agent) => {
+                    if let Some(prompt) = &agent.prompt {
+                        Environment::new().template_from_str(prompt).map_err(|e| {
+                            PipelineError::InvalidTemplate {
+                                step: step.id.clone(),
+                                message: e.to_string(),
+                            }
+                        })?;
+                    }
+                }
+                PipelineStepAction::Script(script) => {
+                    if script.script.trim().is_empty() {
+                        return Err(PipelineError::EmptyScript(step.id.clone()));
+                    }
+                }
+            }
+
+            for transition in &step.next {
+                if let Some(pattern) = &transition.when.output_matches {
+                    Regex::new(pattern).map_err(|e| PipelineError::InvalidPattern {
+                        step: step.id.clone(),
+                        message: e.to_string(),
+                    })?;
+                }
+            }
+        }
+
+        self.check_loops_are_bounded()?;
+        self.check_agent_steps_are_sequential()
+    }
+
+    /// Every cycle must go through a transition with a `max`, otherwise a run could
+    /// loop forever. Transitions with a `max` are left out and the rest must be acyclic.
+    fn check_loops_are_bounded(&self) -> Result<(), PipelineError> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            Visiting,
+            Done,
+        }
+
+        fn visit<'a>(
+            definition: &'a PipelineDefinition,
+            id: &'a str,
+            marks: &mut HashMap<&'a str, Mark>,
+        ) -> Result<(), PipelineError> {
+            match marks.get(id) {
+                Some(Mark::Done) => return Ok(()),
+                Some(Mark::Visiting) => return Err(PipelineError::UnboundedLoop(id.to_string())),
+                None => {}
+            }
+            marks.insert(id, Mark::Visiting);
+            if let Some(step) = definition.step(id) {
+                for transition in step.next.iter().filter(|t| t.max.is_none()) {
+                    visit(definition, &transition.to, marks)?;
+                }
+            }
+            marks.insert(id, Mark::Done);
+            Ok(())
+        }
+
+        let mut marks = HashMap::new();
+        for step in &self.steps {
+            visit(self, &step.id, &mut marks)?;
+        }
+        Ok(())
+    }
+
+    /// Two transitions of a step that can both match start parallel branches. Those
+    /// branches run until they reach a join, and only one of them may reach an agent
+    /// step, because the session's agent can only take one turn at a time.
+    fn check_agent_steps_are_sequential(&self) -> Result<(), PipelineError> {
+        for step in &self.steps {
+            for (i, first) in step.next.iter().enumerate() {
+                for second in &step.next[i + 1..] {
+                    if first.to == second.to || first.when.excludes(&second.when) {
+                        continue;
+                    }
+                    if let (Some(first), Some(second)) = (
+                        self.agent_step_before_join(&first.to),
+                        self.agent_step_before_join(&second.to),
+                    ) {
+                        return Err(PipelineError::ParallelAgentSteps {
+                            step: step.id.clone(),
+                            first: first.to_string(),
+                            second: second.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// First agent step reachable from `start` without passing a step with `needs`
+    fn agent_step_before_join<'a>(&'a self, start: &'a str) -> Option<&'a str> {
+        let mut seen = HashSet::new();
+        let mut pending = vec![start];
+        while let Some(id) = pending.pop() {
+            let Some(step) = self.step(id) else {
+                continue;
+            };
+            if !step.needs.is_empty() || !seen.insert(id) {
+                continue;
+            }
+            if matches!(step.action, PipelineStepAction::Agent(_)) {
+                return Some(id);
+            }
+            pending.extend(step.next.iter().map(|transition| transition.to.as_str()));
+        }
+        None
+    }
+
+    pub fn step(&self, id: &str) -> Option<&PipelineStep> {
+        self.steps.iter().find(|step| step.id == id)
+    }
+
+    /// The step a run starts with
+    pub fn first_step(&self) -> &PipelineStep {
+        &self.steps[0]
+    }
+
+    /// Steps to start after `result`. `times_taken` reports how often the transition
+    /// from the finished step to a target has already been followed in this run.
+    pub fn next_steps(
+        &self,
+        result: &StepResult,
+        times_taken: impl Fn(&str) -> u32,
+    ) -> Vec<&PipelineStep> {
+        let Some(step) = self.step(&result.step) else {
+            return Vec::new();
+        };
+        let mut next: Vec<&PipelineStep> = Vec::new();
+        for transition in &step.next {
+            if !transition.when.matches(result) {
+                continue;
+            }
+            if transition
+                .max
+                .is_some_and(|max| times_taken(&transition.to) >= max)
+            {
+                continue;
+            }
+            if let Some(target) = self.step(&transition.to)
+                && !next.iter().any(|step| step.id == target.id)
+            {
+                next.push(target);
+            }
+        }
+        next
+    }
+}
+
+impl PipelineCondition {
+    /// Whether no step result can match both conditions
+    fn excludes(&self, other: &Self) -> bool {
+        fn differ<T: PartialEq>(a: &Option<T>, b: &Option<T>) -> bool {
+            matches!((a, b), (Some(a), Some(b)) if a != b)
+        }
+        differ(&self.outcome, &other.outcome) || differ(&self.exit_code, &other.exit_code)
+    }
+
+    pub fn matches(&self, result: &StepResult) -> bool {
+        if self
+            .outcome
+            .is_some_and(|outcome| outcome != result.outcome)
+        {
+            return false;
+        }
+        if self
+            .exit_code
+            .is_some_and(|code| Some(code) != result.exit_code)
+        {
+            return false;
+        }
+        if let Some(needle) = &self.output_contains
+            && !result.output.contains(needle.as_str())
+        {
+            return false;
+        }
+        if let Some(pattern) = &self.output_matches {
+            // Patterns are checked when the definition is saved
+            return Regex::new(pattern).is_ok_and(|re| re.is_match(&result.output));
+        }
+        true
+    }
+}
+
+impl AgentStep {
+    /// Render the prompt for this step. `previous` is the step that led here, if any.
+    pub fn render_prompt(
+        &self,
+        task_title: &str,
+        task_description: Option<&str>,
+        task_prompt: &str,
+        previous: Option<&StepResult>,
+    ) -> Result<String, PipelineError> {
+        let Some(template) = &self.prompt else {
+            return Ok(task_prompt.to_string());
+        };
+        let context = minijinja::context! {
+            task => minijinja::context! {
+                title => task_title,
+                description => task_description,
+            },
+            step => previous.map(|result| result.step.as_str()),
+            outcome => previous.map(|result| result.outcome),
+            exit_code => previous.and_then(|result| result.exit_code),
+            output => previous.map(|result| result.output.as_str()).unwrap_or_default(),
+        };
+        Environment::new()
+            .template_from_str(template)
+            .and_then(|template| template.render(context))
+            .map_err(|e| PipelineError::Render(e.to_string()))
+    }
+}
+
+/// Keep the last [`OUTPUT_TAIL_BYTES`] of `output`, on a character boundary
+pub fn output_tail(output: &str) -> &str {
+    if output.len() <= OUTPUT_TAIL_BYTES {
+        return output;
+    }
+    let mut start = output.len() - OUTPUT_TAIL_BYTES;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    &output[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_AND_FIX: &str = r#"
+[[steps]]
+id = "implement"
+agent = {}
+next = [{ to = "test" }]
+
+[[steps]]
+id = "test"
+script = { script = "cargo test", timeout_secs = 900 }
+next = [{ to = "fix", when = { outcome = "failure" }, max = 3 }]
+
+[[steps]]
+id = "fix"
+agent = { prompt = "The tests failed:\n{{ output }}" }
+next = [{ to = "test" }]
+"#;
+
+    fn result(step: &str, outcome: PipelineStepOutcome, output: &str) -> StepResult {
+        StepResult {
+            step: step.to_string(),
+            outcome,
+            exit_code: Some(match outcome {
+                PipelineStepOutcome::Success => 0,
+                PipelineStepOutcome::Failure => 1,
+            }),
+            output: output.to_string(),
+        }
+    }
+
+    fn ids(steps: Vec<&PipelineStep>) -> Vec<&str> {
+        steps.into_iter().map(|step| step.id.as_str()).collect()
+    }
+
+    #[test]
+    fn parses_steps_and_script_options() {
+        let definition = PipelineDefinition::parse(TEST_AND_FIX).unwrap();
+        assert_eq!(definition.first_step().id, "implement");
+        let PipelineStepAction::Script(script) = &definition.step("test").unwrap().action else {
+            panic!("test should be a script step");
+        };
+        assert_eq!(script.script, "cargo test");
+        assert_eq!(script.options.timeout_secs, Some(900));
+    }
+
+    #[test]
+    fn loops_until_max_is_reached() {
+        let definition = PipelineDefinition::parse(TEST_AND_FIX).unwrap();
+        let failed = result("test", PipelineStepOutcome::Failure, "1 failed");
+
+        assert_eq!(ids(definition.next_steps(&failed, |_| 0)), vec!["fix"]);
+        assert_eq!(ids(definition.next_steps(&failed, |_| 2)), vec!["fix"]);
+        assert!(definition.next_steps(&failed, |_| 3).is_empty());
+
+        let passed = result("test", PipelineStepOutcome::Success, "ok");
+        assert!(definition.next_steps(&passed, |_| 0).is_empty());
+    }
+
+    #[test]
+    fn matching_transitions_start_parallel_branches() {
+        let definition = PipelineDefinition::parse(
+            r#"
+[[steps]]
+id = "implement"
+agent = {}
+next = [{ to = "lint" }, { to = "test" }, { to = "docs", when = { output_matches = "docs?/" } }]
+
+[[steps]]
+id = "lint"
+script = { script = "cargo clippy" }
+next = [{ to = "review" }]
+
+[[steps]]
+id = "test"
+script = { script = "cargo test" }
+next = [{ to = "review" }]
+
+[[steps]]
+id = "docs"
+script = { script = "mdbook build" }
+
+[[steps]]
+id = "review"
+needs = ["lint", "test"]
+agent = { prompt = "Review your change" }
+"#,
+        )
+        .unwrap();
+
+        let done = result(
+            "implement",
+            PipelineStepOutcome::Success,
+            "edited src/lib.rs",
+        );
+        assert_eq!(
+            ids(definition.next_steps(&done, |_| 0)),
+            vec!["lint", "test"]
+        );
+
+        let done = result(
+            "implement",
+            PipelineStepOutcome::Success,
+            "edited docs/intro.md",
+        );
+        assert_eq!(
+            ids(definition.next_steps(&done, |_| 0)),
+            vec!["lint", "test", "docs"]
+        );
+    }
+
+    #[test]
+    fn rejects_unbounded_loops_and_unknown_steps() {
+        let unbounded = TEST_AND_FIX.replace(", max = 3", "");
+        assert!(matches!(
+            PipelineDefinition::parse(&unbounded),
+            Err(PipelineError::UnboundedLoop(_))
+        ));
+
+        let unknown = TEST_AND_FIX.replace("to = \"fix\"", "to = \"repair\"");
+        assert!(matches!(
+            PipelineDefinition::parse(&unknown),
+            Err(PipelineError::UnknownStep { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_agent_steps_on_parallel_branches() {
+        let parallel = r#"
+[[steps]]
+id = "test"
+script = { script = "cargo test" }
+next = [{ to = "fix" }, { to = "lint" }]
+
+[[steps]]
+id = "lint"
+script = { script = "cargo clippy" }
+next = [{ to = "tidy", when = { outcome = "failure" } }]
+
+[[steps]]
+id = "fix"
+agent = { prompt = "Fix the tests" }
+
+[[steps]]
+id = "tidy"
+agent = { prompt = "Fix the lints" }
+"#;
+        assert!(matches!(
+            PipelineDefinition::parse(parallel),
+            Err(PipelineError::ParallelAgentSteps { step, .. }) if step == "test"
+        ));
+
+        // Branches on different outcomes never run together
+        let exclusive = parallel.replace(
+            r#"next = [{ to = "fix" }, { to = "lint" }]"#,
+            r#"next = [{ to = "fix", when = { outcome = "failure" } }, { to = "lint", when = { outcome = "success" } }]"#,
+        );
+        PipelineDefinition::parse(&exclusive).unwrap();
+    }
+
+    #[test]
+    fn renders_prompt_with_previous_output() {
+        let definition = PipelineDefinition::parse(TEST_AND_FIX).unwrap();
+        let PipelineStepAction::Agent(fix) = &definition.step("fix").unwrap().action else {
+            panic!("fix should be an agent step");
+        };
+        let failed = result("test", PipelineStepOutcome::Failure, "assertion failed");
+        let prompt = fix
+            .render_prompt("Add parser", None, "Add parser", Some(&failed))
+            .unwrap();
+        assert_eq!(prompt, "The tests failed:\nassertion failed");
+
+        let PipelineStepAction::Agent(implement) = &definition.first_step().action else {
+            panic!("implement should be an agent step");
+        };
+        let prompt = implement
+            .render_prompt("Add parser", None, "Add parser", None)
+            .unwrap();
+        assert_eq!(prompt, "Add parser");
+    }
+
+    #[test]
+    fn output_tail_keeps_char_boundaries() {
+        let output = format!("é{}", "a".repeat(OUTPUT_TAIL_BYTES - 1));
+        let tail = output_tail(&output);
+        assert!(tail.len() < OUTPUT_TAIL_BYTES);
+        assert!(tail.chars().all(|c| c == 'a'));
+    }
+}
@@ -4,7 +4,9 @@ use db::{
     DBService,
     models::{
         merge::{Merge, MergeStatus, PrMerge},
+        repo::Repo,
         task::{Task, TaskStatus},
+        task_history::{TaskActor, TaskHistory},
         workspace::{Workspace, WorkspaceError},
     },
 };
@@ -16,21 +18,21 @@ use tracing::{debug, error, info};
 
 use crate::services::{
     analytics::AnalyticsContext,
-    github::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+    forge::{ForgeError, ForgeRepoInfo, forge_for},
     share::SharePublisher,
 };
 
 #[derive(Debug, Error)]
 enum PrMonitorError {
     #[error(transparent)]
-    GitHubServiceError(#[from] GitHubServiceError),
+    Forge(#[from] ForgeError),
     #[error(transparent)]
     WorkspaceError(#[from] WorkspaceError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
 }
 
-/// Service to monitor GitHub PRs and update task status when they are merged
+/// Service to monitor pull and merge requests and update task status when they are merged
 pub struct PrMonitorService {
     db: DBService,
     poll_interval: Duration,
@@ -71,7 +73,7 @@ impl PrMonitorService {
         }
     }
 
-    /// Check all open PRs for updates with the provided GitHub token
+    /// Check all open PRs for updates on their forges
     async fn check_all_open_prs(&self) -> Result<(), PrMonitorError> {
         let open_prs = Merge::get_open_prs(&self.db.pool).await?;
 
@@ -95,12 +97,18 @@ impl PrMonitorService {
 
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        // GitHubService now uses gh CLI, no token needed
-        let github_service = GitHubService::new()?;
-        let repo_info = GitHubRepoInfo::from_remote_url(&pr_merge.pr_info.url)?;
-
-        let pr_status = github_service
-            .update_pr_status(&repo_info, pr_merge.pr_info.number)
+        // Self-hosted forges may live under a different base URL than the PR link
+        let forge_url = Repo::find_by_id(&self.db.pool, pr_merge.repo_id)
+            .await?
+            .and_then(|repo| repo.forge_url);
+        let repo_info = ForgeRepoInfo::from_url(
+            &pr_merge.pr_info.url,
+            Some(pr_merge.pr_info.forge),
+            forge_url.as_deref(),
+        )?;
+
+        let pr_status = forge_for(&repo_info)?
+            .get_pr_status(&repo_info, pr_merge.pr_info.number)
             .await?;
 
         debug!(
@@ -110,12 +118,12 @@ impl PrMonitorService {
 
         // Update the PR status in the database
         if !matches!(&pr_status.status, MergeStatus::Open) {
-            // Update merge status with the latest information from GitHub
+            // Update merge status with the latest information from the forge
             Merge::update_status(
                 &self.db.pool,
                 pr_merge.id,
                 pr_status.status.clone(),
-                pr_status.merge_commit_sha,
+                pr_status.merge_commit_sha.clone(),
             )
             .await?;
 
@@ -128,7 +136,21 @@ impl PrMonitorService {
                     "PR #{} was merged, updating task {} to done",
                     pr_merge.pr_info.number, workspace.task_id
                 );
-                Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                let previous_status = Task::find_by_id(&self.db.pool, workspace.task_id)
+                    .await?
+                    .map(|task| task.status);
+                Task::update_status_by_system(&self.db.pool, workspace.task_id, TaskStatus::Done)
+                    .await?;
+                if let Some(previous_status) = previous_status {
+                    TaskHistory::record_status_change(
+                        &self.db.pool,
+                        workspace.task_id,
+                        &previous_status,
+                        &TaskStatus::Done,
+                        &TaskActor::system("pr_monitor"),
+                    )
+                    .await?;
+                }
 
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
@@ -8,6 +8,7 @@ use db::models::{
     project_repo::{CreateProjectRepo, ProjectRepo},
     repo::Repo,
     task::Task,
+    task_history::{TaskActor, TaskHistory},
 };
 use ignore::WalkBuilder;
 use sqlx::SqlitePool;
@@ -132,6 +133,10 @@ impl ProjectService {
                     dev_script: None,
                     dev_script_working_dir: None,
                     default_agent_working_dir: Some(repo.name),
+                    verification_command: None,
+                    verification_working_dir: None,
+                    verification_max_retries: None,
+                    usage_budget_usd: None,
                 },
             )
             .await?;
@@ -173,12 +178,27 @@ impl ProjectService {
         project: &Project,
     ) -> Result<Project> {
         if let Some(remote_project_id) = project.remote_project_id {
+            let shared_tasks: Vec<Task> = Task::find_all_shared(pool)
+                .await?
+                .into_iter()
+                .filter(|task| task.project_id == project.id)
+                .collect();
+
             let mut tx = pool.begin().await?;
 
             Task::clear_shared_task_ids_for_remote_project(&mut *tx, remote_project_id).await?;
             Project::set_remote_project_id_tx(&mut *tx, project.id, None).await?;
 
             tx.commit().await?;
+
+            let actor = TaskActor::system("share_sync");
+            for task in &shared_tasks {
+                let unlinked = Task {
+                    shared_task_id: None,
+                    ..task.clone()
+                };
+                TaskHistory::record_changes(pool, task, &unlinked, &actor).await?;
+            }
         }
 
         let updated = Project::find_by_id(pool, project.id)
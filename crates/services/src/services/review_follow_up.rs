@@ -0,0 +1,268 @@
+//! Prompts for addressing the review comments of a pull request with the
+//! workspace's coding agent.
+//!
+//! Comments count as pending when they were written after the last push of the
+//! branch, are not resolved on the forge and were not addressed by an earlier
+//! follow-up. Once the agent's turn ends, its commit is pushed and every comment can
+//! get a reply naming the commit. Replies carry [`REPLY_MARKER`] so they are never
+//! taken for new comments.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use db::models::review_follow_up::{
+    CreateReviewFollowUpComment, ReviewCommentType, ReviewFollowUp,
+};
+
+use crate::services::github::UnifiedPrComment;
+
+/// Hidden in the rendered reply on GitHub, GitLab and Gitea
+pub const REPLY_MARKER: &str = "<!-- vibe-kanban:review-follow-up -->";
+
+/// Comments listed in the prompt; the rest wait for the next follow-up
+pub const MAX_PROMPT_COMMENTS: usize = 30;
+
+/// Diff lines shown above an inline comment
+const MAX_HUNK_LINES: usize = 20;
+
+/// Type and forge id of a comment, as stored with a follow-up
+pub fn comment_ref(comment: &UnifiedPrComment) -> (ReviewCommentType, String) {
+    match comment {
+        UnifiedPrComment::General { id, .. } => (ReviewCommentType::General, id.clone()),
+        UnifiedPrComment::Review { id, .. } => (ReviewCommentType::Review, id.to_string()),
+    }
+}
+
+pub fn create_comment(comment: &UnifiedPrComment) -> CreateReviewFollowUpComment {
+    let (comment_type, comment_id) = comment_ref(comment);
+    let (url, path, line) = match comment {
+        UnifiedPrComment::General { url, .. } => (url.clone(), None, None),
+        UnifiedPrComment::Review {
+            url, path, line, ..
+        } => (url.clone(), Some(path.clone()), *line),
+    };
+    CreateReviewFollowUpComment {
+        comment_id,
+        comment_type,
+        url,
+        path,
+        line,
+    }
+}
+
+/// Comments older than the returned time were already there at the last push.
+/// When an earlier follow-up made that push, the time it collected its comments
+/// counts instead, so comments written while its agent worked are not skipped.
+pub fn comment_cutoff(
+    last_push: Option<DateTime<Utc>>,
+    last_follow_up: Option<&ReviewFollowUp>,
+) -> Option<DateTime<Utc>> {
+    let last_push = last_push?;
+    match last_follow_up {
+        Some(follow_up) if follow_up.completed_at.is_some_and(|done| done >= last_push) => {
+            Some(follow_up.created_at)
+        }
+        _ => Some(last_push),
+    }
+}
+
+/// Comments that still need the agent, oldest first
+pub fn pending_comments(
+    comments: Vec<UnifiedPrComment>,
+    since: Option<DateTime<Utc>>,
+    resolved_ids: &HashSet<String>,
+    addressed: &HashSet<(ReviewCommentType, String)>,
+) -> Vec<UnifiedPrComment> {
+    comments
+        .into_iter()
+        .filter(|comment| since.is_none_or(|since| comment.created_at() > since))
+        .filter(|comment| {
+            let comment_ref = comment_ref(comment);
+            !resolved_ids.contains(&comment_ref.1) && !addressed.contains(&comment_ref)
+        })
+        .filter(|comment| !comment.body().contains(REPLY_MARKER))
+        .collect()
+}
+
+fn quote(body: &str) -> String {
+    body.trim()
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Prompt asking the agent to address `comments` of pull request `pr_number`
+pub fn follow_up_prompt(pr_number: i64, pr_url: &str, comments: &[UnifiedPrComment]) -> String {
+    let mut prompt = format!(
+        "Reviewers left {} comment(s) on pull request #{pr_number} ({pr_url}) that have not \
+         been addressed yet.\n",
+        comments.len()
+    );
+    for (index, comment) in comments.iter().take(MAX_PROMPT_COMMENTS).enumerate() {
+        match comment {
+            UnifiedPrComment::General { author, body, .. } => {
+                prompt.push_str(&format!(
+                    "\n## {}. Comment by @{author}\n\n{}\n",
+                    index + 1,
+                    quote(body)
+                ));
+            }
+            UnifiedPrComment::Review {
+                author,
+                body,
+                path,
+                line,
+                diff_hunk,
+                ..
+            } => {
+                let location = match line {
+                    Some(line) => format!("`{path}` line {line}"),
+                    None => format!("`{path}`"),
+                };
+                prompt.push_str(&format!(
+                    "\n## {}. Comment by @{author} on {location}\n",
+                    index + 1
+                ));
+                // The hunk ends at the commented line
+                let hunk = diff_hunk.lines().collect::<Vec<_>>();
+                if !hunk.is_empty() {
+                    let shown = &hunk[hunk.len().saturating_sub(MAX_HUNK_LINES)..];
+                    prompt.push_str(&format!("\n```diff\n{}\n```\n", shown.join("\n")));
+                }
+                prompt.push_str(&format!("\n{}\n", quote(body)));
+            }
+        }
+    }
+    if comments.len() > MAX_PROMPT_COMMENTS {
+        prompt.push_str(&format!(
+            "\n[{} more comments are left for a later follow-up]\n",
+            comments.len() - MAX_PROMPT_COMMENTS
+        ));
+    }
+    prompt.push_str(
+        "\nMake the changes the comments ask for. If a comment needs no change or you \
+         disagree with it, leave the code as it is and say why in your final message. Do \
+         not push or reply on the pull request: once your turn ends, your changes are \
+         committed and pushed for you.",
+    );
+    prompt
+}
+
+/// Reply to an addressed comment
+pub fn reply_body(commit_sha: &str) -> String {
+    format!("Addressed in {commit_sha}.\n\n{REPLY_MARKER}")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn at(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 25, 12, minute, 0).unwrap()
+    }
+
+    fn general(id: &str, minute: u32, body: &str) -> UnifiedPrComment {
+        UnifiedPrComment::General {
+            id: id.to_string(),
+            author: "reviewer".to_string(),
+            author_association: "MEMBER".to_string(),
+            body: body.to_string(),
+            created_at: at(minute),
+            url: format!("https://github.com/owner/repo/pull/1#issuecomment-{id}"),
+        }
+    }
+
+    fn review(id: i64, minute: u32) -> UnifiedPrComment {
+        UnifiedPrComment::Review {
+            id,
+            author: "reviewer".to_string(),
+            author_association: "MEMBER".to_string(),
+            body: "Handle the error here".to_string(),
+            created_at: at(minute),
+            url: format!("https://github.com/owner/repo/pull/1#discussion_r{id}"),
+            path: "src/lib.rs".to_string(),
+            line: Some(12),
+            diff_hunk: "@@ -10,3 +10,3 @@\n fn load() {\n-    read().ok();\n+    read().unwrap();"
+                .to_string(),
+        }
+    }
+
+    fn follow_up(created: u32, completed: u32) -> ReviewFollowUp {
+        ReviewFollowUp {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            pr_number: 1,
+            execution_process_id: None,
+            base_commit: None,
+            reply_to_comments: true,
+            status: db::models::review_follow_up::ReviewFollowUpStatus::Pushed,
+            commit_sha: Some("abc".to_string()),
+            message: None,
+            created_at: at(created),
+            completed_at: Some(at(completed)),
+        }
+    }
+
+    #[test]
+    fn skips_old_resolved_addressed_and_own_comments() {
+        let comments = vec![
+            general("old", 1, "Before the push"),
+            general("new", 10, "Please rename this"),
+            review(7, 11),
+            review(8, 12),
+            review(9, 13),
+            general("reply", 14, &reply_body("abc")),
+        ];
+        let resolved = HashSet::from(["8".to_string()]);
+        let addressed = HashSet::from([(ReviewCommentType::Review, "9".to_string())]);
+
+        let pending = pending_comments(comments, Some(at(5)), &resolved, &addressed);
+        let refs = pending.iter().map(comment_ref).collect::<Vec<_>>();
+        assert_eq!(
+            refs,
+            vec![
+                (ReviewCommentType::General, "new".to_string()),
+                (ReviewCommentType::Review, "7".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_follow_up_push_counts_from_when_it_collected_comments() {
+        assert_eq!(comment_cutoff(None, None), None);
+        assert_eq!(comment_cutoff(Some(at(20)), None), Some(at(20)));
+        // The follow-up pushed at minute 20 after collecting at minute 5
+        assert_eq!(
+            comment_cutoff(Some(at(20)), Some(&follow_up(5, 21))),
+            Some(at(5))
+        );
+        // Someone pushed after the follow-up
+        assert_eq!(
+            comment_cutoff(Some(at(30)), Some(&follow_up(5, 21))),
+            Some(at(30))
+        );
+    }
+
+    #[test]
+    fn prompt_quotes_comments_with_their_code() {
+        let prompt = follow_up_prompt(
+            1,
+            "https://github.com/owner/repo/pull/1",
+            &[
+                general("new", 10, "Please rename this\nto `load_config`"),
+                review(7, 11),
+            ],
+        );
+
+        assert!(prompt.starts_with("Reviewers left 2 comment(s) on pull request #1"));
+        assert!(prompt.contains("## 1. Comment by @reviewer\n\n> Please rename this\n> to"));
+        assert!(prompt.contains("## 2. Comment by @reviewer on `src/lib.rs` line 12"));
+        assert!(prompt.contains("+    read().unwrap();"));
+        assert!(prompt.contains("Do not push or reply on the pull request"));
+    }
+}
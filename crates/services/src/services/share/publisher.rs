@@ -2,7 +2,9 @@ use db::{
     DBService,
     models::{
         project::Project,
+        project_workflow::ProjectWorkflow,
         task::{CreateTask, Task, TaskStatus},
+        task_history::{TaskActor, TaskHistory},
     },
 };
 use remote::routes::tasks::{
@@ -26,6 +28,9 @@ pub struct SharedTaskDetails {
     pub title: String,
     pub description: Option<String>,
     pub status: TaskStatus,
+    #[serde(default)]
+    #[ts(optional)]
+    pub workflow_state: Option<String>,
 }
 
 impl SharePublisher {
@@ -59,6 +64,8 @@ impl SharePublisher {
         let remote_task = self.client.create_shared_task(&payload).await?;
 
         Task::set_shared_task_id(&self.db.pool, task.id, Some(remote_task.task.id)).await?;
+        self.record_shared_task_id(&task, Some(remote_task.task.id))
+            .await?;
         Ok(remote_task.task.id)
     }
 
@@ -72,6 +79,7 @@ impl SharePublisher {
             title: Some(task.title.clone()),
             description: task.description.clone(),
             status: Some(status::to_remote(&task.status)),
+            workflow_state: task.workflow_state.clone(),
         };
 
         self.client
@@ -118,6 +126,7 @@ impl SharePublisher {
             Task::find_by_shared_task_id(&self.db.pool, shared_task_id).await?
         {
             Task::set_shared_task_id(&self.db.pool, local_task.id, None).await?;
+            self.record_shared_task_id(&local_task, None).await?;
         }
 
         Ok(())
@@ -144,11 +153,44 @@ impl SharePublisher {
         );
 
         let id = Uuid::new_v4();
-        let task = Task::create(&self.db.pool, &create_task, id).await?;
+        let mut task = Task::create(&self.db.pool, &create_task, id).await?;
+
+        // Keep the sharer's workflow state when this project has a matching one
+        if let Some(state_name) = shared_task.workflow_state {
+            let workflow =
+                ProjectWorkflow::find_by_project_id(&self.db.pool, task.project_id).await?;
+            if let Some(state) = workflow
+                .find_state(&state_name)
+                .filter(|state| state.category == task.status)
+            {
+                Task::set_status(&self.db.pool, task.id, &task.status, Some(&state.name)).await?;
+                task.workflow_state = Some(state.name.clone());
+            }
+        }
+
+        TaskHistory::record_created(&self.db.pool, &task, &Self::actor()).await?;
 
         Ok(Some(task))
     }
 
+    fn actor() -> TaskActor {
+        TaskActor::system("share_sync")
+    }
+
+    /// Record a change of the remote link in the task history
+    async fn record_shared_task_id(
+        &self,
+        task: &Task,
+        shared_task_id: Option<Uuid>,
+    ) -> Result<(), ShareError> {
+        let updated = Task {
+            shared_task_id,
+            ..task.clone()
+        };
+        TaskHistory::record_changes(&self.db.pool, task, &updated, &Self::actor()).await?;
+        Ok(())
+    }
+
     async fn shared_task_exists(&self, shared_task_id: Uuid) -> Result<bool, ShareError> {
         Ok(self
             .client
@@ -195,6 +237,16 @@ impl SharePublisher {
 
                 if let Err(e) = Task::batch_unlink_shared_tasks(&self.db.pool, &missing_ids).await {
                     tracing::error!("Failed to batch unlink tasks: {}", e);
+                    continue;
+                }
+
+                for task in tasks
+                    .iter()
+                    .filter(|t| t.shared_task_id.is_some_and(|id| missing_ids.contains(&id)))
+                {
+                    if let Err(e) = self.record_shared_task_id(task, None).await {
+                        tracing::warn!("Failed to record history for task {}: {}", task.id, e);
+                    }
                 }
             }
         }
@@ -0,0 +1,198 @@
+//! Stacked workspaces: a workspace whose branch builds on another workspace's
+//! unmerged branch instead of the project's target branch.
+//!
+//! Every stacked repo records the parent commit it was last based on, so the
+//! workspace's own commits are the ones after it. Restacking replays those commits
+//! onto the parent's branch once it has moved (e.g. after a rebase), or onto the
+//! parent's target branch once the parent has merged, after which the workspace
+//! leaves the stack.
+
+use std::time::Duration;
+
+use db::models::{execution_process::ExecutionProcess, task::TaskStatus, workspace::Workspace};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::container::ContainerService;
+
+/// Default poll interval in seconds (60 seconds).
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
+
+/// Environment variable name for configuring the poll interval.
+const POLL_INTERVAL_ENV_VAR: &str = "STACK_MONITOR_POLL_INTERVAL_SECS";
+
+/// What restacking one repo of a workspace needs to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestackPlan {
+    /// Already based on the tip of the parent's branch
+    UpToDate,
+    /// Replay onto the parent's branch, which has moved to `parent_tip`
+    OntoParent { parent_tip: String },
+    /// The parent has merged: replay onto its target branch and leave the stack
+    OntoTarget,
+    /// The parent's branch is gone without a merge, so there is nothing to follow
+    ParentMissing,
+}
+
+/// Decide how a stacked repo follows its parent. `parent_tip` is None when the
+/// parent's branch no longer exists.
+pub fn plan_restack(
+    stack_base_commit: &str,
+    parent_tip: Option<&str>,
+    parent_merged: bool,
+) -> RestackPlan {
+    if parent_merged {
+        return RestackPlan::OntoTarget;
+    }
+    match parent_tip {
+        None => RestackPlan::ParentMissing,
+        Some(tip) if tip == stack_base_commit => RestackPlan::UpToDate,
+        Some(tip) => RestackPlan::OntoParent {
+            parent_tip: tip.to_string(),
+        },
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum RestackStatus {
+    UpToDate,
+    /// Rebased onto the parent's moved branch
+    Rebased,
+    /// Rebased onto the parent's target branch after the parent merged
+    Unstacked,
+    /// The rebase hit conflicts and was aborted; the branch is unchanged
+    Conflicts,
+    /// Not restacked, e.g. because of uncommitted changes
+    Skipped,
+}
+
+/// Outcome of restacking one repo of a workspace
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RestackResult {
+    pub repo_id: Uuid,
+    pub status: RestackStatus,
+    /// Branch the workspace's commits are based on afterwards
+    pub base_branch: String,
+    pub message: Option<String>,
+}
+
+/// Background service that keeps stacked workspaces on top of their parents.
+pub struct StackMonitorService<C> {
+    container: C,
+    poll_interval: Duration,
+}
+
+impl<C> StackMonitorService<C>
+where
+    C: ContainerService + Clone + Send + Sync + 'static,
+{
+    /// Spawn the monitor as a background task.
+    ///
+    /// The poll interval can be configured via the `STACK_MONITOR_POLL_INTERVAL_SECS`
+    /// environment variable. Defaults to 60 seconds.
+    pub async fn spawn(container: C) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            container,
+            poll_interval: Self::get_poll_interval(),
+        };
+
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    fn get_poll_interval() -> Duration {
+        std::env::var(POLL_INTERVAL_ENV_VAR)
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS))
+    }
+
+    async fn start(&self) {
+        info!(
+            poll_interval_secs = self.poll_interval.as_secs(),
+            "Starting stack monitor service"
+        );
+
+        let mut interval = tokio::time::interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.restack_all().await {
+                error!(error = %e, "Error restacking stacked workspaces");
+            }
+        }
+    }
+
+    async fn restack_all(&self) -> Result<(), sqlx::Error> {
+        let pool = &self.container.db().pool;
+        for workspace in Workspace::find_all_stacked(pool).await? {
+            let finished = matches!(
+                workspace.parent_task(pool).await?.map(|task| task.status),
+                None | Some(TaskStatus::Done | TaskStatus::Cancelled)
+            );
+            // Never rebase under a running agent or script
+            if finished
+                || ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                    pool,
+                    workspace.id,
+                )
+                .await?
+            {
+                continue;
+            }
+
+            match self.container.restack_workspace(&workspace).await {
+                Ok(results) => {
+                    for result in results {
+                        debug!(
+                            workspace_id = %workspace.id,
+                            repo_id = %result.repo_id,
+                            status = ?result.status,
+                            "Restacked workspace repo"
+                        );
+                    }
+                }
+                Err(e) => {
+                    error!(workspace_id = %workspace.id, error = %e, "Failed to restack workspace");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_the_parent_until_it_merges() {
+        assert_eq!(
+            plan_restack("abc", Some("abc"), false),
+            RestackPlan::UpToDate
+        );
+        assert_eq!(
+            plan_restack("abc", Some("def"), false),
+            RestackPlan::OntoParent {
+                parent_tip: "def".to_string()
+            }
+        );
+        // A merged parent's branch may be deleted or still point anywhere
+        assert_eq!(
+            plan_restack("abc", Some("abc"), true),
+            RestackPlan::OntoTarget
+        );
+        assert_eq!(plan_restack("abc", None, true), RestackPlan::OntoTarget);
+    }
+
+    #[test]
+    fn a_deleted_unmerged_parent_is_not_followed() {
+        assert_eq!(plan_restack("abc", None, false), RestackPlan::ParentMissing);
+    }
+}
@@ -0,0 +1,403 @@
+//! Background scheduler that starts ready tasks automatically.
+//!
+//! On every tick the scheduler looks for `Todo` tasks that have never been
+//! attempted and whose blockers are all done, and starts a workspace for each
+//! of them, most urgent first, until the global or per-project concurrency cap
+//! is reached.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use db::models::{
+    execution_process::ExecutionProcess, project_repo::ProjectRepo, task::Task,
+    task_dependency::TaskDependency, workspace::Workspace, workspace_repo::CreateWorkspaceRepo,
+};
+use executors::profile::ExecutorProfileId;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use tracing::{debug, error, info};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::{
+    config::{AutoSchedulerConfig, Config},
+    container::{ContainerError, ContainerService},
+};
+
+/// Default poll interval in seconds (30 seconds).
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 30;
+
+/// Environment variable name for configuring the poll interval.
+const POLL_INTERVAL_ENV_VAR: &str = "TASK_SCHEDULER_POLL_INTERVAL_SECS";
+
+#[derive(Debug, Error)]
+pub enum TaskSchedulerError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error("Project {0} has no repositories")]
+    NoRepositories(Uuid),
+    #[error("Cannot read the current branch of repo {0}: {1}")]
+    CurrentBranch(String, git2::Error),
+}
+
+/// Scheduling state for a single project
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectQueueState {
+    pub project_id: Uuid,
+    pub running_agents: u32,
+    /// Tasks that will be started next, in start order
+    pub ready: Vec<Task>,
+    /// Tasks waiting on unfinished blockers
+    pub blocked: Vec<Task>,
+}
+
+/// Snapshot of what the scheduler sees and would do next
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SchedulerQueueState {
+    pub enabled: bool,
+    pub max_concurrent_agents: u32,
+    pub max_concurrent_agents_per_project: u32,
+    pub running_agents: u32,
+    pub projects: Vec<ProjectQueueState>,
+}
+
+/// Background service that starts ready tasks within a concurrency budget.
+pub struct TaskSchedulerService<C> {
+    container: C,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl<C> TaskSchedulerService<C>
+where
+    C: ContainerService + Clone + Send + Sync + 'static,
+{
+    /// Spawn the scheduler as a background task.
+    ///
+    /// The poll interval can be configured via the `TASK_SCHEDULER_POLL_INTERVAL_SECS`
+    /// environment variable. Defaults to 30 seconds. Scheduling only happens while
+    /// `auto_scheduler.enabled` is set in the config.
+    pub async fn spawn(container: C, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            container,
+            config,
+            poll_interval: Self::get_poll_interval(),
+        };
+
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    fn get_poll_interval() -> Duration {
+        std::env::var(POLL_INTERVAL_ENV_VAR)
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS))
+    }
+
+    async fn start(&self) {
+        info!(
+            poll_interval_secs = self.poll_interval.as_secs(),
+            "Starting task scheduler service"
+        );
+
+        let mut interval = tokio::time::interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.schedule_ready_tasks().await {
+                Ok(0) => debug!("No tasks scheduled"),
+                Ok(started) => info!(started, "Scheduled ready tasks"),
+                Err(e) => error!(error = %e, "Error scheduling ready tasks"),
+            }
+        }
+    }
+
+    /// Start as many ready tasks as the budget allows and return how many were started.
+    async fn schedule_ready_tasks(&self) -> Result<usize, TaskSchedulerError> {
+        let (settings, executor_profile_id) = {
+            let config = self.config.read().await;
+            (
+                config.auto_scheduler.clone(),
+                config.executor_profile.clone(),
+            )
+        };
+        if !settings.enabled {
+            return Ok(0);
+        }
+
+        let pool = &self.container.db().pool;
+        let mut budget = AgentBudget::new(running_agents_by_project(pool).await?, &settings);
+        let mut started = 0;
+
+        for task in TaskDependency::find_ready_tasks(pool).await? {
+            if budget.is_exhausted() {
+                break;
+            }
+            if !budget.has_room_for(task.project_id) {
+                continue;
+            }
+
+            match self.start_task(&task, executor_profile_id.clone()).await {
+                Ok(()) => {
+                    info!(task_id = %task.id, project_id = %task.project_id, "Auto-started task");
+                    budget.record_start(task.project_id);
+                    started += 1;
+                }
+                Err(e) => {
+                    error!(task_id = %task.id, error = %e, "Failed to auto-start task");
+                }
+            }
+        }
+
+        Ok(started)
+    }
+
+    /// Create a workspace on each project repo's current branch and start it, mirroring
+    /// what the UI does when a task is started by hand.
+    async fn start_task(
+        &self,
+        task: &Task,
+        executor_profile_id: ExecutorProfileId,
+    ) -> Result<(), TaskSchedulerError> {
+        let pool = &self.container.db().pool;
+
+        let repos = ProjectRepo::find_repos_for_project(pool, task.project_id).await?;
+        if repos.is_empty() {
+            return Err(TaskSchedulerError::NoRepositories(task.project_id));
+        }
+
+        let mut workspace_repos = Vec::with_capacity(repos.len());
+        for repo in &repos {
+            let target_branch = self
+                .container
+                .git()
+                .get_current_branch(&repo.path)
+                .map_err(|e| TaskSchedulerError::CurrentBranch(repo.name.clone(), e))?;
+            workspace_repos.push(CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch,
+                stack_base_commit: None,
+            });
+        }
+
+        let workspace = self
+            .container
+            .create_workspace(task, "worktree", None, &workspace_repos)
+            .await?;
+        if let Err(e) = self
+            .container
+            .start_workspace(&workspace, executor_profile_id)
+            .await
+        {
+            // A workspace without a running agent would hide the task from the ready
+            // queue forever, so drop it and let the next tick try again.
+            if let Err(cleanup) = self.container.delete(&workspace).await {
+                error!(workspace_id = %workspace.id, error = %cleanup, "Failed to clean up workspace");
+            }
+            Workspace::delete(pool, workspace.id).await?;
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Running coding agents checked against the global and per-project caps
+struct AgentBudget {
+    running: HashMap<Uuid, u32>,
+    total: u32,
+    max_total: u32,
+    max_per_project: u32,
+}
+
+impl AgentBudget {
+    fn new(running: HashMap<Uuid, u32>, settings: &AutoSchedulerConfig) -> Self {
+        Self {
+            total: running.values().sum(),
+            running,
+            max_total: settings.max_concurrent_agents,
+            max_per_project: settings.max_concurrent_agents_per_project,
+        }
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.total >= self.max_total
+    }
+
+    fn has_room_for(&self, project_id: Uuid) -> bool {
+        !self.is_exhausted()
+            && self.running.get(&project_id).copied().unwrap_or(0) < self.max_per_project
+    }
+
+    fn record_start(&mut self, project_id: Uuid) {
+        *self.running.entry(project_id).or_insert(0) += 1;
+        self.total += 1;
+    }
+}
+
+async fn running_agents_by_project(pool: &SqlitePool) -> Result<HashMap<Uuid, u32>, sqlx::Error> {
+    Ok(ExecutionProcess::count_running_agents_by_project(pool)
+        .await?
+        .into_iter()
+        .map(|(project_id, count)| (project_id, count as u32))
+        .collect())
+}
+
+fn project_entry<'a>(
+    projects: &'a mut HashMap<Uuid, ProjectQueueState>,
+    running: &HashMap<Uuid, u32>,
+    project_id: Uuid,
+) -> &'a mut ProjectQueueState {
+    projects
+        .entry(project_id)
+        .or_insert_with(|| ProjectQueueState {
+            project_id,
+            running_agents: running.get(&project_id).copied().unwrap_or(0),
+            ready: Vec::new(),
+            blocked: Vec::new(),
+        })
+}
+
+/// Build the current queue state. Tasks in `ready` are listed in the order the
+/// scheduler would start them, regardless of whether there is budget left.
+pub async fn queue_state(
+    pool: &SqlitePool,
+    settings: &AutoSchedulerConfig,
+) -> Result<SchedulerQueueState, sqlx::Error> {
+    let running = running_agents_by_project(pool).await?;
+    let mut projects: HashMap<Uuid, ProjectQueueState> = HashMap::new();
+
+    for task in TaskDependency::find_ready_tasks(pool).await? {
+        project_entry(&mut projects, &running, task.project_id)
+            .ready
+            .push(task);
+    }
+    for task in TaskDependency::find_blocked_tasks(pool).await? {
+        project_entry(&mut projects, &running, task.project_id)
+            .blocked
+            .push(task);
+    }
+    for &project_id in running.keys() {
+        project_entry(&mut projects, &running, project_id);
+    }
+
+    let mut projects: Vec<ProjectQueueState> = projects.into_values().collect();
+    projects.sort_by_key(|p| p.project_id);
+
+    Ok(SchedulerQueueState {
+        enabled: settings.enabled,
+        max_concurrent_agents: settings.max_concurrent_agents,
+        max_concurrent_agents_per_project: settings.max_concurrent_agents_per_project,
+        running_agents: running.values().sum(),
+        projects,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{
+        models::{task::TaskStatus, task_dependency::CreateTaskDependency},
+        test_utils::{create_project, create_task, pool},
+    };
+
+    use super::*;
+
+    fn settings(
+        max_concurrent_agents: u32,
+        max_concurrent_agents_per_project: u32,
+    ) -> AutoSchedulerConfig {
+        AutoSchedulerConfig {
+            enabled: true,
+            max_concurrent_agents,
+            max_concurrent_agents_per_project,
+        }
+    }
+
+    #[test]
+    fn test_default_scheduler_settings() {
+        let settings = AutoSchedulerConfig::default();
+        assert!(!settings.enabled);
+        assert_eq!(settings.max_concurrent_agents, 3);
+        assert_eq!(settings.max_concurrent_agents_per_project, 1);
+    }
+
+    #[test]
+    fn missing_settings_fall_back_to_defaults() {
+        let settings: AutoSchedulerConfig = serde_json::from_str(r#"{ "enabled": true }"#).unwrap();
+        assert!(settings.enabled);
+        assert_eq!(settings.max_concurrent_agents, 3);
+        assert_eq!(settings.max_concurrent_agents_per_project, 1);
+    }
+
+    #[test]
+    fn budget_respects_the_per_project_cap() {
+        let busy = Uuid::new_v4();
+        let idle = Uuid::new_v4();
+        let mut budget = AgentBudget::new(HashMap::from([(busy, 1)]), &settings(5, 2));
+
+        assert!(budget.has_room_for(busy));
+        budget.record_start(busy);
+        assert!(!budget.has_room_for(busy));
+        // Other projects still get their own share
+        assert!(budget.has_room_for(idle));
+        budget.record_start(idle);
+        budget.record_start(idle);
+        assert!(!budget.has_room_for(idle));
+        assert!(!budget.is_exhausted());
+    }
+
+    #[test]
+    fn budget_respects_the_global_cap() {
+        let projects = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let mut budget = AgentBudget::new(HashMap::from([(projects[0], 1)]), &settings(2, 1));
+
+        assert!(!budget.has_room_for(projects[0]));
+        assert!(budget.has_room_for(projects[1]));
+        budget.record_start(projects[1]);
+        assert!(budget.is_exhausted());
+        assert!(!budget.has_room_for(projects[2]));
+    }
+
+    #[tokio::test]
+    async fn blocked_tasks_are_not_ready() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let blocker = create_task(&pool, project.id, "blocker", TaskStatus::Todo).await;
+        let waiting = create_task(&pool, project.id, "waiting", TaskStatus::Todo).await;
+        TaskDependency::create(
+            &pool,
+            waiting.id,
+            &CreateTaskDependency {
+                depends_on_task_id: blocker.id,
+            },
+        )
+        .await
+        .unwrap();
+
+        let state = queue_state(&pool, &settings(3, 1)).await.unwrap();
+        assert_eq!(state.projects.len(), 1);
+        let ready = state.projects[0]
+            .ready
+            .iter()
+            .map(|t| t.id)
+            .collect::<Vec<_>>();
+        let blocked = state.projects[0]
+            .blocked
+            .iter()
+            .map(|t| t.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ready, vec![blocker.id]);
+        assert_eq!(blocked, vec![waiting.id]);
+
+        let ready = TaskDependency::find_ready_tasks(&pool).await.unwrap();
+        assert!(ready.iter().all(|task| task.id != waiting.id));
+        assert!(ready.iter().all(|task| task.status == TaskStatus::Todo));
+    }
+}
@@ -0,0 +1,283 @@
+//! Token usage recording and per-project budgets.
+//!
+//! Normalizers push the usage reported by coding agents into the message store.
+//! Each report is stored with the cost estimated from the configured price table,
+//! falling back to the cost the agent reported itself for models without a price.
+//! A report that brings the project's spend to its budget stops the running turn.
+
+use db::models::{
+    execution_process_usage::{CreateExecutionProcessUsage, ExecutionProcessUsage},
+    project::Project,
+};
+use executors::profile::ExecutorProfileId;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+use utils::log_msg::TokenUsage;
+use uuid::Uuid;
+
+use super::config::{ModelPrice, UsageConfig};
+
+const TOKENS_PER_PRICE_UNIT: f64 = 1_000_000.0;
+
+#[derive(Debug, Error)]
+pub enum UsageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(
+        "Project '{project}' has used ${spent_usd:.2} of its ${budget_usd:.2} budget; raise the budget to start more coding agent turns"
+    )]
+    BudgetExceeded {
+        project: String,
+        spent_usd: f64,
+        budget_usd: f64,
+    },
+}
+
+/// How the usage reported by a coding agent execution is priced and capped
+pub struct UsageTracking {
+    pub project_id: Uuid,
+    pub executor_profile_id: ExecutorProfileId,
+    pub config: UsageConfig,
+    /// Cancelled to stop the execution once the project has spent its budget
+    pub budget_stop: Option<CancellationToken>,
+}
+
+/// Price whose model prefix is the longest match for `model`
+pub fn find_price<'a>(prices: &'a [ModelPrice], model: &str) -> Option<&'a ModelPrice> {
+    let model = model.to_ascii_lowercase();
+    prices
+        .iter()
+        .filter(|price| !price.model.is_empty())
+        .filter(|price| model.starts_with(&price.model.to_ascii_lowercase()))
+        .max_by_key(|price| price.model.len())
+}
+
+/// Cost of the usage in USD, if the model has a price or the agent reported one
+pub fn estimate_cost(prices: &[ModelPrice], usage: &TokenUsage) -> Option<f64> {
+    let Some(price) = usage
+        .model
+        .as_deref()
+        .and_then(|model| find_price(prices, model))
+    else {
+        return usage.cost_usd;
+    };
+    let cost = usage.input_tokens as f64 * price.input
+        + usage.output_tokens as f64 * price.output
+        + usage.cache_read_tokens as f64 * price.cache_read
+        + usage.cache_write_tokens as f64 * price.cache_write;
+    Some(cost / TOKENS_PER_PRICE_UNIT)
+}
+
+/// Store one usage report of an execution process
+pub async fn record_usage(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+    executor_profile_id: &ExecutorProfileId,
+    config: &UsageConfig,
+    usage: &TokenUsage,
+) -> Result<ExecutionProcessUsage, sqlx::Error> {
+    let tokens = |count: u64| i64::try_from(count).unwrap_or(i64::MAX);
+    ExecutionProcessUsage::create(
+        pool,
+        execution_process_id,
+        &CreateExecutionProcessUsage {
+            executor: executor_profile_id.executor.to_string(),
+            variant: executor_profile_id.variant.clone(),
+            model: usage.model.clone(),
+            input_tokens: tokens(usage.input_tokens),
+            output_tokens: tokens(usage.output_tokens),
+            cache_read_tokens: tokens(usage.cache_read_tokens),
+            cache_write_tokens: tokens(usage.cache_write_tokens),
+            cost_usd: estimate_cost(&config.prices(), usage),
+        },
+    )
+    .await
+}
+
+/// Fail when the project has a budget and its recorded spend has reached it
+pub async fn ensure_within_budget(pool: &SqlitePool, project: &Project) -> Result<(), UsageError> {
+    let Some(budget_usd) = project.usage_budget_usd else {
+        return Ok(());
+    };
+    let spent_usd = ExecutionProcessUsage::totals_for_project(pool, project.id)
+        .await?
+        .cost_usd;
+    if spent_usd >= budget_usd {
+        return Err(UsageError::BudgetExceeded {
+            project: project.name.clone(),
+            spent_usd,
+            budget_usd,
+        });
+    }
+    Ok(())
+}
+
+/// [`ensure_within_budget`] for the project as currently stored, so budget changes
+/// made while an agent runs are taken into account
+pub async fn ensure_project_within_budget(
+    pool: &SqlitePool,
+    project_id: Uuid,
+) -> Result<(), UsageError> {
+    match Project::find_by_id(pool, project_id).await? {
+        Some(project) => ensure_within_budget(pool, &project).await,
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{
+        models::task::TaskStatus,
+        test_utils::{create_execution_process, create_project, create_task, pool},
+    };
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+
+    fn usage(model: Option<&str>) -> TokenUsage {
+        TokenUsage {
+            model: model.map(str::to_string),
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_read_tokens: 2_000_000,
+            cache_write_tokens: 0,
+            cost_usd: Some(9.0),
+        }
+    }
+
+    #[test]
+    fn longest_model_prefix_wins() {
+        let prices = UsageConfig::default_prices();
+        assert_eq!(
+            find_price(&prices, "gpt-5-mini-2025-08-07").map(|p| p.model.as_str()),
+            Some("gpt-5-mini")
+        );
+        assert_eq!(
+            find_price(&prices, "claude-opus-4-5-20251101").map(|p| p.model.as_str()),
+            Some("claude-opus-4-5")
+        );
+        assert_eq!(
+            find_price(&prices, "Claude-Sonnet-4-5").map(|p| p.model.as_str()),
+            Some("claude-sonnet-4")
+        );
+        assert_eq!(
+            find_price(&prices, "o3-mini-2025-01-31").map(|p| p.model.as_str()),
+            Some("o3-mini")
+        );
+        assert_eq!(
+            find_price(&prices, "o3-2025-04-16").map(|p| p.model.as_str()),
+            Some("o3")
+        );
+        assert!(find_price(&prices, "qwen3-coder").is_none());
+    }
+
+    #[test]
+    fn overrides_replace_and_extend_the_built_in_prices() {
+        let config = UsageConfig {
+            price_overrides: vec![
+                ModelPrice {
+                    model: "Claude-Sonnet-4".to_string(),
+                    input: 1.0,
+                    output: 2.0,
+                    cache_read: 0.0,
+                    cache_write: 0.0,
+                },
+                ModelPrice {
+                    model: "qwen3-coder".to_string(),
+                    input: 0.5,
+                    output: 1.0,
+                    cache_read: 0.0,
+                    cache_write: 0.0,
+                },
+            ],
+        };
+        let prices = config.prices();
+
+        assert_eq!(prices.len(), UsageConfig::default_prices().len() + 1);
+        assert_eq!(find_price(&prices, "claude-sonnet-4-5").unwrap().input, 1.0);
+        assert_eq!(find_price(&prices, "qwen3-coder-plus").unwrap().output, 1.0);
+        assert_eq!(find_price(&prices, "claude-opus-4-5").unwrap().input, 5.0);
+    }
+
+    #[test]
+    fn only_overrides_are_saved() {
+        let saved = serde_json::to_value(UsageConfig::default()).unwrap();
+        assert_eq!(saved, serde_json::json!({ "price_overrides": [] }));
+        let loaded: UsageConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(loaded.prices(), UsageConfig::default_prices());
+    }
+
+    #[test]
+    fn cost_comes_from_the_price_table() {
+        let prices = UsageConfig::default_prices();
+        // 1M input at $3, 100k output at $15, 2M cache reads at $0.30
+        let cost = estimate_cost(&prices, &usage(Some("claude-sonnet-4-5"))).unwrap();
+        assert!((cost - 5.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reported_cost_is_used_for_unknown_models() {
+        let prices = UsageConfig::default_prices();
+        assert_eq!(
+            estimate_cost(&prices, &usage(Some("qwen3-coder"))),
+            Some(9.0)
+        );
+        assert_eq!(estimate_cost(&prices, &usage(None)), Some(9.0));
+        let unreported = TokenUsage {
+            cost_usd: None,
+            ..usage(None)
+        };
+        assert_eq!(estimate_cost(&prices, &unreported), None);
+    }
+
+    #[tokio::test]
+    async fn spending_the_budget_stops_further_turns() {
+        let pool = pool().await;
+        let project = create_project(&pool).await;
+        let task = create_task(&pool, project.id, "Task", TaskStatus::InProgress).await;
+        let process = create_execution_process(&pool, task.id).await;
+        let set_budget = |budget: f64| {
+            sqlx::query("UPDATE projects SET usage_budget_usd = $1 WHERE id = $2")
+                .bind(budget)
+                .bind(project.id)
+                .execute(&pool)
+        };
+
+        // $5.10 of Claude Sonnet usage
+        record_usage(
+            &pool,
+            process.id,
+            &ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            &UsageConfig::default(),
+            &usage(Some("claude-sonnet-4-5")),
+        )
+        .await
+        .unwrap();
+        assert!(
+            ensure_project_within_budget(&pool, project.id)
+                .await
+                .is_ok()
+        );
+
+        set_budget(10.0).await.unwrap();
+        assert!(
+            ensure_project_within_budget(&pool, project.id)
+                .await
+                .is_ok()
+        );
+
+        set_budget(5.0).await.unwrap();
+        match ensure_project_within_budget(&pool, project.id).await {
+            Err(UsageError::BudgetExceeded {
+                spent_usd,
+                budget_usd,
+                ..
+            }) => {
+                assert!((spent_usd - 5.1).abs() < 1e-9);
+                assert_eq!(budget_usd, 5.0);
+            }
+            other => panic!("expected the budget to be exceeded, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Follow-up prompts for a project's verification command.
+//!
+//! After each coding agent turn the project's `verification_command` runs in the
+//! workspace. When it fails, the tail of its output is sent back to the agent as a
+//! follow-up until the command passes or `verification_max_retries` is used up.
+
+/// Lines of failure output kept for the fix prompt
+pub const FAILURE_LOG_LINES: usize = 150;
+
+/// Strip ANSI escape sequences and keep the last [`FAILURE_LOG_LINES`] non-empty
+/// lines of the verification output
+pub fn trim_failure_log(output: &str) -> String {
+    let plain = strip_ansi(output);
+    let lines: Vec<&str> = plain
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let start = lines.len().saturating_sub(FAILURE_LOG_LINES);
+    let mut log = lines[start..].join("\n");
+    if start > 0 {
+        log.insert_str(0, &format!("[{start} earlier lines omitted]\n"));
+    }
+    log
+}
+
+/// Prompt asking the agent to fix a failed verification
+pub fn fix_prompt(command: &str, exit_code: Option<i64>, output: &str) -> String {
+    let status = match exit_code {
+        Some(code) => format!("exited with code {code}"),
+        None => "did not finish".to_string(),
+    };
+    let log = trim_failure_log(output);
+    let log = if log.is_empty() {
+        "(no output)".to_string()
+    } else {
+        log
+    };
+    format!(
+        "The verification command `{command}` {status} after your changes.\n\n\
+         ```\n{log}\n```\n\n\
+         Fix the cause of the failure so that the command passes."
+    )
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            out.push(c);
+            continue;
+        }
+        // CSI sequences end with a byte in @..~; other escapes are two characters long
+        if chars.next_if_eq(&'[').is_some() {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            chars.next();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_to_last_lines_without_colors() {
+        let output: String = (0..200)
+            .map(|i| format!("\u{1b}[31mline {i}\u{1b}[0m\n\n"))
+            .collect();
+        let log = trim_failure_log(&output);
+
+        assert!(log.starts_with("[50 earlier lines omitted]\nline 50\n"));
+        assert!(log.ends_with("line 199"));
+        assert!(!log.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn fix_prompt_includes_command_and_status() {
+        let prompt = fix_prompt("cargo test", Some(101), "test foo ... FAILED\n");
+        assert!(prompt.contains("`cargo test` exited with code 101"));
+        assert!(prompt.contains("test foo ... FAILED"));
+
+        let prompt = fix_prompt("cargo test", None, "");
+        assert!(prompt.contains("did not finish"));
+        assert!(prompt.contains("(no output)"));
+    }
+}
@@ -2,10 +2,10 @@
 //!
 //! This worker runs on a configurable interval and processes all pending
 //! webhook deliveries, including retries for previously failed deliveries.
+//! Finished deliveries older than the retention period are pruned hourly.
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use db::DBService;
 use tracing::{debug, error, info};
 
 use crate::services::webhooks::WebhookService;
@@ -16,26 +16,37 @@ const DEFAULT_POLL_INTERVAL_SECS: u64 = 30;
 /// Environment variable name for configuring the poll interval.
 const POLL_INTERVAL_ENV_VAR: &str = "WEBHOOK_WORKER_POLL_INTERVAL_SECS";
 
+/// Default number of days to keep finished deliveries.
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+/// Environment variable name for configuring delivery retention.
+const RETENTION_DAYS_ENV_VAR: &str = "WEBHOOK_DELIVERY_RETENTION_DAYS";
+
+/// How often old deliveries are pruned.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 /// Background worker service for processing webhook deliveries.
 pub struct WebhookWorkerService {
     webhook_service: WebhookService,
     poll_interval: Duration,
+    retention_days: i64,
 }
 
 impl WebhookWorkerService {
     /// Spawn the webhook worker as a background task.
     ///
     /// The poll interval can be configured via the `WEBHOOK_WORKER_POLL_INTERVAL_SECS`
-    /// environment variable. Defaults to 30 seconds.
+    /// environment variable. Defaults to 30 seconds. Successful and failed deliveries
+    /// are kept for `WEBHOOK_DELIVERY_RETENTION_DAYS` days (default 30).
     ///
     /// Returns a JoinHandle for the spawned task.
-    pub async fn spawn(db: DBService) -> tokio::task::JoinHandle<()> {
+    pub async fn spawn(webhook_service: WebhookService) -> tokio::task::JoinHandle<()> {
         let poll_interval = Self::get_poll_interval();
-        let webhook_service = WebhookService::new(db.pool);
 
         let service = Self {
             webhook_service,
             poll_interval,
+            retention_days: Self::get_retention_days(),
         };
 
         tokio::spawn(async move {
@@ -52,6 +63,15 @@ impl WebhookWorkerService {
             .unwrap_or(Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS))
     }
 
+    /// Get the delivery retention from environment variable or use default.
+    fn get_retention_days() -> i64 {
+        std::env::var(RETENTION_DAYS_ENV_VAR)
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|days| *days > 0)
+            .unwrap_or(DEFAULT_RETENTION_DAYS)
+    }
+
     /// Start the worker loop.
     async fn start(&self) {
         info!(
@@ -60,10 +80,16 @@ impl WebhookWorkerService {
         );
 
         let mut interval = tokio::time::interval(self.poll_interval);
+        let mut last_prune: Option<Instant> = None;
 
         loop {
             interval.tick().await;
 
+            if last_prune.is_none_or(|at| at.elapsed() >= PRUNE_INTERVAL) {
+                last_prune = Some(Instant::now());
+                self.prune_deliveries().await;
+            }
+
             match self.process_deliveries().await {
                 Ok((success_count, failure_count)) => {
                     if success_count > 0 || failure_count > 0 {
@@ -83,6 +109,23 @@ impl WebhookWorkerService {
         }
     }
 
+    /// Delete finished deliveries older than the retention period.
+    async fn prune_deliveries(&self) {
+        match self
+            .webhook_service
+            .prune_deliveries(self.retention_days)
+            .await
+        {
+            Ok(0) => {}
+            Ok(pruned) => info!(
+                pruned,
+                retention_days = self.retention_days,
+                "Pruned old webhook deliveries"
+            ),
+            Err(e) => error!(error = %e, "Error pruning webhook deliveries"),
+        }
+    }
+
     /// Process all pending deliveries and return counts.
     ///
     /// Returns a tuple of (success_count, failure_count).
@@ -106,6 +149,13 @@ mod tests {
         assert_eq!(DEFAULT_POLL_INTERVAL_SECS, 30);
     }
 
+    #[test]
+    fn test_default_retention() {
+        // Finished deliveries are kept for 30 days and pruned hourly
+        assert_eq!(DEFAULT_RETENTION_DAYS, 30);
+        assert_eq!(PRUNE_INTERVAL, Duration::from_secs(3600));
+    }
+
     #[test]
     fn test_poll_interval_parsing() {
         // Test that the parsing logic works correctly
@@ -1,14 +1,23 @@
 //! Webhook delivery service with HMAC-SHA256 signing and exponential backoff retry.
+//!
+//! Each webhook can optionally render its request body from a MiniJinja template,
+//! send extra headers and filter which events it receives (see [`WebhookFilters`]).
 
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use chrono::{DateTime, Utc};
 use db::models::{
-    webhook::{Webhook, WebhookEvent},
+    task::{Task, TaskStatus},
+    webhook::{Webhook, WebhookEvent, WebhookFilters},
     webhook_delivery::{CreateWebhookDelivery, WebhookDelivery},
+    webhook_history::{CreateWebhookHistory, WebhookHistory, WebhookHistoryAction},
 };
 use hmac::{Hmac, Mac};
-use reqwest::Client;
+use minijinja::Environment;
+use reqwest::{
+    Client,
+    header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue},
+};
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use sqlx::SqlitePool;
@@ -16,6 +25,8 @@ use thiserror::Error;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+pub mod payloads;
+
 type HmacSha256 = Hmac<Sha256>;
 
 /// Maximum number of delivery attempts before marking as permanently failed.
@@ -35,6 +46,19 @@ const RETRY_DELAYS_SECS: [u64; 7] = [
 /// HTTP request timeout for webhook delivery.
 const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Default number of consecutive permanently failed deliveries before a webhook is disabled.
+const DEFAULT_AUTO_DISABLE_THRESHOLD: i64 = 10;
+
+/// Environment variable name for configuring the auto-disable threshold. `0` turns it off.
+const AUTO_DISABLE_THRESHOLD_ENV_VAR: &str = "WEBHOOK_AUTO_DISABLE_AFTER_FAILURES";
+
+/// Headers set by the delivery engine that custom headers may not override.
+const RESERVED_HEADERS: [&str; 3] = [
+    "x-webhook-signature",
+    "x-webhook-event",
+    "x-webhook-delivery",
+];
+
 /// Errors that can occur during webhook delivery.
 #[derive(Debug, Error)]
 pub enum WebhookError {
@@ -55,6 +79,12 @@ pub enum WebhookError {
 
     #[error("webhook not found: {0}")]
     NotFound(Uuid),
+
+    #[error("template error: {0}")]
+    Template(String),
+
+    #[error("invalid header: {0}")]
+    InvalidHeader(String),
 }
 
 impl WebhookError {
@@ -97,13 +127,19 @@ pub struct DeliveryResult {
 }
 
 /// Service for delivering webhooks with retry logic.
+#[derive(Clone)]
 pub struct WebhookService {
     pool: SqlitePool,
     client: Client,
+    auto_disable_threshold: i64,
 }
 
 impl WebhookService {
-    /// Creates a new WebhookService with the given database pool.
+    /// Creates a new WebhookService with the given database pool. Build one per
+    /// deployment and clone it; clones share the HTTP client.
+    ///
+    /// Webhooks are disabled after `WEBHOOK_AUTO_DISABLE_AFTER_FAILURES` consecutive
+    /// permanently failed deliveries (default 10, `0` disables the check).
     pub fn new(pool: SqlitePool) -> Self {
         let client = Client::builder()
             .timeout(DELIVERY_TIMEOUT)
@@ -111,7 +147,16 @@ impl WebhookService {
             .build()
             .expect("failed to build HTTP client");
 
-        Self { pool, client }
+        let auto_disable_threshold = std::env::var(AUTO_DISABLE_THRESHOLD_ENV_VAR)
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .unwrap_or(DEFAULT_AUTO_DISABLE_THRESHOLD);
+
+        Self {
+            pool,
+            client,
+            auto_disable_threshold,
+        }
     }
 
     /// Sign a payload with HMAC-SHA256 and return the hex-encoded signature.
@@ -125,6 +170,20 @@ impl WebhookService {
         format!("sha256={}", hex::encode(result.into_bytes()))
     }
 
+    /// Verify a "sha256=<hex>" signature produced by [`Self::sign_payload`], in constant time.
+    pub fn verify_signature(secret: &str, payload: &[u8], signature: &str) -> bool {
+        let Some(expected) = signature
+            .strip_prefix("sha256=")
+            .and_then(|hex_sig| hex::decode(hex_sig).ok())
+        else {
+            return false;
+        };
+        let mut mac =
+            HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+        mac.update(payload);
+        mac.verify_slice(&expected).is_ok()
+    }
+
     /// Calculate the next retry delay based on attempt count (0-indexed).
     ///
     /// Returns None if max attempts have been reached.
@@ -137,15 +196,67 @@ impl WebhookService {
         ))
     }
 
+    /// Check that a payload template compiles.
+    pub fn validate_template(template: &str) -> Result<(), WebhookError> {
+        let mut env = Environment::new();
+        env.add_template("payload", template)
+            .map_err(|e| WebhookError::Template(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Check that custom headers are valid HTTP headers and don't override the
+    /// signature, event or delivery headers.
+    pub fn validate_headers(headers: &HashMap<String, String>) -> Result<(), WebhookError> {
+        Self::build_custom_headers(headers).map(|_| ())
+    }
+
+    fn build_custom_headers(headers: &HashMap<String, String>) -> Result<HeaderMap, WebhookError> {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| WebhookError::InvalidHeader(name.clone()))?;
+            if RESERVED_HEADERS.contains(&header_name.as_str()) {
+                return Err(WebhookError::InvalidHeader(format!(
+                    "{} is set by the delivery engine",
+                    name
+                )));
+            }
+            let header_value = HeaderValue::from_str(value)
+                .map_err(|_| WebhookError::InvalidHeader(name.clone()))?;
+            map.insert(header_name, header_value);
+        }
+        Ok(map)
+    }
+
+    /// Render the request body for a payload. Templates see the envelope fields
+    /// (`event`, `timestamp`, `delivery_id` and `data`) as top-level variables.
+    pub fn render_payload(
+        template: Option<&str>,
+        payload: &WebhookPayload,
+    ) -> Result<String, WebhookError> {
+        let Some(template) = template else {
+            return Ok(serde_json::to_string(payload)?);
+        };
+
+        let mut env = Environment::new();
+        env.add_template("payload", template)
+            .map_err(|e| WebhookError::Template(e.to_string()))?;
+        env.get_template("payload")
+            .and_then(|t| t.render(payload))
+            .map_err(|e| WebhookError::Template(e.to_string()))
+    }
+
     /// Queue a new webhook delivery for the given event.
     ///
-    /// Creates a WebhookDelivery record in pending status.
+    /// Creates a WebhookDelivery record in pending status, with the body rendered
+    /// from the webhook's payload template if it has one.
     pub async fn queue_delivery(
         &self,
-        webhook_id: Uuid,
+        webhook: &Webhook,
         event: &WebhookEvent,
         data: serde_json::Value,
     ) -> Result<WebhookDelivery, WebhookError> {
+        let webhook_id = webhook.id;
         let delivery_id = Uuid::new_v4();
         let payload = WebhookPayload {
             event: event.as_str().to_string(),
@@ -154,12 +265,12 @@ impl WebhookService {
             data,
         };
 
-        let payload_json = serde_json::to_string(&payload)?;
+        let body = Self::render_payload(webhook.payload_template.as_deref(), &payload)?;
 
         let create_data = CreateWebhookDelivery {
             webhook_id,
             event_type: event.as_str().to_string(),
-            payload: payload_json,
+            payload: body,
         };
 
         let delivery = WebhookDelivery::create(&self.pool, &create_data).await?;
@@ -192,10 +303,15 @@ impl WebhookService {
 
         let signature = Self::sign_payload(&webhook.secret, &delivery.payload);
 
+        // Custom headers may replace Content-Type, e.g. for plain-text templates
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.extend(Self::build_custom_headers(&webhook.get_headers())?);
+
         let response = self
             .client
             .post(&webhook.url)
-            .header("Content-Type", "application/json")
+            .headers(headers)
             .header("X-Webhook-Signature", &signature)
             .header("X-Webhook-Event", &delivery.event_type)
             .header("X-Webhook-Delivery", delivery.id.to_string())
@@ -245,6 +361,7 @@ impl WebhookService {
             Ok(()) => {
                 // Mark as successful
                 WebhookDelivery::mark_success(&self.pool, delivery.id).await?;
+                Webhook::reset_failures(&self.pool, webhook.id).await?;
                 info!(
                     webhook_id = %webhook.id,
                     delivery_id = %delivery.id,
@@ -303,6 +420,8 @@ impl WebhookService {
                         "Webhook delivery permanently failed"
                     );
 
+                    self.record_permanent_failure(webhook, &error_msg).await?;
+
                     Ok(DeliveryResult {
                         success: false,
                         status_code,
@@ -314,6 +433,85 @@ impl WebhookService {
         }
     }
 
+    /// Count a permanently failed delivery against the webhook and disable it once
+    /// the auto-disable threshold is reached.
+    async fn record_permanent_failure(
+        &self,
+        webhook: &Webhook,
+        last_error: &str,
+    ) -> Result<(), WebhookError> {
+        let failures = Webhook::record_failure(&self.pool, webhook.id).await?;
+        if self.auto_disable_threshold <= 0 || failures < self.auto_disable_threshold {
+            return Ok(());
+        }
+
+        Webhook::set_active(&self.pool, webhook.id, false).await?;
+        WebhookHistory::create(
+            &self.pool,
+            &CreateWebhookHistory {
+                webhook_id: webhook.id,
+                action: WebhookHistoryAction::AutoDisabled,
+                reason: Some(format!(
+                    "Disabled after {} consecutive failed deliveries. Last error: {}",
+                    failures, last_error
+                )),
+            },
+        )
+        .await?;
+
+        warn!(
+            webhook_id = %webhook.id,
+            consecutive_failures = failures,
+            "Webhook disabled after repeated delivery failures"
+        );
+        Ok(())
+    }
+
+    /// Send a delivery again right away with a fresh retry budget and return its
+    /// updated state.
+    pub async fn redeliver(
+        &self,
+        webhook: &Webhook,
+        delivery_id: Uuid,
+    ) -> Result<WebhookDelivery, WebhookError> {
+        let delivery = WebhookDelivery::requeue(&self.pool, delivery_id)
+            .await?
+            .ok_or(WebhookError::NotFound(delivery_id))?;
+
+        info!(
+            webhook_id = %webhook.id,
+            delivery_id = %delivery.id,
+            "Redelivering webhook"
+        );
+        self.process_delivery(webhook, &delivery).await?;
+
+        WebhookDelivery::find_by_id(&self.pool, delivery_id)
+            .await?
+            .ok_or(WebhookError::NotFound(delivery_id))
+    }
+
+    /// Requeue failed deliveries created within a time range for the worker to send
+    /// again. Returns the number of deliveries requeued.
+    pub async fn replay_failed(
+        &self,
+        webhook_id: Uuid,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<u64, WebhookError> {
+        let requeued = WebhookDelivery::requeue_failed(&self.pool, webhook_id, from, to).await?;
+        info!(
+            webhook_id = %webhook_id,
+            requeued,
+            "Requeued failed webhook deliveries"
+        );
+        Ok(requeued)
+    }
+
+    /// Delete finished deliveries older than the retention period.
+    pub async fn prune_deliveries(&self, retention_days: i64) -> Result<u64, WebhookError> {
+        Ok(WebhookDelivery::cleanup_old_deliveries(&self.pool, retention_days).await?)
+    }
+
     /// Process all pending deliveries that are ready to be sent.
     ///
     /// This includes:
@@ -386,14 +584,61 @@ impl WebhookService {
     ///
     /// This is a convenience method that:
     /// 1. Finds all active webhooks for the project subscribed to the event
-    /// 2. Creates a delivery for each webhook
+    /// 2. Drops webhooks whose filters don't match the event's task
+    /// 3. Creates a delivery for each remaining webhook
     pub async fn trigger_event(
         &self,
         project_id: Uuid,
         event: &WebhookEvent,
         data: serde_json::Value,
     ) -> Result<Vec<WebhookDelivery>, WebhookError> {
-        let webhooks = Webhook::find_by_project_and_event(&self.pool, project_id, event).await?;
+        self.trigger_event_with_previous_status(project_id, event, data, None)
+            .await
+    }
+
+    /// Queue deliveries for a task event whose status before the change is known,
+    /// so `from_statuses` filters can match.
+    pub async fn trigger_task_event(
+        &self,
+        event: &WebhookEvent,
+        task: &Task,
+        previous_status: Option<TaskStatus>,
+    ) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        let data = serde_json::to_value(task)?;
+        self.trigger_event_with_previous_status(task.project_id, event, data, previous_status)
+            .await
+    }
+
+    async fn trigger_event_with_previous_status(
+        &self,
+        project_id: Uuid,
+        event: &WebhookEvent,
+        data: serde_json::Value,
+        previous_status: Option<TaskStatus>,
+    ) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        let mut webhooks =
+            Webhook::find_by_project_and_event(&self.pool, project_id, event).await?;
+
+        let filters: Vec<WebhookFilters> = webhooks.iter().map(Webhook::get_filters).collect();
+        if filters.iter().any(|f| !f.is_empty()) {
+            let task = self.resolve_event_task(event, &data).await?;
+            webhooks = webhooks
+                .into_iter()
+                .zip(filters)
+                .filter(|(webhook, filters)| {
+                    let matches = filters.matches(task.as_ref(), previous_status.as_ref());
+                    if !matches {
+                        debug!(
+                            webhook_id = %webhook.id,
+                            event = %event.as_str(),
+                            "Event does not match webhook filters"
+                        );
+                    }
+                    matches
+                })
+                .map(|(webhook, _)| webhook)
+                .collect();
+        }
 
         if webhooks.is_empty() {
             debug!(
@@ -413,16 +658,65 @@ impl WebhookService {
 
         let mut deliveries = Vec::with_capacity(webhooks.len());
         for webhook in webhooks {
-            let delivery = self.queue_delivery(webhook.id, event, data.clone()).await?;
-            deliveries.push(delivery);
+            match self.queue_delivery(&webhook, event, data.clone()).await {
+                Ok(delivery) => deliveries.push(delivery),
+                // A broken template only affects its own webhook
+                Err(WebhookError::Template(e)) => {
+                    error!(
+                        webhook_id = %webhook.id,
+                        event = %event.as_str(),
+                        error = %e,
+                        "Failed to render webhook payload template"
+                    );
+                }
+                Err(e) => return Err(e),
+            }
         }
 
         Ok(deliveries)
     }
+
+    /// Find the task an event is about: task events carry the task itself, other
+    /// payloads reference it by `task_id`.
+    async fn resolve_event_task(
+        &self,
+        event: &WebhookEvent,
+        data: &serde_json::Value,
+    ) -> Result<Option<Task>, WebhookError> {
+        if matches!(
+            event,
+            WebhookEvent::TaskCreated | WebhookEvent::TaskUpdated | WebhookEvent::TaskCompleted
+        ) && let Ok(task) = serde_json::from_value::<Task>(data.clone())
+        {
+            return Ok(Some(task));
+        }
+
+        let task_id = data
+            .get("task_id")
+            .and_then(|v| v.as_str())
+            .and_then(|s| Uuid::parse_str(s).ok());
+        match task_id {
+            Some(task_id) => Ok(Task::find_by_id(&self.pool, task_id).await?),
+            None => Ok(None),
+        }
+    }
+
+    /// Serialize a typed payload from [`payloads`] and queue deliveries for it.
+    pub async fn trigger_payload<T: Serialize>(
+        &self,
+        project_id: Uuid,
+        event: &WebhookEvent,
+        payload: &T,
+    ) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        let data = serde_json::to_value(payload)?;
+        self.trigger_event(project_id, event, data).await
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use db::models::task::TaskPriority;
+
     use super::*;
 
     #[test]
@@ -445,6 +739,34 @@ mod tests {
         assert_ne!(signature, signature3);
     }
 
+    #[test]
+    fn test_verify_signature() {
+        let secret = "test-secret";
+        let payload = r#"{"action":"create_task","title":"From CI"}"#;
+        let signature = WebhookService::sign_payload(secret, payload);
+
+        assert!(WebhookService::verify_signature(
+            secret,
+            payload.as_bytes(),
+            &signature
+        ));
+        assert!(!WebhookService::verify_signature(
+            "other-secret",
+            payload.as_bytes(),
+            &signature
+        ));
+        assert!(!WebhookService::verify_signature(
+            secret,
+            b"tampered",
+            &signature
+        ));
+        assert!(!WebhookService::verify_signature(
+            secret,
+            payload.as_bytes(),
+            signature.trim_start_matches("sha256=")
+        ));
+    }
+
     #[test]
     fn test_next_retry_delay() {
         // Test all valid attempts
@@ -559,4 +881,114 @@ mod tests {
             "550e8400-e29b-41d4-a716-446655440000"
         );
     }
+
+    #[test]
+    fn test_render_payload_template() {
+        let payload = WebhookPayload {
+            event: "task_completed".to_string(),
+            timestamp: Utc::now(),
+            delivery_id: Uuid::new_v4(),
+            data: serde_json::json!({ "title": "Fix login" }),
+        };
+
+        let default_body = WebhookService::render_payload(None, &payload).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&default_body).unwrap()["event"],
+            "task_completed"
+        );
+
+        let body = WebhookService::render_payload(
+            Some(r#"{"text": {{ (event ~ ": " ~ data.title) | tojson }}}"#),
+            &payload,
+        )
+        .unwrap();
+        assert_eq!(body, r#"{"text": "task_completed: Fix login"}"#);
+
+        assert!(WebhookService::validate_template("{{ data.title").is_err());
+    }
+
+    #[test]
+    fn test_validate_headers() {
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), "Bearer abc".to_string());
+        assert!(WebhookService::validate_headers(&headers).is_ok());
+
+        headers.insert(
+            "X-Webhook-Signature".to_string(),
+            "sha256=forged".to_string(),
+        );
+        assert!(WebhookService::validate_headers(&headers).is_err());
+
+        let mut invalid = HashMap::new();
+        invalid.insert("Bad Header".to_string(), "value".to_string());
+        assert!(WebhookService::validate_headers(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_webhook_filters() {
+        let task = Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: "Fix login".to_string(),
+            description: None,
+            status: TaskStatus::InReview,
+            workflow_state: None,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            assignee: Some("alice".to_string()),
+            priority: TaskPriority::High,
+            due_date: None,
+            estimate_hours: None,
+            agent_metadata: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        assert!(WebhookFilters::default().matches(None, None));
+
+        let by_assignee = WebhookFilters {
+            assignees: vec!["alice".to_string()],
+            ..Default::default()
+        };
+        assert!(by_assignee.matches(Some(&task), None));
+        assert!(!by_assignee.matches(None, None));
+
+        let transition = WebhookFilters {
+            from_statuses: vec![TaskStatus::InProgress],
+            to_statuses: vec![TaskStatus::InReview],
+            ..Default::default()
+        };
+        assert!(transition.matches(Some(&task), Some(&TaskStatus::InProgress)));
+        assert!(!transition.matches(Some(&task), Some(&TaskStatus::Todo)));
+        assert!(!transition.matches(Some(&task), Some(&TaskStatus::InReview)));
+        assert!(!transition.matches(Some(&task), None));
+    }
+
+    #[test]
+    fn test_webhook_event_string_round_trip() {
+        let events = [
+            WebhookEvent::TaskCreated,
+            WebhookEvent::TaskUpdated,
+            WebhookEvent::TaskCompleted,
+            WebhookEvent::WorkspaceStarted,
+            WebhookEvent::ExecutionCompleted,
+            WebhookEvent::ExecutionFailed,
+            WebhookEvent::ApprovalRequested,
+            WebhookEvent::ApprovalResponded,
+            WebhookEvent::PrCreated,
+            WebhookEvent::PrMerged,
+            WebhookEvent::PrClosed,
+            WebhookEvent::TaskCommentAdded,
+            WebhookEvent::TaskCommentMentioned,
+            WebhookEvent::TaskAssigned,
+        ];
+
+        for event in events {
+            assert_eq!(WebhookEvent::from_str(event.as_str()), Some(event.clone()));
+            assert_eq!(
+                serde_json::to_value(&event).unwrap(),
+                serde_json::Value::String(event.as_str().to_string())
+            );
+        }
+    }
 }
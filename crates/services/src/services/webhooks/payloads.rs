@@ -0,0 +1,200 @@
+//! Typed `data` payloads for webhook events.
+//!
+//! These structs define the stable JSON schema of the `data` field in
+//! [`super::WebhookPayload`] for each event. Fields may be added over time but
+//! existing fields are never renamed or removed.
+//!
+//! `task_created`, `task_updated` and `task_completed` send the serialized
+//! `Task` and `workspace_started` sends the serialized `Workspace`.
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionContext, ExecutionProcessRunReason, ExecutionProcessStatus},
+    merge::{MergeStatus, PullRequestInfo},
+    task::Task,
+    task_comment::{TaskComment, TaskCommentMention},
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::approvals::{ApprovalRequest, ApprovalStatus};
+use uuid::Uuid;
+
+use crate::services::approvals::remote::RemoteApprovalLinks;
+
+/// Payload for `execution_completed` and `execution_failed`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExecutionEventPayload {
+    pub execution_process_id: Uuid,
+    pub session_id: Uuid,
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    pub status: ExecutionProcessStatus,
+    /// Process exit code, if the process exited normally
+    pub exit_code: Option<i64>,
+    /// Final assistant message for coding agent runs
+    pub summary: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl ExecutionEventPayload {
+    pub fn from_context(ctx: &ExecutionContext, summary: Option<String>) -> Self {
+        let process = &ctx.execution_process;
+        Self {
+            execution_process_id: process.id,
+            session_id: ctx.session.id,
+            workspace_id: ctx.workspace.id,
+            task_id: ctx.task.id,
+            project_id: ctx.project.id,
+            run_reason: process.run_reason.clone(),
+            status: process.status.clone(),
+            exit_code: process.exit_code,
+            summary,
+            started_at: process.started_at,
+            completed_at: process.completed_at,
+        }
+    }
+}
+
+/// Payload for `approval_requested`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ApprovalRequestedPayload {
+    pub approval_id: String,
+    pub execution_process_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub tool_name: String,
+    pub tool_input: serde_json::Value,
+    pub requested_at: DateTime<Utc>,
+    /// The approval is treated as timed out after this time
+    pub timeout_at: DateTime<Utc>,
+    /// Signed one-time link approving the request; set when `VIBE_PUBLIC_URL` is
+    pub approve_url: Option<String>,
+    /// Signed one-time link denying the request; set when `VIBE_PUBLIC_URL` is
+    pub deny_url: Option<String>,
+}
+
+impl ApprovalRequestedPayload {
+    pub fn new(
+        request: &ApprovalRequest,
+        ctx: &ExecutionContext,
+        links: Option<RemoteApprovalLinks>,
+    ) -> Self {
+        let (approve_url, deny_url) = match links {
+            Some(links) => (Some(links.approve_url), Some(links.deny_url)),
+            None => (None, None),
+        };
+        Self {
+            approval_id: request.id.clone(),
+            execution_process_id: request.execution_process_id,
+            task_id: ctx.task.id,
+            project_id: ctx.project.id,
+            tool_name: request.tool_name.clone(),
+            tool_input: request.tool_input.clone(),
+            requested_at: request.created_at,
+            timeout_at: request.timeout_at,
+            approve_url,
+            deny_url,
+        }
+    }
+}
+
+/// Payload for `approval_responded`. Sent for approvals, denials and timeouts.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ApprovalRespondedPayload {
+    pub approval_id: String,
+    pub execution_process_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub tool_name: String,
+    /// One of `approved`, `denied` (with optional `reason`) or `timed_out`
+    pub response: ApprovalStatus,
+    pub responded_at: DateTime<Utc>,
+}
+
+impl ApprovalRespondedPayload {
+    pub fn new(
+        request: &ApprovalRequest,
+        ctx: &ExecutionContext,
+        response: ApprovalStatus,
+    ) -> Self {
+        Self {
+            approval_id: request.id.clone(),
+            execution_process_id: request.execution_process_id,
+            task_id: ctx.task.id,
+            project_id: ctx.project.id,
+            tool_name: request.tool_name.clone(),
+            response,
+            responded_at: Utc::now(),
+        }
+    }
+}
+
+/// Payload for `pr_created`, `pr_merged` and `pr_closed`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PullRequestEventPayload {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub pr_number: i64,
+    pub pr_url: String,
+    pub status: MergeStatus,
+    pub target_branch: String,
+    pub merged_at: Option<DateTime<Utc>>,
+    pub merge_commit_sha: Option<String>,
+}
+
+impl PullRequestEventPayload {
+    pub fn new(
+        task: &Task,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        target_branch: &str,
+        pr_info: &PullRequestInfo,
+    ) -> Self {
+        Self {
+            task_id: task.id,
+            project_id: task.project_id,
+            workspace_id,
+            repo_id,
+            pr_number: pr_info.number,
+            pr_url: pr_info.url.clone(),
+            status: pr_info.status.clone(),
+            target_branch: target_branch.to_string(),
+            merged_at: pr_info.merged_at,
+            merge_commit_sha: pr_info.merge_commit_sha.clone(),
+        }
+    }
+}
+
+/// Payload for `task_comment_added`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskCommentAddedPayload {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub comment: TaskComment,
+}
+
+/// Payload for `task_comment_mentioned`. Sent when a new or edited comment
+/// mentions the task's assignee or one of its agents.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskCommentMentionedPayload {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub comment: TaskComment,
+    /// Only the mentions that were not already on the comment
+    pub mentions: Vec<TaskCommentMention>,
+}
+
+/// Payload for `task_assigned`. Also sent when the assignee is cleared.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskAssignedPayload {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub previous_assignee: Option<String>,
+    pub assignee: Option<String>,
+    pub task: Task,
+}
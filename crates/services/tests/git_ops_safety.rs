@@ -4,6 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use db::models::merge::MergeStrategy;
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
 use services::services::git::{GitCli, GitCliError, GitService};
 use tempfile::TempDir;
@@ -1406,3 +1407,121 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+// Commit on main while the feature branch is checked out in its worktree, leaving
+// `checkout` checked out in the main repo
+fn advance_main(repo_path: &Path, checkout: &str) {
+    let repo = Repository::open(repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    write_file(repo_path, "main_advance.txt", "main advanced\n");
+    commit_all(&repo, "main advances ahead");
+    checkout_branch(&repo, checkout);
+}
+
+#[test]
+fn rebase_strategy_rebases_and_fast_forwards_when_base_ahead() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    advance_main(&repo_path, "old-base");
+
+    let service = GitService::new();
+    let sha = service
+        .merge_changes_with_strategy(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "unused for rebase",
+            MergeStrategy::Rebase,
+            false,
+        )
+        .expect("rebase merge should succeed");
+
+    assert_eq!(service.get_branch_oid(&repo_path, "main").unwrap(), sha);
+    assert_eq!(service.get_branch_oid(&repo_path, "feature").unwrap(), sha);
+
+    // Linear history: the feature commits sit directly on top of the advanced main
+    let repo = Repository::open(&repo_path).unwrap();
+    let tip = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    assert_eq!(tip.parent_count(), 1);
+    assert_eq!(tip.summary(), Some("feature commit"));
+    let tree = tip.tree().unwrap();
+    assert!(tree.get_name("main_advance.txt").is_some());
+    assert!(tree.get_name("feat.txt").is_some());
+}
+
+#[test]
+fn squash_with_rebase_before_merge_succeeds_when_base_ahead() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    advance_main(&repo_path, "main");
+
+    let service = GitService::new();
+    let refused = service.merge_changes_with_strategy(
+        &repo_path,
+        &worktree_path,
+        "feature",
+        "main",
+        "squash",
+        MergeStrategy::Squash,
+        false,
+    );
+    assert!(
+        refused.is_err(),
+        "merge should be refused without rebase_before_merge"
+    );
+
+    service
+        .merge_changes_with_strategy(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "squash",
+            MergeStrategy::Squash,
+            true,
+        )
+        .expect("merge should rebase first and succeed");
+
+    // main is checked out, so the CLI path updated its working tree
+    assert_eq!(
+        fs::read_to_string(repo_path.join("main_advance.txt")).unwrap(),
+        "main advanced\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("feat.txt")).unwrap(),
+        "feat change\n"
+    );
+}
+
+#[test]
+fn merge_commit_strategy_keeps_both_parents() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+
+    let service = GitService::new();
+    let before_main = service.get_branch_oid(&repo_path, "main").unwrap();
+    let before_feature = service.get_branch_oid(&repo_path, "feature").unwrap();
+    let sha = service
+        .merge_changes_with_strategy(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "merge feature",
+            MergeStrategy::MergeCommit,
+            false,
+        )
+        .expect("merge commit should succeed");
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let merge = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    let parents: Vec<String> = merge.parent_ids().map(|id| id.to_string()).collect();
+    assert_eq!(parents, vec![before_main, before_feature]);
+    assert_eq!(merge.message(), Some("merge feature"));
+    assert_eq!(service.get_branch_oid(&repo_path, "main").unwrap(), sha);
+}
@@ -36,6 +36,10 @@ pub fn credentials_path() -> std::path::PathBuf {
     asset_dir().join("credentials.json")
 }
 
+pub fn approval_secret_path() -> std::path::PathBuf {
+    asset_dir().join("approval_secret")
+}
+
 #[derive(RustEmbed)]
 #[folder = "../../assets/sounds"]
 pub struct SoundAssets;
@@ -6,14 +6,39 @@ pub const EV_STDOUT: &str = "stdout";
 pub const EV_STDERR: &str = "stderr";
 pub const EV_JSON_PATCH: &str = "json_patch";
 pub const EV_SESSION_ID: &str = "session_id";
+pub const EV_USAGE: &str = "usage";
 pub const EV_FINISHED: &str = "finished";
 
+/// Tokens reported by a coding agent for one model call or turn
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub model: Option<String>,
+    /// Input tokens that were not read from or written to the prompt cache
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    /// Cost in USD when the agent reports one itself
+    pub cost_usd: Option<f64>,
+}
+
+impl TokenUsage {
+    pub fn is_empty(&self) -> bool {
+        self.input_tokens == 0
+            && self.output_tokens == 0
+            && self.cache_read_tokens == 0
+            && self.cache_write_tokens == 0
+            && self.cost_usd.is_none()
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LogMsg {
     Stdout(String),
     Stderr(String),
     JsonPatch(Patch),
     SessionId(String),
+    Usage(TokenUsage),
     Finished,
 }
 
@@ -24,6 +49,7 @@ impl LogMsg {
             LogMsg::Stderr(_) => EV_STDERR,
             LogMsg::JsonPatch(_) => EV_JSON_PATCH,
             LogMsg::SessionId(_) => EV_SESSION_ID,
+            LogMsg::Usage(_) => EV_USAGE,
             LogMsg::Finished => EV_FINISHED,
         }
     }
@@ -37,6 +63,10 @@ impl LogMsg {
                 Event::default().event(EV_JSON_PATCH).data(data)
             }
             LogMsg::SessionId(s) => Event::default().event(EV_SESSION_ID).data(s.clone()),
+            LogMsg::Usage(usage) => {
+                let data = serde_json::to_string(usage).unwrap_or_else(|_| "{}".to_string());
+                Event::default().event(EV_USAGE).data(data)
+            }
             LogMsg::Finished => Event::default().event(EV_FINISHED).data(""),
         }
     }
@@ -73,6 +103,12 @@ impl LogMsg {
                 EV_JSON_PATCH.len() + json_len + OVERHEAD
             }
             LogMsg::SessionId(s) => EV_SESSION_ID.len() + s.len() + OVERHEAD,
+            LogMsg::Usage(usage) => {
+                EV_USAGE.len()
+                    + std::mem::size_of::<TokenUsage>()
+                    + usage.model.as_ref().map_or(0, String::len)
+                    + OVERHEAD
+            }
             LogMsg::Finished => EV_FINISHED.len() + OVERHEAD,
         }
     }
@@ -9,6 +9,8 @@ import {
   DialogTitle,
 } from '@/components/ui/dialog';
 import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Label } from '@/components/ui/label';
 import RepoBranchSelector from '@/components/tasks/RepoBranchSelector';
 import { ExecutorProfileSelector } from '@/components/settings';
 import { useAttemptCreation } from '@/hooks/useAttemptCreation';
@@ -50,6 +52,7 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
 
     const [userSelectedProfile, setUserSelectedProfile] =
       useState<ExecutorProfileId | null>(null);
+    const [stackOnParent, setStackOnParent] = useState(true);
 
     const { data: attempts = [], isLoading: isLoadingAttempts } =
       useTaskAttemptsWithSessions(taskId, {
@@ -94,6 +97,7 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
     useEffect(() => {
       if (!modal.visible) {
         setUserSelectedProfile(null);
+        setStackOnParent(true);
         resetBranchSelection();
       }
     }, [modal.visible, resetBranchSelection]);
@@ -152,6 +156,8 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
         await createAttempt({
           profile: effectiveProfile,
           repos,
+          stackedOnWorkspaceId:
+            parentAttempt && stackOnParent ? parentAttempt.id : undefined,
         });
 
         modal.hide();
@@ -199,6 +205,30 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
               className="space-y-2"
             />
 
+            {parentAttempt && (
+              <div className="space-y-1">
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="attempt-stack-on-parent"
+                    checked={stackOnParent}
+                    onCheckedChange={setStackOnParent}
+                    className="h-5 w-5"
+                  />
+                  <Label
+                    htmlFor="attempt-stack-on-parent"
+                    className="cursor-pointer text-sm"
+                  >
+                    {t('createAttemptDialog.stackOnParent', {
+                      branch: parentAttempt.branch,
+                    })}
+                  </Label>
+                </div>
+                <p className="text-xs text-muted-foreground">
+                  {t('createAttemptDialog.stackOnParentHelp')}
+                </p>
+              </div>
+            )}
+
             {error && (
               <div className="text-sm text-destructive">
                 {t('createAttemptDialog.error')}
@@ -198,6 +198,10 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
           setError(result.message || t(gitCliErrorKey));
           setGhCliHelp(null);
           return;
+        } else if (result.error.type === 'forge_not_logged_in') {
+          setError(result.error.message);
+          setGhCliHelp(null);
+          return;
         } else if (result.error.type === 'target_branch_not_found') {
           setError(
             t('createPrDialog.errors.targetBranchNotFound', {
@@ -14,6 +14,7 @@ import { Button } from '@/components/ui/button';
 import { Checkbox } from '@/components/ui/checkbox';
 import { MessageSquare, AlertCircle, Loader2 } from 'lucide-react';
 import { usePrComments } from '@/hooks/usePrComments';
+import { attemptsApi } from '@/lib/api';
 import { GitHubCommentCard } from '@/components/ui/github-comment-card';
 import type { UnifiedPrComment } from 'shared/types';
 
@@ -41,6 +42,9 @@ const GitHubCommentsDialogImpl = NiceModal.create<GitHubCommentsDialogProps>(
       repoId
     );
     const [selectedIds, setSelectedIds] = useState<Set<string>>(new Set());
+    const [replyToComments, setReplyToComments] = useState(false);
+    const [addressing, setAddressing] = useState(false);
+    const [addressError, setAddressError] = useState<string | null>(null);
 
     const comments = data?.comments ?? [];
 
@@ -48,6 +52,7 @@ const GitHubCommentsDialogImpl = NiceModal.create<GitHubCommentsDialogProps>(
     useEffect(() => {
       if (modal.visible) {
         setSelectedIds(new Set());
+        setAddressError(null);
       }
     }, [modal.visible]);
 
@@ -80,6 +85,31 @@ const GitHubCommentsDialogImpl = NiceModal.create<GitHubCommentsDialogProps>(
       modal.hide();
     };
 
+    const handleAddress = async () => {
+      setAddressing(true);
+      setAddressError(null);
+      const result = await attemptsApi.addressPrComments(attemptId, {
+        repo_id: repoId,
+        reply_to_comments: replyToComments,
+      });
+      setAddressing(false);
+
+      if (result.success) {
+        modal.resolve({ comments: [] });
+        modal.hide();
+        return;
+      }
+      if (result.error?.type === 'no_pending_comments') {
+        setAddressError(t('tasks:githubComments.dialog.noPendingComments'));
+      } else if (result.error) {
+        setAddressError(getErrorMessage({ error_data: result.error }));
+      } else {
+        setAddressError(
+          result.message || t('tasks:githubComments.dialog.addressFailed')
+        );
+      }
+    };
+
     const handleOpenChange = (open: boolean) => {
       if (!open) {
         modal.resolve({ comments: [] });
@@ -196,7 +226,34 @@ const GitHubCommentsDialogImpl = NiceModal.create<GitHubCommentsDialogProps>(
           </div>
 
           {!errorMessage && !isLoading && comments.length > 0 && (
-            <DialogFooter className="px-4 py-3 border-t">
+            <DialogFooter className="px-4 py-3 border-t sm:items-center">
+              {addressError && (
+                <p className="text-sm text-destructive sm:mr-auto">
+                  {addressError}
+                </p>
+              )}
+              <label
+                className="flex items-center gap-2 text-sm text-muted-foreground sm:mr-auto"
+              >
+                <Checkbox
+                  checked={replyToComments}
+                  onCheckedChange={(checked) =>
+                    setReplyToComments(checked === true)
+                  }
+                />
+                {t('tasks:githubComments.dialog.replyToComments')}
+              </label>
+              <Button
+                variant="outline"
+                onClick={handleAddress}
+                disabled={addressing}
+                title={t('tasks:githubComments.dialog.addressWithAgentHint')}
+              >
+                {addressing && (
+                  <Loader2 className="h-4 w-4 mr-2 animate-spin" />
+                )}
+                {t('tasks:githubComments.dialog.addressWithAgent')}
+              </Button>
               <Button variant="outline" onClick={() => handleOpenChange(false)}>
                 {t('common:buttons.cancel')}
               </Button>
@@ -215,7 +272,9 @@ const GitHubCommentsDialogImpl = NiceModal.create<GitHubCommentsDialogProps>(
 function getErrorMessage(error: unknown): string {
   // Check if it's an API error with error_data
   if (error && typeof error === 'object' && 'error_data' in error) {
-    const errorData = (error as { error_data?: { type?: string } }).error_data;
+    const errorData = (
+      error as { error_data?: { type?: string; message?: string } }
+    ).error_data;
     if (errorData?.type === 'no_pr_attached') {
       return 'No PR is attached to this task attempt. Create a PR first to see comments.';
     }
@@ -225,6 +284,9 @@ function getErrorMessage(error: unknown): string {
     if (errorData?.type === 'github_cli_not_logged_in') {
       return 'GitHub CLI is not logged in. Please run "gh auth login" to authenticate.';
     }
+    if (errorData?.type === 'forge_not_logged_in' && errorData.message) {
+      return errorData.message;
+    }
   }
   return 'Failed to load PR comments. Please try again.';
 }
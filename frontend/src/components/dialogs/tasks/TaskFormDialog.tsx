@@ -175,6 +175,9 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
             parent_workspace_id: null,
             image_ids: images.length > 0 ? images.map((img) => img.id) : null,
             assignee: null, // Preserve existing assignee, don't change via this dialog
+            priority: null,
+            due_date: null,
+            estimate_hours: null,
           },
         },
         { onSuccess: () => modal.remove() }
@@ -191,6 +194,9 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
           mode === 'subtask' ? props.parentTaskAttemptId : null,
         image_ids: imageIds,
         shared_task_id: null,
+        priority: null,
+        due_date: null,
+        estimate_hours: null,
       };
       const shouldAutoStart = value.autoStart && !forceCreateOnlyRef.current;
       if (shouldAutoStart) {
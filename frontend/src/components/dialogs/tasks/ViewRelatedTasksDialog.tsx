@@ -8,9 +8,10 @@ import {
   DialogTitle,
 } from '@/components/ui/dialog';
 import { Button } from '@/components/ui/button';
-import { PlusIcon } from 'lucide-react';
+import { GitBranch, PlusIcon } from 'lucide-react';
 import { openTaskForm } from '@/lib/openTaskForm';
 import { useTaskRelationships } from '@/hooks/useTaskRelationships';
+import { useRestack } from '@/hooks/useRestack';
 import { DataTable, type ColumnDef } from '@/components/ui/table/data-table';
 import type { Task } from 'shared/types';
 import type { Workspace } from 'shared/types';
@@ -33,6 +34,14 @@ const ViewRelatedTasksDialogImpl =
         isError,
         refetch,
       } = useTaskRelationships(attemptId);
+      const restack = useRestack(attemptId);
+
+      const stackedOn = relationships?.stacked_on ?? null;
+      const stackedChildren = relationships?.stacked_children ?? [];
+      const restackIssues = (restack.data ?? []).filter(
+        (result) =>
+          result.status === 'conflicts' || result.status === 'skipped'
+      );
 
       // Combine parent and children into a single list of related tasks
       const relatedTasks: Task[] = [];
@@ -134,6 +143,57 @@ const ViewRelatedTasksDialogImpl =
                 </div>
               )}
 
+              {!isError && (stackedOn || stackedChildren.length > 0) && (
+                <div className="mb-4 space-y-2 text-sm">
+                  {stackedOn && (
+                    <div className="flex items-center gap-2">
+                      <GitBranch size={14} className="shrink-0" />
+                      <span className="flex-1 truncate">
+                        {t('viewRelatedTasksDialog.stackedOn', {
+                          branch: stackedOn.branch,
+                        })}
+                      </span>
+                      {attempt?.stacked_on_workspace_id && (
+                        <Button
+                          variant="outline"
+                          size="sm"
+                          onClick={() => restack.mutate()}
+                          disabled={restack.isPending}
+                        >
+                          {restack.isPending
+                            ? t('viewRelatedTasksDialog.restacking')
+                            : t('viewRelatedTasksDialog.restack')}
+                        </Button>
+                      )}
+                    </div>
+                  )}
+                  {stackedChildren.map((child) => (
+                    <div
+                      key={child.id}
+                      className="flex items-center gap-2 text-muted-foreground"
+                    >
+                      <GitBranch size={14} className="shrink-0" />
+                      <span className="truncate">
+                        {t('viewRelatedTasksDialog.stackedChild', {
+                          branch: child.branch,
+                        })}
+                      </span>
+                    </div>
+                  ))}
+                  {restack.isError && (
+                    <div className="text-destructive">
+                      {t('viewRelatedTasksDialog.restackError')}
+                    </div>
+                  )}
+                  {restackIssues.map((result) => (
+                    <div key={result.repo_id} className="text-destructive">
+                      {result.message ??
+                        t('viewRelatedTasksDialog.restackConflicts')}
+                    </div>
+                  ))}
+                </div>
+              )}
+
               {!isError && (
                 <DataTable
                   data={relatedTasks}
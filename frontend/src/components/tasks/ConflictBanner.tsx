@@ -12,6 +12,8 @@ export type Props = Readonly<{
   op?: ConflictOp | null;
   onResolve?: () => void;
   enableResolve: boolean;
+  onResolveWithAgent?: () => void;
+  enableResolveWithAgent?: boolean;
   enableAbort: boolean;
 }>;
 
@@ -46,6 +48,8 @@ export function ConflictBanner({
   op,
   onResolve,
   enableResolve,
+  onResolveWithAgent,
+  enableResolveWithAgent = false,
   enableAbort,
 }: Props) {
   const { full: opTitle, lower: opTitleLower } = getOperationTitle(op);
@@ -105,6 +109,18 @@ export function ConflictBanner({
             Resolve conflicts
           </Button>
         )}
+        {onResolveWithAgent && (
+          <Button
+            size="sm"
+            variant="outline"
+            onClick={onResolveWithAgent}
+            disabled={!enableResolveWithAgent}
+            className="border-warning/40 text-warning-foreground hover:bg-warning/10 dark:text-warning/90"
+            title="The agent resolves the conflicts, then the operation is continued; it is aborted if conflict markers are left"
+          >
+            Resolve with agent
+          </Button>
+        )}
         <Button
           size="sm"
           variant="outline"
@@ -1,7 +1,10 @@
 import { useCallback, useEffect, useRef, useState } from 'react';
 import { KanbanCard } from '@/components/ui/shadcn-io/kanban';
-import { Link, Loader2, XCircle, User } from 'lucide-react';
-import type { TaskWithAttemptStatus } from 'shared/types';
+import { CheckCircle2, Link, Loader2, XCircle, User } from 'lucide-react';
+import {
+  VerificationStatus,
+  type TaskWithAttemptStatus,
+} from 'shared/types';
 import { ActionsDropdown } from '@/components/ui/actions-dropdown';
 import { Button } from '@/components/ui/button';
 import { useNavigateWithSearch } from '@/hooks';
@@ -35,6 +38,8 @@ export function TaskCard({
   sharedTask,
 }: TaskCardProps) {
   const { t } = useTranslation('tasks');
+  const verificationPassed =
+    task.last_verification_status === VerificationStatus.passed;
   const navigate = useNavigateWithSearch();
   const [isNavigatingToParent, setIsNavigatingToParent] = useState(false);
   const { isSignedIn } = useAuth();
@@ -140,12 +145,28 @@ export function TaskCard({
               : task.description}
           </p>
         )}
-        {task.assignee && (
+        {(task.assignee || task.last_verification_status) && (
           <div className="flex items-center gap-1 mt-1">
-            <Badge variant="secondary" className="text-xs py-0 px-1.5 gap-1">
-              <User className="h-3 w-3" />
-              {task.assignee}
-            </Badge>
+            {task.assignee && (
+              <Badge variant="secondary" className="text-xs py-0 px-1.5 gap-1">
+                <User className="h-3 w-3" />
+                {task.assignee}
+              </Badge>
+            )}
+            {task.last_verification_status && (
+              <Badge
+                variant={verificationPassed ? 'secondary' : 'destructive'}
+                className="text-xs py-0 px-1.5 gap-1"
+                title={t('verification.tooltip')}
+              >
+                {verificationPassed ? (
+                  <CheckCircle2 className="h-3 w-3" />
+                ) : (
+                  <XCircle className="h-3 w-3" />
+                )}
+                {t(`verification.${task.last_verification_status}`)}
+              </Badge>
+            )}
           </div>
         )}
       </div>
@@ -1,8 +1,9 @@
-import { useState, useCallback } from 'react';
+import { useState, useCallback, useMemo } from 'react';
 import { useTranslation } from 'react-i18next';
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
-import { MessageSquare, Send, User } from 'lucide-react';
-import { taskCommentsApi, type TaskComment } from '@/lib/api';
+import { Bot, MessageSquare, Pencil, Reply, Send, User, X } from 'lucide-react';
+import type { TaskComment } from 'shared/types';
+import { taskCommentsApi } from '@/lib/api';
 import { Button } from '@/components/ui/button';
 import { Textarea } from '@/components/ui/textarea';
 import { cn } from '@/lib/utils';
@@ -20,21 +21,173 @@ function formatDate(dateStr: string): string {
   }
 }
 
-function CommentCard({ comment }: { comment: TaskComment }) {
+function CommentEditHistory({ comment }: { comment: TaskComment }) {
+  const { t } = useTranslation('tasks');
+  const { data: edits = [], isLoading } = useQuery({
+    queryKey: ['taskCommentEdits', comment.id, comment.edited_at],
+    queryFn: () => taskCommentsApi.getEdits(comment.task_id, comment.id),
+  });
+
+  if (isLoading) {
+    return (
+      <p className="text-xs text-muted-foreground">
+        {t('comments.loadingEdits', { defaultValue: 'Loading edits...' })}
+      </p>
+    );
+  }
+
+  return (
+    <div className="mt-2 space-y-2 border-l-2 border-border pl-3">
+      {edits.map((edit) => (
+        <div key={edit.id}>
+          <span className="text-xs text-muted-foreground">
+            {edit.edited_by} · {formatDate(edit.edited_at)}
+          </span>
+          <p className="text-xs text-muted-foreground whitespace-pre-wrap break-words line-through">
+            {edit.previous_content}
+          </p>
+        </div>
+      ))}
+    </div>
+  );
+}
+
+interface CommentCardProps {
+  comment: TaskComment;
+  onReply: (comment: TaskComment) => void;
+  onEdit: (comment: TaskComment, content: string) => void;
+  isSaving: boolean;
+}
+
+function CommentCard({
+  comment,
+  onReply,
+  onEdit,
+  isSaving,
+}: CommentCardProps) {
+  const { t } = useTranslation('tasks');
+  const [isEditing, setIsEditing] = useState(false);
+  const [draft, setDraft] = useState(comment.content);
+  const [showEdits, setShowEdits] = useState(false);
+  const AuthorIcon = comment.author_type === 'agent' ? Bot : User;
+
+  const handleSave = () => {
+    const trimmed = draft.trim();
+    if (!trimmed) return;
+    if (trimmed !== comment.content) {
+      onEdit(comment, trimmed);
+    }
+    setIsEditing(false);
+  };
+
   return (
     <div className="p-3 bg-muted/50 rounded-md border border-border">
       <div className="flex items-center justify-between gap-2 mb-2">
         <div className="flex items-center gap-2 min-w-0">
-          <User className="w-4 h-4 text-muted-foreground flex-shrink-0" />
+          <AuthorIcon className="w-4 h-4 text-muted-foreground flex-shrink-0" />
           <span className="font-medium text-sm">{comment.author}</span>
         </div>
-        <span className="text-xs text-muted-foreground flex-shrink-0">
-          {formatDate(comment.created_at)}
-        </span>
+        <div className="flex items-center gap-2 flex-shrink-0">
+          {comment.edited_at && (
+            <button
+              type="button"
+              onClick={() => setShowEdits(!showEdits)}
+              className="text-xs text-muted-foreground hover:text-foreground"
+              title={formatDate(comment.edited_at)}
+            >
+              {t('comments.edited', { defaultValue: '(edited)' })}
+            </button>
+          )}
+          <span className="text-xs text-muted-foreground">
+            {formatDate(comment.created_at)}
+          </span>
+        </div>
       </div>
-      <p className="text-sm text-muted-foreground whitespace-pre-wrap break-words">
-        {comment.content}
-      </p>
+      {isEditing ? (
+        <div className="space-y-2">
+          <Textarea
+            value={draft}
+            onChange={(e) => setDraft(e.target.value)}
+            className="min-h-[60px] resize-none"
+            disabled={isSaving}
+          />
+          <div className="flex justify-end gap-2">
+            <Button
+              size="sm"
+              variant="ghost"
+              onClick={() => {
+                setDraft(comment.content);
+                setIsEditing(false);
+              }}
+            >
+              {t('comments.cancel', { defaultValue: 'Cancel' })}
+            </Button>
+            <Button
+              size="sm"
+              onClick={handleSave}
+              disabled={!draft.trim() || isSaving}
+            >
+              {t('comments.save', { defaultValue: 'Save' })}
+            </Button>
+          </div>
+        </div>
+      ) : (
+        <p className="text-sm text-muted-foreground whitespace-pre-wrap break-words">
+          {comment.content}
+        </p>
+      )}
+      {showEdits && comment.edited_at && (
+        <CommentEditHistory comment={comment} />
+      )}
+      {!isEditing && (
+        <div className="flex gap-3 mt-2">
+          <button
+            type="button"
+            onClick={() => onReply(comment)}
+            className="flex items-center gap-1 text-xs text-muted-foreground hover:text-foreground"
+          >
+            <Reply className="w-3 h-3" />
+            {t('comments.reply', { defaultValue: 'Reply' })}
+          </button>
+          <button
+            type="button"
+            onClick={() => setIsEditing(true)}
+            className="flex items-center gap-1 text-xs text-muted-foreground hover:text-foreground"
+          >
+            <Pencil className="w-3 h-3" />
+            {t('comments.edit', { defaultValue: 'Edit' })}
+          </button>
+        </div>
+      )}
+    </div>
+  );
+}
+
+interface CommentThreadProps extends CommentCardProps {
+  repliesByParent: Map<string, TaskComment[]>;
+}
+
+function CommentThread({
+  comment,
+  repliesByParent,
+  ...cardProps
+}: CommentThreadProps) {
+  const replies = repliesByParent.get(comment.id) ?? [];
+  return (
+    <div className="space-y-2">
+      <CommentCard comment={comment} {...cardProps} />
+      {replies.length > 0 && (
+        <div className="space-y-2 pl-4 border-l border-border">
+          {replies.map((reply) => (
+            <CommentThread
+              key={reply.id}
+              comment={reply}
+              repliesByParent={repliesByParent}
+              {...cardProps}
+            />
+          ))}
+        </div>
+      )}
     </div>
   );
 }
@@ -46,6 +199,7 @@ export function TaskCommentsSection({
   const { t } = useTranslation('tasks');
   const queryClient = useQueryClient();
   const [newComment, setNewComment] = useState('');
+  const [replyTo, setReplyTo] = useState<TaskComment | null>(null);
   const [isExpanded, setIsExpanded] = useState(true);
 
   const {
@@ -63,13 +217,51 @@ export function TaskCommentsSection({
       taskCommentsApi.create(taskId, {
         content,
         author: 'User', // TODO: Get actual user name from auth context
+        parent_comment_id: replyTo?.id ?? null,
       }),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['taskComments', taskId] });
       setNewComment('');
+      setReplyTo(null);
+    },
+  });
+
+  const editCommentMutation = useMutation({
+    mutationFn: ({
+      commentId,
+      content,
+    }: {
+      commentId: string;
+      content: string;
+    }) => taskCommentsApi.update(taskId, commentId, content),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['taskComments', taskId] });
     },
   });
 
+  const handleEditComment = useCallback(
+    (comment: TaskComment, content: string) => {
+      editCommentMutation.mutate({ commentId: comment.id, content });
+    },
+    [editCommentMutation]
+  );
+
+  // Replies whose parent is missing are shown at the top level
+  const { rootComments, repliesByParent } = useMemo(() => {
+    const ids = new Set(comments.map((comment) => comment.id));
+    const roots: TaskComment[] = [];
+    const replies = new Map<string, TaskComment[]>();
+    for (const comment of comments) {
+      const parentId = comment.parent_comment_id;
+      if (parentId && ids.has(parentId)) {
+        replies.set(parentId, [...(replies.get(parentId) ?? []), comment]);
+      } else {
+        roots.push(comment);
+      }
+    }
+    return { rootComments: roots, repliesByParent: replies };
+  }, [comments]);
+
   const handleSubmitComment = useCallback(() => {
     const trimmed = newComment.trim();
     if (!trimmed) return;
@@ -123,17 +315,41 @@ export function TaskCommentsSection({
             </p>
           )}
 
-          {comments.map((comment) => (
-            <CommentCard key={comment.id} comment={comment} />
+          {rootComments.map((comment) => (
+            <CommentThread
+              key={comment.id}
+              comment={comment}
+              repliesByParent={repliesByParent}
+              onReply={setReplyTo}
+              onEdit={handleEditComment}
+              isSaving={editCommentMutation.isPending}
+            />
           ))}
 
           <div className="space-y-2">
+            {replyTo && (
+              <div className="flex items-center justify-between gap-2 text-xs text-muted-foreground">
+                <span className="truncate">
+                  {t('comments.replyingTo', {
+                    defaultValue: 'Replying to {{author}}',
+                    author: replyTo.author,
+                  })}
+                </span>
+                <button
+                  type="button"
+                  onClick={() => setReplyTo(null)}
+                  className="hover:text-foreground"
+                >
+                  <X className="w-3 h-3" />
+                </button>
+              </div>
+            )}
             <Textarea
               value={newComment}
               onChange={(e) => setNewComment(e.target.value)}
               onKeyDown={handleKeyDown}
               placeholder={t('comments.placeholder', {
-                defaultValue: 'Add a comment...',
+                defaultValue: 'Add a comment... Use @name to mention someone',
               })}
               className="min-h-[80px] resize-none"
               disabled={addCommentMutation.isPending}
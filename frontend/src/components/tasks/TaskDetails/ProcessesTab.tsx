@@ -250,6 +250,16 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
                           })}
                         </p>
                       )}
+                      {process.exit_reason === 'timedout' && (
+                        <p className="text-xs text-muted-foreground mt-1">
+                          {t('processes.timedOut')}
+                        </p>
+                      )}
+                      {process.exit_reason === 'budgetexceeded' && (
+                        <p className="text-xs text-muted-foreground mt-1">
+                          {t('processes.budgetExceeded')}
+                        </p>
+                      )}
                     </div>
                   </div>
                   <div className="mt-3 text-xs text-muted-foreground">
@@ -134,6 +134,9 @@ export function NoServerContent({
         parent_workspace_id: null,
         image_ids: null,
         shared_task_id: null,
+        priority: null,
+        due_date: null,
+        estimate_hours: null,
       },
       executor_profile_id: config.executor_profile,
       repos,
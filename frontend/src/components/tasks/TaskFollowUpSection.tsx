@@ -699,6 +699,7 @@ export function TaskFollowUpSection({
                 enableResolve={
                   canSendFollowUp && !isAttemptRunning && isEditable
                 }
+                enableResolveWithAgent={canSendFollowUp && !isAttemptRunning}
                 enableAbort={canSendFollowUp && !isAttemptRunning}
                 conflictResolutionInstructions={conflictResolutionInstructions}
               />
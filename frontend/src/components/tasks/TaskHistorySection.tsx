@@ -50,16 +50,25 @@ function HistoryEntry({ entry }: { entry: TaskHistory }) {
           {formatDate(entry.changed_at)}
         </span>
       </div>
-      <div className="flex items-center gap-2 text-sm">
-        <span className="font-medium">{field}:</span>
-        <span className="text-muted-foreground truncate max-w-[100px]" title={oldVal}>
-          {oldVal}
-        </span>
-        <ArrowRight className="w-3 h-3 text-muted-foreground flex-shrink-0" />
-        <span className="text-foreground truncate max-w-[100px]" title={newVal}>
-          {newVal}
-        </span>
-      </div>
+      {entry.diff ? (
+        <div className="text-sm">
+          <span className="font-medium">{field}:</span>
+          <pre className="mt-1 text-xs whitespace-pre-wrap break-words text-muted-foreground max-h-[120px] overflow-y-auto">
+            {entry.diff}
+          </pre>
+        </div>
+      ) : (
+        <div className="flex items-center gap-2 text-sm">
+          <span className="font-medium">{field}:</span>
+          <span className="text-muted-foreground truncate max-w-[100px]" title={oldVal}>
+            {oldVal}
+          </span>
+          <ArrowRight className="w-3 h-3 text-muted-foreground flex-shrink-0" />
+          <span className="text-foreground truncate max-w-[100px]" title={newVal}>
+            {newVal}
+          </span>
+        </div>
+      )}
     </div>
   );
 }
@@ -11,6 +11,7 @@ type Props = {
   isEditable: boolean;
   onResolve?: () => void;
   enableResolve: boolean;
+  enableResolveWithAgent: boolean;
   enableAbort: boolean;
   conflictResolutionInstructions: string | null;
 };
@@ -21,6 +22,7 @@ export function FollowUpConflictSection({
   branchStatus,
   onResolve,
   enableResolve,
+  enableResolveWithAgent,
   enableAbort,
   conflictResolutionInstructions,
 }: Props) {
@@ -30,7 +32,10 @@ export function FollowUpConflictSection({
   const op = repoWithConflicts?.conflict_op ?? null;
   const openInEditor = useOpenInEditor(workspaceId);
   const repoId = repoWithConflicts?.repo_id;
-  const { abortConflicts } = useAttemptConflicts(workspaceId, repoId);
+  const { abortConflicts, resolveConflictsWithAgent } = useAttemptConflicts(
+    workspaceId,
+    repoId
+  );
 
   // write using setAborting and read through abortingRef in async handlers
   const [aborting, setAborting] = useState(false);
@@ -38,6 +43,8 @@ export function FollowUpConflictSection({
   useEffect(() => {
     abortingRef.current = aborting;
   }, [aborting]);
+  const [resolvingWithAgent, setResolvingWithAgent] = useState(false);
+  const [resolveError, setResolveError] = useState<string | null>(null);
 
   if (!repoWithConflicts) return null;
 
@@ -50,6 +57,23 @@ export function FollowUpConflictSection({
         op={op}
         onResolve={onResolve}
         enableResolve={enableResolve && !aborting}
+        onResolveWithAgent={async () => {
+          if (!enableResolveWithAgent || resolvingWithAgent) return;
+          try {
+            setResolvingWithAgent(true);
+            setResolveError(null);
+            await resolveConflictsWithAgent();
+          } catch (e) {
+            setResolveError(
+              e instanceof Error ? e.message : 'Failed to start the agent'
+            );
+          } finally {
+            setResolvingWithAgent(false);
+          }
+        }}
+        enableResolveWithAgent={
+          enableResolveWithAgent && !aborting && !resolvingWithAgent
+        }
         onOpenEditor={() => {
           if (!workspaceId) return;
           const first = repoWithConflicts.conflicted_files?.[0];
@@ -69,6 +93,9 @@ export function FollowUpConflictSection({
         }}
         enableAbort={enableAbort && !aborting}
       />
+      {resolveError && (
+        <div className="text-sm text-destructive">{resolveError}</div>
+      )}
       {/* Conflict instructions preview (non-editable) */}
       {conflictResolutionInstructions && enableResolve && (
         <div className="text-sm mb-4">
@@ -7,6 +7,7 @@ export { useImageUpload } from './useImageUpload';
 export { useTaskMutations } from './useTaskMutations';
 export { useDevServer } from './useDevServer';
 export { useRebase } from './useRebase';
+export { useRestack } from './useRestack';
 export { useChangeTargetBranch } from './useChangeTargetBranch';
 export { useRenameBranch } from './useRenameBranch';
 export { useMerge } from './useMerge';
@@ -10,6 +10,7 @@ type CreateAttemptArgs = {
   profile: ExecutorProfileId;
   repos: WorkspaceRepoInput[];
   mode?: 'worktree' | 'branch';
+  stackedOnWorkspaceId?: string;
 };
 
 type UseAttemptCreationArgs = {
@@ -24,12 +25,18 @@ export function useAttemptCreation({
   const queryClient = useQueryClient();
 
   const mutation = useMutation({
-    mutationFn: ({ profile, repos, mode = 'worktree' }: CreateAttemptArgs) =>
+    mutationFn: ({
+      profile,
+      repos,
+      mode = 'worktree',
+      stackedOnWorkspaceId,
+    }: CreateAttemptArgs) =>
       attemptsApi.create({
         task_id: taskId,
         executor_profile_id: profile,
         repos,
         mode,
+        stacked_on_workspace_id: stackedOnWorkspaceId,
       } as Parameters<typeof attemptsApi.create>[0]),
     onSuccess: (newAttempt: Workspace) => {
       queryClient.setQueryData(
@@ -61,6 +61,7 @@ export function useAutoLinkSharedTasks({
           title: task.title,
           description: task.description,
           status: task.status,
+          workflow_state: task.workflow_state ?? undefined,
         } as SharedTaskDetails,
         {
           onError: () => {
@@ -57,7 +57,8 @@ export const useExecutionProcesses = (
     (process) =>
       (process.run_reason === 'codingagent' ||
         process.run_reason === 'setupscript' ||
-        process.run_reason === 'cleanupscript') &&
+        process.run_reason === 'cleanupscript' ||
+        process.run_reason === 'pipelinestep') &&
       process.status === 'running'
   );
   const isLoading = !!taskAttemptId && !data && !error; // until first snapshot
@@ -0,0 +1,30 @@
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import { attemptsApi } from '@/lib/api';
+import type { RestackResult } from 'shared/types';
+import { taskRelationshipsKeys } from './useTaskRelationships';
+
+export function useRestack(attemptId: string | undefined) {
+  const queryClient = useQueryClient();
+
+  return useMutation<RestackResult[], unknown, void>({
+    mutationFn: () => {
+      if (!attemptId) return Promise.resolve([]);
+      return attemptsApi.restack(attemptId);
+    },
+    onSuccess: () => {
+      // Restacking can change the base commit, the target branch and the stack itself
+      queryClient.invalidateQueries({
+        queryKey: ['branchStatus', attemptId],
+      });
+      queryClient.invalidateQueries({
+        queryKey: ['taskAttempt', attemptId],
+      });
+      queryClient.invalidateQueries({
+        queryKey: taskRelationshipsKeys.byAttempt(attemptId),
+      });
+    },
+    onError: (err) => {
+      console.error('Failed to restack:', err);
+    },
+  });
+}
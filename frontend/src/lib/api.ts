@@ -30,9 +30,14 @@ import {
   TaskRelationships,
   Tag,
   TagSearchParams,
+  TaskActivity,
+  TaskActivityFilters,
+  TaskComment,
+  TaskCommentEdit,
   TaskHistory,
   TaskWithAttemptStatus,
   UpdateProject,
+  UpdateRepoForgeRequest,
   UpdateTask,
   UpdateTag,
   UserSystemInfo,
@@ -85,12 +90,20 @@ import {
   SharedTaskDetails,
   QueueStatus,
   PrCommentsResponse,
+  AddressPrCommentsRequest,
+  AddressPrCommentsError,
+  ReviewFollowUp,
+  ReviewFollowUpWithComments,
   MergeTaskAttemptRequest,
   PushTaskAttemptRequest,
   RepoBranchStatus,
   AbortConflictsRequest,
+  ConflictResolution,
+  ResolveConflictsRequest,
+  RestackResult,
   Session,
   Workspace,
+  UsageReport,
 } from 'shared/types';
 import type { WorkspaceWithSession } from '@/types/attempt';
 import { createWorkspaceWithSession } from '@/types/attempt';
@@ -469,18 +482,10 @@ export const tasksApi = {
   },
 };
 
-// Task Comment type (not yet in shared/types.ts - defined locally)
-export interface TaskComment {
-  id: string;
-  task_id: string;
-  content: string;
-  author: string;
-  created_at: string;
-}
-
 export interface CreateTaskCommentPayload {
   content: string;
   author: string;
+  parent_comment_id?: string | null;
 }
 
 // Task Comments API
@@ -500,6 +505,41 @@ export const taskCommentsApi = {
     });
     return handleApiResponse<TaskComment>(response);
   },
+
+  update: async (
+    taskId: string,
+    commentId: string,
+    content: string
+  ): Promise<TaskComment> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/comments/${commentId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ content }),
+      }
+    );
+    return handleApiResponse<TaskComment>(response);
+  },
+
+  delete: async (taskId: string, commentId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/comments/${commentId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getEdits: async (
+    taskId: string,
+    commentId: string
+  ): Promise<TaskCommentEdit[]> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/comments/${commentId}/edits`
+    );
+    return handleApiResponse<TaskCommentEdit[]>(response);
+  },
 };
 
 // Task History API
@@ -508,6 +548,39 @@ export const taskHistoryApi = {
     const response = await makeRequest(`/api/tasks/${taskId}/history`);
     return handleApiResponse<TaskHistory[]>(response);
   },
+  getProjectActivity: async (
+    projectId: string,
+    filters: Partial<TaskActivityFilters> = {},
+    limit?: number,
+    offset?: number
+  ): Promise<TaskActivity[]> => {
+    const params = new URLSearchParams();
+    if (filters.task_id) params.set('task_id', filters.task_id);
+    if (filters.actor_type) params.set('actor_type', filters.actor_type);
+    if (filters.changed_by) params.set('changed_by', filters.changed_by);
+    if (filters.field_changed) params.set('field', filters.field_changed);
+    if (filters.since) params.set('since', filters.since);
+    if (filters.until) params.set('until', filters.until);
+    if (limit !== undefined) params.set('limit', String(limit));
+    if (offset !== undefined) params.set('offset', String(offset));
+    const response = await makeRequest(
+      `/api/projects/${projectId}/activity?${params.toString()}`
+    );
+    return handleApiResponse<TaskActivity[]>(response);
+  },
+};
+
+// Token Usage API
+export const usageApi = {
+  getByTaskId: async (taskId: string): Promise<UsageReport> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/usage`);
+    return handleApiResponse<UsageReport>(response);
+  },
+
+  getByProjectId: async (projectId: string): Promise<UsageReport> => {
+    const response = await makeRequest(`/api/projects/${projectId}/usage`);
+    return handleApiResponse<UsageReport>(response);
+  },
 };
 
 // Agent Metadata API
@@ -697,6 +770,16 @@ export const attemptsApi = {
     return handleApiResponseAsResult<void, GitOperationError>(response);
   },
 
+  restack: async (attemptId: string): Promise<RestackResult[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/restack`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<RestackResult[]>(response);
+  },
+
   change_target_branch: async (
     attemptId: string,
     data: ChangeTargetBranchRequest
@@ -742,6 +825,29 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  resolveConflictsWithAgent: async (
+    attemptId: string,
+    data: ResolveConflictsRequest
+  ): Promise<ConflictResolution> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ConflictResolution>(response);
+  },
+
+  getConflictResolutions: async (
+    attemptId: string
+  ): Promise<ConflictResolution[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolutions`
+    );
+    return handleApiResponse<ConflictResolution[]>(response);
+  },
+
   createPR: async (
     attemptId: string,
     data: CreateGitHubPrRequest
@@ -810,6 +916,31 @@ export const attemptsApi = {
     );
     return handleApiResponse<PrCommentsResponse>(response);
   },
+
+  addressPrComments: async (
+    attemptId: string,
+    data: AddressPrCommentsRequest
+  ): Promise<Result<ReviewFollowUp, AddressPrCommentsError>> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/pr/comments/address`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponseAsResult<ReviewFollowUp, AddressPrCommentsError>(
+      response
+    );
+  },
+
+  getReviewFollowUps: async (
+    attemptId: string
+  ): Promise<ReviewFollowUpWithComments[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/pr/review-follow-ups`
+    );
+    return handleApiResponse<ReviewFollowUpWithComments[]>(response);
+  },
 };
 
 // Execution Process APIs
@@ -886,6 +1017,17 @@ export const repoApi = {
     });
     return handleApiResponse<Repo>(response);
   },
+
+  updateForge: async (
+    repoId: string,
+    data: UpdateRepoForgeRequest
+  ): Promise<Repo> => {
+    const response = await makeRequest(`/api/repos/${repoId}/forge`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Repo>(response);
+  },
 };
 
 // Config APIs (backwards compatible)
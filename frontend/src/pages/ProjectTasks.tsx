@@ -807,6 +807,9 @@ export function ProjectTasks() {
           parent_workspace_id: task.parent_workspace_id,
           image_ids: null,
           assignee: task.assignee, // Preserve existing assignee when dragging
+          priority: null,
+          due_date: null,
+          estimate_hours: null,
         });
       } catch (err) {
         console.error('Failed to update task status:', err);
@@ -1,7 +1,7 @@
 import { useCallback, useEffect, useMemo, useState } from 'react';
 import { useSearchParams } from 'react-router-dom';
 import { useTranslation } from 'react-i18next';
-import { useQueryClient } from '@tanstack/react-query';
+import { useQuery, useQueryClient } from '@tanstack/react-query';
 import { isEqual } from 'lodash';
 import {
   Card,
@@ -29,15 +29,25 @@ import { useScriptPlaceholders } from '@/hooks/useScriptPlaceholders';
 import { CopyFilesField } from '@/components/projects/CopyFilesField';
 import { AutoExpandingTextarea } from '@/components/ui/auto-expanding-textarea';
 import { RepoPickerDialog } from '@/components/dialogs/shared/RepoPickerDialog';
-import { projectsApi } from '@/lib/api';
+import { projectsApi, usageApi } from '@/lib/api';
 import { repoBranchKeys } from '@/hooks/useRepoBranches';
-import type { Project, ProjectRepo, Repo, UpdateProject } from 'shared/types';
+import type {
+  MergeStrategy,
+  Project,
+  ProjectRepo,
+  Repo,
+  UpdateProject,
+} from 'shared/types';
 
 interface ProjectFormState {
   name: string;
   dev_script: string;
   dev_script_working_dir: string;
   default_agent_working_dir: string;
+  verification_command: string;
+  verification_working_dir: string;
+  verification_max_retries: string;
+  usage_budget_usd: string;
 }
 
 interface RepoScriptsFormState {
@@ -45,14 +55,23 @@ interface RepoScriptsFormState {
   parallel_setup_script: boolean;
   cleanup_script: string;
   copy_files: string;
+  merge_strategy: MergeStrategy;
+  rebase_before_merge: boolean;
 }
 
+const MERGE_STRATEGIES: MergeStrategy[] = ['squash', 'rebase', 'merge_commit'];
+
 function projectToFormState(project: Project): ProjectFormState {
   return {
     name: project.name,
     dev_script: project.dev_script ?? '',
     dev_script_working_dir: project.dev_script_working_dir ?? '',
     default_agent_working_dir: project.default_agent_working_dir ?? '',
+    verification_command: project.verification_command ?? '',
+    verification_working_dir: project.verification_working_dir ?? '',
+    verification_max_retries: String(project.verification_max_retries),
+    usage_budget_usd:
+      project.usage_budget_usd === null ? '' : String(project.usage_budget_usd),
   };
 }
 
@@ -64,6 +83,8 @@ function projectRepoToScriptsFormState(
     parallel_setup_script: projectRepo?.parallel_setup_script ?? false,
     cleanup_script: projectRepo?.cleanup_script ?? '',
     copy_files: projectRepo?.copy_files ?? '',
+    merge_strategy: projectRepo?.merge_strategy ?? 'squash',
+    rebase_before_merge: projectRepo?.rebase_before_merge ?? false,
   };
 }
 
@@ -86,6 +107,13 @@ export function ProjectSettings() {
   );
   const [selectedProject, setSelectedProject] = useState<Project | null>(null);
 
+  // Spend so far, shown next to the budget
+  const { data: projectUsage } = useQuery({
+    queryKey: ['projectUsage', selectedProjectId],
+    queryFn: () => usageApi.getByProjectId(selectedProjectId),
+    enabled: !!selectedProjectId,
+  });
+
   // Form state
   const [draft, setDraft] = useState<ProjectFormState | null>(null);
   const [saving, setSaving] = useState(false);
@@ -397,6 +425,11 @@ export function ProjectSettings() {
         dev_script_working_dir: draft.dev_script_working_dir.trim() || null,
         default_agent_working_dir:
           draft.default_agent_working_dir.trim() || null,
+        verification_command: draft.verification_command.trim(),
+        verification_working_dir: draft.verification_working_dir.trim(),
+        verification_max_retries:
+          Number.parseInt(draft.verification_max_retries, 10) || 0,
+        usage_budget_usd: Number.parseFloat(draft.usage_budget_usd) || 0,
       };
 
       updateProject.mutate({
@@ -426,6 +459,8 @@ export function ProjectSettings() {
           cleanup_script: scriptsDraft.cleanup_script.trim() || null,
           copy_files: scriptsDraft.copy_files.trim() || null,
           parallel_setup_script: scriptsDraft.parallel_setup_script,
+          merge_strategy: scriptsDraft.merge_strategy,
+          rebase_before_merge: scriptsDraft.rebase_before_merge,
         }
       );
       setSelectedProjectRepo(updatedRepo);
@@ -630,6 +665,92 @@ export function ProjectSettings() {
                 </p>
               </div>
 
+              <div className="space-y-2">
+                <Label htmlFor="verification-command">
+                  {t('settings.projects.scripts.verification.label')}
+                </Label>
+                <Input
+                  id="verification-command"
+                  value={draft.verification_command}
+                  onChange={(e) =>
+                    updateDraft({ verification_command: e.target.value })
+                  }
+                  placeholder={t(
+                    'settings.projects.scripts.verification.placeholder'
+                  )}
+                  className="font-mono"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.scripts.verification.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="verification-working-dir">
+                  {t('settings.projects.scripts.verificationWorkingDir.label')}
+                </Label>
+                <Input
+                  id="verification-working-dir"
+                  value={draft.verification_working_dir}
+                  onChange={(e) =>
+                    updateDraft({ verification_working_dir: e.target.value })
+                  }
+                  placeholder={t(
+                    'settings.projects.scripts.verificationWorkingDir.placeholder'
+                  )}
+                  className="font-mono"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.scripts.verificationWorkingDir.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="verification-max-retries">
+                  {t('settings.projects.scripts.verificationMaxRetries.label')}
+                </Label>
+                <Input
+                  id="verification-max-retries"
+                  type="number"
+                  min={0}
+                  value={draft.verification_max_retries}
+                  onChange={(e) =>
+                    updateDraft({ verification_max_retries: e.target.value })
+                  }
+                  className="w-32"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.scripts.verificationMaxRetries.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="usage-budget">
+                  {t('settings.projects.general.usageBudget.label')}
+                </Label>
+                <Input
+                  id="usage-budget"
+                  type="number"
+                  min={0}
+                  step="0.01"
+                  value={draft.usage_budget_usd}
+                  onChange={(e) =>
+                    updateDraft({ usage_budget_usd: e.target.value })
+                  }
+                  placeholder={t(
+                    'settings.projects.general.usageBudget.placeholder'
+                  )}
+                  className="w-32"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {projectUsage
+                    ? t('settings.projects.general.usageBudget.spent', {
+                        cost: projectUsage.totals.cost_usd.toFixed(2),
+                      })
+                    : t('settings.projects.general.usageBudget.helper')}
+                </p>
+              </div>
+
               {/* Save Button */}
               <div className="flex items-center justify-between pt-4 border-t">
                 {hasUnsavedProjectChanges ? (
@@ -891,6 +1012,65 @@ export function ProjectSettings() {
                         </p>
                       </div>
 
+                      <div className="space-y-2">
+                        <Label htmlFor="merge-strategy">
+                          {t('settings.projects.scripts.mergeStrategy.label')}
+                        </Label>
+                        <Select
+                          value={scriptsDraft.merge_strategy}
+                          onValueChange={(value) =>
+                            updateScriptsDraft({
+                              merge_strategy: value as MergeStrategy,
+                            })
+                          }
+                        >
+                          <SelectTrigger id="merge-strategy">
+                            <SelectValue />
+                          </SelectTrigger>
+                          <SelectContent>
+                            {MERGE_STRATEGIES.map((strategy) => (
+                              <SelectItem key={strategy} value={strategy}>
+                                {t(
+                                  `settings.projects.scripts.mergeStrategy.options.${strategy}`
+                                )}
+                              </SelectItem>
+                            ))}
+                          </SelectContent>
+                        </Select>
+                        <p className="text-sm text-muted-foreground">
+                          {t('settings.projects.scripts.mergeStrategy.helper')}
+                        </p>
+
+                        <div className="flex items-center space-x-2 pt-2">
+                          <Checkbox
+                            id="rebase-before-merge"
+                            checked={
+                              scriptsDraft.merge_strategy === 'rebase' ||
+                              scriptsDraft.rebase_before_merge
+                            }
+                            onCheckedChange={(checked) =>
+                              updateScriptsDraft({
+                                rebase_before_merge: checked === true,
+                              })
+                            }
+                            disabled={scriptsDraft.merge_strategy === 'rebase'}
+                          />
+                          <Label
+                            htmlFor="rebase-before-merge"
+                            className="text-sm font-normal cursor-pointer"
+                          >
+                            {t(
+                              'settings.projects.scripts.mergeStrategy.rebaseBeforeMergeLabel'
+                            )}
+                          </Label>
+                        </div>
+                        <p className="text-sm text-muted-foreground pl-6">
+                          {t(
+                            'settings.projects.scripts.mergeStrategy.rebaseBeforeMergeHelper'
+                          )}
+                        </p>
+                      </div>
+
                       {/* Scripts Save Buttons */}
                       <div className="flex items-center justify-between pt-4 border-t">
                         {hasUnsavedScriptsChanges ? (
@@ -147,6 +147,7 @@ async function main() {
   const args = process.argv.slice(2);
   const isMcpMode = args.includes("--mcp");
   const isReviewMode = args[0] === "review";
+  const isApprovalsMode = args[0] === "approvals";
 
   // Non-blocking update check (skip in MCP mode, local dev mode, and when R2 URL not configured)
   const hasValidR2Url = !R2_BASE_URL.startsWith("__");
@@ -186,6 +187,15 @@ async function main() {
         process.exit(1);
       });
     });
+  } else if (isApprovalsMode) {
+    await extractAndRun("vibe-kanban", (bin) => {
+      const proc = spawn(bin, args, { stdio: "inherit" });
+      proc.on("exit", (c) => process.exit(c || 0));
+      proc.on("error", (e) => {
+        console.error("Approvals CLI error:", e.message);
+        process.exit(1);
+      });
+    });
   } else {
     const modeLabel = LOCAL_DEV_MODE ? " (local dev)" : "";
     console.log(`Starting vibe-kanban v${CLI_VERSION}${modeLabel}...`);
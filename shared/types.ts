@@ -11,33 +11,222 @@ export type SharedTaskResponse = { task: SharedTask, user: UserData | null, };
 
 export type AssigneesQuery = { project_id: string, };
 
-export type SharedTask = { id: string, organization_id: string, project_id: string, creator_user_id: string | null, assignee_user_id: string | null, deleted_by_user_id: string | null, title: string, description: string | null, status: TaskStatus, deleted_at: string | null, shared_at: string | null, created_at: string, updated_at: string, };
+export type SharedTask = { id: string, organization_id: string, project_id: string, creator_user_id: string | null, assignee_user_id: string | null, deleted_by_user_id: string | null, title: string, description: string | null, status: TaskStatus, 
+/**
+ * Name of the project workflow state on the sharing instance, if any
+ */
+workflow_state: string | null, deleted_at: string | null, shared_at: string | null, created_at: string, updated_at: string, };
 
 export type UserData = { user_id: string, first_name: string | null, last_name: string | null, username: string | null, };
 
-export type Project = { id: string, name: string, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, 
+/**
+ * Run after every successful coding agent turn, e.g. `cargo test`
+ */
+verification_command: string | null, 
+/**
+ * Directory to run the verification command from, relative to the workspace
+ * root. Defaults to the agent working directory.
+ */
+verification_working_dir: string | null, 
+/**
+ * Automatic fix follow-ups sent while verification keeps failing
+ */
+verification_max_retries: number, 
+/**
+ * Spend in USD after which no further coding agent turns are started
+ */
+usage_budget_usd: number | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, };
+export type UpdateProject = { name: string | null, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, 
+/**
+ * Left unchanged when omitted; an empty string clears it
+ */
+verification_command?: string | null, 
+/**
+ * Left unchanged when omitted; an empty string clears it
+ */
+verification_working_dir?: string | null, 
+/**
+ * Left unchanged when omitted
+ */
+verification_max_retries?: number | null, 
+/**
+ * Left unchanged when omitted; zero or less removes the budget
+ */
+usage_budget_usd?: number | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
-export type Repo = { id: string, path: string, name: string, display_name: string, created_at: Date, updated_at: Date, };
+export type Repo = { id: string, path: string, name: string, display_name: string, 
+/**
+ * Forge hosting the repo; detected from the remote URL when None
+ */
+forge: ForgeKind | null, 
+/**
+ * Base URL of a self-hosted forge; derived from the remote URL when None
+ */
+forge_url: string | null, created_at: Date, updated_at: Date, };
 
-export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, };
+export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, setup_script_options: ScriptOptions, cleanup_script_options: ScriptOptions, 
+/**
+ * Used when the project's dev server script runs in this repo
+ */
+dev_script_options: ScriptOptions, 
+/**
+ * How direct merges land on the target branch
+ */
+merge_strategy: MergeStrategy, 
+/**
+ * Rebase onto the target branch first when it has moved ahead, instead of
+ * refusing the merge
+ */
+rebase_before_merge: boolean, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
 
-export type UpdateProjectRepo = { setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, };
+export type UpdateProjectRepo = { setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, 
+/**
+ * Left unchanged when omitted
+ */
+setup_script_options?: ScriptOptions, 
+/**
+ * Left unchanged when omitted
+ */
+cleanup_script_options?: ScriptOptions, 
+/**
+ * Left unchanged when omitted
+ */
+dev_script_options?: ScriptOptions, 
+/**
+ * Left unchanged when omitted
+ */
+merge_strategy?: MergeStrategy, 
+/**
+ * Left unchanged when omitted
+ */
+rebase_before_merge?: boolean, };
+
+export type WorkflowState = { id: string, project_id: string, name: string, 
+/**
+ * Built-in status this state counts as
+ */
+category: TaskStatus, position: bigint, created_at: string, };
+
+export type WorkflowTransition = { from_state: string, to_state: string, };
+
+export type ProjectWorkflow = { project_id: string, 
+/**
+ * States in board order. Empty when the project uses the built-in statuses.
+ */
+states: Array<WorkflowState>, 
+/**
+ * Allowed moves. When empty, every move between states is allowed.
+ */
+transitions: Array<WorkflowTransition>, };
+
+export type CreateWorkflowState = { name: string, category: TaskStatus, };
+
+export type SetProjectWorkflow = { states: Array<CreateWorkflowState>, transitions: Array<WorkflowTransition>, };
+
+export type ProjectPipeline = { id: string, project_id: string, name: string, definition: string, created_at: string, updated_at: string, };
+
+export type CreateProjectPipeline = { name: string, definition: string, };
+
+export type UpdateProjectPipeline = { name: string | null, definition: string | null, };
+
+export enum PipelineRunStatus { running = "running", completed = "completed", failed = "failed", cancelled = "cancelled" }
+
+export type PipelineRun = { id: string, session_id: string, 
+/**
+ * None once the pipeline has been deleted
+ */
+pipeline_id: string | null, name: string, 
+/**
+ * TOML source the run was started with
+ */
+definition: string, 
+/**
+ * Coding agent used by agent steps
+ */
+executor_profile_id: ExecutorProfileId, status: PipelineRunStatus, created_at: string, completed_at: string | null, };
+
+export type PipelineStepRun = { id: string, pipeline_run_id: string, step_id: string, execution_process_id: string, 
+/**
+ * The step run whose transition started this one; None for the first step
+ */
+triggered_by_id: string | null, status: ExecutionProcessStatus, exit_code: bigint | null, created_at: string, };
+
+export type PipelineRunWithSteps = { id: string, session_id: string, 
+/**
+ * None once the pipeline has been deleted
+ */
+pipeline_id: string | null, name: string, 
+/**
+ * TOML source the run was started with
+ */
+definition: string, 
+/**
+ * Coding agent used by agent steps
+ */
+executor_profile_id: ExecutorProfileId, status: PipelineRunStatus, created_at: string, completed_at: string | null, steps: Array<PipelineStepRun>, };
+
+export enum ApprovalRuleAction { allow = "allow", deny = "deny", ask = "ask" }
+
+export type ApprovalRule = { id: string, project_id: string, action: ApprovalRuleAction, 
+/**
+ * Tool name as reported by the agent, compared case-insensitively
+ */
+tool_name: string | null, 
+/**
+ * Regex searched in the command a shell tool runs
+ */
+command_regex: string | null, 
+/**
+ * Glob matched against the files a tool reads or edits
+ */
+path_glob: string | null, executor: BaseCodingAgent | null, description: string | null, created_at: string, updated_at: string, };
+
+export type CreateApprovalRule = { action: ApprovalRuleAction, tool_name: string | null, command_regex: string | null, path_glob: string | null, executor: BaseCodingAgent | null, description: string | null, };
+
+export type UpdateApprovalRule = { action: ApprovalRuleAction, tool_name: string | null, command_regex: string | null, path_glob: string | null, executor: BaseCodingAgent | null, description: string | null, };
 
-export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
+export enum ApprovalDecisionStatus { approved = "approved", denied = "denied", timed_out = "timed_out" }
 
-export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
+export enum ApprovalDecider { rule = "rule", user = "user", timeout = "timeout" }
 
-export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, created_at: Date, updated_at: Date, };
+export type ApprovalDecision = { id: string, 
+/**
+ * Id of the approval request; the tool call id when a rule decided it
+ */
+approval_id: string, execution_process_id: string, tool_name: string, tool_input: JsonValue, status: ApprovalDecisionStatus, reason: string | null, decided_by: ApprovalDecider, 
+/**
+ * The matching rule; None for manual decisions or once the rule is deleted
+ */
+rule_id: string | null, created_at: string, };
+
+export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, 
+/**
+ * Commit of the parent workspace's branch this repo was last rebased onto,
+ * while the workspace is stacked
+ */
+stack_base_commit: string | null, };
+
+export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, stack_base_commit?: string, };
+
+export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, 
+/**
+ * Forge hosting the repo; detected from the remote URL when None
+ */
+forge: ForgeKind | null, 
+/**
+ * Base URL of a self-hosted forge; derived from the remote URL when None
+ */
+forge_url: string | null, created_at: Date, updated_at: Date, };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -45,27 +234,123 @@ export type CreateTag = { tag_name: string, content: string, };
 
 export type UpdateTag = { tag_name: string | null, content: string | null, };
 
+export enum VerificationStatus { passed = "passed", failed = "failed" }
+
+export enum ConflictResolutionStatus { running = "running", resolved = "resolved", aborted = "aborted", failed = "failed" }
+
+export type ConflictResolution = { id: string, workspace_id: string, repo_id: string, 
+/**
+ * Agent turn of the current round; the last one once finished
+ */
+execution_process_id: string | null, 
+/**
+ * Files that had conflicts when the current round started
+ */
+conflicted_files: string[], 
+/**
+ * Agent turns used so far; a continued rebase can stop at a later commit
+ */
+rounds: number, status: ConflictResolutionStatus, 
+/**
+ * How the resolution ended, e.g. the files still holding conflict markers
+ */
+message: string | null, created_at: string, completed_at: string | null, };
+
+export enum ReviewFollowUpStatus { running = "running", pushed = "pushed", no_changes = "no_changes", failed = "failed" }
+
+export type ReviewCommentType = "general" | "review";
+
+export type ReviewFollowUp = { id: string, workspace_id: string, repo_id: string, pr_number: number, execution_process_id: string | null, 
+/**
+ * Branch head before the turn
+ */
+base_commit: string | null, 
+/**
+ * Whether each addressed comment gets a reply naming the commit
+ */
+reply_to_comments: boolean, status: ReviewFollowUpStatus, 
+/**
+ * Commit pushed for the comments
+ */
+commit_sha: string | null, 
+/**
+ * How the follow-up ended, e.g. why the push failed
+ */
+message: string | null, created_at: string, completed_at: string | null, };
+
+export type ReviewFollowUpComment = { id: string, review_follow_up_id: string, 
+/**
+ * Id of the comment on the forge
+ */
+comment_id: string, comment_type: ReviewCommentType, url: string, path: string | null, line: number | null, commit_sha: string | null, replied_at: string | null, created_at: string, };
+
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
-export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, assignee: string | null, 
+export type TaskPriority = "low" | "medium" | "high" | "urgent";
+
+export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, 
+/**
+ * Project workflow state within `status`, when the project defines a workflow
+ */
+workflow_state: string | null, parent_workspace_id: string | null, shared_task_id: string | null, assignee: string | null, priority: TaskPriority, due_date: string | null, 
+/**
+ * Estimated effort in hours
+ */
+estimate_hours: number | null, 
 /**
  * JSON-serialized array of AgentMetadataEntry for tracking agent activity
  */
 agent_metadata: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, assignee: string | null, 
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, 
+/**
+ * Result of the latest verified agent turn, if the project verifies turns
+ */
+last_verification_status: VerificationStatus | null, executor: string, 
+/**
+ * True if any task this task depends on is not yet done
+ */
+blocked: boolean, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, 
+/**
+ * Project workflow state within `status`, when the project defines a workflow
+ */
+workflow_state: string | null, parent_workspace_id: string | null, shared_task_id: string | null, assignee: string | null, priority: TaskPriority, due_date: string | null, 
+/**
+ * Estimated effort in hours
+ */
+estimate_hours: number | null, 
 /**
  * JSON-serialized array of AgentMetadataEntry for tracking agent activity
  */
 agent_metadata: string | null, created_at: string, updated_at: string, };
 
-export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
+export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, stacked_on: Workspace | null, stacked_children: Array<Workspace>, };
 
 export type TaskRelationshipsSimple = { current_task: Task, parent_task: Task | null, children: Array<Task>, };
 
-export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, shared_task_id: string | null, };
+export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, shared_task_id: string | null, 
+/**
+ * Defaults to medium
+ */
+priority: TaskPriority | null, due_date: string | null, 
+/**
+ * Estimated effort in hours
+ */
+estimate_hours: number | null, };
 
-export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, assignee: string | null, };
+export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, assignee: string | null, priority: TaskPriority | null, due_date: string | null, 
+/**
+ * Estimated effort in hours
+ */
+estimate_hours: number | null, 
+/**
+ * Remove the due date. Takes precedence over `due_date`.
+ */
+clear_due_date?: boolean, 
+/**
+ * Remove the estimate. Takes precedence over `estimate_hours`.
+ */
+clear_estimate?: boolean, };
 
 export type AgentMetadataEntry = { 
 /**
@@ -97,25 +382,129 @@ export type CreateScratch = { payload: ScratchPayload, };
 
 export type UpdateScratch = { payload: ScratchPayload, };
 
-export type TaskHistory = { id: string, task_id: string, field_changed: string, old_value: string | null, new_value: string | null, changed_by: string, changed_at: string, };
+export type TaskComment = { id: string, task_id: string, 
+/**
+ * The comment this is a reply to, if any
+ */
+parent_comment_id: string | null, content: string, author: string, author_type: TaskActorType, created_at: string, 
+/**
+ * When the comment was last edited
+ */
+edited_at: string | null, };
+
+export type CreateTaskComment = { task_id: string, content: string, author: string, parent_comment_id: string | null, };
+
+export type UpdateTaskComment = { content: string, };
+
+export type TaskCommentEdit = { id: string, comment_id: string, previous_content: string, edited_by: string, edited_at: string, };
+
+export type MentionType = "user" | "agent";
+
+export type TaskCommentMention = { comment_id: string, 
+/**
+ * The mentioned name as it appears on the task
+ */
+mention: string, mention_type: MentionType, };
+
+export type TaskHistory = { id: string, task_id: string, field_changed: string, 
+/**
+ * Previous value. Empty when the change is stored as a diff.
+ */
+old_value: string | null, 
+/**
+ * New value. Empty when the change is stored as a diff.
+ */
+new_value: string | null, changed_by: string, actor_type: TaskActorType, 
+/**
+ * Unified diff for long text fields such as the description
+ */
+diff: string | null, changed_at: string, };
+
+export type CreateTaskHistory = { task_id: string, field_changed: string, old_value: string | null, new_value: string | null, changed_by: string, actor_type: TaskActorType, diff: string | null, };
+
+export type TaskActorType = "user" | "agent" | "system" | "api_token";
 
-export type CreateTaskHistory = { task_id: string, field_changed: string, old_value: string | null, new_value: string | null, changed_by: string, };
+export type TaskActor = { actor_type: TaskActorType, name: string, };
+
+export type TaskActivity = { task_title: string, id: string, task_id: string, field_changed: string, 
+/**
+ * Previous value. Empty when the change is stored as a diff.
+ */
+old_value: string | null, 
+/**
+ * New value. Empty when the change is stored as a diff.
+ */
+new_value: string | null, changed_by: string, actor_type: TaskActorType, 
+/**
+ * Unified diff for long text fields such as the description
+ */
+diff: string | null, changed_at: string, };
+
+export type TaskActivityFilters = { task_id: string | null, actor_type: TaskActorType | null, changed_by: string | null, field_changed: string | null, since: string | null, until: string | null, };
+
+export type TaskDependency = { id: string, task_id: string, depends_on_task_id: string, created_at: string, };
+
+export type CreateTaskDependency = { depends_on_task_id: string, };
+
+export type TaskDependencies = { task_id: string, 
+/**
+ * Tasks that must be done before this task can start
+ */
+blocked_by: Array<Task>, 
+/**
+ * Tasks waiting on this task
+ */
+blocks: Array<Task>, 
+/**
+ * True if any task in `blocked_by` is not done
+ */
+blocked: boolean, };
 
 export type Webhook = { id: string, project_id: string, url: string, secret: string, 
 /**
  * JSON array of event types, e.g., ["task_created", "task_updated"]
  */
-events: string, is_active: boolean, created_at: string, updated_at: string, };
+events: string, is_active: boolean, 
+/**
+ * MiniJinja template for the request body. The generic JSON envelope is sent when unset.
+ */
+payload_template: string | null, 
+/**
+ * JSON object of extra request headers
+ */
+headers: string | null, 
+/**
+ * JSON-serialized WebhookFilters
+ */
+filters: string | null, 
+/**
+ * Deliveries in a row that failed after exhausting their retries
+ */
+consecutive_failures: bigint, created_at: string, updated_at: string, };
+
+export type WebhookFilters = { 
+/**
+ * Only tasks assigned to one of these users
+ */
+assignees: Array<string>, 
+/**
+ * Only tasks currently in one of these statuses
+ */
+to_statuses: Array<TaskStatus>, 
+/**
+ * Only task updates that moved the task out of one of these statuses
+ */
+from_statuses: Array<TaskStatus>, };
 
 export type CreateWebhook = { project_id: string, url: string, secret: string, 
 /**
  * List of event types to subscribe to
  */
-events: Array<WebhookEvent>, };
+events: Array<WebhookEvent>, payload_template: string | null, headers: { [key in string]?: string } | null, filters: WebhookFilters | null, };
 
-export type UpdateWebhook = { url: string | null, secret: string | null, events: Array<WebhookEvent> | null, is_active: boolean | null, };
+export type UpdateWebhook = { url: string | null, secret: string | null, events: Array<WebhookEvent> | null, is_active: boolean | null, payload_template: string | null, headers: { [key in string]?: string } | null, filters: WebhookFilters | null, };
 
-export type WebhookEvent = "task_created" | "task_updated" | "task_completed" | "workspace_started";
+export type WebhookEvent = "task_created" | "task_updated" | "task_completed" | "workspace_started" | "execution_completed" | "execution_failed" | "approval_requested" | "approval_responded" | "pr_created" | "pr_merged" | "pr_closed" | "task_comment_added" | "task_comment_mentioned" | "task_assigned";
 
 export type WebhookDelivery = { id: string, webhook_id: string, event_type: string, payload: string, status: DeliveryStatus, attempts: bigint, last_error: string | null, next_retry_at: string | null, created_at: string, delivered_at: string | null, };
 
@@ -123,6 +512,32 @@ export type CreateWebhookDelivery = { webhook_id: string, event_type: string, pa
 
 export type DeliveryStatus = "pending" | "success" | "failed" | "retrying";
 
+export type WebhookHistoryAction = "enabled" | "disabled" | "auto_disabled";
+
+export type WebhookHistory = { id: string, webhook_id: string, action: WebhookHistoryAction, reason: string | null, created_at: string, };
+
+export type CreateWebhookHistory = { webhook_id: string, action: WebhookHistoryAction, reason: string | null, };
+
+export type ApiTokenScope = "tasks:read" | "tasks:write" | "workspaces:start" | "approvals:respond";
+
+export type ApiToken = { id: string, project_id: string, name: string, 
+/**
+ * First characters of the token, to tell tokens apart
+ */
+token_prefix: string, 
+/**
+ * JSON array of scopes, e.g., ["tasks:read", "tasks:write"]
+ */
+scopes: string, expires_at: string | null, last_used_at: string | null, created_at: string, };
+
+export type CreateApiToken = { project_id: string, name: string, token_hash: string, token_prefix: string, scopes: Array<ApiTokenScope>, expires_at: string | null, };
+
+export type InboundWebhook = { id: string, project_id: string, name: string, is_active: boolean, created_at: string, updated_at: string, };
+
+export type InboundWebhookWithSecret = { id: string, project_id: string, name: string, is_active: boolean, created_at: string, updated_at: string, secret: string, };
+
+export type CreateInboundWebhook = { project_id: string, name: string, secret: string, };
+
 export type Image = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export type CreateImage = { file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, };
@@ -132,11 +547,19 @@ export type Workspace = { id: string, task_id: string, container_ref: string | n
  * Workspace mode: "worktree" (default) or "branch" (ORCHESTRATOR_MANAGED)
  * In "branch" mode, work happens directly in the main repo's checked-out branch
  */
-workspace_mode: string, created_at: string, updated_at: string, };
+workspace_mode: string, created_at: string, updated_at: string, 
+/**
+ * Workspace whose branch this one builds on, until that workspace has merged
+ */
+stacked_on_workspace_id: string | null, };
 
 export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
 
-export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
+export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, exit_reason: ExecutionProcessExitReason | null, 
+/**
+ * Isolation and resource limits the process ran with, if any
+ */
+sandbox: SandboxPolicy | null, 
 /**
  * dropped: true if this process is excluded from the current
  * history view (due to restore/trimming). Hidden from logs/timeline;
@@ -146,19 +569,43 @@ dropped: boolean, started_at: string, completed_at: string | null, created_at: s
 
 export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "pipelinestep";
+
+export type ExecutionProcessExitReason = "timedout" | "budgetexceeded";
+
+export type ExecutionProcessUsage = { id: string, execution_process_id: string, executor: string, variant: string | null, model: string | null, input_tokens: number, output_tokens: number, cache_read_tokens: number, cache_write_tokens: number, 
+/**
+ * None when neither the price table nor the agent knew the cost
+ */
+cost_usd: number | null, created_at: string, };
+
+export type UsageTotals = { input_tokens: number, output_tokens: number, cache_read_tokens: number, cache_write_tokens: number, cost_usd: number, 
+/**
+ * Usage reports without a known cost, which are missing from `cost_usd`
+ */
+unpriced_reports: number, };
+
+export type ExecutorUsage = { executor: string, variant: string | null, input_tokens: number, output_tokens: number, cache_read_tokens: number, cache_write_tokens: number, cost_usd: number, 
+/**
+ * Usage reports without a known cost, which are missing from `cost_usd`
+ */
+unpriced_reports: number, };
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
-export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
+export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, merge_strategy: MergeStrategy, created_at: string, };
 
 export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
 
 export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
-export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
+export type ForgeKind = "github" | "gitlab" | "gitea";
+
+export type MergeStrategy = "squash" | "rebase" | "merge_commit";
+
+export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, forge: ForgeKind, };
 
 export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
 
@@ -236,14 +683,32 @@ export type ListProjectsResponse = { projects: Array<RemoteProject>, };
 
 export type RemoteProjectMembersResponse = { organization_id: string, members: Array<OrganizationMemberWithProfile>, };
 
+export type PendingApproval = { id: string, tool_name: string, tool_input: JsonValue, tool_call_id: string, execution_process_id: string, created_at: string, timeout_at: string, task_id: string, task_title: string, project_id: string, };
+
 export type CreateRemoteProjectRequest = { organization_id: string, name: string, };
 
 export type LinkToExistingRequest = { remote_project_id: string, };
 
+export type UsageReport = { totals: UsageTotals, 
+/**
+ * Usage per executor profile, most expensive first
+ */
+by_executor: Array<ExecutorUsage>, 
+/**
+ * Budget of the project the usage counts towards, if it has one
+ */
+budget_usd: number | null, };
+
 export type RegisterRepoRequest = { path: string, display_name: string | null, };
 
 export type InitRepoRequest = { parent_path: string, folder_name: string, };
 
+export type UpdateRepoForgeRequest = { forge: ForgeKind | null, 
+/**
+ * Base URL of a self-hosted forge, e.g. `https://git.example.com`
+ */
+forge_url: string | null, };
+
 export type TagSearchParams = { search: string | null, };
 
 export type TokenResponse = { access_token: string, expires_at: string | null, };
@@ -252,7 +717,11 @@ export type UserSystemInfo = { config: Config, analytics_user_id: string, login_
 /**
  * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
  */
-capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, 
+/**
+ * Profiles to fall back to when a coding agent fails for a transient reason
+ */
+fallbacks: Array<ExecutorFallbackPolicy>, };
 
 export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
 
@@ -272,6 +741,12 @@ export type CurrentUserResponse = { user_id: string, };
 
 export type CreateFollowUpAttempt = { prompt: string, variant: string | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
+export type StartPipelineRunRequest = { pipeline_id: string, 
+/**
+ * Coding agent for agent steps. Defaults to the one last used in the session.
+ */
+executor_profile_id: ExecutorProfileId | null, };
+
 export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
 
 export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
@@ -298,6 +773,8 @@ export type BulkUpdateTasksResponse = { updated_tasks: Array<Task>, count: numbe
 
 export type GetAgentMetadataResponse = { task_id: string, metadata: Array<AgentMetadataEntry>, count: number, };
 
+export type UpdateTaskWorkflowState = { state: string, };
+
 
 export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
 
@@ -313,21 +790,112 @@ events: Array<WebhookEvent>,
 /**
  * Optional secret for signing payloads. Auto-generated if not provided.
  */
-secret: string | null, };
+secret: string | null, 
+/**
+ * Optional MiniJinja template for the request body, e.g. for Slack-style endpoints
+ */
+payload_template: string | null, 
+/**
+ * Extra headers sent with every delivery
+ */
+headers: { [key in string]?: string } | null, 
+/**
+ * Only deliver events matching these filters
+ */
+filters: WebhookFilters | null, };
+
+export type UpdateWebhookRequest = { url: string | null, secret: string | null, events: Array<WebhookEvent> | null, is_active: boolean | null, payload_template: string | null, headers: { [key in string]?: string } | null, filters: WebhookFilters | null, };
 
-export type UpdateWebhookRequest = { url: string | null, secret: string | null, events: Array<WebhookEvent> | null, is_active: boolean | null, };
+export type WebhookResponse = { id: string, project_id: string, url: string, secret: string, events: Array<WebhookEvent>, is_active: boolean, payload_template: string | null, headers: { [key in string]?: string }, filters: WebhookFilters, consecutive_failures: bigint, created_at: string, updated_at: string, };
 
-export type WebhookResponse = { id: string, project_id: string, url: string, secret: string, events: Array<WebhookEvent>, is_active: boolean, created_at: string, updated_at: string, };
+export type ReplayDeliveriesRequest = { from: string | null, to: string | null, };
+
+export type ReplayDeliveriesResponse = { 
+/**
+ * Number of failed deliveries queued for another attempt
+ */
+requeued: bigint, };
 
 export type TestWebhookResponse = { message: string, };
 
+export type CreateApiTokenRequest = { name: string, scopes: Array<ApiTokenScope>, 
+/**
+ * Optional expiry. Tokens without one stay valid until deleted.
+ */
+expires_at: string | null, };
+
+export type ApiTokenResponse = { id: string, project_id: string, name: string, token_prefix: string, scopes: Array<ApiTokenScope>, expires_at: string | null, last_used_at: string | null, created_at: string, };
+
+export type CreateApiTokenResponse = { 
+/**
+ * The plaintext token. It is only returned here and cannot be retrieved later.
+ */
+token: string, api_token: ApiTokenResponse, };
+
+export type CreateInboundWebhookRequest = { name: string, 
+/**
+ * Optional secret for verifying signatures. Auto-generated if not provided.
+ */
+secret: string | null, };
+
+export type UpdateInboundWebhookRequest = { is_active: boolean, };
+
+export type InboundWebhookAction = { "action": "create_task", title: string, description: string | null, status: TaskStatus | null, assignee: string | null, } | { "action": "transition_task", task_id: string, status: TaskStatus, };
+
+export type ExecutionEventPayload = { execution_process_id: string, session_id: string, workspace_id: string, task_id: string, project_id: string, run_reason: ExecutionProcessRunReason, status: ExecutionProcessStatus, 
+/**
+ * Process exit code, if the process exited normally
+ */
+exit_code: bigint | null, 
+/**
+ * Final assistant message for coding agent runs
+ */
+summary: string | null, started_at: string, completed_at: string | null, };
+
+export type ApprovalRequestedPayload = { approval_id: string, execution_process_id: string, task_id: string, project_id: string, tool_name: string, tool_input: JsonValue, requested_at: string, 
+/**
+ * The approval is treated as timed out after this time
+ */
+timeout_at: string, 
+/**
+ * Signed one-time link approving the request; set when `VIBE_PUBLIC_URL` is
+ */
+approve_url: string | null, 
+/**
+ * Signed one-time link denying the request; set when `VIBE_PUBLIC_URL` is
+ */
+deny_url: string | null, };
+
+export type ApprovalRespondedPayload = { approval_id: string, execution_process_id: string, task_id: string, project_id: string, tool_name: string, 
+/**
+ * One of `approved`, `denied` (with optional `reason`) or `timed_out`
+ */
+response: ApprovalStatus, responded_at: string, };
+
+export type PullRequestEventPayload = { task_id: string, project_id: string, workspace_id: string, repo_id: string, pr_number: bigint, pr_url: string, status: MergeStatus, target_branch: string, merged_at: string | null, merge_commit_sha: string | null, };
+
+export type TaskCommentAddedPayload = { task_id: string, project_id: string, comment: TaskComment, };
+
+export type TaskCommentMentionedPayload = { task_id: string, project_id: string, comment: TaskComment, 
+/**
+ * Only the mentions that were not already on the comment
+ */
+mentions: Array<TaskCommentMention>, };
+
+export type TaskAssignedPayload = { task_id: string, project_id: string, previous_assignee: string | null, assignee: string | null, task: Task, };
+
 export type CreateGitHubPrRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
 
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, mode: WorkspaceMode, };
+export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, mode: WorkspaceMode, 
+/**
+ * Build on this workspace's branch instead of the target branches. Repos the
+ * workspace also uses start from its branch and follow it until it merges.
+ */
+stacked_on_workspace_id?: string, };
 
 export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
 
@@ -339,13 +907,23 @@ export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | {
 
 export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
 
+export type RestackStatus = "up_to_date" | "rebased" | "unstacked" | "conflicts" | "skipped";
+
+export type RestackResult = { repo_id: string, status: RestackStatus, 
+/**
+ * Branch the workspace's commits are based on afterwards
+ */
+base_branch: string, message: string | null, };
+
 export type AbortConflictsRequest = { repo_id: string, };
 
+export type ResolveConflictsRequest = { repo_id: string, };
+
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" };
 
 export type PushError = { "type": "force_push_required" };
 
-export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, };
+export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "forge_not_logged_in", forge: ForgeKind, message: string, };
 
 export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
@@ -369,10 +947,36 @@ export type AttachExistingPrRequest = { repo_id: string, };
 
 export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
 
-export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" };
+export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "forge_not_logged_in", forge: ForgeKind, message: string, };
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
+export type AddressPrCommentsRequest = { repo_id: string, 
+/**
+ * Answer every addressed comment with the pushed commit
+ */
+reply_to_comments?: boolean, };
+
+export type AddressPrCommentsError = { "type": "no_pr_attached" } | { "type": "no_pending_comments" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "forge_not_logged_in", forge: ForgeKind, message: string, };
+
+export type ReviewFollowUpWithComments = { id: string, workspace_id: string, repo_id: string, pr_number: number, execution_process_id: string | null, 
+/**
+ * Branch head before the turn
+ */
+base_commit: string | null, 
+/**
+ * Whether each addressed comment gets a reply naming the commit
+ */
+reply_to_comments: boolean, status: ReviewFollowUpStatus, 
+/**
+ * Commit pushed for the comments
+ */
+commit_sha: string | null, 
+/**
+ * How the follow-up ended, e.g. why the push failed
+ */
+message: string | null, created_at: string, completed_at: string | null, comments: Array<ReviewFollowUpComment>, };
+
 export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string, body: string, created_at: string, url: string, } | { "comment_type": "review", id: bigint, author: string, author_association: string, body: string, created_at: string, url: string, path: string, line: bigint | null, diff_hunk: string, };
 
 export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
@@ -393,7 +997,7 @@ export type DirectoryEntry = { name: string, path: string, is_directory: boolean
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, auto_scheduler: AutoSchedulerConfig, sandbox: SandboxConfig, usage: UsageConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -413,9 +1017,44 @@ export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO" | "ZH_HANS";
 
 export type ShowcaseState = { seen_features: Array<string>, };
 
+export type AutoSchedulerConfig = { enabled: boolean, 
+/**
+ * Maximum coding agents running at once across all projects
+ */
+max_concurrent_agents: number, 
+/**
+ * Maximum coding agents running at once within a single project
+ */
+max_concurrent_agents_per_project: number, };
+
+export type UsageConfig = { 
+/**
+ * Replace the built-in price of the same model, or add a new model
+ */
+price_overrides: Array<ModelPrice>, };
+
+export type ModelPrice = { 
+/**
+ * Matched against the start of the model name reported by the agent; the
+ * longest match wins
+ */
+model: string, input: number, output: number, cache_read: number, cache_write: number, };
+
+export type SchedulerQueueState = { enabled: boolean, max_concurrent_agents: number, max_concurrent_agents_per_project: number, running_agents: number, projects: Array<ProjectQueueState>, };
+
+export type ProjectQueueState = { project_id: string, running_agents: number, 
+/**
+ * Tasks that will be started next, in start order
+ */
+ready: Array<Task>, 
+/**
+ * Tasks waiting on unfinished blockers
+ */
+blocked: Array<Task>, };
+
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
-export type SharedTaskDetails = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, };
+export type SharedTaskDetails = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, workflow_state?: string, };
 
 export type QueuedMessage = { 
 /**
@@ -441,20 +1080,56 @@ export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript" | "PipelineStep" | "Verification";
 
-export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
+export type ScriptRequest = { script: string, context: ScriptContext, 
 /**
  * Optional relative path to execute the script in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, language: ScriptRequestLanguage, 
+/**
+ * Interpreter command, e.g. `ruby -e` or `/opt/venv/bin/python -c`. The script is
+ * passed as its last argument. Required for `Custom`, overrides the default otherwise.
+ */
+interpreter: string | null, 
+/**
+ * Kill the script and fail the process after this many seconds
+ */
+timeout_secs: number | null, 
+/**
+ * Data segment limit per process in megabytes, on top of the sandbox config
+ */
+memory_limit_mb: number | null, 
+/**
+ * CPU time limit per process in seconds, on top of the sandbox config
+ */
+cpu_time_limit_secs: number | null, };
 
-export type ScriptRequestLanguage = "Bash";
+export type ScriptRequestLanguage = "Bash" | "Python" | "Node" | "PowerShell" | "Custom";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
+export type ScriptOptions = { language: ScriptRequestLanguage, 
+/**
+ * Interpreter command, e.g. `ruby -e` or `/opt/venv/bin/python -c`. The script is
+ * passed as its last argument. Required for `Custom`, overrides the default otherwise.
+ */
+interpreter: string | null, 
+/**
+ * Kill the script and fail the process after this many seconds
+ */
+timeout_secs: number | null, 
+/**
+ * Data segment limit per process in megabytes, on top of the sandbox config
+ */
+memory_limit_mb: number | null, 
+/**
+ * CPU time limit per process in seconds, on top of the sandbox config
+ */
+cpu_time_limit_secs: number | null, };
+
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", CUSTOM_CLI = "CUSTOM_CLI", OPENAI_COMPATIBLE = "OPENAI_COMPATIBLE" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_CLI": CustomCli } | { "OPENAI_COMPATIBLE": OpenaiCompatible };
 
 export type AvailabilityInfo = { "type": "LOGIN_DETECTED", last_auth_timestamp: bigint, } | { "type": "INSTALLATION_FOUND" } | { "type": "NOT_FOUND" };
 
@@ -468,6 +1143,41 @@ base: string,
  */
 params: Array<string> | null, };
 
+export enum SandboxBackend { NONE = "NONE", BUBBLEWRAP = "BUBBLEWRAP" }
+
+export type SandboxConfig = { 
+/**
+ * `BUBBLEWRAP` runs processes in Linux user namespaces via `bwrap`
+ */
+backend: SandboxBackend, 
+/**
+ * Extra paths visible read-only inside the sandbox. `~` expands to the home directory.
+ */
+read_only_paths: Array<string>, 
+/**
+ * Extra paths writable inside the sandbox, such as agent credentials and caches
+ */
+read_write_paths: Array<string>, 
+/**
+ * Cut the sandbox off from the network. Coding agents need the network to reach
+ * their model API, so this suits scripts and local models.
+ */
+deny_network: boolean, 
+/**
+ * Data segment limit (RLIMIT_DATA) per process, in megabytes
+ */
+memory_limit_mb: number | null, 
+/**
+ * CPU time limit (RLIMIT_CPU) per process, in seconds
+ */
+cpu_time_limit_secs: number | null, };
+
+export type SandboxPolicy = { backend: SandboxBackend, read_only_paths: Array<string>, read_write_paths: Array<string>, 
+/**
+ * Paths inside writable mounts that stay read-only, such as git hooks and config
+ */
+protected_paths: Array<string>, deny_network: boolean, memory_limit_mb: number | null, cpu_time_limit_secs: number | null, };
+
 export type ExecutorProfileId = { 
 /**
  * The executor type (e.g., "CLAUDE_CODE", "AMP")
@@ -478,9 +1188,28 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_CLI": CustomCli } | { "OPENAI_COMPATIBLE": OpenaiCompatible } };
 
-export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, 
+/**
+ * Profiles to fall back to when a coding agent fails for a transient reason
+ */
+fallbacks: Array<ExecutorFallbackPolicy>, };
+
+export type ExecutorFallbackPolicy = { 
+/**
+ * The profiles of the chain, starting with the preferred one
+ */
+chain: Array<ExecutorProfileId>, 
+/**
+ * How often a profile is retried after a transient failure before the next
+ * profile of the chain takes over
+ */
+retries: number, 
+/**
+ * Seconds to wait before the first retry of a profile; doubles with every retry
+ */
+backoff_secs: number, };
 
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER" }
 
@@ -520,6 +1249,16 @@ export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
+export type CustomCli = { append_prompt: AppendPrompt, initial_command: string, follow_up_command?: string | null, output_format: CustomCliOutputFormat, json_lines?: JsonLinesMapping | null, session_id?: CustomCliSessionId | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
+export type CustomCliOutputFormat = "plain_text" | "json_lines" | "acp";
+
+export type JsonLinesMapping = { kind_pointer: string, text_pointer: string, tool_name_pointer: string, tool_input_pointer: string, assistant_kinds: Array<string>, thinking_kinds: Array<string>, tool_kinds: Array<string>, error_kinds: Array<string>, };
+
+export type CustomCliSessionId = { regex?: string | null, json_pointer?: string | null, };
+
+export type OpenaiCompatible = { append_prompt: AppendPrompt, base_url?: string | null, model: string, api_key_env?: string | null, temperature?: number | null, max_requests?: number | null, command_timeout_secs?: number | null, approvals?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
 export type AppendPrompt = string | null;
 
 export type CodingAgentInitialRequest = { prompt: string, 